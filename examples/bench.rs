@@ -0,0 +1,49 @@
+//! Times a generation of a big soup on one thread and split across the compute pool's
+//! threads, to check the split pays off on this machine:
+//!
+//!     cargo run --release --example bench -- [side] [generations]
+//!
+//! The board is `side` cells each way (default 1000), a third of them alive.
+
+use bevy::tasks::{ComputeTaskPool, TaskPool};
+use bevy_game_of_life::{seed, step_in_bands, Config, EdgeMode, Rule};
+use rand::{rngs::StdRng, SeedableRng};
+
+use std::time::Instant;
+
+fn main() {
+    let args: Vec<usize> = std::env::args()
+        .skip(1)
+        .map(|a| a.parse().unwrap_or(0))
+        .collect();
+    let side = args.first().copied().unwrap_or(1000).max(1) as i32;
+    let generations = args.get(1).copied().unwrap_or(20).max(1);
+
+    let config = Config {
+        width: side,
+        height: side,
+        density: Some(0.33),
+        ..Default::default()
+    };
+    let rule = Rule::default();
+    let edge = EdgeMode::default();
+    let soup = seed(&mut StdRng::seed_from_u64(0), &config);
+    let threads = ComputeTaskPool::init(TaskPool::default).thread_num();
+
+    let mut per_generation = vec![];
+    for bands in [1, threads] {
+        let mut alives = soup.clone();
+        let started = Instant::now();
+        for _ in 0..generations {
+            alives = step_in_bands(&alives, &rule, &config, edge, bands);
+        }
+        let each = started.elapsed() / generations as u32;
+        println!(
+            "{side}x{side}, {bands} band(s): {each:.2?} a generation, {} alive at the end",
+            alives.len()
+        );
+        per_generation.push(each);
+    }
+    let speedup = per_generation[0].as_secs_f64() / per_generation[1].as_secs_f64();
+    println!("{speedup:.2}x on {threads} thread(s)");
+}
@@ -0,0 +1,44 @@
+//! The board as a slow background behind a game, through `GameOfLifePlugin`:
+//!
+//!     cargo run --example embed
+
+use bevy::prelude::*;
+use bevy_game_of_life::{Config, GameOfLifePlugin, Palette, Rule};
+
+use std::time::Duration;
+
+fn main() {
+    App::new()
+        .insert_resource(ClearColor(Color::MIDNIGHT_BLUE))
+        .add_plugins(DefaultPlugins)
+        .add_plugins(GameOfLifePlugin {
+            config: Config {
+                width: 64,
+                height: 36,
+                density: Some(0.3),
+                sparse: true,
+                ..Default::default()
+            },
+            rule: Rule::named("highlife").unwrap(),
+            tick: Some(Duration::from_millis(250)),
+            palette: Palette {
+                alive: Color::rgba(1.0, 1.0, 1.0, 0.15),
+                ..Default::default()
+            },
+        })
+        .add_systems(Startup, setup)
+        .run();
+}
+
+// the game's own camera and something in front of the board
+fn setup(mut commands: Commands) {
+    commands.spawn(Camera2dBundle::default());
+    commands.spawn(SpriteBundle {
+        sprite: Sprite {
+            color: Color::ORANGE,
+            custom_size: Some(Vec2::splat(80.0)),
+            ..Default::default()
+        },
+        ..Default::default()
+    });
+}
@@ -0,0 +1,34 @@
+//! Runs a seeded soup with no window and prints a summary that is stable across runs,
+//! so two commits can be compared by diffing the output:
+//!
+//!     cargo run --example replay -- <seed> <generations>
+//!
+//! The seed is the one the app logs at startup.
+
+use bevy_game_of_life::{live_set_hash, seed, step, Config, EdgeMode, Rule};
+use rand::{rngs::StdRng, SeedableRng};
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let parsed = match args.as_slice() {
+        [s, g] => s.parse::<u64>().ok().zip(g.parse::<usize>().ok()),
+        _ => None,
+    };
+    let Some((rng_seed, generations)) = parsed else {
+        eprintln!("usage: replay <seed> <generations>");
+        std::process::exit(2);
+    };
+
+    let config = Config::default();
+    let rule = Rule::default();
+    let edge = EdgeMode::default();
+    let mut alives = seed(&mut StdRng::seed_from_u64(rng_seed), &config);
+    for _ in 0..generations {
+        alives = step(&alives, &rule, &config, edge);
+    }
+
+    println!("seed: {rng_seed}");
+    println!("generations: {generations}");
+    println!("population: {}", alives.len());
+    println!("hash: {:016x}", live_set_hash(&alives));
+}
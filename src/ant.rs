@@ -0,0 +1,236 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use std::collections::HashMap;
+
+use crate::plugin::TREND_WINDOW;
+use crate::{Cell, Config, Coord, Dashboard, EdgeMode, LiveCells};
+
+// which way an ant turns on a cell of a color
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Turn {
+    Left,
+    Right,
+    // turn back the way it came
+    Back,
+    // carry straight on
+    Ahead,
+}
+
+// a turmite on the board: one turn per cell color, a cell the ant leaves
+// taking the next color round; "RL" is Langton's ant, where color 0 is a dead
+// cell and the others are alive
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(try_from = "String", into = "String")]
+pub struct Turmite(pub Vec<Turn>);
+
+impl Turmite {
+    // a letter a color, R, L, U (back) or N (none), at least two of them
+    pub fn parse(s: &str) -> Result<Turmite, String> {
+        let turns = s
+            .trim()
+            .chars()
+            .map(|c| match c.to_ascii_uppercase() {
+                'L' => Ok(Turn::Left),
+                'R' => Ok(Turn::Right),
+                'U' => Ok(Turn::Back),
+                'N' => Ok(Turn::Ahead),
+                _ => Err(format!("bad turn {c:?}, expected R, L, U or N")),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if !(2..=u8::MAX as usize).contains(&turns.len()) {
+            return Err(format!("a turmite needs 2 to 255 turns, got {s:?}"));
+        }
+        Ok(Turmite(turns))
+    }
+
+    pub fn colors(&self) -> u8 {
+        self.0.len() as u8
+    }
+}
+
+impl std::fmt::Display for Turmite {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        for turn in &self.0 {
+            let c = match turn {
+                Turn::Left => 'L',
+                Turn::Right => 'R',
+                Turn::Back => 'U',
+                Turn::Ahead => 'N',
+            };
+            write!(f, "{c}")?;
+        }
+        Ok(())
+    }
+}
+
+impl TryFrom<String> for Turmite {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Turmite, String> {
+        Turmite::parse(&s)
+    }
+}
+
+impl From<Turmite> for String {
+    fn from(turmite: Turmite) -> String {
+        turmite.to_string()
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Ant {
+    pub at: Coord,
+    // one cell's step, (0, 1) for up
+    pub heading: Coord,
+}
+
+// the ants under `Config::turmite` and the color of every cell they've been
+// on; colors missing are 0
+#[derive(Resource, Default, Debug)]
+pub struct Ants {
+    pub ants: Vec<Ant>,
+    pub colors: HashMap<Coord, u8>,
+}
+
+impl Ants {
+    // one ant on `at`, heading up
+    pub fn new(at: Coord) -> Ants {
+        Ants {
+            ants: vec![Ant {
+                at,
+                heading: (0, 1),
+            }],
+            colors: HashMap::new(),
+        }
+    }
+
+    pub fn color(&self, c: Coord) -> u8 {
+        self.colors.get(&c).copied().unwrap_or(0)
+    }
+
+    // every ant turns by the color it's on, moves that cell on to the next
+    // color and steps forward; the ones that walk off a dead edge are gone.
+    // Returns the cells that changed, with their new colors
+    pub fn step(&mut self, turmite: &Turmite, edge: EdgeMode, config: &Config) -> Vec<(Coord, u8)> {
+        let mut changed = vec![];
+        for ant in &mut self.ants {
+            let color = self.colors.get(&ant.at).copied().unwrap_or(0);
+            let (dx, dy) = ant.heading;
+            ant.heading = match turmite.0[color as usize % turmite.0.len()] {
+                Turn::Left => (-dy, dx),
+                Turn::Right => (dy, -dx),
+                Turn::Back => (-dx, -dy),
+                Turn::Ahead => (dx, dy),
+            };
+            let next = (color + 1) % turmite.colors();
+            if next == 0 {
+                self.colors.remove(&ant.at);
+            } else {
+                self.colors.insert(ant.at, next);
+            }
+            changed.push((ant.at, next));
+            let ahead = (ant.at.0 + ant.heading.0, ant.at.1 + ant.heading.1);
+            // parked off the board, to be dropped below
+            ant.at = edge.resolve(ahead, config).unwrap_or((i32::MIN, i32::MIN));
+        }
+        self.ants.retain(|ant| ant.at != (i32::MIN, i32::MIN));
+        changed
+    }
+}
+
+#[test]
+fn test_ants_step() {
+    let langton = Turmite::parse("rl").unwrap();
+    assert_eq!(langton.to_string(), "RL");
+    assert!(Turmite::parse("R").is_err());
+    assert!(Turmite::parse("RX").is_err());
+    let config = Config::default();
+    let mut ants = Ants::new((0, 0));
+    // on white it turns right: the first four steps go round a square
+    for _ in 0..4 {
+        ants.step(&langton, EdgeMode::default(), &config);
+    }
+    assert_eq!(ants.ants[0].at, (0, 0));
+    assert_eq!(ants.colors.len(), 4);
+    // and back on a black cell it turns left and clears it
+    let changed = ants.step(&langton, EdgeMode::default(), &config);
+    assert_eq!(changed, vec![((0, 0), 0)]);
+    assert_eq!(ants.ants[0].heading, (-1, 0));
+    // straight off a dead edge
+    let mut ants = Ants::new((0, 9));
+    ants.step(&Turmite::parse("NN").unwrap(), EdgeMode::default(), &config);
+    assert!(ants.ants.is_empty());
+    let mut ants = Ants::new((0, 9));
+    ants.step(
+        &Turmite::parse("NN").unwrap(),
+        EdgeMode::wrapping(true, true),
+        &config,
+    );
+    assert_eq!(ants.ants[0].at, (0, -10));
+}
+
+pub fn turmite_mode(config: Res<Config>) -> bool {
+    config.turmite.is_some()
+}
+
+pub fn place_ants(config: Res<Config>, mut ants: ResMut<Ants>) {
+    *ants = Ants::new(config.pattern_at);
+}
+
+// the ants' tick in place of dead_or_alive: a cell is alive while its color
+// isn't 0
+pub fn move_ants(
+    mut commands: Commands,
+    mut db: ResMut<Dashboard>,
+    config: Res<Config>,
+    edge: Res<EdgeMode>,
+    mut ants: ResMut<Ants>,
+    mut live: ResMut<LiveCells>,
+    mut query: Query<(Entity, &mut Cell)>,
+) {
+    let Some(turmite) = &config.turmite else {
+        return;
+    };
+    let mut changed: HashMap<_, _> = ants.step(turmite, *edge, &config).into_iter().collect();
+    db.round += 1;
+    (db.births, db.deaths) = (0, 0);
+    for (entity, mut cell) in query.iter_mut() {
+        let Some(color) = changed.remove(&cell.index_xy) else {
+            continue;
+        };
+        if cell.frozen {
+            continue;
+        }
+        let alive = color > 0;
+        match (live.0.contains(&cell.index_xy), alive) {
+            (false, true) => {
+                cell.born = db.round;
+                db.births += 1;
+            }
+            (true, false) => db.deaths += 1,
+            _ => {}
+        }
+        live.set(&mut cell, alive);
+        if !alive && config.sparse {
+            commands.entity(entity).despawn();
+        }
+    }
+    // sparse mode has no entity for a dead cell yet
+    for (xy, color) in changed {
+        if color > 0 {
+            let mut cell = Cell::new(xy, false);
+            cell.born = db.round;
+            db.births += 1;
+            live.set(&mut cell, true);
+            commands.spawn(cell);
+        }
+    }
+    db.survival = live.0.len();
+    let survival = db.survival;
+    db.peak = db.peak.max(survival);
+    db.history.push_back(survival);
+    if db.history.len() > TREND_WINDOW {
+        db.history.pop_front();
+    }
+}
@@ -0,0 +1,134 @@
+use bevy::prelude::*;
+use bevy_game_of_life::{
+    percentiles, step, step_once, Config, EdgeMode, LiveCells, PendingCells, Rule, SimPlugin,
+    SimRng,
+};
+
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use crate::texture::board_texels;
+
+// the same runs every time, so engine changes compare across commits
+pub struct Workload {
+    pub name: &'static str,
+    pub config: Config,
+    pub generations: usize,
+}
+
+pub fn standard() -> Vec<Workload> {
+    let board = Config {
+        width: 512,
+        height: 512,
+        ..Default::default()
+    };
+    vec![
+        Workload {
+            name: "acorn",
+            config: Config {
+                pattern: Some(PathBuf::from("acorn")),
+                sparse: true,
+                ..board.clone()
+            },
+            generations: 5000,
+        },
+        Workload {
+            name: "soup",
+            config: Config {
+                density: Some(0.5),
+                ..board
+            },
+            generations: 100,
+        },
+    ]
+}
+
+// a generation's time in each phase: `step` works out the next live set, the
+// neighbor counts and the rule in one pass; `sync` is the rest of the tick,
+// the cell entities following it and the period detector; `texture` rewrites
+// the board texture --texture draws from, the render side of a generation
+#[derive(Default, Debug)]
+pub struct Phases {
+    pub step: VecDeque<Duration>,
+    pub sync: VecDeque<Duration>,
+    pub texture: VecDeque<Duration>,
+    pub population: usize,
+}
+
+pub fn measure(workload: &Workload, seed: u64) -> Phases {
+    let (rule, edge) = (Rule::default(), EdgeMode::default());
+    let config = workload.config.clone();
+    let mut app = App::new();
+    app.insert_resource(SimRng::seeded(seed))
+        .add_plugins(MinimalPlugins)
+        .insert_resource(config.clone())
+        .insert_resource(rule.clone())
+        .insert_resource(edge)
+        .add_plugins(SimPlugin);
+    app.update();
+    while !app.world.resource::<PendingCells>().0.is_empty() {
+        app.update();
+    }
+    let mut phases = Phases::default();
+    for _ in 0..workload.generations {
+        let live = app.world.resource::<LiveCells>().0.clone();
+        let started = Instant::now();
+        std::hint::black_box(step(&live, &rule, &config, edge));
+        let stepped = started.elapsed();
+        // the tick works the generation out again, and then syncs
+        let started = Instant::now();
+        step_once(&mut app.world);
+        let tick = started.elapsed();
+        let live = &app.world.resource::<LiveCells>().0;
+        let started = Instant::now();
+        std::hint::black_box(board_texels(&config, live, Color::WHITE, Color::GRAY));
+        phases.texture.push_back(started.elapsed());
+        phases.step.push_back(stepped);
+        phases.sync.push_back(tick.saturating_sub(stepped));
+    }
+    phases.population = app.world.resource::<LiveCells>().0.len();
+    phases
+}
+
+#[test]
+fn test_measure() {
+    let glider = Workload {
+        name: "glider",
+        config: Config {
+            pattern: Some(PathBuf::from("glider")),
+            sparse: true,
+            ..Default::default()
+        },
+        generations: 4,
+    };
+    let phases = measure(&glider, 1);
+    assert_eq!(phases.population, 5);
+    assert_eq!(phases.step.len(), 4);
+    assert_eq!((phases.sync.len(), phases.texture.len()), (4, 4));
+}
+
+// --bench: each workload's phases as median / p95 / max a generation
+pub fn run(seed: u64) {
+    for workload in standard() {
+        let started = Instant::now();
+        let phases = measure(&workload, seed);
+        let (w, h) = (workload.config.width, workload.config.height);
+        println!(
+            "{} on {w}x{h}, {} generations in {:.2?}, {} alive at the end",
+            workload.name,
+            workload.generations,
+            started.elapsed(),
+            phases.population
+        );
+        for (phase, samples) in [
+            ("step", &phases.step),
+            ("sync", &phases.sync),
+            ("texture", &phases.texture),
+        ] {
+            if let Some([_, median, p95, max]) = percentiles(samples) {
+                println!("  {phase:<8} median {median:>9.2?}  p95 {p95:>9.2?}  max {max:>9.2?}");
+            }
+        }
+    }
+}
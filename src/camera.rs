@@ -0,0 +1,435 @@
+use bevy::input::mouse::{MouseMotion, MouseScrollUnit, MouseWheel};
+use bevy::prelude::*;
+use bevy::window::{PrimaryWindow, WindowResized};
+use bevy_game_of_life::{bounding_box, Config, Coord, LiveCells};
+
+use crate::draw::{cell_center, cursor_cell};
+use crate::{worlds, Theme};
+
+// room left around whatever gets framed
+const FIT_MARGIN: f32 = 1.1;
+// screen pixels a second the arrow keys pan by, whatever the zoom
+const PAN_SPEED: f32 = 600.0;
+// zoom per wheel notch, and the pixels of a touchpad scroll that make one
+const ZOOM_STEP: f32 = 1.1;
+const PIXELS_PER_NOTCH: f32 = 100.0;
+// from a cell across the window to a few pixels a cell on a huge board
+const MIN_SCALE: f32 = 0.02;
+const MAX_SCALE: f32 = 50.0;
+
+// orthographic scale at which `extent` world units just fit into `window` pixels
+pub fn fit_scale(extent: Vec2, window: Vec2) -> f32 {
+    (extent.x / window.x).max(extent.y / window.y) * FIT_MARGIN
+}
+
+#[test]
+fn test_fit_scale() {
+    let close = |a: f32, b: f32| (a - b).abs() < 1e-4;
+    // a square board in a landscape window is bound by the height
+    assert!(close(
+        fit_scale(Vec2::new(480.0, 480.0), Vec2::new(1280.0, 720.0)),
+        480.0 / 720.0 * FIT_MARGIN
+    ));
+    // a wide board in the same window by the width
+    assert!(close(
+        fit_scale(Vec2::new(2400.0, 480.0), Vec2::new(1280.0, 720.0)),
+        2400.0 / 1280.0 * FIT_MARGIN
+    ));
+    // portrait window
+    assert!(close(
+        fit_scale(Vec2::new(480.0, 480.0), Vec2::new(400.0, 800.0)),
+        1.2 * FIT_MARGIN
+    ));
+}
+
+// camera translation and scale once zoomed by `factor`, keeping the world
+// point under `offset` (pixels from the window center, y up) where it is
+pub fn zoom_about(translation: Vec2, scale: f32, offset: Vec2, factor: f32) -> (Vec2, f32) {
+    let zoomed = (scale * factor).clamp(MIN_SCALE, MAX_SCALE);
+    (translation + offset * (scale - zoomed), zoomed)
+}
+
+#[test]
+fn test_zoom_about() {
+    let (translation, scale) = (Vec2::new(100.0, -40.0), 2.0);
+    let offset = Vec2::new(30.0, 20.0);
+    let under = translation + offset * scale;
+    let (moved, zoomed) = zoom_about(translation, scale, offset, 0.5);
+    assert_eq!(zoomed, 1.0);
+    assert_eq!(moved + offset * zoomed, under);
+    // zooming about the center doesn't move the camera
+    assert_eq!(
+        zoom_about(translation, scale, Vec2::ZERO, 2.0).0,
+        translation
+    );
+    assert_eq!(zoom_about(translation, scale, offset, 1e6).1, MAX_SCALE);
+}
+
+// the cell the coordinate readouts count from, real cells keep their index_xy
+// and picking still goes by those
+#[derive(Resource, Default, Debug, Clone, Copy)]
+pub struct DisplayOrigin(pub Coord);
+
+impl DisplayOrigin {
+    pub fn shown(&self, (x, y): Coord) -> Coord {
+        (x - self.0 .0, y - self.0 .1)
+    }
+}
+
+#[test]
+fn test_display_origin() {
+    assert_eq!(DisplayOrigin::default().shown((3, -4)), (3, -4));
+    let origin = DisplayOrigin((5, -2));
+    assert_eq!(origin.shown((5, -2)), (0, 0));
+    assert_eq!(origin.shown((0, 0)), (-5, 2));
+}
+
+// End makes the hovered cell read (0, 0) and centers the camera on it,
+// Shift+End goes back to the real origin
+pub fn recenter_origin(
+    keys: Res<Input<KeyCode>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    config: Res<Config>,
+    theme: Res<Theme>,
+    mut origin: ResMut<DisplayOrigin>,
+    mut transforms: Query<&mut Transform, With<Camera>>,
+) {
+    if !keys.just_pressed(KeyCode::End) {
+        return;
+    }
+    let shift = keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    let picked = if shift {
+        Some((0, 0))
+    } else {
+        cursor_cell(&windows, &cameras, theme.pitch(), config.topology)
+            .filter(|c| config.contains(*c))
+    };
+    let Some(cell) = picked else {
+        return;
+    };
+    origin.0 = cell;
+    let center = cell_center(cell, theme.pitch(), config.topology);
+    for mut transform in transforms.iter_mut() {
+        transform.translation.x = center.x;
+        transform.translation.y = center.y;
+    }
+}
+
+// which side of the window `--panel` keeps for the dashboard
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq)]
+pub enum PanelSide {
+    Left,
+    Right,
+}
+
+// a strip of the window, in logical pixels, that the boards are framed beside
+// so the dashboard never covers a cell; no side leaves the whole window to them
+#[derive(Resource, Default, Debug, Clone, Copy)]
+pub struct Panel {
+    pub side: Option<PanelSide>,
+    pub width: f32,
+}
+
+impl Panel {
+    // camera translation and scale that fit `extent` around `center` into
+    // what's left of `window`
+    pub fn fit(&self, center: Vec2, extent: Vec2, window: Vec2) -> (Vec2, f32) {
+        let Some(side) = self.side else {
+            return (center, fit_scale(extent, window));
+        };
+        let width = self.width.min(window.x - 1.0);
+        let scale = fit_scale(extent, Vec2::new(window.x - width, window.y));
+        // the free area's middle is off the window's by half the panel
+        let shift = width / 2.0 * scale;
+        let x = match side {
+            PanelSide::Left => center.x - shift,
+            PanelSide::Right => center.x + shift,
+        };
+        (Vec2::new(x, center.y), scale)
+    }
+
+    // the world the camera shows beside the panel, as the center and extent
+    // `fit` would frame it from
+    fn shown(&self, translation: Vec2, scale: f32, window: Vec2) -> (Vec2, Vec2) {
+        let width = self.side.map_or(0.0, |_| self.width.min(window.x - 1.0));
+        let shift = width / 2.0 * scale;
+        let x = match self.side {
+            Some(PanelSide::Left) => translation.x + shift,
+            Some(PanelSide::Right) => translation.x - shift,
+            None => translation.x,
+        };
+        let free = Vec2::new(window.x - width, window.y);
+        (Vec2::new(x, translation.y), free * scale / FIT_MARGIN)
+    }
+
+    // the camera that keeps showing what it did in a window of `old` size once
+    // it's `new`
+    pub fn refit(&self, translation: Vec2, scale: f32, old: Vec2, new: Vec2) -> (Vec2, f32) {
+        let (center, extent) = self.shown(translation, scale, old);
+        self.fit(center, extent, new)
+    }
+}
+
+#[test]
+fn test_refit() {
+    let close = |a: Vec2, b: Vec2| (a - b).length() < 1e-3;
+    let (old, new) = (Vec2::new(1280.0, 720.0), Vec2::new(640.0, 720.0));
+    for side in [None, Some(PanelSide::Left), Some(PanelSide::Right)] {
+        let panel = Panel { side, width: 300.0 };
+        let camera = (Vec2::new(40.0, -7.0), 1.5);
+        // the same window keeps the same camera
+        let (translation, scale) = panel.refit(camera.0, camera.1, old, old);
+        assert!(close(translation, camera.0) && (scale - camera.1).abs() < 1e-4);
+        // what was shown still fits a narrower one
+        let (shown, extent) = panel.shown(camera.0, camera.1, old);
+        let (translation, scale) = panel.refit(camera.0, camera.1, old, new);
+        let (now, fits) = panel.shown(translation, scale, new);
+        assert!(close(now, shown));
+        assert!(fits.x >= extent.x - 1e-3 && fits.y >= extent.y - 1e-3);
+    }
+    // a window twice the size each way shows the same at twice the zoom
+    let (_, scale) = Panel::default().refit(Vec2::ZERO, 2.0, old, old * 2.0);
+    assert!((scale - 1.0).abs() < 1e-4);
+}
+
+#[test]
+fn test_panel_fit() {
+    let window = Vec2::new(1280.0, 720.0);
+    // screen x of a world x, 0 at the window's left edge
+    let screen = |x: f32, (camera, scale): (Vec2, f32)| (x - camera.x) / scale + window.x / 2.0;
+    for side in [PanelSide::Left, PanelSide::Right] {
+        let panel = Panel {
+            side: Some(side),
+            width: 400.0,
+        };
+        for (w, h) in [(3.0, 3.0), (20.0, 20.0), (200.0, 20.0), (20.0, 300.0)] {
+            let (center, extent) = (Vec2::new(17.0, -4.0), Vec2::new(w, h) * 24.0);
+            let fitted = panel.fit(center, extent, window);
+            let left = screen(center.x - extent.x / 2.0, fitted);
+            let right = screen(center.x + extent.x / 2.0, fitted);
+            let top = extent.y / 2.0 / fitted.1;
+            assert!(top <= window.y / 2.0);
+            match side {
+                PanelSide::Left => assert!(left >= 400.0 && right <= window.x),
+                PanelSide::Right => assert!(left >= 0.0 && right <= window.x - 400.0),
+            }
+        }
+    }
+    // without a panel it's the plain fit
+    let plain = Panel::default().fit(Vec2::ONE, Vec2::splat(480.0), window);
+    assert_eq!(plain, (Vec2::ONE, fit_scale(Vec2::splat(480.0), window)));
+}
+
+// center and size of every board together
+pub fn boards_extent(config: &Config, pitch: f32) -> (Vec2, Vec2) {
+    let (cols, rows) = worlds::layout(config.worlds);
+    let board = Vec2::new(config.width as f32, config.height as f32) * pitch;
+    let span = worlds::offset(cols * rows - 1, config, pitch).abs();
+    let center = worlds::layout_center(config, pitch);
+    // cells sit on their centers, so the board's own middle is off by half a cell
+    // for even sizes
+    let skew = Vec2::new(
+        config.x_range().start as f32 + config.x_range().end as f32 - 1.0,
+        config.y_range().start as f32 + config.y_range().end as f32 - 1.0,
+    ) / 2.0
+        * pitch;
+    (center + skew, span + board)
+}
+
+// center and size of the live cells' bounding box
+fn live_extent(live: &LiveCells, pitch: f32) -> Option<(Vec2, Vec2)> {
+    let ((min_x, min_y), (max_x, max_y)) = bounding_box(&live.0)?;
+    let center = Vec2::new((min_x + max_x) as f32, (min_y + max_y) as f32) / 2.0;
+    let size = Vec2::new((max_x - min_x + 1) as f32, (max_y - min_y + 1) as f32);
+    Some((center * pitch, size * pitch))
+}
+
+// Home frames all the boards, Shift+Home just the live cells of the main one
+pub fn fit_to_view(
+    keys: Res<Input<KeyCode>>,
+    config: Res<Config>,
+    theme: Res<Theme>,
+    panel: Res<Panel>,
+    live: Res<LiveCells>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    mut cameras: Query<(&mut Transform, &mut OrthographicProjection)>,
+) {
+    if !keys.just_pressed(KeyCode::Home) {
+        return;
+    }
+    let shift = keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    let framed = if shift {
+        live_extent(&live, theme.pitch())
+    } else {
+        Some(boards_extent(&config, theme.pitch()))
+    };
+    let (Some((center, extent)), Ok(window)) = (framed, windows.get_single()) else {
+        return;
+    };
+    let window = Vec2::new(window.width(), window.height());
+    let (translation, scale) = panel.fit(center, extent, window);
+    for (mut transform, mut projection) in cameras.iter_mut() {
+        transform.translation.x = translation.x;
+        transform.translation.y = translation.y;
+        projection.scale = scale;
+    }
+}
+
+// a resized window keeps showing what it did, the board no further cropped
+// or shrunk into a corner
+pub fn refit_on_resize(
+    mut resized: EventReader<WindowResized>,
+    windows: Query<(Entity, &Window), With<PrimaryWindow>>,
+    panel: Res<Panel>,
+    mut last: Local<Option<Vec2>>,
+    mut cameras: Query<(&mut Transform, &mut OrthographicProjection)>,
+) {
+    let Ok((primary, window)) = windows.get_single() else {
+        return;
+    };
+    let size = Vec2::new(window.width(), window.height());
+    let old = *last.get_or_insert(size);
+    if !resized.iter().any(|e| e.window == primary) || size.min_element() < 1.0 {
+        return;
+    }
+    *last = Some(size);
+    if old.min_element() < 1.0 {
+        return;
+    }
+    for (mut transform, mut projection) in cameras.iter_mut() {
+        let (translation, scale) = panel.refit(
+            transform.translation.truncate(),
+            projection.scale,
+            old,
+            size,
+        );
+        transform.translation.x = translation.x;
+        transform.translation.y = translation.y;
+        projection.scale = scale;
+    }
+}
+
+// the arrow keys and a middle-button drag move the camera, the board follows
+// the mouse; Alt+arrows resize the board instead
+pub fn pan_camera(
+    keys: Res<Input<KeyCode>>,
+    buttons: Res<Input<MouseButton>>,
+    time: Res<Time>,
+    mut motion: EventReader<MouseMotion>,
+    mut cameras: Query<(&mut Transform, &OrthographicProjection)>,
+) {
+    let arrows = [
+        (KeyCode::Left, Vec2::NEG_X),
+        (KeyCode::Right, Vec2::X),
+        (KeyCode::Down, Vec2::NEG_Y),
+        (KeyCode::Up, Vec2::Y),
+    ];
+    let alt = keys.any_pressed([KeyCode::AltLeft, KeyCode::AltRight]);
+    let mut pan: Vec2 = arrows
+        .iter()
+        .filter(|(key, _)| keys.pressed(*key) && !alt)
+        .map(|(_, dir)| *dir * PAN_SPEED * time.delta_seconds())
+        .sum();
+    let dragged: Vec2 = motion.iter().map(|m| m.delta).sum();
+    if buttons.pressed(MouseButton::Middle) {
+        // screen y points down
+        pan -= Vec2::new(dragged.x, -dragged.y);
+    }
+    if pan == Vec2::ZERO {
+        return;
+    }
+    for (mut transform, projection) in cameras.iter_mut() {
+        transform.translation += (pan * projection.scale).extend(0.0);
+    }
+}
+
+// the mouse wheel zooms about the cursor
+pub fn zoom_camera(
+    mut wheel: EventReader<MouseWheel>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    mut cameras: Query<(&mut Transform, &mut OrthographicProjection)>,
+) {
+    let notches: f32 = wheel
+        .iter()
+        .map(|w| match w.unit {
+            MouseScrollUnit::Line => w.y,
+            MouseScrollUnit::Pixel => w.y / PIXELS_PER_NOTCH,
+        })
+        .sum();
+    let Ok(window) = windows.get_single() else {
+        return;
+    };
+    if notches == 0.0 {
+        return;
+    }
+    let offset = window.cursor_position().map_or(Vec2::ZERO, |p| {
+        Vec2::new(p.x - window.width() / 2.0, window.height() / 2.0 - p.y)
+    });
+    // scrolling up brings the board closer
+    let factor = ZOOM_STEP.powf(-notches);
+    for (mut transform, mut projection) in cameras.iter_mut() {
+        let (translation, scale) = zoom_about(
+            transform.translation.truncate(),
+            projection.scale,
+            offset,
+            factor,
+        );
+        transform.translation.x = translation.x;
+        transform.translation.y = translation.y;
+        projection.scale = scale;
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Bookmark {
+    translation: Vec2,
+    scale: f32,
+}
+
+// camera slots 1 through 9
+#[derive(Resource, Default, Debug)]
+pub struct Bookmarks([Option<Bookmark>; 9]);
+
+const DIGITS: [KeyCode; 9] = [
+    KeyCode::Key1,
+    KeyCode::Key2,
+    KeyCode::Key3,
+    KeyCode::Key4,
+    KeyCode::Key5,
+    KeyCode::Key6,
+    KeyCode::Key7,
+    KeyCode::Key8,
+    KeyCode::Key9,
+];
+
+// Shift+digit saves the view, Ctrl+digit goes back to it; bare digits pick draw tools
+pub fn bookmarks(
+    keys: Res<Input<KeyCode>>,
+    mut bookmarks: ResMut<Bookmarks>,
+    mut cameras: Query<(&mut Transform, &mut OrthographicProjection)>,
+) {
+    let shift = keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    let ctrl = keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+    let Some(slot) = DIGITS.iter().position(|k| keys.just_pressed(*k)) else {
+        return;
+    };
+    let Ok((mut transform, mut projection)) = cameras.get_single_mut() else {
+        return;
+    };
+    if shift {
+        bookmarks.0[slot] = Some(Bookmark {
+            translation: transform.translation.truncate(),
+            scale: projection.scale,
+        });
+        info!("camera saved to slot {}", slot + 1);
+    } else if ctrl {
+        let Some(mark) = bookmarks.0[slot] else {
+            info!("no camera saved in slot {}", slot + 1);
+            return;
+        };
+        transform.translation.x = mark.translation.x;
+        transform.translation.y = mark.translation.y;
+        projection.scale = mark.scale;
+    }
+}
@@ -0,0 +1,198 @@
+use std::collections::{BTreeMap, HashSet};
+use std::sync::OnceLock;
+
+use crate::pattern::{parse_cells, place_pattern};
+use crate::{step, Config, Coord, EdgeMode, Rule};
+
+// small Conway objects worth naming, in one phase each, and their period
+const KNOWN: [(&str, &str, usize); 8] = [
+    ("block", "OO\nOO", 1),
+    ("beehive", ".OO.\nO..O\n.OO.", 1),
+    ("blinker", "OOO", 2),
+    ("toad", ".OOO\nOOO.", 2),
+    ("beacon", "OO..\nOO..\n..OO\n..OO", 2),
+    (
+        "pulsar",
+        "..OOO...OOO..\n\
+         .............\n\
+         O....O.O....O\n\
+         O....O.O....O\n\
+         O....O.O....O\n\
+         ..OOO...OOO..\n\
+         .............\n\
+         ..OOO...OOO..\n\
+         O....O.O....O\n\
+         O....O.O....O\n\
+         O....O.O....O\n\
+         .............\n\
+         ..OOO...OOO..",
+        3,
+    ),
+    ("glider", ".O.\n..O\nOOO", 4),
+    ("LWSS", ".O..O\nO....\nO...O\nOOOO.", 4),
+];
+
+// the same cells however they're moved, turned or mirrored: of the 8
+// orientations, the one that sorts first once its corner is at the origin
+pub fn canonical(cells: &[Coord]) -> Vec<Coord> {
+    let orientations: [fn(Coord) -> Coord; 8] = [
+        |(x, y)| (x, y),
+        |(x, y)| (-x, y),
+        |(x, y)| (x, -y),
+        |(x, y)| (-x, -y),
+        |(x, y)| (y, x),
+        |(x, y)| (-y, x),
+        |(x, y)| (y, -x),
+        |(x, y)| (-y, -x),
+    ];
+    orientations
+        .iter()
+        .map(|turn| {
+            let moved: Vec<_> = cells.iter().map(|c| turn(*c)).collect();
+            let min_x = moved.iter().map(|c| c.0).min().unwrap_or(0);
+            let min_y = moved.iter().map(|c| c.1).min().unwrap_or(0);
+            let mut shape: Vec<_> = moved.iter().map(|(x, y)| (x - min_x, y - min_y)).collect();
+            shape.sort();
+            shape
+        })
+        .min()
+        .unwrap_or_default()
+}
+
+// every phase of every known object, canonical, run once on a board with room
+// for a glider to fly a period
+fn catalog() -> &'static Vec<(Vec<Coord>, &'static str)> {
+    static CATALOG: OnceLock<Vec<(Vec<Coord>, &'static str)>> = OnceLock::new();
+    CATALOG.get_or_init(|| {
+        let config = Config {
+            width: 24,
+            height: 24,
+            ..Default::default()
+        };
+        let mut entries = vec![];
+        for (name, cells, period) in KNOWN {
+            let pattern = parse_cells(cells).expect("the catalog parses");
+            let mut phase = place_pattern(&pattern, (0, 0), &config);
+            for _ in 0..period {
+                let cells: Vec<_> = phase.iter().copied().collect();
+                entries.push((canonical(&cells), name));
+                phase = step(&phase, &Rule::default(), &config, EdgeMode::default());
+            }
+        }
+        entries
+    })
+}
+
+// the name of a group of cells in any phase, orientation and place
+pub fn name_of(cells: &[Coord]) -> Option<&'static str> {
+    let shape = canonical(cells);
+    catalog()
+        .iter()
+        .find(|(known, _)| *known == shape)
+        .map(|(_, name)| *name)
+}
+
+// live cells split into objects: cells up to 2 apart (one dead cell between)
+// belong together, which keeps the pulsar's quarters in one piece
+fn objects(alives: &HashSet<Coord>) -> Vec<Vec<Coord>> {
+    let mut left = alives.clone();
+    let mut found = vec![];
+    while let Some(&start) = left.iter().next() {
+        left.remove(&start);
+        let mut object = vec![start];
+        let mut i = 0;
+        while i < object.len() {
+            let (x, y) = object[i];
+            for dx in -2..=2 {
+                for dy in -2..=2 {
+                    if left.remove(&(x + dx, y + dy)) {
+                        object.push((x + dx, y + dy));
+                    }
+                }
+            }
+            i += 1;
+        }
+        found.push(object);
+    }
+    found
+}
+
+// the objects that have a name, with their cells
+pub fn named_objects(alives: &HashSet<Coord>) -> Vec<(&'static str, Vec<Coord>)> {
+    objects(alives)
+        .into_iter()
+        .filter_map(|object| Some((name_of(&object)?, object)))
+        .collect()
+}
+
+// e.g. "12 blocks, 3 gliders, blinker": how many of each, the most first
+pub fn census<'a>(names: impl IntoIterator<Item = &'a str>) -> String {
+    let mut counts = BTreeMap::new();
+    for name in names {
+        *counts.entry(name).or_insert(0) += 1;
+    }
+    let mut counts: Vec<_> = counts.into_iter().collect();
+    counts.sort_by_key(|(_, n)| std::cmp::Reverse(*n));
+    counts
+        .into_iter()
+        .map(|(name, n)| match n {
+            1 => name.to_string(),
+            n if name == "unknown" || name.to_lowercase().ends_with('s') => format!("{n} {name}"),
+            n => format!("{n} {name}s"),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+// every object on the board by name, `unknown` for the rest; empty for an
+// empty board
+pub fn identify(alives: &HashSet<Coord>) -> String {
+    let objects = objects(alives);
+    census(
+        objects
+            .iter()
+            .map(|object| name_of(object).unwrap_or("unknown")),
+    )
+}
+
+#[test]
+fn test_identify() {
+    let blinker = [(5, 5), (6, 5), (7, 5)];
+    assert_eq!(name_of(&blinker), Some("blinker"));
+    // standing up is the other phase
+    assert_eq!(name_of(&[(0, -1), (0, 0), (0, 1)]), Some("blinker"));
+    let block = [(-3, -3), (-2, -3), (-3, -2), (-2, -2)];
+    assert_eq!(name_of(&block), Some("block"));
+    // a glider flying the other way, in another phase
+    let glider = [(0, 0), (2, 0), (1, 1), (2, 1), (1, 2)];
+    assert_eq!(name_of(&glider), Some("glider"));
+    assert_eq!(name_of(&[(0, 0), (1, 0)]), None);
+    // an LWSS flying down
+    let lwss = [
+        (0, 0),
+        (3, 0),
+        (4, -1),
+        (0, -2),
+        (4, -2),
+        (1, -3),
+        (2, -3),
+        (3, -3),
+        (4, -3),
+    ];
+    assert_eq!(name_of(&lwss), Some("LWSS"));
+
+    let board: HashSet<_> = blinker
+        .iter()
+        .chain(&block)
+        .copied()
+        .chain([(-8, 6), (-7, 6), (-8, 7), (-7, 7), (9, -9)])
+        .collect();
+    assert_eq!(identify(&board), "2 blocks, blinker, unknown");
+    assert_eq!(identify(&HashSet::new()), "");
+    let named = named_objects(&board);
+    assert_eq!(named.len(), 3);
+    assert!(named
+        .iter()
+        .all(|(name, cells)| name_of(cells) == Some(name)));
+    assert_eq!(census(["LWSS", "LWSS", "glider"]), "2 LWSS, glider");
+}
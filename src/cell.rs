@@ -0,0 +1,280 @@
+use bevy::prelude::{Component, Event, Resource};
+use serde::{Deserialize, Serialize};
+
+use std::collections::HashSet;
+
+use crate::{Config, Coord};
+
+#[derive(Component, Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct Cell {
+    pub state: State,
+    pub index_xy: Coord,
+    // has been alive at least once since seeding
+    pub ever_alive: bool,
+    // round of the latest birth
+    pub born: usize,
+    // confetti color, fixed per coordinate
+    pub hue: f32,
+    // an obstacle: keeps its state forever, but still counts as a neighbor
+    pub frozen: bool,
+}
+
+// serialized the way it displays, "." and "O" as in plaintext patterns
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub enum State {
+    #[serde(rename = ".")]
+    Dead,
+    #[serde(rename = "O")]
+    Alive,
+    // a Generations rule's decay, from 1 right after dying; neither alive as a
+    // neighbor nor able to be born
+    Dying(u8),
+    // a Wireworld conductor, where electron heads (Alive) and tails (Dying(1))
+    // run along
+    #[serde(rename = "#")]
+    Wire,
+}
+
+impl std::fmt::Display for State {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            State::Dead => write!(f, "."),
+            State::Alive => write!(f, "O"),
+            State::Dying(stage) => write!(f, "{stage}"),
+            State::Wire => write!(f, "#"),
+        }
+    }
+}
+
+impl State {
+    // the state after a tick in which the rule said alive or not, for a rule
+    // with `states` states in all; only dead cells are born, and a live cell
+    // that doesn't survive decays through the stages in between first
+    pub fn next(&self, alive: bool, states: u8) -> State {
+        let last_stage = states.saturating_sub(2);
+        match self {
+            State::Dying(stage) if *stage < last_stage => State::Dying(stage + 1),
+            State::Dying(_) => State::Dead,
+            State::Alive if !alive && last_stage > 0 => State::Dying(1),
+            // a wire only carries electrons under Wireworld
+            State::Wire => State::Dead,
+            _ if alive => State::Alive,
+            _ => State::Dead,
+        }
+    }
+
+    // Wireworld's tick, `alive` being whether 1 or 2 of the neighbors are
+    // heads: a head leaves a tail, a tail turns back into wire, a wire next to
+    // the heads becomes one and empty cells stay empty
+    pub fn next_wire(&self, alive: bool) -> State {
+        match self {
+            State::Alive => State::Dying(1),
+            State::Dying(_) => State::Wire,
+            State::Wire if alive => State::Alive,
+            State::Wire => State::Wire,
+            State::Dead => State::Dead,
+        }
+    }
+}
+
+#[test]
+fn test_state_next() {
+    // life-like rules just follow the rule
+    assert_eq!(State::Alive.next(false, 2), State::Dead);
+    assert_eq!(State::Dead.next(true, 2), State::Alive);
+    // Brian's Brain: alive, dying, dead, whatever the neighbors
+    assert_eq!(State::Alive.next(false, 3), State::Dying(1));
+    assert_eq!(State::Dying(1).next(true, 3), State::Dead);
+    assert_eq!(State::Alive.next(true, 3), State::Alive);
+    // Star Wars has two stages of decay
+    assert_eq!(State::Dying(1).next(true, 4), State::Dying(2));
+    assert_eq!(State::Dying(2).next(false, 4), State::Dead);
+    // left over from a Generations rule, gone at once under a life-like one
+    assert_eq!(State::Dying(1).next(true, 2), State::Dead);
+    assert_eq!(State::Wire.next(true, 2), State::Dead);
+}
+
+#[test]
+fn test_state_next_wire() {
+    // empty, head, tail, wire and round again
+    assert_eq!(State::Dead.next_wire(true), State::Dead);
+    assert_eq!(State::Alive.next_wire(true), State::Dying(1));
+    assert_eq!(State::Dying(1).next_wire(true), State::Wire);
+    assert_eq!(State::Wire.next_wire(false), State::Wire);
+    assert_eq!(State::Wire.next_wire(true), State::Alive);
+}
+
+impl std::fmt::Display for Cell {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let (x, y) = self.index_xy;
+        write!(f, "{} at ({x}, {y})", self.state)
+    }
+}
+
+impl Cell {
+    pub fn new(index_xy: Coord, alive: bool) -> Cell {
+        Cell {
+            state: if alive { State::Alive } else { State::Dead },
+            index_xy,
+            ever_alive: alive,
+            born: 0,
+            hue: coord_hue(index_xy),
+            frozen: false,
+        }
+    }
+}
+
+// pseudo-random hue in 0..360, the same for a coordinate every time
+pub fn coord_hue((x, y): Coord) -> f32 {
+    let mut h = (x as u32 as u64) << 32 | y as u32 as u64;
+    // splitmix64 finalizer
+    h = (h ^ (h >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    h = (h ^ (h >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    h ^= h >> 31;
+    (h % 360) as f32
+}
+
+#[test]
+fn test_coord_hue() {
+    assert_eq!(coord_hue((3, -4)), coord_hue((3, -4)));
+    let hues: HashSet<_> = crate::Config::default()
+        .coords()
+        .map(|c| coord_hue(c) as u32)
+        .collect();
+    assert!(hues.iter().all(|h| *h < 360));
+    // spread over the wheel, not clumped on a few values
+    assert!(hues.len() > 200);
+}
+
+// one cell changing state in a tick, sent only when `Config::cell_events` is on
+#[derive(Event, Debug, PartialEq, Clone)]
+pub struct CellEvent {
+    pub coord: Coord,
+    pub from: State,
+    pub to: State,
+    // the generation it changed into
+    pub generation: usize,
+}
+
+// every live coord, kept in step with the cells' own state
+#[derive(Resource, Default, Debug)]
+pub struct LiveCells(pub HashSet<Coord>);
+
+impl LiveCells {
+    // the one place a cell changes state, so the set never drifts
+    pub fn set(&mut self, cell: &mut Cell, alive: bool) {
+        if alive {
+            cell.state = State::Alive;
+            cell.ever_alive = true;
+            self.0.insert(cell.index_xy);
+        } else {
+            cell.state = State::Dead;
+            self.0.remove(&cell.index_xy);
+        }
+    }
+}
+
+// rough heap footprint of a set of coords: one entry plus one control byte per
+// bucket, which is how the std hash set lays them out
+pub fn set_bytes(set: &HashSet<Coord>) -> usize {
+    set.capacity() * (std::mem::size_of::<Coord>() + 1)
+}
+
+// the whole board as rows of `State`s, top row first
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct Snapshot {
+    pub width: i32,
+    pub height: i32,
+    pub rows: Vec<String>,
+}
+
+impl Snapshot {
+    pub fn new(config: &Config, alives: &HashSet<Coord>) -> Snapshot {
+        let rows = config
+            .y_range()
+            .rev()
+            .map(|y| {
+                config
+                    .x_range()
+                    .map(|x| {
+                        let alive = alives.contains(&(x, y));
+                        if alive { State::Alive } else { State::Dead }.to_string()
+                    })
+                    .collect()
+            })
+            .collect();
+        Snapshot {
+            width: config.width,
+            height: config.height,
+            rows,
+        }
+    }
+
+    pub fn alives(&self) -> Result<HashSet<Coord>, String> {
+        let config = Config {
+            width: self.width,
+            height: self.height,
+            ..Default::default()
+        };
+        if self.rows.len() != self.height as usize {
+            return Err(format!(
+                "{} rows for a height of {}",
+                self.rows.len(),
+                self.height
+            ));
+        }
+        let mut alives = HashSet::new();
+        for (row, y) in self.rows.iter().zip(config.y_range().rev()) {
+            if row.chars().count() != self.width as usize {
+                return Err(format!("row {row:?} isn't {} wide", self.width));
+            }
+            for (c, x) in row.chars().zip(config.x_range()) {
+                match c {
+                    'O' => {
+                        alives.insert((x, y));
+                    }
+                    '.' => {}
+                    _ => return Err(format!("unexpected {c:?} in snapshot")),
+                }
+            }
+        }
+        Ok(alives)
+    }
+}
+
+impl std::fmt::Display for Snapshot {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.rows.join("\n"))
+    }
+}
+
+#[test]
+fn test_serialization_round_trip() {
+    for state in [State::Alive, State::Dead] {
+        let back: State = ron::from_str(&ron::to_string(&state).unwrap()).unwrap();
+        assert_eq!(back, state);
+    }
+    assert_eq!(State::Alive.to_string(), "O");
+
+    let cell = Cell::new((3, -2), true);
+    assert_eq!(cell.to_string(), "O at (3, -2)");
+    let back: Cell = ron::from_str(&ron::to_string(&cell).unwrap()).unwrap();
+    assert_eq!(back, cell);
+
+    let config = Config {
+        width: 3,
+        height: 3,
+        ..Default::default()
+    };
+    let blinker = HashSet::from([(-1, 0), (0, 0), (1, 0)]);
+    let snapshot = Snapshot::new(&config, &blinker);
+    assert_eq!(snapshot.to_string(), "...\nOOO\n...");
+    let back: Snapshot = ron::from_str(&ron::to_string(&snapshot).unwrap()).unwrap();
+    assert_eq!(back.alives().unwrap(), blinker);
+
+    let broken = Snapshot {
+        rows: vec!["...".into(), "OX.".into(), "...".into()],
+        ..snapshot
+    };
+    assert!(broken.alives().is_err());
+}
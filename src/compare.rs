@@ -0,0 +1,187 @@
+use bevy::prelude::*;
+use bevy_game_of_life::{
+    set_bytes, Cell, Config, Coord, LiveCells, PeriodDetector, Rule, SimState,
+};
+
+use std::collections::HashSet;
+
+use crate::draw::cell_center;
+use crate::{replace_board, Dashboard, Theme};
+
+// generations of a run kept for comparing, from its first one on; every one
+// is a copy of the live set, about 9 bytes per live cell
+pub const MAX_COMPARED: usize = 1000;
+
+// this run from its seed, and the run before the last replay of that seed
+#[derive(Resource, Default, Debug)]
+pub struct Comparison {
+    // the round the seed was laid down at
+    start: usize,
+    run: Vec<HashSet<Coord>>,
+    previous: Option<(Rule, Vec<HashSet<Coord>>)>,
+}
+
+// who has a cell alive at the same generation
+#[derive(Debug, Default, PartialEq)]
+pub struct Diff {
+    pub previous_only: HashSet<Coord>,
+    pub current_only: HashSet<Coord>,
+    pub both: HashSet<Coord>,
+}
+
+impl Comparison {
+    fn record(&mut self, round: usize, alives: &HashSet<Coord>) {
+        // the first generation ever seen is the seed
+        if self.run.is_empty() && self.previous.is_none() {
+            self.start = round;
+        }
+        let Some(i) = round.checked_sub(self.start) else {
+            return;
+        };
+        // coming back to an earlier generation, as scrubbing does, forks the run
+        self.run.truncate(i);
+        if self.run.len() == i && i < MAX_COMPARED {
+            self.run.push(alives.clone());
+        }
+    }
+
+    // lay this run aside, to be run again from its seed; whatever it is
+    // compared with goes
+    fn replay(&mut self, rule: &Rule) -> Option<HashSet<Coord>> {
+        let seed = self.run.first()?.clone();
+        self.previous = Some((rule.clone(), std::mem::take(&mut self.run)));
+        Some(seed)
+    }
+
+    // the first generation of this run, which G or scrubbing back to it lays
+    // down again
+    pub fn seed(&self) -> Option<&HashSet<Coord>> {
+        self.run.first()
+    }
+
+    pub fn previous_rule(&self) -> Option<&Rule> {
+        self.previous.as_ref().map(|(rule, _)| rule)
+    }
+
+    pub fn diff(&self, round: usize, alives: &HashSet<Coord>) -> Option<Diff> {
+        let (_, previous) = self.previous.as_ref()?;
+        let then = previous.get(round.checked_sub(self.start)?)?;
+        Some(Diff {
+            previous_only: then.difference(alives).copied().collect(),
+            current_only: alives.difference(then).copied().collect(),
+            both: then.intersection(alives).copied().collect(),
+        })
+    }
+
+    pub fn bytes(&self) -> usize {
+        let previous = self.previous.iter().flat_map(|(_, p)| p);
+        self.run.iter().chain(previous).map(set_bytes).sum()
+    }
+}
+
+#[test]
+fn test_comparison() {
+    use bevy_game_of_life::{step, EdgeMode};
+    let config = Config::default();
+    let edge = EdgeMode::default();
+    let conway = Rule::default();
+    let lwd = Rule::life_without_death();
+    let blinker = HashSet::from([(-1, 0), (0, 0), (1, 0)]);
+    let mut comparison = Comparison::default();
+    let mut board = blinker.clone();
+    for round in 5..10 {
+        comparison.record(round, &board);
+        board = step(&board, &conway, &config, edge);
+    }
+    assert_eq!(comparison.diff(6, &board), None);
+    assert_eq!(comparison.seed(), Some(&blinker));
+    assert_eq!(comparison.replay(&conway), Some(blinker.clone()));
+    assert_eq!(comparison.seed(), None);
+
+    let mut board = blinker.clone();
+    comparison.record(5, &board);
+    board = step(&board, &lwd, &config, edge);
+    comparison.record(6, &board);
+    // the vertical blinker against a blinker that kept its old cells
+    let diff = comparison.diff(6, &board).unwrap();
+    assert_eq!(diff.both, HashSet::from([(0, -1), (0, 0), (0, 1)]));
+    assert_eq!(diff.current_only, HashSet::from([(-1, 0), (1, 0)]));
+    assert!(diff.previous_only.is_empty());
+    assert_eq!(comparison.previous_rule(), Some(&conway));
+    // past the recorded part there's nothing to compare with
+    assert_eq!(comparison.diff(10, &board), None);
+}
+
+pub fn record_generation(
+    db: Res<Dashboard>,
+    live: Res<LiveCells>,
+    mut comparison: ResMut<Comparison>,
+) {
+    comparison.record(db.round, &live.0);
+}
+
+// G lays the run aside and goes back to its seed, paused so the rule can be
+// switched before the next run starts; the two are then told apart generation
+// by generation
+#[allow(clippy::too_many_arguments)]
+pub fn replay_seed(
+    mut commands: Commands,
+    keys: Res<Input<KeyCode>>,
+    config: Res<Config>,
+    rule: Res<Rule>,
+    mut comparison: ResMut<Comparison>,
+    mut db: ResMut<Dashboard>,
+    mut detector: ResMut<PeriodDetector>,
+    mut sim_state: ResMut<NextState<SimState>>,
+    mut live: ResMut<LiveCells>,
+    mut cells: Query<(Entity, &mut Cell)>,
+) {
+    if !keys.just_pressed(KeyCode::G) {
+        return;
+    }
+    let Some(seed) = comparison.replay(&rule) else {
+        return;
+    };
+    replace_board(&seed, &config, &mut commands, &mut live, &mut cells);
+    db.round = comparison.start;
+    db.survival = live.0.len();
+    db.history.clear();
+    db.forecast = None;
+    *detector = PeriodDetector::default();
+    comparison.record(db.round, &live.0);
+    sim_state.set(SimState::Paused);
+    info!(
+        "back to the seed of round {}, compared with the run under {}",
+        db.round, *rule
+    );
+}
+
+pub fn draw_comparison(
+    mut gizmos: Gizmos,
+    db: Res<Dashboard>,
+    config: Res<Config>,
+    theme: Res<Theme>,
+    live: Res<LiveCells>,
+    comparison: Res<Comparison>,
+) {
+    let Some(diff) = comparison.diff(db.round, &live.0) else {
+        return;
+    };
+    // outlines, so they read over any cell coloring and where sparse mode has no cell
+    let size = Vec2::splat(theme.size * 0.8);
+    let sets = [
+        (&diff.previous_only, Color::ORANGE_RED),
+        (&diff.current_only, Color::LIME_GREEN),
+        (&diff.both, Color::rgba(1.0, 1.0, 1.0, 0.3)),
+    ];
+    for (set, color) in sets {
+        for c in set {
+            gizmos.rect_2d(
+                cell_center(*c, theme.pitch(), config.topology),
+                0.0,
+                size,
+                color,
+            );
+        }
+    }
+}
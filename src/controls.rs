@@ -0,0 +1,496 @@
+use bevy::prelude::*;
+use bevy_game_of_life::{
+    seed, Cell, Config, Coord, EdgeMode, LiveCells, PeriodDetector, Rule, SimRng, SimState,
+};
+
+use std::collections::HashSet;
+use std::time::Duration;
+
+use crate::draw::DrawTool;
+use crate::resize::resized;
+use crate::{replace_board, scaled_period, AdaptiveSpeed, Dashboard, Theme};
+
+// the control panel: buttons for what is otherwise on the keyboard, Tab shows
+// and hides it
+#[derive(Resource, Default, Debug)]
+pub struct Controls(pub bool);
+
+#[derive(Component)]
+pub struct ControlsNode;
+
+#[derive(Component)]
+pub struct ControlsText;
+
+#[derive(Component)]
+pub struct RuleFieldText;
+
+// the panel's rule field: a click on it to type any rule --rule takes, Enter
+// to run it and Escape to leave the rule as it was. The keys go to nothing
+// else meanwhile
+#[derive(Resource, Default, Debug)]
+pub struct RuleField {
+    typing: bool,
+    text: String,
+    // why the last Enter didn't take, shown under the text
+    error: Option<String>,
+}
+
+impl RuleField {
+    // the rule typed in, if the board can run it
+    fn rule(&self, config: &Config) -> Result<Rule, String> {
+        let rule = Rule::named(self.text.trim())?;
+        config.runs(&rule)?;
+        Ok(rule)
+    }
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub enum Control {
+    Pause,
+    Step,
+    Reseed,
+    Slower,
+    Faster,
+    PreviousRule,
+    NextRule,
+    Sparser,
+    Denser,
+    SmallerRegion,
+    LargerRegion,
+    Edges,
+    Palette,
+    Narrower,
+    Wider,
+    Shorter,
+    Taller,
+    RuleField,
+}
+
+// rows of buttons, with their labels
+const ROWS: [&[(Control, &str)]; 8] = [
+    &[
+        (Control::Pause, "Pause"),
+        (Control::Step, "Step"),
+        (Control::Reseed, "Reseed"),
+    ],
+    &[(Control::Slower, "Slower"), (Control::Faster, "Faster")],
+    &[
+        (Control::PreviousRule, "< Rule"),
+        (Control::NextRule, "Rule >"),
+    ],
+    &[(Control::Sparser, "Sparser"), (Control::Denser, "Denser")],
+    &[
+        (Control::SmallerRegion, "Region -"),
+        (Control::LargerRegion, "Region +"),
+    ],
+    &[(Control::Edges, "Edges"), (Control::Palette, "Palette")],
+    &[(Control::Narrower, "Width -"), (Control::Wider, "Width +")],
+    &[
+        (Control::Shorter, "Height -"),
+        (Control::Taller, "Height +"),
+    ],
+];
+
+// how much Sparser / Denser change the reseed density by
+const DENSITY_STEP: f32 = 0.05;
+// and Region - / Region + the share of the board it seeds
+const REGION_STEP: f32 = 0.1;
+
+// the density a reseed fills its region to, whether or not --density was given
+fn density(config: &Config) -> f32 {
+    let (xs, ys) = config.seed_ranges();
+    config
+        .density
+        .unwrap_or(config.seed_count() as f32 / (xs.len() * ys.len()) as f32)
+}
+
+fn stepped_density(density: f32, denser: bool) -> f32 {
+    let step = if denser { DENSITY_STEP } else { -DENSITY_STEP };
+    // rounded to the step so repeated presses don't drift
+    ((density + step) / DENSITY_STEP)
+        .round()
+        .clamp(0.0, 1.0 / DENSITY_STEP)
+        * DENSITY_STEP
+}
+
+// never all the way down to nothing
+fn stepped_region(region: f32, larger: bool) -> f32 {
+    let step = if larger { REGION_STEP } else { -REGION_STEP };
+    ((region + step) / REGION_STEP)
+        .round()
+        .clamp(1.0, 1.0 / REGION_STEP)
+        * REGION_STEP
+}
+
+#[test]
+fn test_rule_field() {
+    let mut field = RuleField {
+        text: " B36/S23".to_string(),
+        ..Default::default()
+    };
+    let config = Config::default();
+    assert_eq!(field.rule(&config), Rule::parse("B36/S23"));
+    field.text = "highlife".to_string();
+    assert_eq!(field.rule(&config), Rule::parse("B36/S23"));
+    field.text = "B9/S".to_string();
+    assert!(field.rule(&config).is_err());
+    // parses, but not on the GPU
+    field.text = "brianbrain".to_string();
+    assert!(field.rule(&config).is_ok());
+    let gpu = Config {
+        backend: bevy_game_of_life::Backend::Gpu,
+        ..Default::default()
+    };
+    assert!(field.rule(&gpu).is_err());
+}
+
+#[test]
+fn test_stepped_density() {
+    assert!((stepped_density(0.3, true) - 0.35).abs() < 1e-6);
+    assert!((stepped_density(0.31, false) - 0.25).abs() < 1e-6);
+    assert_eq!(stepped_density(0.0, false), 0.0);
+    assert_eq!(stepped_density(1.0, true), 1.0);
+    assert!((stepped_region(1.0, false) - 0.9).abs() < 1e-6);
+    assert!((stepped_region(0.1, false) - 0.1).abs() < 1e-6);
+    assert_eq!(stepped_region(1.0, true), 1.0);
+}
+
+// a fresh soup over the seed region at the density set now, the round and
+// what was learned about the last board starting over
+#[allow(clippy::too_many_arguments)]
+pub fn reseed(
+    config: &Config,
+    rng: &mut SimRng,
+    db: &mut Dashboard,
+    detector: &mut PeriodDetector,
+    commands: &mut Commands,
+    live: &mut LiveCells,
+    cells: &mut Query<(Entity, &mut Cell)>,
+) {
+    let board = seed(&mut rng.0, config);
+    restart(&board, config, db, detector, commands, live, cells);
+}
+
+// `board` in place of the cells, from round 0
+pub fn restart(
+    board: &HashSet<Coord>,
+    config: &Config,
+    db: &mut Dashboard,
+    detector: &mut PeriodDetector,
+    commands: &mut Commands,
+    live: &mut LiveCells,
+    cells: &mut Query<(Entity, &mut Cell)>,
+) {
+    replace_board(board, config, commands, live, cells);
+    *detector = PeriodDetector::default();
+    db.round = 0;
+    (db.births, db.deaths) = (0, 0);
+    db.survival = live.0.len();
+    db.peak = db.survival;
+    db.history.clear();
+    db.forecast = None;
+}
+
+// R, but with the stamp tool R turns the stamp
+#[allow(clippy::too_many_arguments)]
+pub fn reseed_on_key(
+    mut commands: Commands,
+    keys: Res<Input<KeyCode>>,
+    tool: Res<DrawTool>,
+    config: Res<Config>,
+    mut rng: ResMut<SimRng>,
+    mut db: ResMut<Dashboard>,
+    mut detector: ResMut<PeriodDetector>,
+    mut live: ResMut<LiveCells>,
+    mut cells: Query<(Entity, &mut Cell)>,
+) {
+    if *tool == DrawTool::Stamp || !keys.just_pressed(KeyCode::R) {
+        return;
+    }
+    reseed(
+        &config,
+        &mut rng,
+        &mut db,
+        &mut detector,
+        &mut commands,
+        &mut live,
+        &mut cells,
+    );
+}
+
+// the Step button, for the single_step condition to pick up this frame
+#[derive(Resource, Default, Debug)]
+pub struct StepPressed(pub bool);
+
+pub fn spawn_controls(mut commands: Commands) {
+    let button_style = Style {
+        padding: UiRect::axes(Val::Px(10.0), Val::Px(4.0)),
+        ..Default::default()
+    };
+    let label = |text: &str| {
+        TextBundle::from_section(
+            text,
+            TextStyle {
+                font_size: 20.0,
+                ..Default::default()
+            },
+        )
+    };
+    commands
+        .spawn((
+            ControlsNode,
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    bottom: Val::Px(10.0),
+                    right: Val::Px(10.0),
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(4.0),
+                    padding: UiRect::all(Val::Px(8.0)),
+                    ..Default::default()
+                },
+                background_color: Color::rgba(0.0, 0.0, 0.0, 0.6).into(),
+                visibility: Visibility::Hidden,
+                ..Default::default()
+            },
+        ))
+        .with_children(|panel| {
+            panel.spawn((ControlsText, label("")));
+            for row in ROWS {
+                panel
+                    .spawn(NodeBundle {
+                        style: Style {
+                            column_gap: Val::Px(4.0),
+                            ..Default::default()
+                        },
+                        ..Default::default()
+                    })
+                    .with_children(|buttons| {
+                        for (control, text) in row {
+                            buttons
+                                .spawn((
+                                    *control,
+                                    ButtonBundle {
+                                        style: button_style.clone(),
+                                        background_color: Color::DARK_GRAY.into(),
+                                        ..Default::default()
+                                    },
+                                ))
+                                .with_children(|button| {
+                                    button.spawn(label(text));
+                                });
+                        }
+                    });
+            }
+            panel
+                .spawn((
+                    Control::RuleField,
+                    ButtonBundle {
+                        style: button_style.clone(),
+                        background_color: Color::DARK_GRAY.into(),
+                        ..Default::default()
+                    },
+                ))
+                .with_children(|button| {
+                    button.spawn((RuleFieldText, label("")));
+                });
+        });
+}
+
+pub fn toggle_controls(
+    keys: Res<Input<KeyCode>>,
+    mut controls: ResMut<Controls>,
+    mut nodes: Query<&mut Visibility, With<ControlsNode>>,
+) {
+    if !keys.just_pressed(KeyCode::Tab) {
+        return;
+    }
+    controls.0 = !controls.0;
+    for mut visibility in nodes.iter_mut() {
+        *visibility = if controls.0 {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
+
+// no drawing through the panel: false while the mouse is over one of its
+// buttons, or the minimap
+pub fn pointer_free(
+    controls: Res<Controls>,
+    buttons: Query<&Interaction, With<Control>>,
+    minimap: Query<&Interaction, With<crate::minimap::MinimapNode>>,
+) -> bool {
+    (!controls.0 || buttons.iter().all(|i| *i == Interaction::None))
+        && minimap.iter().all(|i| *i == Interaction::None)
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn press_controls(
+    mut commands: Commands,
+    mut config: ResMut<Config>,
+    mut fixed_time: ResMut<FixedTime>,
+    speed: Res<AdaptiveSpeed>,
+    (sim_state, mut next): (Res<State<SimState>>, ResMut<NextState<SimState>>),
+    mut step: ResMut<StepPressed>,
+    mut rule: ResMut<Rule>,
+    mut edge: ResMut<EdgeMode>,
+    mut theme: ResMut<Theme>,
+    mut field: ResMut<RuleField>,
+    mut rng: ResMut<SimRng>,
+    mut db: ResMut<Dashboard>,
+    mut detector: ResMut<PeriodDetector>,
+    mut live: ResMut<LiveCells>,
+    mut cells: Query<(Entity, &mut Cell)>,
+    mut buttons: Query<(&Interaction, &Control, &mut BackgroundColor), Changed<Interaction>>,
+) {
+    step.0 = false;
+    for (interaction, control, mut color) in buttons.iter_mut() {
+        *color = match interaction {
+            Interaction::Pressed => Color::GRAY,
+            Interaction::Hovered => Color::rgb(0.4, 0.4, 0.4),
+            Interaction::None => Color::DARK_GRAY,
+        }
+        .into();
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        match control {
+            Control::Pause => next.set(sim_state.get().toggled()),
+            // only while paused, as with Enter
+            Control::Step => step.0 = true,
+            Control::Reseed => reseed(
+                &config,
+                &mut rng,
+                &mut db,
+                &mut detector,
+                &mut commands,
+                &mut live,
+                &mut cells,
+            ),
+            // left to the adaptive speed while that's on
+            Control::Slower | Control::Faster if speed.enabled => {}
+            Control::Slower | Control::Faster => {
+                let faster = *control == Control::Faster;
+                let period = scaled_period(fixed_time.period.as_secs_f32(), faster);
+                fixed_time.period = Duration::from_secs_f32(period);
+            }
+            Control::PreviousRule | Control::NextRule => {
+                *rule = config.next_preset(&rule, *control == Control::NextRule);
+            }
+            Control::Sparser | Control::Denser => {
+                let d = stepped_density(density(&config), *control == Control::Denser);
+                config.density = Some(d);
+            }
+            Control::SmallerRegion | Control::LargerRegion => {
+                let larger = *control == Control::LargerRegion;
+                config.seed_region = stepped_region(config.seed_region, larger);
+            }
+            Control::Edges => edge.cycle(),
+            // as F1 does
+            Control::Palette => *theme = theme.with_preset(theme.preset.next()),
+            // as Alt+arrows do, resize_board doing the rest
+            Control::Narrower | Control::Wider => {
+                config.width = resized(config.width, *control == Control::Wider);
+            }
+            Control::Shorter | Control::Taller => {
+                config.height = resized(config.height, *control == Control::Taller);
+            }
+            // from the rule there is now
+            Control::RuleField if !field.typing => {
+                *field = RuleField {
+                    typing: true,
+                    text: rule.to_string(),
+                    error: None,
+                };
+            }
+            Control::RuleField => {}
+        }
+    }
+}
+
+// ahead of the key bindings, which see no keys while the field takes them
+pub fn type_rule(
+    config: Res<Config>,
+    mut rule: ResMut<Rule>,
+    mut field: ResMut<RuleField>,
+    mut keys: ResMut<Input<KeyCode>>,
+    mut chars: EventReader<ReceivedCharacter>,
+) {
+    if !field.typing {
+        chars.clear();
+        return;
+    }
+    for c in chars.iter().filter(|c| !c.char.is_control()) {
+        field.text.push(c.char);
+    }
+    if keys.just_pressed(KeyCode::Back) {
+        field.text.pop();
+    }
+    if keys.just_pressed(KeyCode::Escape) {
+        (field.typing, field.error) = (false, None);
+    }
+    if keys.any_just_pressed([KeyCode::Return, KeyCode::NumpadEnter]) {
+        match field.rule(&config) {
+            Ok(typed) => {
+                *rule = typed;
+                (field.typing, field.error) = (false, None);
+            }
+            Err(e) => field.error = Some(e),
+        }
+    }
+    keys.reset_all();
+}
+
+pub fn show_rule_field(field: Res<RuleField>, mut texts: Query<&mut Text, With<RuleFieldText>>) {
+    if !field.is_changed() {
+        return;
+    }
+    let shown = match (field.typing, &field.error) {
+        (false, _) => "Type a rule".to_string(),
+        (true, None) => format!("{}_", field.text),
+        (true, Some(e)) => format!("{}_\n{e}", field.text),
+    };
+    for mut text in texts.iter_mut() {
+        text.sections[0].value.clone_from(&shown);
+    }
+}
+
+// what the buttons change, as it is now
+#[allow(clippy::too_many_arguments)]
+pub fn update_controls(
+    controls: Res<Controls>,
+    config: Res<Config>,
+    fixed_time: Res<FixedTime>,
+    sim_state: Res<State<SimState>>,
+    rule: Res<Rule>,
+    edge: Res<EdgeMode>,
+    db: Res<Dashboard>,
+    theme: Res<Theme>,
+    mut texts: Query<&mut Text, With<ControlsText>>,
+) {
+    if !controls.0 {
+        return;
+    }
+    let state = if *sim_state.get() == SimState::Paused {
+        "paused"
+    } else {
+        "running"
+    };
+    let shown = format!(
+        "{state} at round {}\ntick {:.3}s\nrule {}\nreseed density {:.0}%, region {:.0}%\nedges {}, palette {}\nboard {}x{}",
+        db.round,
+        fixed_time.period.as_secs_f32(),
+        *rule,
+        100.0 * density(&config),
+        100.0 * config.seed_region,
+        *edge,
+        theme.preset.name(),
+        config.width,
+        config.height,
+    );
+    for mut text in texts.iter_mut() {
+        text.sections[0].value.clone_from(&shown);
+    }
+}
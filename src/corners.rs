@@ -0,0 +1,91 @@
+use bevy::prelude::*;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use bevy_game_of_life::Cell;
+
+use crate::worlds::SideCell;
+use crate::Theme;
+
+// pixels along each side of the rounded cell texture
+const TEXTURE_SIZE: u32 = 64;
+
+// how much of every pixel of a `size` square with its corners rounded by `radius`
+// (a fraction of the side, at most half) is covered, with a pixel of falloff so
+// the curve doesn't come out jagged
+fn rounded_square_alpha(size: u32, radius: f32) -> Vec<u8> {
+    let half = size as f32 / 2.0;
+    let r = radius.clamp(0.0, 0.5) * size as f32;
+    let mut alpha = Vec::with_capacity((size * size) as usize);
+    for y in 0..size {
+        for x in 0..size {
+            let p = Vec2::new(x as f32 + 0.5, y as f32 + 0.5) - Vec2::splat(half);
+            // signed distance to the edge, negative inside
+            let q = p.abs() - Vec2::splat(half - r);
+            let d = q.max(Vec2::ZERO).length() + q.max_element().min(0.0) - r;
+            alpha.push(((0.5 - d).clamp(0.0, 1.0) * 255.0).round() as u8);
+        }
+    }
+    alpha
+}
+
+#[test]
+fn test_rounded_square_alpha() {
+    let at = |alpha: &[u8], x: u32, y: u32| alpha[(y * 16 + x) as usize];
+    let sharp = rounded_square_alpha(16, 0.0);
+    assert!(sharp.iter().all(|a| *a == 255));
+    let round = rounded_square_alpha(16, 0.25);
+    // the very corners are cut off, the middle and the middles of the sides stay
+    assert_eq!(at(&round, 0, 0), 0);
+    assert_eq!(at(&round, 15, 15), 0);
+    assert_eq!(at(&round, 8, 8), 255);
+    assert_eq!(at(&round, 0, 8), 255);
+    // and it's the same all the way around
+    assert_eq!(at(&round, 1, 2), at(&round, 14, 13));
+}
+
+// white, so the sprite color still tints it
+fn rounded_square(radius: f32) -> Image {
+    let data = rounded_square_alpha(TEXTURE_SIZE, radius)
+        .into_iter()
+        .flat_map(|a| [255, 255, 255, a])
+        .collect();
+    Image::new(
+        Extent3d {
+            width: TEXTURE_SIZE,
+            height: TEXTURE_SIZE,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+    )
+}
+
+// none with sharp corners, cells keep the default plain white texture then
+#[derive(Resource, Default, Debug)]
+pub struct CellTexture(Option<Handle<Image>>);
+
+pub fn load_cell_texture(
+    theme: Res<Theme>,
+    mut images: ResMut<Assets<Image>>,
+    mut texture: ResMut<CellTexture>,
+) {
+    if theme.corner_radius > 0.0 {
+        texture.0 = Some(images.add(rounded_square(theme.corner_radius)));
+    }
+}
+
+// main board cells get their sprite a little after they spawn
+type NewSprites = (Added<Handle<Image>>, Or<(With<Cell>, With<SideCell>)>);
+
+// cells come and go in sparse mode, so every new one gets it as it appears
+pub fn round_new_cells(
+    texture: Res<CellTexture>,
+    mut query: Query<&mut Handle<Image>, NewSprites>,
+) {
+    let Some(rounded) = &texture.0 else {
+        return;
+    };
+    for mut handle in query.iter_mut() {
+        *handle = rounded.clone();
+    }
+}
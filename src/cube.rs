@@ -0,0 +1,199 @@
+use bevy::input::mouse::{MouseMotion, MouseWheel};
+use bevy::prelude::*;
+use bevy_game_of_life::{cube_range, seed3, step3, Coord3, Rule3, SimRng, SimState};
+
+use std::collections::{HashMap, HashSet};
+
+// the soup fills a block this far from the middle each way
+const SEED_RADIUS: i32 = 5;
+const SEED_DENSITY: f64 = 0.3;
+// radians a second the camera turns on its own, and per pixel dragged
+const DRIFT: f32 = 0.1;
+const DRAG: f32 = 0.005;
+
+// the 3D board: live cells, a cube entity each, in a cube `side` cells across
+#[derive(Resource, Debug)]
+pub struct Life3d {
+    side: i32,
+    round: usize,
+    alives: HashSet<Coord3>,
+    cubes: HashMap<Coord3, Entity>,
+}
+
+// the camera circles the middle of the board at `distance`, looking at it
+#[derive(Component, Debug)]
+struct Orbit {
+    yaw: f32,
+    pitch: f32,
+    distance: f32,
+}
+
+impl Orbit {
+    fn transform(&self) -> Transform {
+        let rotation = Quat::from_euler(EulerRot::YXZ, self.yaw, self.pitch, 0.0);
+        Transform::from_translation(rotation * Vec3::new(0.0, 0.0, self.distance))
+            .looking_at(Vec3::ZERO, Vec3::Y)
+    }
+}
+
+#[test]
+fn test_orbit() {
+    let orbit = Orbit {
+        yaw: 1.0,
+        pitch: -0.4,
+        distance: 50.0,
+    };
+    let transform = orbit.transform();
+    assert!((transform.translation.length() - 50.0).abs() < 1e-3);
+    // looking at the middle
+    let ahead = transform.forward();
+    assert!((ahead + transform.translation.normalize()).length() < 1e-3);
+}
+
+#[derive(Resource)]
+struct CubeLook(Handle<Mesh>, Handle<StandardMaterial>);
+
+// `--life3d`: its own window and app, without any of the 2D board; Space
+// pauses, a left drag turns the camera and the wheel moves it in and out
+pub fn run(rule: Rule3, side: i32, tick: f32, seed: Option<u64>) {
+    let mut rng = seed.map_or_else(SimRng::default, SimRng::seeded);
+    let alives = seed3(side, SEED_RADIUS, SEED_DENSITY, &mut rng.0);
+    App::new()
+        .add_plugins(DefaultPlugins.set(WindowPlugin {
+            primary_window: Some(crate::primary_window()),
+            ..Default::default()
+        }))
+        .insert_resource(FixedTime::new_from_secs(tick))
+        .insert_resource(rule)
+        .insert_resource(Life3d {
+            side,
+            round: 0,
+            alives,
+            cubes: HashMap::new(),
+        })
+        .add_state::<SimState>()
+        .add_systems(Startup, (setup, show_cells).chain())
+        .add_systems(
+            FixedUpdate,
+            (step_cells.run_if(in_state(SimState::Running)), show_cells).chain(),
+        )
+        .add_systems(Update, (orbit_camera, toggle_pause, update_title))
+        .run();
+}
+
+fn setup(
+    mut commands: Commands,
+    life: Res<Life3d>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let orbit = Orbit {
+        yaw: 0.6,
+        pitch: -0.5,
+        distance: life.side as f32 * 2.0,
+    };
+    commands.spawn((
+        Camera3dBundle {
+            transform: orbit.transform(),
+            ..Default::default()
+        },
+        orbit,
+    ));
+    commands.spawn(DirectionalLightBundle {
+        transform: Transform::from_xyz(1.0, 2.0, 3.0).looking_at(Vec3::ZERO, Vec3::Y),
+        ..Default::default()
+    });
+    commands.insert_resource(AmbientLight {
+        brightness: 0.3,
+        ..Default::default()
+    });
+    commands.insert_resource(CubeLook(
+        meshes.add(shape::Cube::new(0.9).into()),
+        materials.add(Color::rgb(0.3, 0.8, 1.0).into()),
+    ));
+}
+
+fn step_cells(rule: Res<Rule3>, mut life: ResMut<Life3d>) {
+    life.alives = step3(&life.alives, &rule, life.side);
+    life.round += 1;
+}
+
+// a cube for each live cell, sharing the one mesh and material; only the
+// births and deaths touch entities
+fn show_cells(mut commands: Commands, look: Res<CubeLook>, mut life: ResMut<Life3d>) {
+    let life = &mut *life;
+    life.cubes.retain(|c, entity| {
+        let alive = life.alives.contains(c);
+        if !alive {
+            commands.entity(*entity).despawn();
+        }
+        alive
+    });
+    // offset by half a cell on even sides, so the cube's middle is the origin
+    let middle = (cube_range(life.side).start + cube_range(life.side).end - 1) as f32 / 2.0;
+    for &(x, y, z) in &life.alives {
+        life.cubes.entry((x, y, z)).or_insert_with(|| {
+            let at = Vec3::new(x as f32, y as f32, z as f32) - Vec3::splat(middle);
+            commands
+                .spawn(PbrBundle {
+                    mesh: look.0.clone(),
+                    material: look.1.clone(),
+                    transform: Transform::from_translation(at),
+                    ..Default::default()
+                })
+                .id()
+        });
+    }
+}
+
+fn orbit_camera(
+    time: Res<Time>,
+    buttons: Res<Input<MouseButton>>,
+    mut motion: EventReader<MouseMotion>,
+    mut wheel: EventReader<MouseWheel>,
+    mut cameras: Query<(&mut Orbit, &mut Transform)>,
+) {
+    let dragged: Vec2 = motion.iter().map(|m| m.delta).sum();
+    let scrolled: f32 = wheel.iter().map(|w| w.y).sum();
+    let Ok((mut orbit, mut transform)) = cameras.get_single_mut() else {
+        return;
+    };
+    if buttons.pressed(MouseButton::Left) {
+        orbit.yaw -= dragged.x * DRAG;
+        orbit.pitch = (orbit.pitch - dragged.y * DRAG).clamp(-1.5, 1.5);
+    } else {
+        orbit.yaw += DRIFT * time.delta_seconds();
+    }
+    orbit.distance = (orbit.distance * (1.0 - scrolled * 0.1)).max(2.0);
+    *transform = orbit.transform();
+}
+
+fn toggle_pause(
+    keys: Res<Input<KeyCode>>,
+    sim_state: Res<State<SimState>>,
+    mut next: ResMut<NextState<SimState>>,
+) {
+    if keys.just_pressed(KeyCode::Space) {
+        next.set(sim_state.get().toggled());
+    }
+}
+
+fn update_title(life: Res<Life3d>, rule: Res<Rule3>, mut windows: Query<&mut Window>) {
+    if !life.is_changed() {
+        return;
+    }
+    let Rule3 {
+        survive_low,
+        survive_high,
+        birth_low,
+        birth_high,
+    } = *rule;
+    let title = format!(
+        "Life 3D — {survive_low},{survive_high},{birth_low},{birth_high} — gen {} — pop {}",
+        life.round,
+        life.alives.len()
+    );
+    for mut window in windows.iter_mut() {
+        window.title = title.clone();
+    }
+}
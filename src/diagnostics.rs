@@ -0,0 +1,175 @@
+use bevy::diagnostic::{
+    Diagnostic, DiagnosticId, Diagnostics, DiagnosticsStore, FrameTimeDiagnosticsPlugin,
+    RegisterDiagnostic,
+};
+use bevy::prelude::*;
+use bevy_game_of_life::{Cell, Dashboard, StepTiming};
+
+// measurements each keeps, the overlay showing their average
+const HISTORY: usize = 60;
+// seconds between two refreshes of the overlay
+const OVERLAY_REFRESH: f32 = 0.5;
+
+pub const STEP_TIME: DiagnosticId =
+    DiagnosticId::from_u128(0x6a5f_0d41_2c3e_4b8f_9e17_52a0_c3d8_0001);
+pub const CELLS_UPDATED: DiagnosticId =
+    DiagnosticId::from_u128(0x6a5f_0d41_2c3e_4b8f_9e17_52a0_c3d8_0002);
+pub const CELLS_COLORED: DiagnosticId =
+    DiagnosticId::from_u128(0x6a5f_0d41_2c3e_4b8f_9e17_52a0_c3d8_0003);
+
+// what the overlay shows and --log-diagnostics logs, Bevy's frame time first
+pub const SHOWN: [DiagnosticId; 5] = [
+    FrameTimeDiagnosticsPlugin::FPS,
+    FrameTimeDiagnosticsPlugin::FRAME_TIME,
+    STEP_TIME,
+    CELLS_UPDATED,
+    CELLS_COLORED,
+];
+
+// how long a CPU tick takes to work out the next generation, how many cells
+// it turned on or off, and how many cell sprites the color pass rewrote, as
+// Bevy diagnostics, beside the frame time ones
+pub struct SimDiagnosticsPlugin;
+
+impl Plugin for SimDiagnosticsPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_diagnostic(Diagnostic::new(STEP_TIME, "step_time", HISTORY).with_suffix("ms"))
+            .register_diagnostic(
+                Diagnostic::new(CELLS_UPDATED, "cells_updated", HISTORY).with_suffix(" cells"),
+            )
+            .register_diagnostic(
+                Diagnostic::new(CELLS_COLORED, "cells_colored", HISTORY).with_suffix(" sprites"),
+            );
+    }
+}
+
+// after a tick
+pub fn measure_step(mut diagnostics: Diagnostics, timing: Res<StepTiming>, db: Res<Dashboard>) {
+    // the HashLife and GPU backends don't time theirs
+    if let Some(step) = timing.samples.back().filter(|_| timing.is_changed()) {
+        diagnostics.add_measurement(STEP_TIME, || step.as_secs_f64() * 1000.0);
+    }
+    if db.is_changed() {
+        diagnostics.add_measurement(CELLS_UPDATED, || (db.births + db.deaths) as f64);
+    }
+}
+
+// after the color pass, every sprite it wrote to since the last frame
+pub fn measure_coloring(
+    mut diagnostics: Diagnostics,
+    sprites: Query<(), (Changed<Sprite>, With<Cell>)>,
+) {
+    diagnostics.add_measurement(CELLS_COLORED, || sprites.iter().count() as f64);
+}
+
+#[test]
+fn test_diagnostics() {
+    let mut app = App::new();
+    app.add_plugins(SimDiagnosticsPlugin)
+        .init_resource::<StepTiming>()
+        .init_resource::<Dashboard>()
+        .add_systems(Update, (measure_step, measure_coloring));
+    app.world
+        .resource_mut::<StepTiming>()
+        .samples
+        .push_back(std::time::Duration::from_micros(2500));
+    let mut db = app.world.resource_mut::<Dashboard>();
+    (db.births, db.deaths) = (3, 4);
+    app.world
+        .spawn((Cell::new((0, 0), true), Sprite::default()));
+    app.world.spawn(Cell::new((1, 0), false));
+    app.update();
+    let value = |app: &App, id| app.world.resource::<DiagnosticsStore>().get(id)?.value();
+    assert_eq!(value(&app, STEP_TIME), Some(2.5));
+    assert_eq!(value(&app, CELLS_UPDATED), Some(7.0));
+    assert_eq!(value(&app, CELLS_COLORED), Some(1.0));
+    // nothing new until the next tick, and no sprite touched
+    app.update();
+    let store = app.world.resource::<DiagnosticsStore>();
+    assert_eq!(store.get(STEP_TIME).unwrap().history_len(), 1);
+    assert_eq!(value(&app, CELLS_COLORED), Some(0.0));
+}
+
+// Shift+F3: the diagnostics in the top-right corner
+#[derive(Resource, Debug)]
+pub struct DiagnosticsOverlay {
+    enabled: bool,
+    refresh: Timer,
+}
+
+impl Default for DiagnosticsOverlay {
+    fn default() -> DiagnosticsOverlay {
+        DiagnosticsOverlay {
+            enabled: false,
+            refresh: Timer::from_seconds(OVERLAY_REFRESH, TimerMode::Repeating),
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct OverlayText;
+
+pub fn spawn_overlay(mut commands: Commands) {
+    commands.spawn((
+        OverlayText,
+        TextBundle {
+            text: Text::from_section(
+                "",
+                TextStyle {
+                    font_size: 18.0,
+                    ..Default::default()
+                },
+            ),
+            style: Style {
+                position_type: PositionType::Absolute,
+                top: Val::Px(10.0),
+                right: Val::Px(10.0),
+                ..Default::default()
+            },
+            background_color: Color::rgba(0.0, 0.0, 0.0, 0.6).into(),
+            visibility: Visibility::Hidden,
+            ..Default::default()
+        },
+    ));
+}
+
+pub fn toggle_overlay(
+    keys: Res<Input<KeyCode>>,
+    mut overlay: ResMut<DiagnosticsOverlay>,
+    mut texts: Query<&mut Visibility, With<OverlayText>>,
+) {
+    let shift = keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    if !(shift && keys.just_pressed(KeyCode::F3)) {
+        return;
+    }
+    overlay.enabled = !overlay.enabled;
+    for mut visibility in texts.iter_mut() {
+        *visibility = if overlay.enabled {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
+
+pub fn update_overlay(
+    time: Res<Time>,
+    store: Res<DiagnosticsStore>,
+    mut overlay: ResMut<DiagnosticsOverlay>,
+    mut texts: Query<&mut Text, With<OverlayText>>,
+) {
+    if !overlay.enabled || !overlay.refresh.tick(time.delta()).just_finished() {
+        return;
+    }
+    let lines: Vec<_> = SHOWN
+        .iter()
+        .filter_map(|id| store.get(*id))
+        .map(|d| match d.average() {
+            Some(average) => format!("{} {average:.2}{}", d.name, d.suffix),
+            None => format!("{} -", d.name),
+        })
+        .collect();
+    for mut text in texts.iter_mut() {
+        text.sections[0].value = lines.join("\n");
+    }
+}
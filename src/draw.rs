@@ -0,0 +1,709 @@
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use bevy_game_of_life::pattern::{builtin, load_pattern, place_pattern, Pattern, LIBRARY};
+use bevy_game_of_life::{Cell, Config, Coord, LiveCells, State, Symmetry, Topology};
+
+use std::collections::HashSet;
+
+use crate::scrub::Timeline;
+use crate::select::Selection;
+use crate::wire::WirePaint;
+use crate::{Dashboard, Theme};
+
+#[derive(Resource, Default, Debug, PartialEq, Clone, Copy)]
+pub enum DrawTool {
+    #[default]
+    Pen,
+    // click the start, click the end
+    Line,
+    Rect,
+    FilledRect,
+    // drop the stamp pattern centered on the click
+    Stamp,
+    // drag out a rectangle to copy or cut
+    Select,
+}
+
+impl std::fmt::Display for DrawTool {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let name = match self {
+            DrawTool::Pen => "pen",
+            DrawTool::Line => "line",
+            DrawTool::Rect => "rectangle",
+            DrawTool::FilledRect => "filled rectangle",
+            DrawTool::Stamp => "stamp",
+            DrawTool::Select => "select",
+        };
+        write!(f, "{name}")
+    }
+}
+
+// what the pen lays down around the cursor
+#[derive(Resource, Debug, Clone, Copy)]
+pub struct Brush {
+    // 1 is a single cell, each step adds a ring
+    pub radius: i32,
+    pub round: bool,
+}
+
+impl Default for Brush {
+    fn default() -> Self {
+        Brush {
+            radius: 1,
+            round: false,
+        }
+    }
+}
+
+impl std::fmt::Display for Brush {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let shape = if self.round { "round" } else { "square" };
+        write!(f, "{shape} brush {}", self.radius)
+    }
+}
+
+pub const MAX_BRUSH: i32 = 5;
+
+impl Brush {
+    pub fn cells(&self, (x, y): Coord) -> Vec<Coord> {
+        let reach = self.radius - 1;
+        let limit = (reach as f32 + 0.5).powi(2);
+        (-reach..=reach)
+            .flat_map(|dx| (-reach..=reach).map(move |dy| (dx, dy)))
+            .filter(|(dx, dy)| !self.round || (dx * dx + dy * dy) as f32 <= limit)
+            .map(|(dx, dy)| (x + dx, y + dy))
+            .collect()
+    }
+}
+
+#[test]
+fn test_brush_cells() {
+    let square = |radius| Brush {
+        radius,
+        round: false,
+    };
+    let round = |radius| Brush {
+        radius,
+        round: true,
+    };
+    assert_eq!(square(1).cells((3, 3)), vec![(3, 3)]);
+    assert_eq!(round(1).cells((3, 3)), vec![(3, 3)]);
+    assert_eq!(square(3).cells((0, 0)).len(), 25);
+    // the corners of the 5x5 get shaved off
+    let disk = round(3).cells((0, 0));
+    assert_eq!(disk.len(), 21);
+    assert!(!disk.contains(&(2, 2)) && disk.contains(&(2, 1)));
+}
+
+// what the stamp tool drops, as currently turned, and its name
+#[derive(Resource, Debug)]
+pub struct Stamp(pub Pattern, pub String);
+
+impl Stamp {
+    // the `--pattern` if it loads, a glider otherwise
+    pub fn new(config: &Config) -> Stamp {
+        let loaded = config.pattern.as_ref().and_then(|path| {
+            let pattern = load_pattern(path).ok()?;
+            let name = path.file_stem().map(|s| s.to_string_lossy().into_owned());
+            Some(Stamp(pattern, name.unwrap_or_default()))
+        });
+        loaded.unwrap_or_else(|| Stamp::builtin(0))
+    }
+
+    fn builtin(i: usize) -> Stamp {
+        let name = LIBRARY[i].0;
+        Stamp(builtin(name).unwrap(), name.to_string())
+    }
+}
+
+// S switches the stamp to the next pattern of the library (Shift+S the one
+// before) and picks the stamp tool; Ctrl+S drops it in the middle of the board
+#[allow(clippy::too_many_arguments)]
+pub fn pick_stamp(
+    mut commands: Commands,
+    keys: Res<Input<KeyCode>>,
+    config: Res<Config>,
+    db: Res<Dashboard>,
+    mut tool: ResMut<DrawTool>,
+    mut stamp: ResMut<Stamp>,
+    mut live: ResMut<LiveCells>,
+    mut cells: Query<&mut Cell>,
+) {
+    if !keys.just_pressed(KeyCode::S) {
+        return;
+    }
+    if keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]) {
+        let coords = place_pattern(&stamp.0, config.pattern_at, &config);
+        paint(
+            &coords,
+            true,
+            db.round,
+            &mut commands,
+            &mut live,
+            &mut cells,
+        );
+        return;
+    }
+    let step = if shift_held(&keys) {
+        LIBRARY.len() - 1
+    } else {
+        1
+    };
+    let next = LIBRARY
+        .iter()
+        .position(|(name, _)| *name == stamp.1)
+        .map_or(0, |i| (i + step) % LIBRARY.len());
+    *stamp = Stamp::builtin(next);
+    *tool = DrawTool::Stamp;
+}
+
+// everything the dashboard tells about drawing
+#[derive(SystemParam)]
+pub struct DrawState<'w> {
+    pub tool: Res<'w, DrawTool>,
+    pub stamp: Res<'w, Stamp>,
+    pub brush: Res<'w, Brush>,
+    pub symmetry: Res<'w, Symmetry>,
+    pub anchor: Res<'w, DrawAnchor>,
+    pub selection: Res<'w, Selection>,
+    pub wire: Res<'w, WirePaint>,
+}
+
+// first click of a two-click tool
+#[derive(Resource, Default, Debug)]
+pub struct DrawAnchor(pub Option<Coord>);
+
+// where a Shift-drag erase started, on the board
+#[derive(Resource, Default, Debug)]
+pub struct EraseDrag(pub Option<Coord>);
+
+fn shift_held(keys: &Input<KeyCode>) -> bool {
+    keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight])
+}
+
+// cells are `pitch` apart, see Theme::pitch, with the hex lattice's odd rows
+// shifted half a cell
+pub fn world_to_cell(world: Vec2, pitch: f32, topology: Topology) -> Coord {
+    let y = (world.y / pitch).round() as i32;
+    let x = (world.x / pitch - topology.row_shift(y)).round() as i32;
+    (x, y)
+}
+
+pub fn clamp_to_board((x, y): Coord, config: &Config) -> Coord {
+    let (xs, ys) = (config.x_range(), config.y_range());
+    (x.clamp(xs.start, xs.end - 1), y.clamp(ys.start, ys.end - 1))
+}
+
+// the cell under the mouse, whether or not it is on the board
+pub fn cursor_cell(
+    windows: &Query<&Window, With<PrimaryWindow>>,
+    cameras: &Query<(&Camera, &GlobalTransform)>,
+    pitch: f32,
+    topology: Topology,
+) -> Option<Coord> {
+    let cursor = windows.get_single().ok()?.cursor_position()?;
+    let (camera, transform) = cameras.get_single().ok()?;
+    let world = camera.viewport_to_world_2d(transform, cursor)?;
+    Some(world_to_cell(world, pitch, topology))
+}
+
+// Bresenham, both ends included
+pub fn line((mut x0, mut y0): Coord, (x1, y1): Coord) -> Vec<Coord> {
+    let dx = (x1 - x0).abs();
+    let dy = -(y1 - y0).abs();
+    let sx = if x0 < x1 { 1 } else { -1 };
+    let sy = if y0 < y1 { 1 } else { -1 };
+    let mut err = dx + dy;
+    let mut result = vec![];
+    loop {
+        result.push((x0, y0));
+        if x0 == x1 && y0 == y1 {
+            return result;
+        }
+        let e2 = 2 * err;
+        if e2 >= dy {
+            err += dy;
+            x0 += sx;
+        }
+        if e2 <= dx {
+            err += dx;
+            y0 += sy;
+        }
+    }
+}
+
+pub fn rect(a: Coord, b: Coord, filled: bool) -> Vec<Coord> {
+    let (x0, x1) = (a.0.min(b.0), a.0.max(b.0));
+    let (y0, y1) = (a.1.min(b.1), a.1.max(b.1));
+    (x0..=x1)
+        .flat_map(|x| (y0..=y1).map(move |y| (x, y)))
+        .filter(|(x, y)| filled || *x == x0 || *x == x1 || *y == y0 || *y == y1)
+        .collect()
+}
+
+#[test]
+fn test_line() {
+    assert_eq!(
+        line((0, 0), (5, 2)),
+        vec![(0, 0), (1, 0), (2, 1), (3, 1), (4, 2), (5, 2)]
+    );
+    assert_eq!(
+        line((2, 1), (2, -2)),
+        vec![(2, 1), (2, 0), (2, -1), (2, -2)]
+    );
+    assert_eq!(
+        line((0, 0), (-3, -3)),
+        vec![(0, 0), (-1, -1), (-2, -2), (-3, -3)]
+    );
+    assert_eq!(line((4, 4), (4, 4)), vec![(4, 4)]);
+    // both directions cover the same number of cells
+    assert_eq!(line((-7, 3), (6, -2)).len(), line((6, -2), (-7, 3)).len());
+}
+
+#[test]
+fn test_rect() {
+    assert_eq!(rect((0, 0), (2, 2), true).len(), 9);
+    let outline = rect((2, 2), (0, 0), false);
+    assert_eq!(outline.len(), 8);
+    assert!(!outline.contains(&(1, 1)));
+}
+
+#[test]
+fn test_clamp_to_board() {
+    let config = Config::default();
+    assert_eq!(clamp_to_board((30, -30), &config), (9, -10));
+    assert_eq!(clamp_to_board((3, 4), &config), (3, 4));
+}
+
+pub fn select_tool(
+    keys: Res<Input<KeyCode>>,
+    mut tool: ResMut<DrawTool>,
+    mut anchor: ResMut<DrawAnchor>,
+) {
+    // with a modifier the digits are camera bookmarks
+    let modifiers = [
+        KeyCode::ShiftLeft,
+        KeyCode::ShiftRight,
+        KeyCode::ControlLeft,
+        KeyCode::ControlRight,
+    ];
+    if keys.any_pressed(modifiers) {
+        return;
+    }
+    let picked = [
+        (KeyCode::Key1, DrawTool::Pen),
+        (KeyCode::Key2, DrawTool::Line),
+        (KeyCode::Key3, DrawTool::Rect),
+        (KeyCode::Key4, DrawTool::FilledRect),
+        (KeyCode::Key5, DrawTool::Stamp),
+        (KeyCode::Key6, DrawTool::Select),
+    ]
+    .into_iter()
+    .find(|(key, _)| keys.just_pressed(*key));
+    if let Some((_, t)) = picked {
+        *tool = t;
+        anchor.0 = None;
+    }
+}
+
+pub fn cycle_symmetry(keys: Res<Input<KeyCode>>, mut symmetry: ResMut<Symmetry>) {
+    if keys.just_pressed(KeyCode::Y) {
+        symmetry.cycle();
+    }
+}
+
+pub fn adjust_brush(keys: Res<Input<KeyCode>>, mut brush: ResMut<Brush>) {
+    if keys.just_pressed(KeyCode::BracketLeft) {
+        brush.radius = (brush.radius - 1).max(1);
+    }
+    if keys.just_pressed(KeyCode::BracketRight) {
+        brush.radius = (brush.radius + 1).min(MAX_BRUSH);
+    }
+    if keys.just_pressed(KeyCode::Backslash) {
+        brush.round = !brush.round;
+    }
+}
+
+// R turns the stamp a quarter clockwise, F mirrors it
+pub fn orient_stamp(keys: Res<Input<KeyCode>>, tool: Res<DrawTool>, mut stamp: ResMut<Stamp>) {
+    if *tool != DrawTool::Stamp {
+        return;
+    }
+    if keys.just_pressed(KeyCode::R) {
+        stamp.0 = stamp.0.rotate();
+    }
+    if keys.just_pressed(KeyCode::F) {
+        stamp.0 = stamp.0.flip();
+    }
+}
+
+pub fn cell_center((x, y): Coord, pitch: f32, topology: Topology) -> Vec2 {
+    Vec2::new((x as f32 + topology.row_shift(y)) * pitch, y as f32 * pitch)
+}
+
+#[test]
+fn test_clicks_follow_the_gap() {
+    // the middle of a cell and anywhere short of halfway into the gap hit it
+    for gap in [0.0, crate::GAP, 13.0] {
+        let pitch = crate::SIZE + gap;
+        for c in [(0, 0), (3, -7), (-9, 9)] {
+            for topology in [Topology::Square, Topology::Hex] {
+                let center = cell_center(c, pitch, topology);
+                assert_eq!(world_to_cell(center, pitch, topology), c);
+                let edge = center + Vec2::splat(0.49 * pitch);
+                assert_eq!(world_to_cell(edge, pitch, topology), c);
+            }
+        }
+    }
+    // with the old spacing far cells would be missed
+    let widened = cell_center((9, 0), crate::SIZE + 13.0, Topology::Square);
+    let narrow = crate::SIZE + crate::GAP;
+    assert_ne!(world_to_cell(widened, narrow, Topology::Square), (9, 0));
+}
+
+// outline of what the pen would paint under the cursor and at its mirror points
+#[allow(clippy::too_many_arguments)]
+pub fn preview_brush(
+    mut gizmos: Gizmos,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    config: Res<Config>,
+    theme: Res<Theme>,
+    tool: Res<DrawTool>,
+    brush: Res<Brush>,
+    symmetry: Res<Symmetry>,
+) {
+    if *tool != DrawTool::Pen {
+        return;
+    }
+    let Some(cursor) = cursor_cell(&windows, &cameras, theme.pitch(), config.topology) else {
+        return;
+    };
+    let extent = (2 * brush.radius - 1) as f32 * theme.pitch();
+    let mut points = symmetry.images(cursor, &config);
+    if points.is_empty() {
+        // off the board, nothing to mirror
+        points.push(cursor);
+    }
+    for (i, point) in points.into_iter().enumerate() {
+        let center = cell_center(point, theme.pitch(), config.topology);
+        // the mirror points a little fainter
+        let color = if i == 0 {
+            Color::YELLOW
+        } else {
+            Color::rgba(1.0, 1.0, 0.0, 0.5)
+        };
+        if brush.round {
+            gizmos.circle_2d(center, extent / 2.0, color);
+        } else {
+            gizmos.rect_2d(center, 0.0, Vec2::splat(extent), color);
+        }
+    }
+}
+
+// exactly the cells a click would set, already cut to the board, the mirror
+// images a little fainter
+#[allow(clippy::too_many_arguments)]
+pub fn preview_stamp(
+    mut gizmos: Gizmos,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    config: Res<Config>,
+    theme: Res<Theme>,
+    tool: Res<DrawTool>,
+    stamp: Res<Stamp>,
+    symmetry: Res<Symmetry>,
+) {
+    if *tool != DrawTool::Stamp {
+        return;
+    }
+    let Some(cursor) = cursor_cell(&windows, &cameras, theme.pitch(), config.topology) else {
+        return;
+    };
+    let placed = place_pattern(&stamp.0, cursor, &config);
+    let images: HashSet<_> = placed
+        .iter()
+        .flat_map(|c| symmetry.images(*c, &config))
+        .collect();
+    for c in &images {
+        let color = if placed.contains(c) {
+            Color::rgba(0.5, 0.9, 1.0, 0.6)
+        } else {
+            Color::rgba(0.5, 0.9, 1.0, 0.3)
+        };
+        gizmos.rect_2d(
+            cell_center(*c, theme.pitch(), config.topology),
+            0.0,
+            Vec2::splat(theme.size),
+            color,
+        );
+    }
+}
+
+// `change` on the cell at each of `coords`; with `spawn`, coords without an
+// entity, as dead cells have in sparse mode, get a dead one to change first
+pub fn change_cells(
+    coords: &HashSet<Coord>,
+    spawn: bool,
+    commands: &mut Commands,
+    cells: &mut Query<&mut Cell>,
+    mut change: impl FnMut(&mut Cell),
+) {
+    let mut missing = coords.clone();
+    for mut cell in cells.iter_mut() {
+        if missing.remove(&cell.index_xy) {
+            change(&mut cell);
+        }
+    }
+    if !spawn {
+        return;
+    }
+    for xy in missing {
+        let mut cell = Cell::new(xy, false);
+        change(&mut cell);
+        commands.spawn(cell);
+    }
+}
+
+// `round` is the birth round given to cells that come alive
+pub fn paint(
+    coords: &HashSet<Coord>,
+    alive: bool,
+    round: usize,
+    commands: &mut Commands,
+    live: &mut LiveCells,
+    cells: &mut Query<&mut Cell>,
+) {
+    change_cells(coords, alive, commands, cells, |cell| {
+        if cell.frozen {
+            return;
+        }
+        if alive && cell.state == State::Dead {
+            cell.born = round;
+        }
+        live.set(cell, alive);
+    });
+}
+
+// flips the frozen flag, dead cells without an entity getting one to freeze
+pub fn toggle_frozen(
+    coords: &HashSet<Coord>,
+    commands: &mut Commands,
+    cells: &mut Query<&mut Cell>,
+) {
+    change_cells(coords, true, commands, cells, |cell| {
+        cell.frozen = !cell.frozen;
+    });
+}
+
+#[test]
+fn test_freeze_sparse() {
+    let mut world = World::new();
+    // a sparse board: the live cell alone has an entity
+    world.spawn(Cell::new((0, 0), true));
+    let targets = HashSet::from([(0, 0), (1, 0)]);
+    let mut schedule = Schedule::default();
+    schedule.add_systems(move |mut commands: Commands, mut cells: Query<&mut Cell>| {
+        toggle_frozen(&targets, &mut commands, &mut cells);
+    });
+    schedule.run(&mut world);
+    let mut cells: Vec<_> = world.query::<&Cell>().iter(&world).cloned().collect();
+    cells.sort_by_key(|c| c.index_xy);
+    let frozen: Vec<_> = cells
+        .iter()
+        .map(|c| (c.index_xy, c.state.clone(), c.frozen))
+        .collect();
+    assert_eq!(
+        frozen,
+        [((0, 0), State::Alive, true), ((1, 0), State::Dead, true)]
+    );
+    // and back, no cell spawned twice
+    schedule.run(&mut world);
+    let cells: Vec<_> = world.query::<&Cell>().iter(&world).cloned().collect();
+    assert_eq!(cells.len(), 2);
+    assert!(cells.iter().all(|c| !c.frozen));
+}
+
+// with Ctrl held a click flips the frozen flag of whatever the tool covers instead
+#[allow(clippy::too_many_arguments)]
+pub fn draw(
+    mut commands: Commands,
+    keys: Res<Input<KeyCode>>,
+    buttons: Res<Input<MouseButton>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    config: Res<Config>,
+    theme: Res<Theme>,
+    db: Res<Dashboard>,
+    tool: Res<DrawTool>,
+    brush: Res<Brush>,
+    stamp: Res<Stamp>,
+    symmetry: Res<Symmetry>,
+    mut anchor: ResMut<DrawAnchor>,
+    timeline: Res<Timeline>,
+    mut live: ResMut<LiveCells>,
+    mut cells: Query<&mut Cell>,
+) {
+    // Shift-drags erase instead, whatever the tool
+    if timeline.scrubbing || shift_held(&keys) {
+        return;
+    }
+    let Some(cursor) = cursor_cell(&windows, &cameras, theme.pitch(), config.topology) else {
+        return;
+    };
+    let freeze = keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+    // the right button wipes with the pen brush, whatever the tool
+    let wiping = !freeze && buttons.pressed(MouseButton::Right);
+    let Some(targets) = stroke(
+        *tool,
+        cursor,
+        &buttons,
+        freeze,
+        wiping,
+        &brush,
+        &stamp,
+        &config,
+        &mut anchor,
+    ) else {
+        return;
+    };
+    // images() only keeps what's on the board
+    let targets: HashSet<_> = targets
+        .into_iter()
+        .flat_map(|c| symmetry.images(c, &config))
+        .collect();
+    if freeze {
+        toggle_frozen(&targets, &mut commands, &mut cells);
+        return;
+    }
+    paint(
+        &targets,
+        !wiping,
+        db.round,
+        &mut commands,
+        &mut live,
+        &mut cells,
+    );
+}
+
+// the cells the tool covers this frame, if it does anything
+#[allow(clippy::too_many_arguments)]
+pub fn stroke(
+    tool: DrawTool,
+    cursor: Coord,
+    buttons: &Input<MouseButton>,
+    freeze: bool,
+    wiping: bool,
+    brush: &Brush,
+    stamp: &Stamp,
+    config: &Config,
+    anchor: &mut DrawAnchor,
+) -> Option<Vec<Coord>> {
+    let targets = match tool {
+        _ if wiping => brush.cells(cursor),
+        // held down it would flip back and forth every frame
+        DrawTool::Pen if freeze && buttons.just_pressed(MouseButton::Left) => brush.cells(cursor),
+        DrawTool::Pen if !freeze && buttons.pressed(MouseButton::Left) => brush.cells(cursor),
+        DrawTool::Pen => return None,
+        DrawTool::Select => return None,
+        DrawTool::Stamp if buttons.just_pressed(MouseButton::Left) => {
+            place_pattern(&stamp.0, cursor, config)
+                .into_iter()
+                .collect()
+        }
+        _ if !buttons.just_pressed(MouseButton::Left) => return None,
+        two_click => {
+            let end = clamp_to_board(cursor, config);
+            let Some(start) = anchor.0.take() else {
+                anchor.0 = Some(end);
+                return None;
+            };
+            match two_click {
+                DrawTool::Line => line(start, end),
+                DrawTool::Rect => rect(start, end, false),
+                _ => rect(start, end, true),
+            }
+        }
+    };
+    Some(targets)
+}
+
+// Shift and drag kills everything in the rectangle on release, corners clamped to
+// the board so a drag off the edge clears up to it; a plain click takes one cell
+#[allow(clippy::too_many_arguments)]
+pub fn erase_rect(
+    mut commands: Commands,
+    keys: Res<Input<KeyCode>>,
+    buttons: Res<Input<MouseButton>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    config: Res<Config>,
+    theme: Res<Theme>,
+    db: Res<Dashboard>,
+    timeline: Res<Timeline>,
+    mut drag: ResMut<EraseDrag>,
+    mut live: ResMut<LiveCells>,
+    mut cells: Query<&mut Cell>,
+) {
+    if timeline.scrubbing {
+        drag.0 = None;
+        return;
+    }
+    let cursor = cursor_cell(&windows, &cameras, theme.pitch(), config.topology)
+        .map(|c| clamp_to_board(c, &config));
+    if buttons.just_pressed(MouseButton::Left) && shift_held(&keys) {
+        drag.0 = cursor;
+    }
+    if !buttons.just_released(MouseButton::Left) {
+        return;
+    }
+    let (Some(start), Some(end)) = (drag.0.take(), cursor) else {
+        return;
+    };
+    let targets = rect(start, end, true).into_iter().collect();
+    paint(
+        &targets,
+        false,
+        db.round,
+        &mut commands,
+        &mut live,
+        &mut cells,
+    );
+}
+
+// the rectangle a release would clear, while the drag is on
+pub fn preview_erase(
+    mut gizmos: Gizmos,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    config: Res<Config>,
+    theme: Res<Theme>,
+    drag: Res<EraseDrag>,
+) {
+    let Some(start) = drag.0 else {
+        return;
+    };
+    let Some(cursor) = cursor_cell(&windows, &cameras, theme.pitch(), config.topology) else {
+        return;
+    };
+    let end = clamp_to_board(cursor, &config);
+    let (low, high) = (
+        cell_center(start, theme.pitch(), config.topology).min(cell_center(
+            end,
+            theme.pitch(),
+            config.topology,
+        )),
+        cell_center(start, theme.pitch(), config.topology).max(cell_center(
+            end,
+            theme.pitch(),
+            config.topology,
+        )),
+    );
+    gizmos.rect_2d(
+        (low + high) / 2.0,
+        0.0,
+        high - low + Vec2::splat(theme.size),
+        Color::rgba(1.0, 0.3, 0.3, 0.8),
+    );
+}
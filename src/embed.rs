@@ -0,0 +1,96 @@
+use bevy::prelude::*;
+
+use std::time::Duration;
+
+use crate::{Cell, Config, Rule, SimPlugin, State};
+
+// how the embedded board looks: a square sprite per cell, `size` world units
+// apart around `origin`, at depth `z` so it can sit behind a game
+#[derive(Resource, Debug, Clone)]
+pub struct Palette {
+    pub alive: Color,
+    pub dead: Color,
+    pub size: f32,
+    pub origin: Vec2,
+    pub z: f32,
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Palette {
+            alive: Color::WHITE,
+            dead: Color::rgba(0.0, 0.0, 0.0, 0.0),
+            size: 20.0,
+            origin: Vec2::ZERO,
+            z: -10.0,
+        }
+    }
+}
+
+// the sim plus the plainest rendering, to drop into another Bevy app:
+//
+//     app.add_plugins(GameOfLifePlugin {
+//         config: Config { width: 64, height: 36, sparse: true, ..default() },
+//         ..default()
+//     });
+//
+// the generations run in FixedUpdate, so `tick`, when set, becomes the app's
+// fixed timestep; the app brings its own camera
+#[derive(Default)]
+pub struct GameOfLifePlugin {
+    pub config: Config,
+    pub rule: Rule,
+    pub tick: Option<Duration>,
+    pub palette: Palette,
+}
+
+impl Plugin for GameOfLifePlugin {
+    fn build(&self, app: &mut App) {
+        if let Some(tick) = self.tick {
+            app.insert_resource(FixedTime::new(tick));
+        }
+        app.insert_resource(self.config.clone())
+            .insert_resource(self.rule.clone())
+            .insert_resource(self.palette.clone())
+            .add_plugins(SimPlugin)
+            .add_systems(Update, (dress_embedded_cells, color_embedded_cells).chain());
+    }
+}
+
+fn cell_color(cell: &Cell, palette: &Palette) -> Color {
+    match cell.state {
+        State::Alive => palette.alive,
+        State::Wire => palette.alive.with_a(0.5),
+        _ => palette.dead,
+    }
+}
+
+// the sim spawns bare cells, they get a sprite the frame they show up
+fn dress_embedded_cells(
+    mut commands: Commands,
+    palette: Res<Palette>,
+    cells: Query<(Entity, &Cell), Added<Cell>>,
+) {
+    for (entity, cell) in cells.iter() {
+        let (x, y) = cell.index_xy;
+        let at = palette.origin + Vec2::new(x as f32, y as f32) * palette.size;
+        commands.entity(entity).insert(SpriteBundle {
+            sprite: Sprite {
+                color: cell_color(cell, &palette),
+                custom_size: Some(Vec2::splat(palette.size)),
+                ..Default::default()
+            },
+            transform: Transform::from_translation(at.extend(palette.z)),
+            ..Default::default()
+        });
+    }
+}
+
+fn color_embedded_cells(
+    palette: Res<Palette>,
+    mut cells: Query<(&mut Sprite, &Cell), Changed<Cell>>,
+) {
+    for (mut sprite, cell) in cells.iter_mut() {
+        sprite.color = cell_color(cell, &palette);
+    }
+}
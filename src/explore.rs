@@ -0,0 +1,268 @@
+use bevy::prelude::*;
+use bevy_game_of_life::{Cell, Config, Dashboard, LiveCells, PeriodDetector, Rule, SimRng};
+use rand::Rng;
+
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+
+use crate::controls::reseed;
+
+// how a rule's run on the board ended
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Ending {
+    DiedOut,
+    // the stagnation detector caught it repeating with this period
+    Settled(usize),
+    StillGoing,
+}
+
+// one rule's go, from the mutation that made it to the next
+#[derive(Debug, Clone)]
+pub struct Trial {
+    pub rule: Rule,
+    pub ending: Ending,
+    // generations before it ended, or all of them when it didn't
+    pub lived: usize,
+    pub peak: usize,
+}
+
+impl Trial {
+    // still going beats any ending, then the longer the better, then an
+    // oscillator over a still life
+    pub fn fitness(&self) -> (bool, usize, bool) {
+        let oscillates = matches!(self.ending, Ending::Settled(p) if p > 1);
+        (self.ending == Ending::StillGoing, self.lived, oscillates)
+    }
+}
+
+impl std::fmt::Display for Trial {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let (rule, lived, peak) = (&self.rule, self.lived, self.peak);
+        match self.ending {
+            Ending::DiedOut => write!(f, "{rule}: died out after {lived}"),
+            Ending::Settled(p) => write!(f, "{rule}: settled after {lived}, period {p}"),
+            Ending::StillGoing => write!(f, "{rule}: still going after {lived}"),
+        }?;
+        write!(f, ", peak {peak}")
+    }
+}
+
+// M: every `every` generations, or as soon as the board dies or settles, or
+// on Shift+M, the rule it ran under is scored and the fittest so far is
+// mutated for the next; with `reseed` a fresh soup each time, otherwise the
+// board carries on, unless there's nothing left of it
+#[derive(Resource, Debug)]
+pub struct Explorer {
+    pub enabled: bool,
+    every: usize,
+    reseed: bool,
+    log: Option<File>,
+    // the rule the next mutation starts from, and how it did
+    parent: Option<(Rule, (bool, usize, bool))>,
+    // the round the current rule took over at, and its most cells
+    started: usize,
+    peak: usize,
+    last_round: usize,
+    requested: bool,
+}
+
+impl Explorer {
+    pub fn new(every: usize, reseed: bool, log: Option<&Path>) -> std::io::Result<Explorer> {
+        let log = match log {
+            Some(path) => Some(File::options().create(true).append(true).open(path)?),
+            None => None,
+        };
+        Ok(Explorer {
+            enabled: false,
+            every,
+            reseed,
+            log,
+            parent: None,
+            started: 0,
+            peak: 0,
+            last_round: 0,
+            requested: false,
+        })
+    }
+
+    // the rule to try next: a step off this one if it did at least as well
+    // as the parent, otherwise another off the parent
+    pub fn next_rule(&mut self, trial: &Trial, rng: &mut impl Rng) -> Rule {
+        let fitness = trial.fitness();
+        if self
+            .parent
+            .as_ref()
+            .is_none_or(|(_, best)| fitness >= *best)
+        {
+            self.parent = Some((trial.rule.clone(), fitness));
+        }
+        let mut rule = self.parent.as_ref().map(|(r, _)| r.clone()).unwrap();
+        rule.mutate(rng);
+        rule
+    }
+
+    // next_rule, for the plain life-like rules the mutation knows, as long as
+    // the board runs what it comes up with
+    pub fn next_runnable(
+        &mut self,
+        trial: &Trial,
+        config: &Config,
+        rng: &mut impl Rng,
+    ) -> Result<Rule, String> {
+        if !trial.rule.life_like() {
+            return Err(format!(
+                "{} has no birth and survival digits to mutate",
+                trial.rule
+            ));
+        }
+        let rule = self.next_rule(trial, rng);
+        config.runs(&rule)?;
+        Ok(rule)
+    }
+}
+
+#[test]
+fn test_explorer() {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    let mut rng = StdRng::seed_from_u64(3);
+    let mut explorer = Explorer::new(50, false, None).unwrap();
+    let trial = |rule: &str, ending, lived| Trial {
+        rule: Rule::parse(rule).unwrap(),
+        ending,
+        lived,
+        peak: 10,
+    };
+    let differences = |a: &Rule, b: &Rule| {
+        let digits = a.birth.iter().zip(&b.birth);
+        digits
+            .chain(a.survival.iter().zip(&b.survival))
+            .filter(|(x, y)| x != y)
+            .count()
+    };
+    let life = trial("B3/S23", Ending::StillGoing, 50);
+    assert!(life.fitness() > trial("B3/S23", Ending::Settled(2), 49).fitness());
+    assert!(
+        trial("B3/S", Ending::Settled(2), 9).fitness()
+            > trial("B3/S", Ending::Settled(1), 9).fitness()
+    );
+    assert!(
+        trial("B3/S", Ending::DiedOut, 9).fitness() < trial("B3/S", Ending::DiedOut, 10).fitness()
+    );
+
+    let next = explorer.next_rule(&life, &mut rng);
+    assert_eq!(differences(&next, &life.rule), 1);
+    // a worse rule is dropped, the next one is a step off Life again
+    let worse = trial(&next.to_string(), Ending::DiedOut, 3);
+    let again = explorer.next_rule(&worse, &mut rng);
+    assert_eq!(differences(&again, &life.rule), 1);
+    assert!(life.to_string().contains("still going after 50"));
+    let config = Config::default();
+    assert!(explorer.next_runnable(&life, &config, &mut rng).is_ok());
+    for rule in ["wireworld", "bugs", "immigration", "brianbrain"] {
+        let trial = Trial {
+            rule: Rule::named(rule).unwrap(),
+            ..life.clone()
+        };
+        assert!(explorer.next_runnable(&trial, &config, &mut rng).is_err());
+    }
+}
+
+pub fn toggle_explore(
+    keys: Res<Input<KeyCode>>,
+    rule: Res<Rule>,
+    db: Res<Dashboard>,
+    live: Res<LiveCells>,
+    mut explorer: ResMut<Explorer>,
+) {
+    if !keys.just_pressed(KeyCode::M) {
+        return;
+    }
+    if keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]) {
+        explorer.requested = explorer.enabled;
+        return;
+    }
+    if !explorer.enabled && !rule.life_like() {
+        warn!("only life-like rules are explored, not {}", *rule);
+        return;
+    }
+    explorer.enabled = !explorer.enabled;
+    // the rule on the board gets its go from here
+    explorer.started = db.round;
+    explorer.last_round = db.round;
+    explorer.peak = live.0.len();
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn explore(
+    mut commands: Commands,
+    config: Res<Config>,
+    mut db: ResMut<Dashboard>,
+    mut explorer: ResMut<Explorer>,
+    mut rule: ResMut<Rule>,
+    mut rng: ResMut<SimRng>,
+    mut detector: ResMut<PeriodDetector>,
+    mut live: ResMut<LiveCells>,
+    mut cells: Query<(Entity, &mut Cell)>,
+) {
+    if !explorer.enabled || db.round == explorer.last_round && !explorer.requested {
+        return;
+    }
+    explorer.last_round = db.round;
+    explorer.peak = explorer.peak.max(live.0.len());
+    // a reseed or a jump back puts the board behind where the trial started
+    explorer.started = explorer.started.min(db.round);
+    let lived = db.round - explorer.started;
+    let ending = match db.settled {
+        _ if live.0.is_empty() => Ending::DiedOut,
+        Some((_, period)) => Ending::Settled(period),
+        None if lived >= explorer.every || explorer.requested => Ending::StillGoing,
+        None => return,
+    };
+    let trial = Trial {
+        rule: rule.clone(),
+        ending,
+        // a cycle counts from where it started
+        lived: match (ending, db.settled) {
+            (Ending::Settled(_), Some((since, _))) => since.saturating_sub(explorer.started),
+            _ => lived,
+        },
+        peak: explorer.peak,
+    };
+    info!("round {}: {trial}", db.round);
+    if let Some(log) = explorer.log.as_mut() {
+        if let Err(e) = writeln!(log, "{trial}") {
+            warn!("couldn't log the trial: {e}");
+        }
+    }
+    // a rule of another kind switched to since, or one the board refuses
+    match explorer.next_runnable(&trial, &config, &mut rng.0) {
+        Ok(next) => *rule = next,
+        Err(e) => {
+            warn!("{e}, exploring stops");
+            explorer.enabled = false;
+            return;
+        }
+    }
+    info!("round {}: trying {}", db.round, *rule);
+    if explorer.reseed || live.0.is_empty() {
+        reseed(
+            &config,
+            &mut rng,
+            &mut db,
+            &mut detector,
+            &mut commands,
+            &mut live,
+            &mut cells,
+        );
+    } else {
+        // the detector's history was made under the old rule
+        *detector = PeriodDetector::default();
+        db.settled = None;
+    }
+    explorer.started = db.round;
+    explorer.last_round = db.round;
+    explorer.peak = live.0.len();
+    explorer.requested = false;
+}
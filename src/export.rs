@@ -0,0 +1,111 @@
+use bevy::prelude::*;
+use bevy_game_of_life::pattern::{to_cells, to_life106, to_rle, Pattern};
+use bevy_game_of_life::{bounding_box, Coord, LiveCells, Rule};
+
+use std::collections::HashSet;
+use std::fmt::Write;
+
+use crate::{Dashboard, Theme};
+
+fn hex(color: Color) -> String {
+    let [r, g, b, _] = color.as_rgba_u8();
+    format!("#{r:02x}{g:02x}{b:02x}")
+}
+
+// one square per live cell, cropped to the live bounding box with a gap wide margin
+pub fn to_svg(alives: &HashSet<Coord>, theme: &Theme) -> String {
+    let ((min_x, min_y), (max_x, max_y)) = bounding_box(alives).unwrap_or(((0, 0), (0, 0)));
+    let (gap, pitch, size) = (theme.gap, theme.pitch(), theme.size);
+    let width = gap + (max_x - min_x + 1) as f32 * pitch;
+    let height = gap + (max_y - min_y + 1) as f32 * pitch;
+
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" \
+         viewBox=\"0 0 {width} {height}\">\n\
+         <rect width=\"100%\" height=\"100%\" fill=\"{}\"/>\n",
+        hex(theme.background)
+    );
+    let mut sorted: Vec<_> = alives.iter().collect();
+    sorted.sort();
+    for (x, y) in sorted {
+        // svg rows grow downwards
+        let left = gap + (x - min_x) as f32 * pitch;
+        let top = gap + (max_y - y) as f32 * pitch;
+        writeln!(
+            svg,
+            "<rect x=\"{left}\" y=\"{top}\" width=\"{size}\" height=\"{size}\" fill=\"{}\"/>",
+            hex(theme.alive)
+        )
+        .unwrap();
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+#[test]
+fn test_to_svg() {
+    use crate::{GAP, SIZE};
+
+    let theme = Theme::default();
+    let svg = to_svg(&HashSet::from([(-1, 2), (1, 2), (0, 1)]), &theme);
+    assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+    assert!(svg.trim_end().ends_with("</svg>"));
+    // background plus one square per cell
+    assert_eq!(svg.matches("<rect").count(), 4);
+    // 3 by 2 cells, nothing more
+    let (w, h) = (GAP + 3.0 * (SIZE + GAP), GAP + 2.0 * (SIZE + GAP));
+    assert!(svg.contains(&format!("viewBox=\"0 0 {w} {h}\"")));
+    // the top-left cell sits right at the margin
+    assert!(svg.contains(&format!("<rect x=\"{GAP}\" y=\"{GAP}\"")));
+}
+
+pub fn export_svg(
+    keys: Res<Input<KeyCode>>,
+    live: Res<LiveCells>,
+    theme: Res<Theme>,
+    db: Res<Dashboard>,
+) {
+    let shift = keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    if !(shift && keys.just_pressed(KeyCode::E)) {
+        return;
+    }
+    let path = format!("board-{}.svg", db.round);
+    match std::fs::write(&path, to_svg(&live.0, &theme)) {
+        Ok(()) => info!("exported {path}"),
+        Err(e) => error!("can't write {path}: {e}"),
+    }
+}
+
+// E alone writes the live cells as board-<round>.rle, .cells and .lif, for
+// Golly, LifeWiki and the like; Shift and Ctrl+E are the other exports
+pub fn export_pattern(
+    keys: Res<Input<KeyCode>>,
+    live: Res<LiveCells>,
+    rule: Res<Rule>,
+    db: Res<Dashboard>,
+) {
+    let modified = keys.any_pressed([
+        KeyCode::ShiftLeft,
+        KeyCode::ShiftRight,
+        KeyCode::ControlLeft,
+        KeyCode::ControlRight,
+    ]);
+    if modified || !keys.just_pressed(KeyCode::E) {
+        return;
+    }
+    let pattern = Pattern::from_board(&live.0);
+    let files = [
+        (format!("board-{}.rle", db.round), to_rle(&pattern, &rule)),
+        (
+            format!("board-{}.cells", db.round),
+            to_cells(&pattern, &rule),
+        ),
+        (format!("board-{}.lif", db.round), to_life106(&pattern)),
+    ];
+    for (path, text) in files {
+        match std::fs::write(&path, text) {
+            Ok(()) => info!("exported {path}"),
+            Err(e) => error!("can't write {path}: {e}"),
+        }
+    }
+}
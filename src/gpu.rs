@@ -0,0 +1,402 @@
+use bevy::asset::load_internal_asset;
+use bevy::prelude::*;
+use bevy::reflect::TypeUuid;
+use bevy::render::extract_resource::{ExtractResource, ExtractResourcePlugin};
+use bevy::render::render_asset::RenderAssets;
+use bevy::render::render_graph::{self, RenderGraph};
+use bevy::render::render_resource::*;
+use bevy::render::renderer::{RenderContext, RenderDevice};
+use bevy::render::texture::ImageSampler;
+use bevy::render::{Render, RenderApp, RenderSet};
+use bevy_game_of_life::{Boundary, Config, Coord, Dashboard, EdgeMode, LiveCells, Rule};
+
+use std::borrow::Cow;
+use std::collections::HashSet;
+
+use crate::Theme;
+
+const LIFE_SHADER: HandleUntyped =
+    HandleUntyped::weak_from_u64(Shader::TYPE_UUID, 0x6c69_6665_5f67_7075);
+// matches @workgroup_size in life.wgsl
+const WORKGROUP_SIZE: u32 = 8;
+
+// the board as two textures: generation n is in images[n % 2], and a tick
+// runs the shader from that one into the other
+#[derive(Resource, Clone, ExtractResource)]
+pub struct GpuBoard {
+    images: [Handle<Image>; 2],
+    generation: u64,
+    size: (u32, u32),
+    // the shader's uniform, as laid out in life.wgsl
+    params: Vec<u8>,
+}
+
+#[derive(Component)]
+pub struct GpuLayer;
+
+// Params in life.wgsl: the rule as bit masks, which axes wrap, the size and
+// the live color; mirrored and open edges count as dead there
+fn shader_params(rule: &Rule, edge: EdgeMode, size: (u32, u32), alive: Color) -> Vec<u8> {
+    let mask = |set: &[bool; 9]| (0..9).filter(|n| set[*n]).fold(0u32, |m, n| m | 1 << n);
+    let wraps = |b: Boundary| (b == Boundary::Wrap) as u32;
+    let ints = [
+        mask(&rule.birth),
+        mask(&rule.survival),
+        wraps(edge.x),
+        wraps(edge.y),
+        size.0,
+        size.1,
+        // the color is 16-byte aligned
+        0,
+        0,
+    ];
+    ints.iter()
+        .flat_map(|i| i.to_le_bytes())
+        .chain(
+            alive
+                .as_linear_rgba_f32()
+                .iter()
+                .flat_map(|f| f.to_le_bytes()),
+        )
+        .collect()
+}
+
+// a texel per cell, rows from the top, alive ones opaque `alive`
+fn board_pixels(config: &Config, alives: &HashSet<Coord>, alive: Color) -> Vec<u8> {
+    let (xs, ys) = (config.x_range(), config.y_range());
+    let mut data = vec![0; (config.width * config.height * 4) as usize];
+    let texel = alive
+        .as_linear_rgba_f32()
+        .map(|c| (c * 255.0).round() as u8);
+    for &(x, y) in alives.iter().filter(|c| config.contains(**c)) {
+        let i = ((ys.end - 1 - y) * config.width + x - xs.start) as usize * 4;
+        data[i..i + 4].copy_from_slice(&texel);
+    }
+    data
+}
+
+#[test]
+fn test_shader_params() {
+    let highlife = Rule::parse("B36/S23").unwrap();
+    let params = shader_params(
+        &highlife,
+        EdgeMode::wrapping(true, false),
+        (7, 5),
+        Color::WHITE,
+    );
+    let int = |i: usize| u32::from_le_bytes(params[i * 4..i * 4 + 4].try_into().unwrap());
+    assert_eq!(params.len(), 48);
+    assert_eq!((int(0), int(1)), (0b100_1000, 0b1100));
+    assert_eq!((int(2), int(3), int(4), int(5)), (1, 0, 7, 5));
+    assert_eq!(f32::from_le_bytes(params[44..48].try_into().unwrap()), 1.0);
+
+    let config = Config {
+        width: 3,
+        height: 2,
+        ..Default::default()
+    };
+    // (-1, 0) is the top left of a 3x2 board
+    let pixels = board_pixels(&config, &HashSet::from([(-1, 0), (5, 5)]), Color::WHITE);
+    assert_eq!(pixels.len(), 24);
+    assert_eq!(pixels[..4], [255; 4]);
+    assert_eq!(pixels.iter().filter(|p| **p > 0).count(), 4);
+}
+
+fn board_image(config: &Config, data: Vec<u8>) -> Image {
+    let mut image = Image::new(
+        Extent3d {
+            width: config.width as u32,
+            height: config.height as u32,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8Unorm,
+    );
+    image.texture_descriptor.usage =
+        TextureUsages::COPY_DST | TextureUsages::STORAGE_BINDING | TextureUsages::TEXTURE_BINDING;
+    // crisp cells, however far zoomed in
+    image.sampler_descriptor = ImageSampler::nearest();
+    image
+}
+
+// the seeded cells into the first texture, laid over a board of dead color
+pub fn spawn_gpu_board(
+    mut commands: Commands,
+    config: Res<Config>,
+    theme: Res<Theme>,
+    rule: Res<Rule>,
+    edge: Res<EdgeMode>,
+    live: Res<LiveCells>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    let pixels = board_pixels(&config, &live.0, theme.alive);
+    let blank = vec![0; pixels.len()];
+    let images = [pixels, blank].map(|data| images.add(board_image(&config, data)));
+    let pitch = theme.pitch();
+    let (xs, ys) = (config.x_range(), config.y_range());
+    let center = Vec2::new(
+        (xs.start + xs.end - 1) as f32 / 2.0,
+        (ys.start + ys.end - 1) as f32 / 2.0,
+    ) * pitch;
+    let size = Vec2::new(config.width as f32, config.height as f32) * pitch;
+    commands.spawn(SpriteBundle {
+        sprite: Sprite {
+            color: theme.dead,
+            custom_size: Some(size),
+            ..Default::default()
+        },
+        transform: Transform::from_translation(center.extend(0.0)),
+        ..Default::default()
+    });
+    commands.spawn((
+        GpuLayer,
+        SpriteBundle {
+            sprite: Sprite {
+                custom_size: Some(size),
+                ..Default::default()
+            },
+            texture: images[0].clone(),
+            transform: Transform::from_translation(center.extend(0.1)),
+            ..Default::default()
+        },
+    ));
+    let size = (config.width as u32, config.height as u32);
+    commands.insert_resource(GpuBoard {
+        images,
+        generation: 0,
+        size,
+        params: shader_params(&rule, *edge, size, theme.alive),
+    });
+}
+
+// a generation a tick while running; the render world catches up on its frame
+pub fn tick_gpu_board(mut board: ResMut<GpuBoard>, mut db: ResMut<Dashboard>) {
+    board.generation += 1;
+    db.round += 1;
+}
+
+// the sprite on the texture the latest generation went into, and the rule,
+// edges and color as they are now
+pub fn show_gpu_board(
+    config: Res<Config>,
+    rule: Res<Rule>,
+    edge: Res<EdgeMode>,
+    theme: Res<Theme>,
+    board: Option<ResMut<GpuBoard>>,
+    mut layers: Query<&mut Handle<Image>, With<GpuLayer>>,
+) {
+    let Some(mut board) = board else {
+        return;
+    };
+    // whatever picked it, a rule the shader can't run isn't uploaded
+    if rule.is_changed() || edge.is_changed() || theme.is_changed() {
+        match config.runs(&rule) {
+            Ok(()) => board.params = shader_params(&rule, *edge, board.size, theme.alive),
+            Err(e) => warn!("{e}, the GPU board keeps its rule"),
+        }
+    }
+    let current = &board.images[(board.generation % 2) as usize];
+    for mut texture in layers.iter_mut() {
+        if *texture != *current {
+            *texture = current.clone();
+        }
+    }
+}
+
+pub struct GpuPlugin;
+
+impl Plugin for GpuPlugin {
+    fn build(&self, app: &mut App) {
+        load_internal_asset!(app, LIFE_SHADER, "life.wgsl", Shader::from_wgsl);
+        app.add_plugins(ExtractResourcePlugin::<GpuBoard>::default())
+            .add_systems(Update, show_gpu_board);
+        let render_app = app.sub_app_mut(RenderApp);
+        render_app.add_systems(Render, queue_bind_groups.in_set(RenderSet::Queue));
+        let mut graph = render_app.world.resource_mut::<RenderGraph>();
+        graph.add_node("game_of_life", LifeNode::default());
+        graph.add_node_edge(
+            "game_of_life",
+            bevy::render::main_graph::node::CAMERA_DRIVER,
+        );
+    }
+
+    fn finish(&self, app: &mut App) {
+        app.sub_app_mut(RenderApp).init_resource::<LifePipeline>();
+    }
+}
+
+#[derive(Resource)]
+struct LifePipeline {
+    layout: BindGroupLayout,
+    update: CachedComputePipelineId,
+}
+
+impl FromWorld for LifePipeline {
+    fn from_world(world: &mut World) -> Self {
+        let entry = |binding, ty| BindGroupLayoutEntry {
+            binding,
+            visibility: ShaderStages::COMPUTE,
+            ty,
+            count: None,
+        };
+        let layout =
+            world
+                .resource::<RenderDevice>()
+                .create_bind_group_layout(&BindGroupLayoutDescriptor {
+                    label: Some("life_layout"),
+                    entries: &[
+                        entry(
+                            0,
+                            BindingType::Texture {
+                                sample_type: TextureSampleType::Float { filterable: false },
+                                view_dimension: TextureViewDimension::D2,
+                                multisampled: false,
+                            },
+                        ),
+                        entry(
+                            1,
+                            BindingType::StorageTexture {
+                                access: StorageTextureAccess::WriteOnly,
+                                format: TextureFormat::Rgba8Unorm,
+                                view_dimension: TextureViewDimension::D2,
+                            },
+                        ),
+                        entry(
+                            2,
+                            BindingType::Buffer {
+                                ty: BufferBindingType::Uniform,
+                                has_dynamic_offset: false,
+                                min_binding_size: None,
+                            },
+                        ),
+                    ],
+                });
+        let update =
+            world
+                .resource::<PipelineCache>()
+                .queue_compute_pipeline(ComputePipelineDescriptor {
+                    label: Some(Cow::from("life_update")),
+                    layout: vec![layout.clone()],
+                    push_constant_ranges: vec![],
+                    shader: LIFE_SHADER.typed(),
+                    shader_defs: vec![],
+                    entry_point: Cow::from("update"),
+                });
+        LifePipeline { layout, update }
+    }
+}
+
+// [from images[0] into images[1], the other way round]
+#[derive(Resource)]
+struct LifeBindGroups([BindGroup; 2]);
+
+fn queue_bind_groups(
+    mut commands: Commands,
+    pipeline: Res<LifePipeline>,
+    gpu_images: Res<RenderAssets<Image>>,
+    board: Option<Res<GpuBoard>>,
+    device: Res<RenderDevice>,
+) {
+    let Some(board) = board else {
+        return;
+    };
+    let (Some(a), Some(b)) = (
+        gpu_images.get(&board.images[0]),
+        gpu_images.get(&board.images[1]),
+    ) else {
+        return;
+    };
+    let params = device.create_buffer_with_data(&BufferInitDescriptor {
+        label: Some("life_params"),
+        contents: &board.params,
+        usage: BufferUsages::UNIFORM,
+    });
+    let group = |from: &TextureView, to: &TextureView| {
+        device.create_bind_group(&BindGroupDescriptor {
+            label: Some("life_bind_group"),
+            layout: &pipeline.layout,
+            entries: &[
+                BindGroupEntry {
+                    binding: 0,
+                    resource: BindingResource::TextureView(from),
+                },
+                BindGroupEntry {
+                    binding: 1,
+                    resource: BindingResource::TextureView(to),
+                },
+                BindGroupEntry {
+                    binding: 2,
+                    resource: params.as_entire_binding(),
+                },
+            ],
+        })
+    };
+    commands.insert_resource(LifeBindGroups([
+        group(&a.texture_view, &b.texture_view),
+        group(&b.texture_view, &a.texture_view),
+    ]));
+}
+
+// the generations the main world ticked since the last frame, one dispatch
+// each, alternating between the textures
+#[derive(Default)]
+struct LifeNode {
+    // generations the textures are at, and the ones this frame runs
+    done: u64,
+    steps: std::ops::Range<u64>,
+}
+
+impl render_graph::Node for LifeNode {
+    fn update(&mut self, world: &mut World) {
+        self.steps = 0..0;
+        let ready = world
+            .get_resource::<LifePipeline>()
+            .map(|p| {
+                world
+                    .resource::<PipelineCache>()
+                    .get_compute_pipeline(p.update)
+            })
+            .is_some_and(|p| p.is_some());
+        let Some(board) = world.get_resource::<GpuBoard>() else {
+            return;
+        };
+        // until the shader compiles, the ticks wait
+        if ready && world.contains_resource::<LifeBindGroups>() {
+            self.steps = self.done..board.generation;
+            self.done = board.generation;
+        }
+    }
+
+    fn run(
+        &self,
+        _graph: &mut render_graph::RenderGraphContext,
+        render_context: &mut RenderContext,
+        world: &World,
+    ) -> Result<(), render_graph::NodeRunError> {
+        if self.steps.is_empty() {
+            return Ok(());
+        }
+        let (pipeline, groups, board) = (
+            world.resource::<LifePipeline>(),
+            world.resource::<LifeBindGroups>(),
+            world.resource::<GpuBoard>(),
+        );
+        let Some(update) = world
+            .resource::<PipelineCache>()
+            .get_compute_pipeline(pipeline.update)
+        else {
+            return Ok(());
+        };
+        let mut pass = render_context
+            .command_encoder()
+            .begin_compute_pass(&ComputePassDescriptor::default());
+        pass.set_pipeline(update);
+        let (w, h) = board.size;
+        for generation in self.steps.clone() {
+            pass.set_bind_group(0, &groups.0[(generation % 2) as usize], &[]);
+            pass.dispatch_workgroups(w.div_ceil(WORKGROUP_SIZE), h.div_ceil(WORKGROUP_SIZE), 1);
+        }
+        Ok(())
+    }
+}
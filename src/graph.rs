@@ -0,0 +1,135 @@
+use bevy::prelude::*;
+
+use std::collections::VecDeque;
+
+use crate::Dashboard;
+
+// generations the graph goes back
+pub const GRAPH_LEN: usize = 300;
+// size of the chart in logical pixels, and its distance from the window corner
+const CHART: Vec2 = Vec2::new(300.0, 120.0);
+const MARGIN: f32 = 10.0;
+
+// population, births and deaths of the last GRAPH_LEN generations, drawn in the
+// bottom-left corner while A is on; starts over whenever the board goes back
+// to an earlier round
+#[derive(Resource, Default, Debug)]
+pub struct PopulationGraph {
+    pub enabled: bool,
+    // (round, population, births, deaths), newest at the back
+    samples: VecDeque<(usize, usize, usize, usize)>,
+}
+
+impl PopulationGraph {
+    fn record(&mut self, db: &Dashboard) {
+        match self.samples.back() {
+            Some(&(last, ..)) if db.round == last => return,
+            Some(&(last, ..)) if db.round < last => self.samples.clear(),
+            _ => {}
+        }
+        self.samples
+            .push_back((db.round, db.survival, db.births, db.deaths));
+        if self.samples.len() > GRAPH_LEN {
+            self.samples.pop_front();
+        }
+    }
+}
+
+// chart coordinates, the origin at the bottom left and `max` at the top; the
+// newest value at the right edge
+fn chart_points(values: &[usize], max: usize, size: Vec2) -> Vec<Vec2> {
+    let dx = size.x / (GRAPH_LEN - 1) as f32;
+    let offset = GRAPH_LEN.saturating_sub(values.len()) as f32 * dx;
+    values
+        .iter()
+        .enumerate()
+        .map(|(i, v)| {
+            let y = if max == 0 {
+                0.0
+            } else {
+                *v as f32 / max as f32 * size.y
+            };
+            Vec2::new(offset + i as f32 * dx, y)
+        })
+        .collect()
+}
+
+#[test]
+fn test_population_graph() {
+    let mut graph = PopulationGraph::default();
+    let mut db = Dashboard::default();
+    for round in 0..GRAPH_LEN + 5 {
+        db.round = round;
+        db.survival = round;
+        graph.record(&db);
+        graph.record(&db);
+    }
+    assert_eq!(graph.samples.len(), GRAPH_LEN);
+    assert_eq!(graph.samples.front().unwrap().0, 5);
+    db.round = 2;
+    graph.record(&db);
+    assert_eq!(graph.samples.len(), 1);
+
+    let size = Vec2::new(299.0, 10.0);
+    let points = chart_points(&[0, 5, 10], 10, size);
+    assert_eq!(points[2], Vec2::new(299.0, 10.0));
+    assert_eq!(points[1], Vec2::new(298.0, 5.0));
+    assert_eq!(chart_points(&[0, 0], 0, size)[0].y, 0.0);
+}
+
+pub fn toggle_graph(keys: Res<Input<KeyCode>>, mut graph: ResMut<PopulationGraph>) {
+    if keys.just_pressed(KeyCode::A) {
+        graph.enabled = !graph.enabled;
+    }
+}
+
+pub fn record_population(db: Res<Dashboard>, mut graph: ResMut<PopulationGraph>) {
+    graph.record(&db);
+}
+
+// population in white against its own peak, births in green and deaths in red
+// against theirs, so a big board doesn't flatten them
+pub fn draw_graph(
+    mut gizmos: Gizmos,
+    graph: Res<PopulationGraph>,
+    cameras: Query<(&Camera, &GlobalTransform)>,
+) {
+    if !graph.enabled {
+        return;
+    }
+    let Ok((camera, transform)) = cameras.get_single() else {
+        return;
+    };
+    let Some(viewport) = camera.logical_viewport_size() else {
+        return;
+    };
+    // viewport pixels count down from the top
+    let bottom_left = Vec2::new(MARGIN, viewport.y - MARGIN);
+    let to_world =
+        |p: Vec2| camera.viewport_to_world_2d(transform, bottom_left + Vec2::new(p.x, -p.y));
+    let frame = [
+        Vec2::ZERO,
+        Vec2::new(CHART.x, 0.0),
+        CHART,
+        Vec2::new(0.0, CHART.y),
+        Vec2::ZERO,
+    ];
+    gizmos.linestrip_2d(frame.into_iter().filter_map(to_world), Color::DARK_GRAY);
+    let column = |f: fn(&(usize, usize, usize, usize)) -> usize| -> Vec<usize> {
+        graph.samples.iter().map(f).collect()
+    };
+    let population = column(|s| s.1);
+    let births = column(|s| s.2);
+    let deaths = column(|s| s.3);
+    let peak = population.iter().copied().max().unwrap_or(0);
+    let changes = births.iter().chain(&deaths).copied().max().unwrap_or(0);
+    let series = [
+        (population, peak, Color::WHITE),
+        (births, changes, Color::GREEN),
+        (deaths, changes, Color::RED),
+    ];
+    for (values, max, color) in series {
+        let points = chart_points(&values, max, CHART);
+        gizmos.linestrip_2d(points.into_iter().filter_map(to_world), color);
+    }
+}
@@ -0,0 +1,388 @@
+use bevy::prelude::*;
+
+use std::collections::{HashMap, HashSet};
+
+use crate::plugin::follow_generation;
+use crate::{Cell, CellEvent, Config, Coord, Dashboard, LiveCells, Rule};
+
+// the biggest super-step, 2^16 generations a tick
+pub const MAX_SUPER_STEP: u8 = 16;
+// the memo is thrown away past this many nodes, and the tree built again from
+// the live cells
+const MAX_NODES: usize = 1 << 22;
+// coordinates are i32, so the tree can't cover more than this
+const MAX_LEVEL: u8 = 30;
+
+type Id = u32;
+
+// a square 2^level cells across, as its four quarters: low x low y, high x low
+// y, low x high y, high x high y. Ids 0 and 1 are the dead and the live cell
+#[derive(Debug)]
+struct Node {
+    children: [Id; 4],
+    level: u8,
+    population: u64,
+}
+
+// Gosper's HashLife over a life-like rule: every distinct square is stored
+// once, and what it turns into is memoized per step size, so a pattern that
+// repeats itself in time or space is worked out once. The root is centered on
+// the origin and grows as the pattern does; there are no edges
+pub struct Universe {
+    nodes: Vec<Node>,
+    index: HashMap<[Id; 4], Id>,
+    // (node, k) to its middle half 2^k generations on
+    results: HashMap<(Id, u8), Id>,
+    // the empty node of each level
+    empties: Vec<Id>,
+    root: Id,
+    rule: Rule,
+    include_center: bool,
+}
+
+impl Universe {
+    pub fn new(alives: &HashSet<Coord>, rule: &Rule, include_center: bool) -> Universe {
+        let leaf = |population| Node {
+            children: [0; 4],
+            level: 0,
+            population,
+        };
+        let mut universe = Universe {
+            nodes: vec![leaf(0), leaf(1)],
+            index: HashMap::new(),
+            results: HashMap::new(),
+            empties: vec![0],
+            root: 0,
+            rule: rule.clone(),
+            include_center,
+        };
+        universe.root = universe.empty(3);
+        for &c in alives {
+            while !universe.covers(c) {
+                universe.expand();
+            }
+            let level = universe.level(universe.root);
+            let half = 1 << (level - 1);
+            universe.root = universe.set(universe.root, (c.0 + half, c.1 + half));
+        }
+        universe
+    }
+
+    // what it was built for; a different rule needs a new one
+    pub fn runs(&self, rule: &Rule, include_center: bool) -> bool {
+        self.rule == *rule && self.include_center == include_center
+    }
+
+    fn level(&self, id: Id) -> u8 {
+        self.nodes[id as usize].level
+    }
+
+    fn population(&self, id: Id) -> u64 {
+        self.nodes[id as usize].population
+    }
+
+    fn children(&self, id: Id) -> [Id; 4] {
+        self.nodes[id as usize].children
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    fn join(&mut self, children: [Id; 4]) -> Id {
+        if let Some(&id) = self.index.get(&children) {
+            return id;
+        }
+        let id = self.nodes.len() as Id;
+        self.nodes.push(Node {
+            children,
+            level: self.level(children[0]) + 1,
+            population: children.iter().map(|c| self.population(*c)).sum(),
+        });
+        self.index.insert(children, id);
+        id
+    }
+
+    fn empty(&mut self, level: u8) -> Id {
+        while self.empties.len() <= level as usize {
+            let below = *self.empties.last().unwrap();
+            let next = self.join([below; 4]);
+            self.empties.push(next);
+        }
+        self.empties[level as usize]
+    }
+
+    fn covers(&self, (x, y): Coord) -> bool {
+        let half = 1i64 << (self.level(self.root) - 1);
+        let inside = |v: i32| (-half..half).contains(&(v as i64));
+        inside(x) && inside(y)
+    }
+
+    // the root a level up, itself in the middle of it
+    fn expand(&mut self) {
+        let level = self.level(self.root);
+        let e = self.empty(level - 1);
+        let [a, b, c, d] = self.children(self.root);
+        let children = [
+            self.join([e, e, e, a]),
+            self.join([e, e, b, e]),
+            self.join([e, c, e, e]),
+            self.join([d, e, e, e]),
+        ];
+        self.root = self.join(children);
+    }
+
+    // the node with the cell `at`, from its low corner, alive
+    fn set(&mut self, id: Id, (x, y): Coord) -> Id {
+        let level = self.level(id);
+        if level == 0 {
+            return 1;
+        }
+        let half = 1 << (level - 1);
+        let quarter = (x >= half) as usize + 2 * (y >= half) as usize;
+        let mut children = self.children(id);
+        children[quarter] = self.set(children[quarter], (x % half, y % half));
+        self.join(children)
+    }
+
+    // the four middle grandchildren as a node a level down
+    fn center(&mut self, id: Id) -> Id {
+        let [a, b, c, d] = self.children(id);
+        let children = [
+            self.children(a)[3],
+            self.children(b)[2],
+            self.children(c)[1],
+            self.children(d)[0],
+        ];
+        self.join(children)
+    }
+
+    // the 4x4 grandchildren, by x then y from the low corner
+    fn grandchild(&self, id: Id, gx: usize, gy: usize) -> Id {
+        let child = self.children(id)[gx / 2 + 2 * (gy / 2)];
+        self.children(child)[gx % 2 + 2 * (gy % 2)]
+    }
+
+    // a 4x4 node's middle 2x2, a generation on
+    fn base(&mut self, id: Id) -> Id {
+        let alive = |gx: usize, gy: usize| self.grandchild(id, gx, gy) == 1;
+        let mut next = [0; 4];
+        for (i, (x, y)) in [(1, 1), (2, 1), (1, 2), (2, 2)].into_iter().enumerate() {
+            let mut count = 0;
+            for nx in x - 1..=x + 1 {
+                for ny in y - 1..=y + 1 {
+                    if (nx, ny) != (x, y) || self.include_center {
+                        count += alive(nx, ny) as usize;
+                    }
+                }
+            }
+            next[i] = self.rule.next_alive(alive(x, y), count) as Id;
+        }
+        self.join(next)
+    }
+
+    // the middle half of `id`, 2^k generations on; k is at most level - 2
+    fn advance(&mut self, id: Id, k: u8) -> Id {
+        let level = self.level(id);
+        if self.population(id) == 0 {
+            return self.empty(level - 1);
+        }
+        if let Some(&result) = self.results.get(&(id, k)) {
+            return result;
+        }
+        let result = if level == 2 {
+            self.base(id)
+        } else {
+            // the nine overlapping half-size squares
+            let mut nine = [0; 9];
+            for j in 0..3 {
+                for i in 0..3 {
+                    let quarters = [
+                        self.grandchild(id, i, j),
+                        self.grandchild(id, i + 1, j),
+                        self.grandchild(id, i, j + 1),
+                        self.grandchild(id, i + 1, j + 1),
+                    ];
+                    let square = self.join(quarters);
+                    // at full speed the two halves take 2^(level - 3) each
+                    let first = if k == level - 2 { k - 1 } else { k };
+                    nine[i + 3 * j] = self.advance(square, first);
+                }
+            }
+            let mut four = [0; 4];
+            for (q, (i, j)) in [(0, 0), (1, 0), (0, 1), (1, 1)].into_iter().enumerate() {
+                let square = self.join([
+                    nine[i + 3 * j],
+                    nine[i + 1 + 3 * j],
+                    nine[i + 3 * (j + 1)],
+                    nine[i + 1 + 3 * (j + 1)],
+                ]);
+                four[q] = if k == level - 2 {
+                    self.advance(square, k - 1)
+                } else {
+                    self.center(square)
+                };
+            }
+            self.join(four)
+        };
+        self.results.insert((id, k), result);
+        result
+    }
+
+    // 2^k generations on. The root first grows until the pattern sits in its
+    // middle with room to spread that far, light speed being a cell a
+    // generation; false if that would go past MAX_LEVEL
+    pub fn step(&mut self, k: u8) -> bool {
+        loop {
+            let level = self.level(self.root);
+            let [a, b, c, d] = self.children(self.root);
+            let inner = [
+                self.children(self.children(a)[3])[3],
+                self.children(self.children(b)[2])[2],
+                self.children(self.children(c)[1])[1],
+                self.children(self.children(d)[0])[0],
+            ];
+            let all_inside = self.population(self.root)
+                == inner.iter().map(|n| self.population(*n)).sum::<u64>();
+            if level >= k + 3 && all_inside {
+                break;
+            }
+            if level >= MAX_LEVEL {
+                return false;
+            }
+            self.expand();
+        }
+        self.expand();
+        self.root = self.advance(self.root, k);
+        true
+    }
+
+    pub fn cells(&self) -> HashSet<Coord> {
+        let mut cells = HashSet::new();
+        let half = 1 << (self.level(self.root) - 1);
+        self.collect(self.root, (-half, -half), &mut cells);
+        cells
+    }
+
+    fn collect(&self, id: Id, (x, y): Coord, cells: &mut HashSet<Coord>) {
+        if self.population(id) == 0 {
+            return;
+        }
+        let level = self.level(id);
+        if level == 0 {
+            cells.insert((x, y));
+            return;
+        }
+        let half = 1 << (level - 1);
+        let [a, b, c, d] = self.children(id);
+        self.collect(a, (x, y), cells);
+        self.collect(b, (x + half, y), cells);
+        self.collect(c, (x, y + half), cells);
+        self.collect(d, (x + half, y + half), cells);
+    }
+}
+
+#[test]
+fn test_universe() {
+    use crate::{step, EdgeMode};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    let open = EdgeMode::open();
+    let config = Config::default();
+    let rule = Rule::parse("B36/S23").unwrap();
+    let soup = crate::seed(&mut StdRng::seed_from_u64(3), &config);
+    let mut universe = Universe::new(&soup, &rule, false);
+    assert_eq!(universe.cells(), soup);
+    // a generation at a time, and then 8 at once, as the plain step has it
+    let mut expected = soup.clone();
+    for _ in 0..5 {
+        expected = step(&expected, &rule, &config, open);
+        assert!(universe.step(0));
+        assert_eq!(universe.cells(), expected);
+    }
+    for _ in 0..8 {
+        expected = step(&expected, &rule, &config, open);
+    }
+    assert!(universe.step(3));
+    assert_eq!(universe.cells(), expected);
+
+    // a glider a million generations out, a quarter million cells away
+    let glider = HashSet::from([(0, 1), (1, 0), (-1, -1), (0, -1), (1, -1)]);
+    let mut universe = Universe::new(&glider, &Rule::default(), false);
+    for _ in 0..(1 << 20) / (1 << 16) {
+        assert!(universe.step(MAX_SUPER_STEP));
+    }
+    let cells = universe.cells();
+    assert_eq!(cells.len(), 5);
+    let far = 1 << 18;
+    let moved: HashSet<_> = glider.iter().map(|(x, y)| (x + far, y - far)).collect();
+    assert_eq!(cells, moved);
+}
+
+// the HashLife backend's state: the tree, rebuilt whenever the cells were
+// changed by anything else, and the super-step, 2^super_step generations a
+// tick, PageUp / PageDown in the app
+#[derive(Resource, Default)]
+pub struct HashLife {
+    pub super_step: u8,
+    universe: Option<Universe>,
+}
+
+impl HashLife {
+    pub fn new(super_step: u8) -> HashLife {
+        HashLife {
+            super_step: super_step.min(MAX_SUPER_STEP),
+            universe: None,
+        }
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.universe.as_ref().map_or(0, Universe::node_count)
+    }
+}
+
+pub fn hashlife_backend(config: Res<Config>) -> bool {
+    config.backend == crate::Backend::Hashlife
+}
+
+// dead_or_alive for Backend::Hashlife
+#[allow(clippy::too_many_arguments)]
+pub fn hashlife_tick(
+    mut commands: Commands,
+    mut db: ResMut<Dashboard>,
+    config: Res<Config>,
+    rule: Res<Rule>,
+    mut hashlife: ResMut<HashLife>,
+    mut live: ResMut<LiveCells>,
+    mut events: EventWriter<CellEvent>,
+    mut query: Query<(Entity, &mut Cell)>,
+) {
+    if live.0.is_empty() {
+        return;
+    }
+    let hashlife = &mut *hashlife;
+    // this system's own changes to LiveCells don't count
+    let stale = hashlife.universe.as_ref().is_none_or(|u| {
+        live.is_changed() || !u.runs(&rule, config.include_center) || u.node_count() > MAX_NODES
+    });
+    if stale {
+        hashlife.universe = Some(Universe::new(&live.0, &rule, config.include_center));
+    }
+    let universe = hashlife.universe.as_mut().unwrap();
+    if !universe.step(hashlife.super_step) {
+        warn!("the pattern got too big for the HashLife tree");
+        return;
+    }
+    let next = universe.cells();
+    follow_generation(
+        &next,
+        1 << hashlife.super_step,
+        &mut commands,
+        &mut db,
+        &config,
+        &rule,
+        &mut live,
+        &mut events,
+        &mut query,
+    );
+}
@@ -0,0 +1,191 @@
+use bevy::prelude::*;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use bevy_game_of_life::{Config, Coord, LiveCells};
+
+use std::collections::HashSet;
+
+use crate::Theme;
+
+// binomial weights, applied across and then down: two cells out each way for
+// 10 multiplications a pixel
+const KERNEL: [f32; 5] = [1.0 / 16.0, 4.0 / 16.0, 6.0 / 16.0, 4.0 / 16.0, 1.0 / 16.0];
+// the opacities Shift+F8 steps through
+const OPACITIES: [f32; 3] = [0.25, 0.5, 0.75];
+
+// a blurred live-cell density over the main board, like a weather map of
+// where things are going on; redrawn whenever the live cells change while on
+#[derive(Resource, Debug)]
+pub struct HeatMap {
+    enabled: bool,
+    opacity: f32,
+    // drawn again on the next frame even if the cells stay the same
+    redraw: bool,
+}
+
+impl Default for HeatMap {
+    fn default() -> Self {
+        HeatMap {
+            enabled: false,
+            opacity: OPACITIES[1],
+            redraw: false,
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct HeatLayer;
+
+// one pass of the kernel along the rows (`across`) or the columns of a w x h
+// field; past the border counts as empty
+fn blur(field: &[f32], w: usize, h: usize, across: bool) -> Vec<f32> {
+    let (len, stride) = if across { (w, 1) } else { (h, w) };
+    let reach = KERNEL.len() / 2;
+    let mut out = vec![0.0; field.len()];
+    for (i, o) in out.iter_mut().enumerate() {
+        let pos = if across { i % w } else { i / w };
+        let line_start = i - pos * stride;
+        for (k, weight) in KERNEL.iter().enumerate() {
+            if let Some(from) = (pos + k).checked_sub(reach).filter(|f| *f < len) {
+                *o += weight * field[line_start + from * stride];
+            }
+        }
+    }
+    out
+}
+
+// live cells blurred, one value per cell with the rows from the top, scaled so
+// the densest spot is 1
+fn density_field(config: &Config, alives: &HashSet<Coord>) -> Vec<f32> {
+    let (w, h) = (config.width as usize, config.height as usize);
+    let (xs, ys) = (config.x_range(), config.y_range());
+    let mut field = vec![0.0; w * h];
+    for &(x, y) in alives.iter().filter(|c| config.contains(**c)) {
+        let (px, py) = ((x - xs.start) as usize, (ys.end - 1 - y) as usize);
+        field[py * w + px] = 1.0;
+    }
+    let mut field = blur(&blur(&field, w, h, true), w, h, false);
+    let max = field.iter().copied().fold(0.0, f32::max);
+    if max > 0.0 {
+        field.iter_mut().for_each(|d| *d /= max);
+    }
+    field
+}
+
+#[test]
+fn test_density_field() {
+    let config = Config {
+        width: 7,
+        height: 5,
+        ..Default::default()
+    };
+    // a lone cell in the middle spreads two cells each way, evenly
+    let field = density_field(&config, &HashSet::from([(0, 0)]));
+    let at = |x: usize, y: usize| field[y * 7 + x];
+    assert_eq!(at(3, 2), 1.0);
+    assert_eq!(at(2, 2), at(4, 2));
+    assert_eq!(at(3, 1), at(3, 3));
+    assert!((at(2, 2) - 4.0 / 6.0).abs() < 1e-6);
+    assert!((at(1, 0) - 1.0 / 36.0).abs() < 1e-6);
+    assert_eq!(at(0, 2), 0.0);
+    // a cluster outshines a stray cell, however far apart
+    let field = density_field(
+        &config,
+        &HashSet::from([(-3, 2), (2, -1), (3, -1), (3, -2)]),
+    );
+    assert_eq!(field.iter().copied().fold(0.0, f32::max), 1.0);
+    assert!(field[0] < field[4 * 7 + 6]);
+    assert!(density_field(&config, &HashSet::new())
+        .iter()
+        .all(|d| *d == 0.0));
+}
+
+fn heat_image(config: &Config, alives: &HashSet<Coord>, theme: &Theme, opacity: f32) -> Image {
+    let data = density_field(config, alives)
+        .into_iter()
+        .flat_map(|d| {
+            let alpha = if d > 0.0 { opacity * d.sqrt() } else { 0.0 };
+            theme.heat.sample(d).with_a(alpha).as_rgba_u8()
+        })
+        .collect();
+    // a pixel per cell, smoothed further by the default linear sampler
+    Image::new(
+        Extent3d {
+            width: config.width as u32,
+            height: config.height as u32,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+    )
+}
+
+// F8 shows / hides the layer, Shift+F8 steps its opacity
+pub fn toggle_heat_map(
+    mut commands: Commands,
+    keys: Res<Input<KeyCode>>,
+    mut heat: ResMut<HeatMap>,
+    layers: Query<Entity, With<HeatLayer>>,
+) {
+    if !keys.just_pressed(KeyCode::F8) {
+        return;
+    }
+    if keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]) {
+        let next = OPACITIES
+            .iter()
+            .position(|o| *o == heat.opacity)
+            .map_or(0, |i| i + 1);
+        heat.opacity = OPACITIES[next % OPACITIES.len()];
+    } else {
+        heat.enabled = !heat.enabled;
+    }
+    if heat.enabled {
+        heat.redraw = true;
+        return;
+    }
+    for layer in layers.iter() {
+        commands.entity(layer).despawn();
+    }
+}
+
+// over the cells of the main board, whenever they, the gap between them or
+// the board's size change
+pub fn update_heat_map(
+    mut commands: Commands,
+    config: Res<Config>,
+    theme: Res<Theme>,
+    live: Res<LiveCells>,
+    mut heat: ResMut<HeatMap>,
+    mut images: ResMut<Assets<Image>>,
+    layers: Query<Entity, With<HeatLayer>>,
+) {
+    let stale = std::mem::take(&mut heat.redraw)
+        || live.is_changed()
+        || theme.is_changed()
+        || config.is_changed();
+    if !heat.enabled || !stale {
+        return;
+    }
+    // the old texture goes with its handle
+    for layer in layers.iter() {
+        commands.entity(layer).despawn();
+    }
+    let pitch = theme.pitch();
+    let (xs, ys) = (config.x_range(), config.y_range());
+    let center = Vec2::new(
+        (xs.start + xs.end - 1) as f32 / 2.0,
+        (ys.start + ys.end - 1) as f32 / 2.0,
+    ) * pitch;
+    commands.spawn((
+        HeatLayer,
+        SpriteBundle {
+            sprite: Sprite {
+                custom_size: Some(Vec2::new(config.width as f32, config.height as f32) * pitch),
+                ..Default::default()
+            },
+            texture: images.add(heat_image(&config, &live.0, &theme, heat.opacity)),
+            transform: Transform::from_translation(center.extend(0.5)),
+            ..Default::default()
+        },
+    ));
+}
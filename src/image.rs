@@ -0,0 +1,191 @@
+use serde::{Deserialize, Serialize};
+
+use std::collections::HashSet;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+
+use crate::{Config, Coord};
+
+// a PNG in place of the soup, stretched over the whole board, a cell alive
+// where its patch of the picture is darker than `threshold` (lighter, with
+// `invert`); with `dither` the shades in between come out as a scatter of
+// live cells as dense as the patch is dark, Floyd–Steinberg style
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct ImageSeed {
+    pub path: PathBuf,
+    pub threshold: f32,
+    pub dither: bool,
+    pub invert: bool,
+}
+
+// luminance 0 (black) to 1 (white), row by row from the top
+#[derive(Debug, PartialEq, Clone)]
+pub struct Gray {
+    pub width: usize,
+    pub height: usize,
+    pub values: Vec<f32>,
+}
+
+impl Gray {
+    pub fn load(path: &Path) -> Result<Gray, String> {
+        let at = |e: &dyn std::fmt::Display| format!("{}: {e}", path.display());
+        let file = File::open(path).map_err(|e| at(&e))?;
+        let mut decoder = png::Decoder::new(file);
+        // 8 bits a channel, palettes and low bit depths spelled out
+        decoder.set_transformations(png::Transformations::normalize_to_color8());
+        let mut reader = decoder.read_info().map_err(|e| at(&e))?;
+        let mut buffer = vec![0; reader.output_buffer_size()];
+        let frame = reader.next_frame(&mut buffer).map_err(|e| at(&e))?;
+        let channels = frame.color_type.samples();
+        let values = buffer[..frame.buffer_size()]
+            .chunks_exact(channels)
+            .map(|px| {
+                let px: Vec<f32> = px.iter().map(|v| *v as f32 / 255.0).collect();
+                let (shade, alpha) = match px[..] {
+                    [v] => (v, 1.0),
+                    [v, a] => (v, a),
+                    [r, g, b] => (0.2126 * r + 0.7152 * g + 0.0722 * b, 1.0),
+                    [r, g, b, a, ..] => (0.2126 * r + 0.7152 * g + 0.0722 * b, a),
+                    _ => (1.0, 1.0),
+                };
+                // see-through is the white of the page
+                shade * alpha + 1.0 - alpha
+            })
+            .collect();
+        Ok(Gray {
+            width: frame.width as usize,
+            height: frame.height as usize,
+            values,
+        })
+    }
+
+    // `width` x `height`, each value the mean of the patch it covers
+    pub fn resize(&self, width: usize, height: usize) -> Gray {
+        let mut values = Vec::with_capacity(width * height);
+        let span = |i: usize, to: usize, from: usize| {
+            let start = i * from / to;
+            start..((i + 1) * from / to).max(start + 1)
+        };
+        for row in 0..height {
+            let rows = span(row, height, self.height);
+            for col in 0..width {
+                let cols = span(col, width, self.width);
+                let (mut sum, mut n) = (0.0, 0);
+                for y in rows.clone() {
+                    for x in cols.clone() {
+                        sum += self.values[y * self.width + x];
+                        n += 1;
+                    }
+                }
+                values.push(sum / n as f32);
+            }
+        }
+        Gray {
+            width,
+            height,
+            values,
+        }
+    }
+}
+
+// which cells are alive, row by row from the top
+pub fn threshold(gray: &Gray, level: f32, dither: bool, invert: bool) -> Vec<bool> {
+    let mut ink: Vec<f32> = gray
+        .values
+        .iter()
+        .map(|v| if invert { *v } else { 1.0 - v })
+        .collect();
+    let cut = if invert { level } else { 1.0 - level };
+    let mut alive = vec![false; ink.len()];
+    for y in 0..gray.height {
+        for x in 0..gray.width {
+            let i = y * gray.width + x;
+            alive[i] = ink[i] > cut;
+            if !dither {
+                continue;
+            }
+            // what the cell got wrong goes to the ones not done yet
+            let error = ink[i] - if alive[i] { 1.0 } else { 0.0 };
+            let mut spread = |dx: isize, dy: usize, share: f32| {
+                let nx = x as isize + dx;
+                if nx >= 0 && (nx as usize) < gray.width && y + dy < gray.height {
+                    ink[(y + dy) * gray.width + nx as usize] += error * share;
+                }
+            };
+            spread(1, 0, 7.0 / 16.0);
+            spread(-1, 1, 3.0 / 16.0);
+            spread(0, 1, 5.0 / 16.0);
+            spread(1, 1, 1.0 / 16.0);
+        }
+    }
+    alive
+}
+
+impl ImageSeed {
+    pub fn seed(&self, config: &Config) -> Result<HashSet<Coord>, String> {
+        let (width, height) = (config.width as usize, config.height as usize);
+        let gray = Gray::load(&self.path)?.resize(width, height);
+        let alive = threshold(&gray, self.threshold, self.dither, self.invert);
+        let (left, top) = (config.x_range().start, config.y_range().end - 1);
+        Ok((0..width * height)
+            .filter(|i| alive[*i])
+            .map(|i| (left + (i % width) as i32, top - (i / width) as i32))
+            .collect())
+    }
+}
+
+#[test]
+fn test_image_seed() {
+    // black on the left, white on the right, a mid gray band at the bottom
+    let gray = Gray {
+        width: 4,
+        height: 2,
+        values: vec![0.0, 0.0, 1.0, 1.0, 0.5, 0.5, 0.5, 0.5],
+    };
+    let half = gray.resize(2, 1);
+    assert_eq!(half.values, vec![0.25, 0.75]);
+    assert_eq!(gray.resize(8, 4).values.len(), 32);
+
+    let plain = threshold(&gray, 0.4, false, false);
+    assert_eq!(
+        plain,
+        [true, true, false, false, false, false, false, false]
+    );
+    let inverted = threshold(&gray, 0.4, false, true);
+    assert_eq!(inverted, [false, false, true, true, true, true, true, true]);
+    // dithered, an even gray comes out half alive
+    let flat = Gray {
+        width: 10,
+        height: 10,
+        values: vec![0.5; 100],
+    };
+    let alive = threshold(&flat, 0.5, true, false)
+        .iter()
+        .filter(|a| **a)
+        .count();
+    assert!((45..=55).contains(&alive));
+    assert!(threshold(&flat, 0.5, false, false).iter().all(|a| !a));
+
+    // a black pixel over a see-through one, on a board of two cells
+    let path = std::env::temp_dir().join("gol-test-image.png");
+    let mut encoder = png::Encoder::new(File::create(&path).unwrap(), 1, 2);
+    encoder.set_color(png::ColorType::Rgba);
+    let mut writer = encoder.write_header().unwrap();
+    writer
+        .write_image_data(&[0, 0, 0, 255, 0, 0, 0, 0])
+        .unwrap();
+    writer.finish().unwrap();
+    let config = Config {
+        width: 1,
+        height: 2,
+        ..Default::default()
+    };
+    let image = ImageSeed {
+        path,
+        threshold: 0.5,
+        dither: false,
+        invert: false,
+    };
+    let top = config.y_range().end - 1;
+    assert_eq!(image.seed(&config), Ok(HashSet::from([(0, top)])));
+}
@@ -0,0 +1,172 @@
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use bevy_game_of_life::{
+    alive_neighbor_count, Cell, Config, Dashboard, EdgeMode, LiveCells, Rule, State, Topology,
+};
+
+use crate::camera::DisplayOrigin;
+use crate::draw::{cell_center, cursor_cell};
+use crate::Theme;
+
+// on-screen pixels a cell needs before the lines between cells are drawn
+const GRID_PIXELS: f32 = 4.0;
+
+// what the hovered cell does next tick, and which clause of the rule says so
+#[derive(Resource, Default, Debug)]
+pub struct Inspector(pub bool);
+
+#[derive(Component)]
+pub struct InspectorText;
+
+// `/`: lines between the cells of the main board and the axes through the
+// shown origin, red along x and green along y
+#[derive(Resource, Default, Debug)]
+pub struct GridOverlay(pub bool);
+
+pub fn explain(alive: bool, frozen: bool, count: usize, rule: &Rule) -> String {
+    let now = if alive { State::Alive } else { State::Dead };
+    let clause = if frozen {
+        "frozen, so it stays".to_string()
+    } else {
+        match (alive, rule.next_alive(alive, count)) {
+            (true, true) => format!("S{count} in {rule}: survives"),
+            (true, false) => format!("no S{count} in {rule}: dies"),
+            (false, true) => format!("B{count} in {rule}: born"),
+            (false, false) => format!("no B{count} in {rule}: stays dead"),
+        }
+    };
+    let next = if frozen || rule.next_alive(alive, count) == alive {
+        now.clone()
+    } else if alive {
+        State::Dead
+    } else {
+        State::Alive
+    };
+    format!("{now} with {count} neighbors\n{clause}\nnext: {next}")
+}
+
+#[test]
+fn test_explain() {
+    let rule = Rule::default();
+    assert_eq!(
+        explain(true, false, 2, &rule),
+        "O with 2 neighbors\nS2 in B3/S23: survives\nnext: O"
+    );
+    assert_eq!(
+        explain(true, false, 4, &rule),
+        "O with 4 neighbors\nno S4 in B3/S23: dies\nnext: ."
+    );
+    assert_eq!(
+        explain(false, false, 3, &rule),
+        ". with 3 neighbors\nB3 in B3/S23: born\nnext: O"
+    );
+    assert_eq!(
+        explain(false, true, 3, &rule),
+        ". with 3 neighbors\nfrozen, so it stays\nnext: ."
+    );
+}
+
+pub fn spawn_inspector(mut commands: Commands) {
+    commands.spawn((
+        InspectorText,
+        TextBundle::from_section(
+            String::new(),
+            TextStyle {
+                font_size: 20.0,
+                color: Color::YELLOW,
+                ..Default::default()
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            ..Default::default()
+        }),
+    ));
+}
+
+pub fn toggle_inspector(keys: Res<Input<KeyCode>>, mut inspector: ResMut<Inspector>) {
+    if keys.just_pressed(KeyCode::Q) {
+        inspector.0 = !inspector.0;
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn update_inspector(
+    inspector: Res<Inspector>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    config: Res<Config>,
+    theme: Res<Theme>,
+    origin: Res<DisplayOrigin>,
+    rule: Res<Rule>,
+    edge: Res<EdgeMode>,
+    live: Res<LiveCells>,
+    db: Res<Dashboard>,
+    cells: Query<&Cell>,
+    mut text: Query<(&mut Text, &mut Style), With<InspectorText>>,
+) {
+    let Ok((mut text, mut style)) = text.get_single_mut() else {
+        return;
+    };
+    let hovered = cursor_cell(&windows, &cameras, theme.pitch(), config.topology)
+        .filter(|c| config.contains(*c));
+    let (Some(xy), true) = (hovered, inspector.0) else {
+        text.sections[0].value.clear();
+        return;
+    };
+    let alive = live.0.contains(&xy);
+    let cell = cells.iter().find(|c| c.index_xy == xy);
+    let frozen = cell.is_some_and(|c| c.frozen);
+    let count = alive_neighbor_count(&xy, &live.0, &config, *edge);
+    let (x, y) = origin.shown(xy);
+    text.sections[0].value = format!("({x}, {y}) {}", explain(alive, frozen, count, &rule));
+    if let Some(cell) = cell.filter(|_| alive) {
+        let age = db.round.saturating_sub(cell.born);
+        text.sections[0].value += &format!("\nalive for {age} generations");
+    }
+    // just below and right of the pointer
+    if let Some(cursor) = windows.get_single().ok().and_then(|w| w.cursor_position()) {
+        style.left = Val::Px(cursor.x + 16.0);
+        style.top = Val::Px(cursor.y + 16.0);
+    }
+}
+
+pub fn toggle_grid(keys: Res<Input<KeyCode>>, mut grid: ResMut<GridOverlay>) {
+    if keys.just_pressed(KeyCode::Slash) {
+        grid.0 = !grid.0;
+    }
+}
+
+// the cell lines only on the square lattice, and once they're far enough
+// apart on screen not to blur into a fill
+pub fn draw_grid(
+    mut gizmos: Gizmos,
+    grid: Res<GridOverlay>,
+    config: Res<Config>,
+    theme: Res<Theme>,
+    origin: Res<DisplayOrigin>,
+    cameras: Query<&OrthographicProjection>,
+) {
+    if !grid.0 {
+        return;
+    }
+    let pitch = theme.pitch();
+    let (xs, ys) = (config.x_range(), config.y_range());
+    let corner = |x: i32, y: i32| Vec2::new(x as f32 - 0.5, y as f32 - 0.5) * pitch;
+    let scale = cameras.iter().next().map_or(1.0, |p| p.scale);
+    if config.topology == Topology::Square && pitch / scale >= GRID_PIXELS {
+        let color = theme.text.with_a(0.2);
+        for x in xs.start..=xs.end {
+            gizmos.line_2d(corner(x, ys.start), corner(x, ys.end), color);
+        }
+        for y in ys.start..=ys.end {
+            gizmos.line_2d(corner(xs.start, y), corner(xs.end, y), color);
+        }
+    }
+    let center = cell_center(origin.0, pitch, config.topology);
+    let (low, high) = (corner(xs.start, ys.start), corner(xs.end, ys.end));
+    let x_axis = (Vec2::new(low.x, center.y), Vec2::new(high.x, center.y));
+    let y_axis = (Vec2::new(center.x, low.y), Vec2::new(center.x, high.y));
+    gizmos.line_2d(x_axis.0, x_axis.1, Color::rgba(1.0, 0.3, 0.3, 0.8));
+    gizmos.line_2d(y_axis.0, y_axis.1, Color::rgba(0.3, 1.0, 0.3, 0.8));
+}
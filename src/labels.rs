@@ -0,0 +1,146 @@
+use bevy::prelude::*;
+use bevy_game_of_life::{
+    bounding_box, named_objects, Config, Coord, Dashboard, LiveCells, Rule, Topology,
+};
+
+use crate::draw::cell_center;
+use crate::Theme;
+
+// labels past this many aren't drawn, the census still counts them
+const MAX_LABELS: usize = 200;
+
+// `'`: the known objects on a Conway board outlined and named wherever they
+// stand apart from the rest, a soup still churning around them or not, and
+// counted on the dashboard; an object only counts once it's been there two
+// generations running, so a passing shape doesn't flash a label
+#[derive(Resource, Default, Debug)]
+pub struct ObjectLabels {
+    pub enabled: bool,
+    // the objects labeled, and all the named ones this generation, for the
+    // next to be checked against
+    pub shown: Vec<Found>,
+    found: Vec<Found>,
+    round: Option<usize>,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Found {
+    pub name: &'static str,
+    // the lowest and the highest corner
+    pub bounds: (Coord, Coord),
+}
+
+// the ones found last generation too, where spaceships have moved on a cell at most
+fn stable(found: &[Found], before: &[Found]) -> Vec<Found> {
+    let near = |a: &Found, b: &Found| {
+        let ((ax0, ay0), (ax1, ay1)) = a.bounds;
+        let ((bx0, by0), (bx1, by1)) = b.bounds;
+        a.name == b.name && ax0 <= bx1 + 1 && bx0 <= ax1 + 1 && ay0 <= by1 + 1 && by0 <= ay1 + 1
+    };
+    found
+        .iter()
+        .filter(|f| before.iter().any(|b| near(f, b)))
+        .copied()
+        .collect()
+}
+
+#[test]
+fn test_stable() {
+    let found = |name, x| Found {
+        name,
+        bounds: ((x, 0), (x + 2, 2)),
+    };
+    let before = [found("glider", 0), found("block", 10)];
+    // a glider a cell on, a block where it was, and a blinker out of nowhere
+    let now = [found("glider", 1), found("block", 10), found("blinker", 20)];
+    assert_eq!(stable(&now, &before), vec![now[0], now[1]]);
+    // a block where the glider was isn't the glider
+    assert!(stable(&[found("block", 0)], &[found("glider", 0)]).is_empty());
+    assert!(stable(&[found("glider", 4)], &before).is_empty());
+}
+
+pub fn toggle_labels(keys: Res<Input<KeyCode>>, mut labels: ResMut<ObjectLabels>) {
+    if keys.just_pressed(KeyCode::Apostrophe) {
+        labels.enabled = !labels.enabled;
+        labels.shown.clear();
+        labels.found.clear();
+        labels.round = None;
+    }
+}
+
+// the catalog is Conway's on the square lattice
+pub fn conway(rule: &Rule, config: &Config) -> bool {
+    *rule == Rule::default() && config.topology == Topology::Square
+}
+
+pub fn find_objects(
+    rule: Res<Rule>,
+    config: Res<Config>,
+    db: Res<Dashboard>,
+    live: Res<LiveCells>,
+    mut labels: ResMut<ObjectLabels>,
+) {
+    if !labels.enabled || labels.round == Some(db.round) && !live.is_changed() {
+        return;
+    }
+    labels.round = Some(db.round);
+    if !conway(&rule, &config) {
+        labels.shown.clear();
+        return;
+    }
+    let found: Vec<_> = named_objects(&live.0)
+        .into_iter()
+        .filter_map(|(name, cells)| {
+            let bounds = bounding_box(&cells.into_iter().collect())?;
+            Some(Found { name, bounds })
+        })
+        .collect();
+    labels.shown = stable(&found, &labels.found);
+    labels.found = found;
+}
+
+#[derive(Component)]
+pub struct ObjectLabel;
+
+pub fn draw_labels(
+    mut commands: Commands,
+    mut gizmos: Gizmos,
+    theme: Res<Theme>,
+    labels: Res<ObjectLabels>,
+    texts: Query<Entity, With<ObjectLabel>>,
+) {
+    let pitch = theme.pitch();
+    let color = theme.text.with_a(0.7);
+    let corner = |(x, y): Coord| cell_center((x, y), pitch, Topology::Square);
+    for found in labels.shown.iter().take(MAX_LABELS) {
+        let (low, high) = (corner(found.bounds.0), corner(found.bounds.1));
+        let size = high - low + Vec2::splat(pitch);
+        gizmos.rect_2d((low + high) / 2.0, 0.0, size, color);
+    }
+    if !labels.is_changed() {
+        return;
+    }
+    for entity in texts.iter() {
+        commands.entity(entity).despawn();
+    }
+    for found in labels.shown.iter().take(MAX_LABELS) {
+        let (low, high) = (corner(found.bounds.0), corner(found.bounds.1));
+        let above = Vec2::new((low.x + high.x) / 2.0, high.y + pitch);
+        commands.spawn((
+            ObjectLabel,
+            Text2dBundle {
+                text: Text::from_section(
+                    found.name,
+                    TextStyle {
+                        font_size: pitch.max(8.0),
+                        color,
+                        ..Default::default()
+                    },
+                )
+                .with_alignment(TextAlignment::Center),
+                transform: Transform::from_translation(above.extend(2.0)),
+                ..Default::default()
+            },
+        ));
+    }
+}
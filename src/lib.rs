@@ -0,0 +1,611 @@
+//! The game of life simulation itself, with no rendering attached; `GameOfLifePlugin`
+//! adds the plainest one, to embed the board in another Bevy app.
+
+use bevy::prelude::Resource;
+use serde::{Deserialize, Serialize};
+
+use std::ops::Range;
+use std::path::PathBuf;
+
+mod ant;
+mod catalog;
+mod cell;
+mod embed;
+mod grid;
+mod hashlife;
+mod image;
+mod life3d;
+mod ltl;
+pub mod pattern;
+mod plugin;
+mod rule;
+mod script;
+mod sim;
+mod symmetry;
+
+pub use ant::{move_ants, place_ants, turmite_mode, Ant, Ants, Turmite, Turn};
+pub use catalog::{census, identify, name_of, named_objects};
+pub use cell::{coord_hue, set_bytes, Cell, CellEvent, LiveCells, Snapshot, State};
+pub use embed::{GameOfLifePlugin, Palette};
+pub use grid::Grid;
+pub use hashlife::{hashlife_backend, hashlife_tick, HashLife, Universe, MAX_SUPER_STEP};
+pub use image::ImageSeed;
+pub use life3d::{cube_range, seed3, step3, Coord3, Rule3};
+pub use ltl::{step_larger, Larger, Neighborhood, MAX_RADIUS};
+pub use plugin::{
+    cap_population, colored, dead_or_alive, detect_period, follow_lineage, note_settled,
+    percentiles, population_cap, ready, seed_board, spawn_pending_cells, starting_cells,
+    step_manually, step_once, Dashboard, FlatOut, Lineage, ManualClock, PendingCells, SimPlugin,
+    SimRng, SimSet, SimState, StepTiming, Trend, SPAWN_CHUNK,
+};
+pub use rule::{Rule, PRESETS};
+pub use script::{Script, NEIGHBORS};
+pub use sim::{
+    alive_neighbor_count, bounding_box, forecast, forecast_with_ash, forecast_within, forecasts,
+    inherited_color, live_set_hash, next_lineage, quadrant_counts, seed, seed_blobs, seed_colors,
+    step, step_counted, step_in_bands, tune_density, Forecast, PeriodDetector, Spaceship,
+    StepStats, FORECAST_CAP, MAX_PERIOD,
+};
+pub use symmetry::Symmetry;
+
+pub const HALF_LEN: i32 = 10;
+pub const INIT_ALIVE_COUNT: usize = 64;
+
+pub type Coord = (i32, i32);
+
+// anything left out of a serialized one takes its default
+#[derive(Resource, Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(default)]
+pub struct Config {
+    // in cells, centered on the origin: see x_range / y_range
+    pub width: i32,
+    pub height: i32,
+    // start from this pattern instead of a random soup
+    pub pattern: Option<PathBuf>,
+    // where the middle of the pattern goes
+    pub pattern_at: Coord,
+    // start from a picture instead of a random soup, see ImageSeed
+    pub image: Option<ImageSeed>,
+    // share of the board seeded alive; without it the soup is INIT_ALIVE_COUNT
+    // cells whatever the board size
+    pub density: Option<f32>,
+    // share of the board's width and height around the middle the soup is
+    // seeded in, the density being a share of that; 1 is the whole board
+    pub seed_region: f32,
+    // separate random disks seeded instead of the one soup over the board
+    pub blobs: Vec<Blob>,
+    // start with every cell dead and the sim paused, to draw from scratch
+    pub empty: bool,
+    // population swings up to this size still count as stable on the dashboard
+    pub trend_threshold: usize,
+    // pause once the population climbs to this many cells
+    pub population_cap: Option<usize>,
+    // pause once the board settles into a still life or an oscillator
+    pub stop_when_settled: bool,
+    // generations run before the first frame
+    pub warmup: usize,
+    // independent boards laid out side by side, the first one is the main board
+    pub worlds: usize,
+    // rules for the 2nd, 3rd, .. world; the rest run the main rule
+    pub world_rules: Vec<Rule>,
+    // the other worlds start from the main board's own seed, soup or pattern,
+    // instead of soups of their own, to tell the rules apart
+    pub same_seed: bool,
+    // only live cells get an entity, spawned on birth and despawned on death
+    pub sparse: bool,
+    // totalistic counting: a live cell counts itself as one of its neighbors
+    pub include_center: bool,
+    // how the seeded cells are split between the colors of multi-color rules
+    pub color_init: ColorInit,
+    // send a CellEvent for every birth and death, which is a lot of them
+    pub cell_events: bool,
+    // log the work every tick took at debug level, see StepStats
+    pub step_stats: bool,
+    // what drives the ticks, see Stepping
+    pub stepping: Stepping,
+    // where the generations are worked out, see Backend
+    pub backend: Backend,
+    // which cells are neighbors, see Topology
+    pub topology: Topology,
+    // ants walk the board in place of the rule, see Turmite
+    pub turmite: Option<Turmite>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            width: 2 * HALF_LEN,
+            height: 2 * HALF_LEN,
+            pattern: None,
+            pattern_at: (0, 0),
+            image: None,
+            density: None,
+            seed_region: 1.0,
+            blobs: vec![],
+            empty: false,
+            trend_threshold: 2,
+            population_cap: None,
+            stop_when_settled: false,
+            warmup: 0,
+            worlds: 1,
+            world_rules: vec![],
+            same_seed: false,
+            sparse: false,
+            include_center: false,
+            color_init: ColorInit::default(),
+            cell_events: false,
+            step_stats: false,
+            stepping: Stepping::default(),
+            backend: Backend::default(),
+            topology: Topology::default(),
+            turmite: None,
+        }
+    }
+}
+
+fn centered(len: i32) -> Range<i32> {
+    -(len / 2)..len - len / 2
+}
+
+impl Config {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.width < 1 || self.height < 1 {
+            return Err(format!(
+                "the board needs at least one cell each way, got {}x{}",
+                self.width, self.height
+            ));
+        }
+        // cell counts and indices are i32s in places
+        if self.width.checked_mul(self.height).is_none() {
+            return Err(format!(
+                "a {}x{} board has too many cells to count",
+                self.width, self.height
+            ));
+        }
+        if let Some(d) = self.density.filter(|d| !(0.0..=1.0).contains(d)) {
+            return Err(format!("the density is a share of the board, got {d}"));
+        }
+        if !(self.seed_region > 0.0 && self.seed_region <= 1.0) {
+            return Err(format!(
+                "the seed region is a share of the board above 0, got {}",
+                self.seed_region
+            ));
+        }
+        if let Some(image) = self
+            .image
+            .as_ref()
+            .filter(|i| !(0.0..=1.0).contains(&i.threshold))
+        {
+            return Err(format!(
+                "the image threshold is a luminance from 0 to 1, got {}",
+                image.threshold
+            ));
+        }
+        for blob in &self.blobs {
+            blob.validate()?;
+        }
+        if self.worlds < 1 {
+            return Err("there has to be at least one world".to_string());
+        }
+        if self.world_rules.len() >= self.worlds {
+            return Err(format!(
+                "{} extra world rules for {} extra worlds",
+                self.world_rules.len(),
+                self.worlds - 1
+            ));
+        }
+        if self.topology == Topology::Hex && self.backend != Backend::Cpu {
+            return Err("the GPU and HashLife boards only run the square lattice".to_string());
+        }
+        if self.turmite.is_some() && (self.backend != Backend::Cpu || self.worlds > 1) {
+            return Err("the ants walk the one CPU board only".to_string());
+        }
+        Ok(())
+    }
+
+    // whether this backend and lattice can run `rule`, at startup and for
+    // every rule picked later
+    pub fn runs(&self, rule: &Rule) -> Result<(), String> {
+        let square_cpu = self.backend != Backend::Gpu && self.topology == Topology::Square;
+        if rule.wireworld && self.backend == Backend::Gpu {
+            return Err("the GPU board doesn't run Wireworld".to_string());
+        }
+        if rule.larger.is_some() && !square_cpu {
+            return Err("Larger than Life rules run on the CPU's square lattice only".to_string());
+        }
+        if rule.script.is_some() && !square_cpu {
+            return Err("rule scripts run on the CPU's square lattice only".to_string());
+        }
+        if rule.colors > 1 && self.backend != Backend::Cpu {
+            return Err(
+                "the cells' colors are worked out a generation at a time, on the CPU backend only"
+                    .to_string(),
+            );
+        }
+        // the shader only has birth and survival masks, no dying states
+        if rule.states > 2 && self.backend == Backend::Gpu {
+            return Err(format!("the GPU board runs two-state rules, not {rule}"));
+        }
+        // the tree is made of live and dead cells, from the 8 around each
+        if self.backend == Backend::Hashlife && (!rule.life_like() || rule.birth[0]) {
+            return Err(format!(
+                "the HashLife backend runs two-state life-like rules without B0, not {rule}"
+            ));
+        }
+        Ok(())
+    }
+
+    // the preset after (or before) `rule` that this board runs, Conway
+    // running on all of them
+    pub fn next_preset(&self, rule: &Rule, forward: bool) -> Rule {
+        let mut next = rule.next_preset(forward);
+        for _ in 0..PRESETS.len() {
+            if self.runs(&next).is_ok() {
+                break;
+            }
+            next = next.next_preset(forward);
+        }
+        next
+    }
+
+    // -10..10 for the default 20, -1..2 for 3
+    pub fn x_range(&self) -> Range<i32> {
+        centered(self.width)
+    }
+
+    pub fn y_range(&self) -> Range<i32> {
+        centered(self.height)
+    }
+
+    pub fn contains(&self, (x, y): Coord) -> bool {
+        self.x_range().contains(&x) && self.y_range().contains(&y)
+    }
+
+    pub fn area(&self) -> usize {
+        self.width as usize * self.height as usize
+    }
+
+    // where a fresh soup goes: seed_region of the board each way, at least a
+    // cell, around the middle
+    pub fn seed_ranges(&self) -> (Range<i32>, Range<i32>) {
+        let side = |len: i32| ((len as f32 * self.seed_region).round() as i32).clamp(1, len);
+        (centered(side(self.width)), centered(side(self.height)))
+    }
+
+    // live cells in a fresh soup
+    pub fn seed_count(&self) -> usize {
+        let (xs, ys) = self.seed_ranges();
+        let region = xs.len() * ys.len();
+        let count = match self.density {
+            Some(d) => (d * region as f32).round() as usize,
+            None => INIT_ALIVE_COUNT,
+        };
+        // a tiny board may not even have that many cells
+        count.min(region)
+    }
+
+    pub fn coords(&self) -> impl Iterator<Item = Coord> {
+        let ys = self.y_range();
+        self.x_range()
+            .flat_map(move |x| ys.clone().map(move |y| (x, y)))
+    }
+}
+
+#[test]
+fn test_config_validate() {
+    let config = |width, height| Config {
+        width,
+        height,
+        ..Default::default()
+    };
+    assert!(config(20, 20).validate().is_ok());
+    assert!(config(1, 1).validate().is_ok());
+    assert!(config(0, 5).validate().is_err());
+    assert!(config(5, -3).validate().is_err());
+    assert!(config(46_340, 46_340).validate().is_ok());
+    assert!(config(65_536, 65_536).validate().is_err());
+    assert_eq!(config(46_340, 46_340).area(), 46_340 * 46_340);
+    let worlds = |worlds, rules: usize| Config {
+        worlds,
+        world_rules: vec![Rule::default(); rules],
+        ..Default::default()
+    };
+    assert!(worlds(4, 3).validate().is_ok());
+    assert!(worlds(0, 0).validate().is_err());
+    assert!(worlds(2, 2).validate().is_err());
+    let density = |density| Config {
+        density: Some(density),
+        ..Default::default()
+    };
+    assert!(density(1.0).validate().is_ok());
+    assert!(density(1.5).validate().is_err());
+    assert_eq!(density(0.25).seed_count(), 100);
+    assert_eq!(config(4, 4).seed_count(), 16);
+    let region = |seed_region| Config {
+        seed_region,
+        density: Some(0.5),
+        ..Default::default()
+    };
+    assert_eq!(region(0.5).seed_ranges(), (-5..5, -5..5));
+    assert_eq!(region(0.5).seed_count(), 50);
+    assert_eq!(region(0.01).seed_ranges(), (0..1, 0..1));
+    assert!(region(0.0).validate().is_err());
+    assert!(region(1.2).validate().is_err());
+    assert_eq!(config(3, 3).x_range(), -1..2);
+    assert_eq!(config(20, 4).y_range(), -2..2);
+}
+
+#[test]
+fn test_runs() {
+    let on = |backend| Config {
+        backend,
+        ..Default::default()
+    };
+    let brian = Rule::named("brianbrain").unwrap();
+    assert!(on(Backend::Cpu).runs(&brian).is_ok());
+    assert!(on(Backend::Hashlife).runs(&brian).is_err());
+    assert!(on(Backend::Gpu).runs(&Rule::wireworld()).is_err());
+    assert!(on(Backend::Gpu).runs(&brian).is_err());
+    assert!(on(Backend::Hashlife)
+        .runs(&Rule::parse("B03/S23").unwrap())
+        .is_err());
+    // cycling the presets either way never lands on one the board can't run
+    for backend in [Backend::Cpu, Backend::Gpu, Backend::Hashlife] {
+        let config = on(backend);
+        for forward in [true, false] {
+            let mut rule = Rule::default();
+            let mut seen = std::collections::HashSet::new();
+            for _ in 0..2 * PRESETS.len() {
+                rule = config.next_preset(&rule, forward);
+                assert_eq!(config.runs(&rule), Ok(()), "{rule} on {backend:?}");
+                seen.insert(rule.to_string());
+            }
+            let all = seen.len() == PRESETS.len();
+            assert_eq!(all, backend == Backend::Cpu, "{backend:?}");
+        }
+    }
+    let hashlife = on(Backend::Hashlife);
+    assert_eq!(
+        hashlife.next_preset(&Rule::default(), false),
+        Rule::named("maze").unwrap()
+    );
+}
+
+// a disk of random soup, each cell in it alive with the given chance
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Copy)]
+pub struct Blob {
+    pub center: Coord,
+    // cells whose distance to the center is at most this, 0 is the center alone
+    pub radius: i32,
+    pub density: f32,
+}
+
+impl Blob {
+    // `x,y,radius,density`, as in `--blob -5,3,4,0.5`
+    pub fn parse(s: &str) -> Result<Blob, String> {
+        let parts: Vec<_> = s.split(',').map(str::trim).collect();
+        let [x, y, radius, density] = parts[..] else {
+            return Err(format!("expected x,y,radius,density, got {s:?}"));
+        };
+        let int = |p: &str| p.parse::<i32>().map_err(|e| format!("{p:?}: {e}"));
+        let blob = Blob {
+            center: (int(x)?, int(y)?),
+            radius: int(radius)?,
+            density: density.parse().map_err(|e| format!("{density:?}: {e}"))?,
+        };
+        blob.validate()?;
+        Ok(blob)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.radius < 0 {
+            return Err(format!(
+                "a blob radius can't be negative, got {}",
+                self.radius
+            ));
+        }
+        if !(0.0..=1.0).contains(&self.density) {
+            return Err(format!("a blob density is a chance, got {}", self.density));
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn test_blob_parse() {
+    let blob = Blob::parse("-5, 3,4,0.5").unwrap();
+    assert_eq!((blob.center, blob.radius, blob.density), ((-5, 3), 4, 0.5));
+    assert!(Blob::parse("1,2,3").is_err());
+    assert!(Blob::parse("1,2,-3,0.5").is_err());
+    assert!(Blob::parse("1,2,3,2").is_err());
+    assert!(Blob::parse("a,2,3,0.5").is_err());
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq, Clone, Copy)]
+pub enum ColorInit {
+    // each cell drawn from the seeded rng
+    #[default]
+    Random,
+    // alternating cells, or 2x2 tiles with four colors
+    Checkerboard,
+    // left/right halves, or quadrants with four colors
+    Halves,
+}
+
+// Bevy's FixedUpdate loop, or an accumulator of frame time in Update that
+// runs the FixedUpdate schedule itself; both tick at `FixedTime::period`
+#[derive(clap::ValueEnum, Serialize, Deserialize, Debug, Default, PartialEq, Clone, Copy)]
+pub enum Stepping {
+    #[default]
+    Fixed,
+    Manual,
+}
+
+// the CPU step over LiveCells and the cell entities, a compute shader over a
+// pair of textures the app draws from, or a HashLife tree skipping ahead
+// 2^super_step generations a tick; with the GPU the cells stay as seeded
+#[derive(clap::ValueEnum, Serialize, Deserialize, Debug, Default, PartialEq, Clone, Copy)]
+pub enum Backend {
+    #[default]
+    Cpu,
+    Gpu,
+    Hashlife,
+}
+
+// the 8 cells around a square, or the 6 around a hexagon: on the hex lattice
+// the odd rows sit half a cell to the right of the even ones, so which cells
+// above and below touch depends on the row
+#[derive(clap::ValueEnum, Serialize, Deserialize, Debug, Default, PartialEq, Clone, Copy)]
+pub enum Topology {
+    #[default]
+    Square,
+    Hex,
+}
+
+const SQUARE_NEIGHBORS: [Coord; 8] = [
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, -1),
+    (0, 1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+];
+const EVEN_ROW_NEIGHBORS: [Coord; 6] = [(-1, 0), (1, 0), (-1, 1), (0, 1), (-1, -1), (0, -1)];
+const ODD_ROW_NEIGHBORS: [Coord; 6] = [(-1, 0), (1, 0), (0, 1), (1, 1), (0, -1), (1, -1)];
+
+impl Topology {
+    // from a cell in row `y` to its neighbors
+    pub fn offsets(self, y: i32) -> &'static [Coord] {
+        match self {
+            Topology::Square => &SQUARE_NEIGHBORS,
+            Topology::Hex if y.rem_euclid(2) == 0 => &EVEN_ROW_NEIGHBORS,
+            Topology::Hex => &ODD_ROW_NEIGHBORS,
+        }
+    }
+
+    // how far right of the grid row `y` is drawn, in cells
+    pub fn row_shift(self, y: i32) -> f32 {
+        match self {
+            Topology::Hex if y.rem_euclid(2) == 1 => 0.5,
+            _ => 0.0,
+        }
+    }
+}
+
+#[test]
+fn test_topology() {
+    assert_eq!(Topology::Square.offsets(3).len(), 8);
+    // every hex neighbor has the cell as a neighbor back
+    for y in [-3, 0, 1, 4] {
+        let hex = Topology::Hex.offsets(y);
+        assert_eq!(hex.len(), 6);
+        for (dx, dy) in hex {
+            let back = Topology::Hex.offsets(y + dy);
+            assert!(back.contains(&(-dx, -dy)), "{y} {dx} {dy}");
+        }
+    }
+    assert_eq!(Topology::Hex.row_shift(-1), 0.5);
+    assert_eq!(Topology::Square.row_shift(-1), 0.0);
+}
+
+// what a neighbor past the border is, along one axis
+#[derive(Default, Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Boundary {
+    // a ring of cells just outside the board that are always dead: they count
+    // as neighbors, always as zero, and nothing is ever born there
+    #[default]
+    Dead,
+    // the cell on the far side of the board
+    Wrap,
+    // the board reflected at the border, so past it is the cell just inside
+    Mirror,
+    // no border at all: cells live on past it, the board is where they start
+    Open,
+}
+
+// the boundary policy of each axis, dead all around by default
+#[derive(Resource, Default, Debug, PartialEq, Clone, Copy)]
+pub struct EdgeMode {
+    pub x: Boundary,
+    pub y: Boundary,
+}
+
+impl EdgeMode {
+    // an unbounded universe; only rules with B0, which the whole board takes
+    // part in, keep to the board
+    pub fn open() -> EdgeMode {
+        EdgeMode {
+            x: Boundary::Open,
+            y: Boundary::Open,
+        }
+    }
+
+    pub fn wrapping(x: bool, y: bool) -> EdgeMode {
+        let boundary = |wrap| if wrap { Boundary::Wrap } else { Boundary::Dead };
+        EdgeMode {
+            x: boundary(x),
+            y: boundary(y),
+        }
+    }
+
+    // the board cell a neighbor coordinate stands for, None for the dead ring
+    pub fn resolve(&self, (x, y): Coord, config: &Config) -> Option<Coord> {
+        if config.contains((x, y)) {
+            return Some((x, y));
+        }
+        let axis = |v: i32, range: Range<i32>, boundary: Boundary| {
+            if range.contains(&v) {
+                return Some(v);
+            }
+            match boundary {
+                Boundary::Dead => None,
+                Boundary::Open => Some(v),
+                Boundary::Wrap => {
+                    Some((v - range.start).rem_euclid(range.len() as i32) + range.start)
+                }
+                Boundary::Mirror => {
+                    let reflected = if v < range.start {
+                        2 * range.start - 1 - v
+                    } else {
+                        2 * range.end - 1 - v
+                    };
+                    Some(reflected.clamp(range.start, range.end - 1))
+                }
+            }
+        };
+        Some((
+            axis(x, config.x_range(), self.x)?,
+            axis(y, config.y_range(), self.y)?,
+        ))
+    }
+
+    // bounded -> cylinder around x -> cylinder around y -> torus -> mirror
+    pub fn cycle(&mut self) {
+        use Boundary::*;
+        (self.x, self.y) = match (self.x, self.y) {
+            (Dead, Dead) => (Wrap, Dead),
+            (Wrap, Dead) => (Dead, Wrap),
+            (Dead, Wrap) => (Wrap, Wrap),
+            (Wrap, Wrap) => (Mirror, Mirror),
+            _ => (Dead, Dead),
+        };
+    }
+}
+
+impl std::fmt::Display for EdgeMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        use Boundary::*;
+        let name = match (self.x, self.y) {
+            (Dead, Dead) => "bounded",
+            (Wrap, Dead) => "cylinder (x wraps)",
+            (Dead, Wrap) => "cylinder (y wraps)",
+            (Wrap, Wrap) => "torus",
+            (Mirror, Mirror) => "mirror",
+            (Open, Open) => "unbounded",
+            _ => "mixed",
+        };
+        write!(f, "{name}")
+    }
+}
@@ -0,0 +1,51 @@
+// one generation of a life-like rule, from `input` into `output`; a cell is
+// alive where its alpha is set
+
+struct Params {
+    // bit n set: born / survives with n live neighbors
+    birth: u32,
+    survival: u32,
+    wrap_x: u32,
+    wrap_y: u32,
+    size: vec2<u32>,
+    alive: vec4<f32>,
+}
+
+@group(0) @binding(0) var input: texture_2d<f32>;
+@group(0) @binding(1) var output: texture_storage_2d<rgba8unorm, write>;
+@group(0) @binding(2) var<uniform> params: Params;
+
+fn alive_at(x: i32, y: i32) -> u32 {
+    let size = vec2<i32>(params.size);
+    var p = vec2<i32>(x, y);
+    if params.wrap_x != 0u {
+        p.x = (p.x + size.x) % size.x;
+    }
+    if params.wrap_y != 0u {
+        p.y = (p.y + size.y) % size.y;
+    }
+    if p.x < 0 || p.y < 0 || p.x >= size.x || p.y >= size.y {
+        return 0u;
+    }
+    return u32(textureLoad(input, p, 0).a > 0.5);
+}
+
+@compute @workgroup_size(8, 8, 1)
+fn update(@builtin(global_invocation_id) id: vec3<u32>) {
+    if id.x >= params.size.x || id.y >= params.size.y {
+        return;
+    }
+    let x = i32(id.x);
+    let y = i32(id.y);
+    var count = 0u;
+    for (var dx = -1; dx <= 1; dx += 1) {
+        for (var dy = -1; dy <= 1; dy += 1) {
+            if dx != 0 || dy != 0 {
+                count += alive_at(x + dx, y + dy);
+            }
+        }
+    }
+    let mask = select(params.birth, params.survival, alive_at(x, y) == 1u);
+    let next = ((mask >> count) & 1u) == 1u;
+    textureStore(output, vec2<i32>(x, y), select(vec4<f32>(0.0), params.alive, next));
+}
@@ -0,0 +1,135 @@
+use bevy::prelude::Resource;
+use rand::Rng;
+
+use std::collections::{HashMap, HashSet};
+
+pub type Coord3 = (i32, i32, i32);
+
+// Bays' four-digit 3D rules: a live cell survives with `survive_low` to
+// `survive_high` of its 26 neighbors alive, a dead one is born with
+// `birth_low` to `birth_high`; 4555 and 5766 are the well known ones
+#[derive(Resource, Debug, PartialEq, Clone, Copy)]
+pub struct Rule3 {
+    pub survive_low: u8,
+    pub survive_high: u8,
+    pub birth_low: u8,
+    pub birth_high: u8,
+}
+
+impl Default for Rule3 {
+    fn default() -> Self {
+        Rule3::parse("4555").unwrap()
+    }
+}
+
+impl Rule3 {
+    // "4555", or with commas for counts past 9: "10,21,10,21"
+    pub fn parse(s: &str) -> Result<Rule3, String> {
+        let s = s.trim();
+        let counts: Vec<u8> = if s.contains(',') {
+            s.split(',')
+                .map(|n| n.trim().parse().map_err(|e| format!("{n:?}: {e}")))
+                .collect::<Result<_, _>>()?
+        } else {
+            s.chars()
+                .map(|c| {
+                    c.to_digit(10)
+                        .map(|d| d as u8)
+                        .ok_or_else(|| format!("bad neighbor count {c:?}"))
+                })
+                .collect::<Result<_, _>>()?
+        };
+        let [survive_low, survive_high, birth_low, birth_high] = counts[..] else {
+            return Err(format!("expected four counts in 3D rule {s:?}"));
+        };
+        if survive_low > survive_high || birth_low > birth_high || survive_high.max(birth_high) > 26
+        {
+            return Err(format!("3D rule {s:?} needs low <= high <= 26"));
+        }
+        Ok(Rule3 {
+            survive_low,
+            survive_high,
+            birth_low,
+            birth_high,
+        })
+    }
+
+    pub fn next(&self, alive: bool, neighbors: u8) -> bool {
+        if alive {
+            (self.survive_low..=self.survive_high).contains(&neighbors)
+        } else {
+            (self.birth_low..=self.birth_high).contains(&neighbors)
+        }
+    }
+}
+
+// a cube `side` cells across, centered on the origin like Config's board
+pub fn cube_range(side: i32) -> std::ops::Range<i32> {
+    -side / 2..side - side / 2
+}
+
+// the soup: each cell of a `radius`-wide block in the middle alive with
+// `density`, leaving room around it to grow into
+pub fn seed3(side: i32, radius: i32, density: f64, rng: &mut impl Rng) -> HashSet<Coord3> {
+    let r = radius.min(side / 2);
+    let span = -r..r.max(1);
+    let mut alives = HashSet::new();
+    for x in span.clone() {
+        for y in span.clone() {
+            for z in span.clone() {
+                if rng.gen_bool(density) {
+                    alives.insert((x, y, z));
+                }
+            }
+        }
+    }
+    alives
+}
+
+// the next generation in the cube, with nothing alive past its faces
+pub fn step3(alives: &HashSet<Coord3>, rule: &Rule3, side: i32) -> HashSet<Coord3> {
+    let range = cube_range(side);
+    let mut counts: HashMap<Coord3, u8> = HashMap::new();
+    for &(x, y, z) in alives {
+        for dx in -1..=1 {
+            for dy in -1..=1 {
+                for dz in -1..=1 {
+                    if (dx, dy, dz) != (0, 0, 0) {
+                        *counts.entry((x + dx, y + dy, z + dz)).or_default() += 1;
+                    }
+                }
+            }
+        }
+    }
+    let inside = |(x, y, z): &&Coord3| range.contains(x) && range.contains(y) && range.contains(z);
+    let survivors = alives
+        .iter()
+        .filter(|c| rule.next(true, counts.get(c).copied().unwrap_or(0)));
+    let births = counts
+        .iter()
+        .filter(|(c, n)| !alives.contains(c) && rule.next(false, **n))
+        .map(|(c, _)| c);
+    survivors.chain(births).filter(inside).copied().collect()
+}
+
+#[test]
+fn test_step3() {
+    let rule = Rule3::parse("5766").unwrap();
+    assert_eq!(
+        (rule.survive_low, rule.survive_high, rule.birth_low),
+        (5, 7, 6)
+    );
+    assert_eq!(Rule3::parse("10,21,10,21").unwrap().birth_high, 21);
+    assert!(Rule3::parse("455").is_err());
+    assert!(Rule3::parse("5455").is_err());
+    // a 2x2x2 block under 4555: each cell has 7 neighbors and dies, each cell
+    // touching a face of it has 4 and none is born
+    let block: HashSet<_> = (0..2)
+        .flat_map(|x| (0..2).flat_map(move |y| (0..2).map(move |z| (x, y, z))))
+        .collect();
+    assert!(step3(&block, &Rule3::default(), 10).is_empty());
+    // under 4755 it's still, and a cube too small for it clips it
+    let still = Rule3::parse("4755").unwrap();
+    assert_eq!(step3(&block, &still, 10), block);
+    assert!(step3(&block, &still, 2).len() < block.len());
+}
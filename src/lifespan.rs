@@ -0,0 +1,98 @@
+use bevy::prelude::*;
+use bevy_game_of_life::{Config, Coord, LiveCells};
+
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+
+use crate::Dashboard;
+
+// generations every cell of the main board has been alive for, the seed
+// included; starts over whenever the board goes back to an earlier round, as
+// after G or scrubbing
+#[derive(Resource, Default, Debug)]
+pub struct Lifespans {
+    alive_for: HashMap<Coord, usize>,
+    // round counted last, so a round is never counted twice
+    round: Option<usize>,
+}
+
+impl Lifespans {
+    fn record(&mut self, round: usize, alives: &HashSet<Coord>) {
+        match self.round {
+            Some(last) if round == last => return,
+            Some(last) if round < last => self.alive_for.clear(),
+            _ => {}
+        }
+        self.round = Some(round);
+        for c in alives {
+            *self.alive_for.entry(*c).or_default() += 1;
+        }
+    }
+
+    // `x,y,generations` for every cell of the board, top row first
+    fn to_csv(&self, config: &Config) -> String {
+        let mut csv = "x,y,generations\n".to_string();
+        for y in config.y_range().rev() {
+            for x in config.x_range() {
+                let n = self.alive_for.get(&(x, y)).copied().unwrap_or(0);
+                writeln!(csv, "{x},{y},{n}").unwrap();
+            }
+        }
+        csv
+    }
+}
+
+#[test]
+fn test_lifespans() {
+    use bevy_game_of_life::{step, EdgeMode, Rule};
+
+    let config = Config {
+        width: 3,
+        height: 3,
+        ..Default::default()
+    };
+    let mut lifespans = Lifespans::default();
+    let mut blinker = HashSet::from([(-1, 0), (0, 0), (1, 0)]);
+    for round in 0..3 {
+        lifespans.record(round, &blinker);
+        // a frame without a tick changes nothing
+        lifespans.record(round, &blinker);
+        blinker = step(&blinker, &Rule::default(), &config, EdgeMode::default());
+    }
+    // the middle all three generations, the ends of the row in 0 and 2, the
+    // ends of the column in 1 only
+    let csv = lifespans.to_csv(&config);
+    assert_eq!(
+        csv,
+        "x,y,generations\n\
+         -1,1,0\n0,1,1\n1,1,0\n\
+         -1,0,2\n0,0,3\n1,0,2\n\
+         -1,-1,0\n0,-1,1\n1,-1,0\n"
+    );
+    // back at the seed it all starts over
+    lifespans.record(0, &HashSet::from([(1, 1)]));
+    assert_eq!(lifespans.alive_for, HashMap::from([((1, 1), 1)]));
+}
+
+// at startup and after every tick
+pub fn count_lifespans(db: Res<Dashboard>, live: Res<LiveCells>, mut lifespans: ResMut<Lifespans>) {
+    lifespans.record(db.round, &live.0);
+}
+
+// Ctrl+E writes the counts so far to the working directory, for plotting elsewhere
+pub fn export_lifespans(
+    keys: Res<Input<KeyCode>>,
+    config: Res<Config>,
+    db: Res<Dashboard>,
+    lifespans: Res<Lifespans>,
+) {
+    let ctrl = keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+    if !(ctrl && keys.just_pressed(KeyCode::E)) {
+        return;
+    }
+    let path = format!("lifespans-{}.csv", db.round);
+    match std::fs::write(&path, lifespans.to_csv(&config)) {
+        Ok(()) => info!("exported {path}"),
+        Err(e) => error!("can't write {path}: {e}"),
+    }
+}
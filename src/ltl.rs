@@ -0,0 +1,262 @@
+use std::collections::HashSet;
+use std::ops::Range;
+
+use crate::{bounding_box, Boundary, Config, Coord, EdgeMode};
+
+// which cells within the radius count
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Neighborhood {
+    // the (2r+1)x(2r+1) square
+    Moore,
+    // the diamond, |dx| + |dy| <= r
+    VonNeumann,
+    // the disk, dx² + dy² <= r² + r as Golly draws it
+    Circular,
+}
+
+// a Larger than Life rule, written the way Golly does: "R5,C0,M1,S34..58,
+// B34..45,NM" is Bugs. A live cell survives with a count in the S range and a
+// dead one is born with one in the B range; the count takes in the cell
+// itself with M1
+#[derive(Debug, PartialEq, Clone)]
+pub struct Larger {
+    pub radius: i32,
+    pub neighborhood: Neighborhood,
+    pub middle: bool,
+    pub survival: (u32, u32),
+    pub birth: (u32, u32),
+}
+
+// past that the counts and the tables get silly
+pub const MAX_RADIUS: i32 = 500;
+
+impl Larger {
+    // the rule and its state count, C0 and C2 being plain alive / dead
+    pub fn parse(s: &str) -> Result<(Larger, u8), String> {
+        let mut larger = Larger {
+            radius: 1,
+            neighborhood: Neighborhood::Moore,
+            middle: false,
+            survival: (0, 0),
+            birth: (0, 0),
+        };
+        let mut states = 2;
+        let (mut has_s, mut has_b) = (false, false);
+        let range = |v: &str| -> Result<(u32, u32), String> {
+            let (low, high) = v.split_once("..").unwrap_or((v, v));
+            let number = |n: &str| n.trim().parse::<u32>().map_err(|e| format!("{n:?}: {e}"));
+            Ok((number(low)?, number(high)?))
+        };
+        for part in s.trim().split(',') {
+            let part = part.trim();
+            let (key, value) = part.split_at(part.len().min(1));
+            match key.to_ascii_uppercase().as_str() {
+                "R" => match value.parse() {
+                    Ok(r) if (1..=MAX_RADIUS).contains(&r) => larger.radius = r,
+                    _ => return Err(format!("bad radius {value:?}, expected 1 to {MAX_RADIUS}")),
+                },
+                "C" => match value.parse::<u8>() {
+                    Ok(0..=2) => states = 2,
+                    Ok(n) => states = n,
+                    Err(e) => return Err(format!("bad state count {value:?}: {e}")),
+                },
+                "M" => larger.middle = value == "1",
+                "S" => (larger.survival, has_s) = (range(value)?, true),
+                "B" => (larger.birth, has_b) = (range(value)?, true),
+                "N" => {
+                    larger.neighborhood = match value.to_ascii_uppercase().as_str() {
+                        "M" => Neighborhood::Moore,
+                        "N" => Neighborhood::VonNeumann,
+                        "C" => Neighborhood::Circular,
+                        _ => {
+                            return Err(format!("unknown neighborhood {value:?}, try NM, NN or NC"))
+                        }
+                    }
+                }
+                _ => return Err(format!("unexpected {part:?} in rule {s:?}")),
+            }
+        }
+        if !(has_s && has_b) {
+            return Err(format!("rule {s:?} needs both an S and a B range"));
+        }
+        for (low, high) in [larger.survival, larger.birth] {
+            if low > high || low > larger.cells() {
+                return Err(format!(
+                    "range {low}..{high} in rule {s:?} can't be met, at most {} cells count",
+                    larger.cells()
+                ));
+            }
+        }
+        Ok((larger, states))
+    }
+
+    // how many cells the count can reach
+    fn cells(&self) -> u32 {
+        let r = self.radius;
+        let mut total = 0;
+        for dy in -r..=r {
+            total += 2 * self.reach(dy) + 1;
+        }
+        total as u32 - !self.middle as u32
+    }
+
+    // how far the neighborhood goes either side in the row `dy` away
+    fn reach(&self, dy: i32) -> i32 {
+        let r = self.radius;
+        match self.neighborhood {
+            Neighborhood::Moore => r,
+            Neighborhood::VonNeumann => r - dy.abs(),
+            Neighborhood::Circular => ((r * r + r - dy * dy) as f64).sqrt() as i32,
+        }
+    }
+
+    pub fn next_alive(&self, alive: bool, count: u32) -> bool {
+        let (low, high) = if alive { self.survival } else { self.birth };
+        (low..=high).contains(&count)
+    }
+
+    // a string Larger::parse reads back
+    pub fn to_rulestring(&self, states: u8) -> String {
+        let states = if states > 2 { states } else { 0 };
+        let n = match self.neighborhood {
+            Neighborhood::Moore => 'M',
+            Neighborhood::VonNeumann => 'N',
+            Neighborhood::Circular => 'C',
+        };
+        let (s, b) = (self.survival, self.birth);
+        format!(
+            "R{},C{states},M{},S{}..{},B{}..{},N{n}",
+            self.radius, self.middle as u8, s.0, s.1, b.0, b.1
+        )
+    }
+}
+
+// live counts over rectangles of a grid in O(1) each: `table` holds the live
+// cells above and left of each corner
+struct SummedArea {
+    left: i32,
+    top: i32,
+    width: usize,
+    table: Vec<u32>,
+}
+
+impl SummedArea {
+    fn new(xs: Range<i32>, ys: Range<i32>, alive: impl Fn(Coord) -> bool) -> SummedArea {
+        let width = xs.len() + 1;
+        let mut table = vec![0; width * (ys.len() + 1)];
+        for (j, y) in ys.clone().enumerate() {
+            let mut row = 0;
+            for (i, x) in xs.clone().enumerate() {
+                row += alive((x, y)) as u32;
+                table[(j + 1) * width + i + 1] = table[j * width + i + 1] + row;
+            }
+        }
+        SummedArea {
+            left: xs.start,
+            top: ys.start,
+            width,
+            table,
+        }
+    }
+
+    // live cells in x0..=x1, y0..=y1, which have to be inside the grid
+    fn sum(&self, (x0, y0): Coord, (x1, y1): Coord) -> u32 {
+        let at = |x: i32, y: i32| {
+            self.table[(y - self.top) as usize * self.width + (x - self.left) as usize]
+        };
+        at(x1 + 1, y1 + 1) + at(x0, y0) - at(x0, y1 + 1) - at(x1 + 1, y0)
+    }
+}
+
+fn reach_axis(
+    board: Range<i32>,
+    lived: Option<Range<i32>>,
+    boundary: Boundary,
+    r: i32,
+) -> Range<i32> {
+    match (boundary, lived) {
+        // the rule can't reach further than r past the live cells
+        (Boundary::Open, Some(lived)) => lived.start - r..lived.end + r,
+        (Boundary::Open, None) => 0..0,
+        _ => board,
+    }
+}
+
+// the next generation under `larger`: the board's cells (out to r past the
+// live ones along open edges) decided from a summed-area table of the board
+// padded by r on every side, through the edges
+pub fn step_larger(
+    alives: &HashSet<Coord>,
+    larger: &Larger,
+    config: &Config,
+    edge: EdgeMode,
+) -> HashSet<Coord> {
+    let r = larger.radius;
+    let lived = bounding_box(alives);
+    let xs = reach_axis(
+        config.x_range(),
+        lived.map(|((x0, _), (x1, _))| x0..x1 + 1),
+        edge.x,
+        r,
+    );
+    let ys = reach_axis(
+        config.y_range(),
+        lived.map(|((_, y0), (_, y1))| y0..y1 + 1),
+        edge.y,
+        r,
+    );
+    let table = SummedArea::new(xs.start - r..xs.end + r, ys.start - r..ys.end + r, |c| {
+        edge.resolve(c, config).is_some_and(|c| alives.contains(&c))
+    });
+    let mut next = HashSet::new();
+    for y in ys {
+        for x in xs.clone() {
+            let alive = alives.contains(&(x, y));
+            let mut count = match larger.neighborhood {
+                Neighborhood::Moore => table.sum((x - r, y - r), (x + r, y + r)),
+                _ => (-r..=r)
+                    .map(|dy| {
+                        let reach = larger.reach(dy);
+                        table.sum((x - reach, y + dy), (x + reach, y + dy))
+                    })
+                    .sum(),
+            };
+            if alive && !larger.middle {
+                count -= 1;
+            }
+            if larger.next_alive(alive, count) {
+                next.insert((x, y));
+            }
+        }
+    }
+    next
+}
+
+#[test]
+fn test_step_larger() {
+    let (bugs, states) = Larger::parse("R5,C0,M1,S34..58,B34..45,NM").unwrap();
+    assert_eq!((bugs.radius, states, bugs.middle), (5, 2, true));
+    assert_eq!((bugs.survival, bugs.birth), ((34, 58), (34, 45)));
+    assert_eq!(bugs.to_rulestring(states), "R5,C0,M1,S34..58,B34..45,NM");
+    assert_eq!(bugs.cells(), 121);
+    assert!(Larger::parse("R5,C0,M1,S34..58").is_err());
+    assert!(Larger::parse("R0,C0,M1,S1,B1").is_err());
+    assert!(Larger::parse("R1,C0,M0,S9,B3").is_err());
+    let (diamond, _) = Larger::parse("R2,C0,M0,S1..2,B3,NN").unwrap();
+    assert_eq!(diamond.cells(), 12);
+    let (disk, _) = Larger::parse("R2,C0,M0,S1,B1,NC").unwrap();
+    assert_eq!(disk.cells(), 20);
+
+    // radius 1 without the middle is plain B3/S23, edges and all
+    let (life, _) = Larger::parse("R1,C0,M0,S2..3,B3..3,NM").unwrap();
+    let config = Config::default();
+    let soup = crate::seed(&mut rand::thread_rng(), &config);
+    for edge in [
+        EdgeMode::default(),
+        EdgeMode::wrapping(true, false),
+        EdgeMode::open(),
+    ] {
+        let expected = crate::step(&soup, &crate::Rule::default(), &config, edge);
+        assert_eq!(step_larger(&soup, &life, &config, edge), expected);
+    }
+}
@@ -1,196 +1,2586 @@
+use bevy::audio::AddAudioSource;
+use bevy::diagnostic::{FrameTimeDiagnosticsPlugin, LogDiagnosticsPlugin};
+use bevy::ecs::system::SystemParam;
 use bevy::prelude::*;
-use rand::random;
+use bevy::window::PrimaryWindow;
+use bevy_game_of_life::pattern::{load_pattern, place_pattern, to_format, Pattern};
+use bevy_game_of_life::{
+    alive_neighbor_count, bounding_box, cap_population, census, dead_or_alive, forecast,
+    forecast_within, forecasts, identify, percentiles, quadrant_counts, set_bytes, starting_cells,
+    step, step_once, tune_density, Ants, Blob, Boundary, Cell, CellEvent, Config, Coord, Dashboard,
+    EdgeMode, FlatOut, Forecast, ImageSeed, Lineage, LiveCells, PendingCells, PeriodDetector, Rule,
+    Rule3, SimPlugin, SimRng, SimSet, SimState, State, StepTiming, Stepping, Symmetry, Topology,
+    Trend, Turmite, FORECAST_CAP,
+};
+use bevy_game_of_life::{hashlife_backend, Backend, HashLife, MAX_SUPER_STEP};
+use clap::{error::ErrorKind, CommandFactory, Parser};
+use serde::{Deserialize, Serialize};
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+mod bench;
+mod camera;
+mod compare;
+mod controls;
+mod corners;
+mod cube;
+mod diagnostics;
+mod draw;
+mod explore;
+mod export;
+mod gpu;
+mod graph;
+mod heat;
+mod inspect;
+mod labels;
+mod lifespan;
+mod menu;
+mod minimap;
+mod net;
+mod pop;
+mod ramp;
+mod record;
+mod replay;
+mod resize;
+mod ribbon;
+mod save;
+mod screenshot;
+mod scrub;
+mod select;
+mod session;
+mod sound;
+mod soups;
+mod stats;
+mod texture;
+mod themes;
+mod touch;
+mod trail;
+mod turmite;
+mod wire;
+mod worlds;
+
+use draw::{DrawState, DrawTool, Stamp};
+use ramp::ColorRamp;
 
 const SIZE: f32 = 20.0;
+// default space between two cells, `,` and `.` change it while running
 const GAP: f32 = 4.0;
-const HALF_LEN: i32 = 10;
-const INIT_ALIVE_COUNT: usize = 64;
 const TICK: f32 = 1.0;
+// shortest and longest tick `=` and `-` go to
+const MIN_TICK: f32 = 0.01;
+const MAX_TICK: f32 = 10.0;
+// seconds the generations per second on the dashboard are counted over
+const THROUGHPUT_WINDOW: f32 = 1.0;
+// seconds between two window title updates
+const TITLE_REFRESH: f32 = 0.5;
+// share of the board changing in a tick at which the adaptive speed is slowest
+const BUSIEST_ACTIVITY: f32 = 0.1;
+// seconds the border pulse takes to fade at most
+const PULSE_SECS: f32 = 0.15;
+// hue turn between two growth rings, in degrees
+const RING_HUE_STEP: f32 = 15.0;
+// generations alive by which a cell has gone fully dark in the age view
+const OLD_AGE: usize = 100;
+// the live cells of Immigration (the first two) and QuadLife
+const LINEAGE_COLORS: [Color; 4] = [
+    Color::rgb(0.95, 0.3, 0.25),
+    Color::rgb(0.3, 0.55, 1.0),
+    Color::rgb(0.3, 0.85, 0.35),
+    Color::rgb(0.95, 0.8, 0.2),
+];
 
-type Coord = (i32, i32);
+#[derive(Parser, Debug)]
+#[command(about = "A deadly simple game of life")]
+struct Cli {
+    /// Board width in cells
+    #[arg(long, default_value_t = Config::default().width, allow_negative_numbers = true)]
+    width: i32,
+    /// Board height in cells
+    #[arg(long, default_value_t = Config::default().height, allow_negative_numbers = true)]
+    height: i32,
+    /// RLE (.rle), plaintext (.cells), Macrocell (.mc) or Life 1.06 pattern to start from, centered on the board
+    #[arg(long)]
+    pattern: Option<PathBuf>,
+    /// Cell `x,y` the middle of the pattern goes to (default the origin)
+    #[arg(long, value_parser = parse_coord, allow_hyphen_values = true, default_value = "0,0")]
+    pattern_at: Coord,
+    /// PNG to start from instead, stretched over the board, a cell alive where the picture is dark
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["pattern", "stdin"])]
+    image: Option<PathBuf>,
+    /// Luminance from 0 (black) to 1 (white) under which an `--image` cell is alive
+    #[arg(long, default_value_t = 0.5, requires = "image")]
+    image_threshold: f32,
+    /// Dither the `--image`, so its grays come out as scattered cells instead of all or nothing
+    #[arg(long, requires = "image")]
+    dither: bool,
+    /// Bring the light parts of the `--image` alive instead of the dark ones
+    #[arg(long, requires = "image")]
+    image_invert: bool,
+    /// Read the starting pattern from stdin instead, plaintext, Life 1.06 or RLE
+    #[arg(long, conflicts_with = "pattern")]
+    stdin: bool,
+    /// Share of the board the random soup starts alive, 0 to 1 (default: 64 cells)
+    #[arg(long)]
+    density: Option<f32>,
+    /// Share of the board's width and height, around the middle, the random soup goes in (default 1, all of it)
+    #[arg(long, default_value_t = 1.0)]
+    seed_region: f32,
+    /// Seed a random disk `x,y,radius,density` instead of the whole board, repeatable
+    #[arg(
+        long = "blob",
+        value_parser = Blob::parse,
+        allow_hyphen_values = true,
+        conflicts_with_all = ["density", "tune_density"]
+    )]
+    blobs: Vec<Blob>,
+    /// Start with every cell dead and paused, to draw a pattern from scratch
+    #[arg(long, alias = "edit", conflicts_with_all = ["pattern", "stdin", "density", "blobs", "warmup"])]
+    empty: bool,
+    /// Print the density whose soups average this many live cells over their first
+    /// generations, then exit
+    #[arg(long, value_name = "TARGET")]
+    tune_density: Option<f32>,
+    /// Seed of the random soup, as logged at startup, to run it again
+    #[arg(long)]
+    seed: Option<u64>,
+    /// Generations kept to scrub or rewind back through (Backspace while paused)
+    #[arg(long, value_name = "N", default_value_t = scrub::Timeline::history())]
+    history: usize,
+    /// Width in pixels of the board PNG F12 writes, whatever the window's size
+    #[arg(long, value_name = "PX", default_value_t = 1024, value_parser = clap::value_parser!(u32).range(1..))]
+    screenshot_width: u32,
+    /// Milliseconds each frame of an F10 recording shows for
+    #[arg(long, value_name = "MS", default_value_t = 100)]
+    record_delay: u16,
+    /// Width in pixels of the frames of an F10 recording
+    #[arg(long, value_name = "PX", default_value_t = 400, value_parser = clap::value_parser!(u32).range(1..))]
+    record_width: u32,
+    /// Pick up a run saved with Shift+F9: its board, round, seed and rule
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["pattern", "stdin", "empty", "config"])]
+    load: Option<PathBuf>,
+    /// Write every edit to the board, with the generation it came in on, and every speed change to this file, to play back with `--replay`
+    #[arg(long, value_name = "FILE")]
+    record_edits: Option<PathBuf>,
+    /// Set up as a `--record-edits` file was recorded, seed and all, and make its edits again as the generations come
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["config", "load", "record_edits"])]
+    replay: Option<PathBuf>,
+    /// Run this many generations without a window, as fast as they go, and print a summary
+    #[arg(long, requires = "generations", conflicts_with_all = ["tune_density", "assert_extinct_by"])]
+    headless: bool,
+    /// Generations a --headless run goes for
+    #[arg(long, value_name = "N", requires = "headless")]
+    generations: Option<usize>,
+    /// Write the board a --headless run ends on to this file, as RLE, or plaintext or Life 1.06 by a .cells or .lif extension
+    #[arg(long, value_name = "FILE", requires = "headless")]
+    output: Option<PathBuf>,
+    /// Write a row per generation to this file as the run goes: round, population, births, deaths and the
+    /// live bounding box, as CSV, or JSON lines for a .json file
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["life3d", "soup_search", "tune_density", "assert_extinct_by"])]
+    stats_out: Option<PathBuf>,
+    /// Run the board for others to join with --join, on this address (like 0.0.0.0:7777): they're sent
+    /// every change and what they draw lands here
+    #[arg(long, value_name = "ADDR", conflicts_with_all = ["join", "headless", "soup_search", "tune_density", "assert_extinct_by", "life3d"])]
+    host: Option<String>,
+    /// Show the board a --host runs at this address, drawing on it instead of running one here
+    #[arg(long, value_name = "ADDR", conflicts_with_all = ["headless", "soup_search", "tune_density", "assert_extinct_by", "life3d", "load"])]
+    join: Option<String>,
+    /// Run headless and exit nonzero unless the board dies out or settles by this generation
+    #[arg(long, value_name = "N", conflicts_with = "tune_density")]
+    assert_extinct_by: Option<usize>,
+    /// Time the standard workloads without a window, an acorn and a dense soup on a 512x512 board,
+    /// and print how long a generation spends in each phase; build with --release
+    #[arg(long, conflicts_with_all = ["headless", "soup_search", "tune_density", "assert_extinct_by", "host", "join"])]
+    bench: bool,
+    /// Run --runs random soups without a window, each until it settles or --soup-cap
+    /// generations, and print what they came to; the long transients are saved as RLE
+    #[arg(long, conflicts_with_all = ["headless", "tune_density", "assert_extinct_by", "turmite"])]
+    soup_search: bool,
+    /// Soups a --soup-search runs, from --seed on
+    #[arg(
+        long,
+        value_name = "N",
+        default_value_t = 1000,
+        requires = "soup_search"
+    )]
+    runs: usize,
+    /// Generations a --soup-search soup gets to settle in
+    #[arg(long, value_name = "N", default_value_t = FORECAST_CAP, requires = "soup_search")]
+    soup_cap: usize,
+    /// Population swing still shown as stable, so oscillators don't flicker the trend
+    #[arg(long, default_value_t = Config::default().trend_threshold)]
+    trend_threshold: usize,
+    /// Pause once this many cells are alive (Life without Death defaults to half the board)
+    #[arg(long)]
+    population_cap: Option<usize>,
+    /// Pause once the board settles into a still life or an oscillator
+    #[arg(long)]
+    stop_when_settled: bool,
+    /// Generations to run instantly at startup, so the board opens past the raw soup
+    #[arg(long, default_value_t = 0)]
+    warmup: usize,
+    /// Rule to run: a rulestring like B36/S23, a preset (life, highlife, daynight, seeds, quadlife, ..) or a .rhai script
+    #[arg(long, value_parser = Rule::named, default_value = "life")]
+    rule: Rule,
+    /// Independent boards to run side by side, each with its own soup
+    #[arg(long, default_value_t = Config::default().worlds)]
+    worlds: usize,
+    /// Rule of the 2nd, 3rd, .. world, repeatable; the others run the main rule
+    #[arg(long = "world-rule", value_parser = Rule::named)]
+    world_rules: Vec<Rule>,
+    /// Start the other worlds from the main board's seed instead of soups of their own, to compare rules on it
+    #[arg(long)]
+    same_seed: bool,
+    /// Only keep entities for live cells, which pays off on big, sparse boards
+    #[arg(long)]
+    sparse: bool,
+    /// No border: cells live on past the board, which is only where they start (implies --sparse)
+    #[arg(long)]
+    unbounded: bool,
+    /// Draw the board as one texture instead of a sprite per cell, for huge boards (implies --sparse)
+    #[arg(long, conflicts_with = "unbounded")]
+    texture: bool,
+    /// Count a live cell as its own neighbor (totalistic rules)
+    #[arg(long)]
+    include_center: bool,
+    /// Send an event for every birth and death, and log how many per generation
+    #[arg(long)]
+    cell_events: bool,
+    /// Log cells evaluated, neighbor lookups and step time every tick, at debug level
+    #[arg(long)]
+    step_stats: bool,
+    /// What drives the ticks: Bevy's fixed timestep, or a time accumulator in Update
+    #[arg(long, value_enum, default_value_t = Stepping::default())]
+    stepping: Stepping,
+    /// Where the generations are worked out: the CPU, or a compute shader for very large boards
+    #[arg(long, value_enum, default_value_t = Backend::default())]
+    backend: Backend,
+    /// Generations the HashLife backend skips a tick, as a power of 2 (PageUp / PageDown change it)
+    #[arg(long, default_value_t = 0, value_parser = clap::value_parser!(u8).range(..=MAX_SUPER_STEP as i64))]
+    super_step: u8,
+    /// How the cells tile: squares with 8 neighbors, or hexagons with 6 (odd rows drawn half a cell over)
+    #[arg(long, value_enum, default_value_t = Topology::default())]
+    topology: Topology,
+    /// Walk ants over an empty board instead of running the rule: a turn per cell color, R, L, U (back) or N (none); RL is Langton's ant
+    #[arg(long, value_name = "TURNS", value_parser = Turmite::parse, conflicts_with_all = ["headless", "tune_density", "assert_extinct_by"])]
+    turmite: Option<Turmite>,
+    /// Play 3D life under this rule instead ("4555", "5766"), in its own window with an orbiting camera
+    #[arg(long, value_name = "RULE", value_parser = Rule3::parse, conflicts_with = "headless")]
+    life3d: Option<Rule3>,
+    /// Cells across the 3D board each way
+    #[arg(long, default_value_t = 30, value_parser = clap::value_parser!(i32).range(1..))]
+    cube_side: i32,
+    /// Start in smooth mode (`V`), with births and deaths animated
+    #[arg(long)]
+    smooth: bool,
+    /// Side of a cell in world units; the gap between cells scales along
+    #[arg(long, default_value_t = SIZE)]
+    cell_size: f32,
+    /// Length of a tick in milliseconds, until `=` / `-` or the adaptive speed change it
+    #[arg(long, default_value_t = TICK * 1000.0)]
+    tick_ms: f32,
+    /// Round off the cell corners, as a fraction of the cell size from 0 (square) to 0.5
+    #[arg(long, default_value_t = Theme::default().corner_radius)]
+    corner_radius: f32,
+    /// Colors to start in, F1 goes on to the next; over a --config file's theme too
+    #[arg(long, value_enum)]
+    theme: Option<themes::ThemePreset>,
+    /// Shortest tick in seconds the adaptive speed goes down to on a quiet board
+    #[arg(long, default_value_t = 0.1)]
+    fastest_tick: f32,
+    /// Longest tick in seconds the adaptive speed goes up to on a busy board
+    #[arg(long, default_value_t = TICK)]
+    slowest_tick: f32,
+    /// Scale the `;` sound plays its notes in
+    #[arg(long, value_enum, default_value_t = sound::Scale::default())]
+    sound_scale: sound::Scale,
+    /// Beats a minute of the `;` sound's step sequencer, a column a beat
+    #[arg(long, value_name = "BPM", default_value_t = 240.0, value_parser = sound::parse_tempo)]
+    sound_tempo: f32,
+    /// Generations each rule gets under `M`'s rule exploration, unless it dies out or settles sooner
+    #[arg(long, value_name = "N", default_value_t = 50, value_parser = clap::value_parser!(u32).range(1..))]
+    explore_every: u32,
+    /// Start each rule `M` tries on a fresh soup, instead of the board the last one left
+    #[arg(long)]
+    explore_reseed: bool,
+    /// Append how each rule `M` tries did to this file, a line each
+    #[arg(long, value_name = "FILE")]
+    explore_log: Option<PathBuf>,
+    /// Keep a strip on this side of the window for the dashboard, the boards framed beside it
+    #[arg(long, value_enum)]
+    panel: Option<camera::PanelSide>,
+    /// Width of the `--panel` strip in logical pixels
+    #[arg(long, default_value_t = 360.0, requires = "panel")]
+    panel_width: f32,
+    /// Print the whole setup, seed included, as RON once the board is seeded
+    #[arg(long)]
+    print_config: bool,
+    /// Set up from a RON file like `--print-config` writes, or a short TOML one, instead of the board flags
+    #[arg(long, value_name = "FILE")]
+    config: Option<PathBuf>,
+    /// Go straight to the board, without the main menu first
+    #[arg(long)]
+    no_menu: bool,
+    /// Log the frame time and the simulation's diagnostics every second
+    #[arg(long)]
+    log_diagnostics: bool,
+}
 
-#[derive(Component, Debug, Clone)]
-struct Cell {
-    state: State,
-    index_xy: Coord,
+// `x,y`, as in `--pattern-at -10,4`
+fn parse_coord(s: &str) -> Result<Coord, String> {
+    let (x, y) = s.split_once(',').ok_or("expected x,y")?;
+    let number = |n: &str| n.trim().parse::<i32>().map_err(|e| format!("{n:?}: {e}"));
+    Ok((number(x)?, number(y)?))
 }
 
-#[derive(Debug, PartialEq, Clone)]
-enum State {
-    Dead,
-    Alive,
+#[test]
+fn test_parse_coord() {
+    assert_eq!(parse_coord("3,-4"), Ok((3, -4)));
+    assert_eq!(parse_coord(" -10, 0"), Ok((-10, 0)));
+    assert!(parse_coord("3").is_err());
+    assert!(parse_coord("3,a").is_err());
+}
+
+impl From<Cli> for Config {
+    fn from(cli: Cli) -> Self {
+        Config {
+            width: cli.width,
+            height: cli.height,
+            // `-` is how load_pattern spells stdin
+            pattern: if cli.stdin {
+                Some(PathBuf::from("-"))
+            } else {
+                cli.pattern
+            },
+            image: cli.image.map(|path| ImageSeed {
+                path,
+                threshold: cli.image_threshold,
+                dither: cli.dither,
+                invert: cli.image_invert,
+            }),
+            density: cli.density,
+            seed_region: cli.seed_region,
+            blobs: cli.blobs,
+            pattern_at: cli.pattern_at,
+            empty: cli.empty,
+            trend_threshold: cli.trend_threshold,
+            population_cap: cli.population_cap,
+            stop_when_settled: cli.stop_when_settled,
+            warmup: cli.warmup,
+            worlds: cli.worlds,
+            world_rules: cli.world_rules,
+            same_seed: cli.same_seed,
+            sparse: cli.sparse || cli.unbounded || cli.texture,
+            include_center: cli.include_center,
+            // only multi-color rules read it, set from a --config file
+            color_init: Default::default(),
+            cell_events: cli.cell_events,
+            step_stats: cli.step_stats,
+            stepping: cli.stepping,
+            backend: cli.backend,
+            topology: cli.topology,
+            turmite: cli.turmite,
+        }
+    }
 }
 
+#[derive(Component)]
+struct DashboardText;
+
+// big faint round/population readout in the middle of the board
+#[derive(Component)]
+struct BoardCounter;
+
+// what the live set and the generation history behind period detection and
+// scrubbing take up, in bytes
 #[derive(Resource, Default, Debug)]
-struct Dashboard {
-    round: usize,
-    survival: usize,
+struct MemoryView {
+    enabled: bool,
+    live: usize,
+    history: usize,
 }
 
-fn seed() -> HashSet<Coord> {
-    let mut result = HashSet::new();
+// 1 KB is 1024 bytes
+fn human_bytes(bytes: usize) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{size:.1} {}", UNITS[unit])
+    }
+}
 
-    while result.len() < INIT_ALIVE_COUNT {
-        // not on edge
-        let x = random::<i32>() % HALF_LEN;
-        let y = random::<i32>() % HALF_LEN;
-        result.insert((x, y));
+#[test]
+fn test_human_bytes() {
+    assert_eq!(human_bytes(0), "0 B");
+    assert_eq!(human_bytes(1023), "1023 B");
+    assert_eq!(human_bytes(1536), "1.5 KB");
+    assert_eq!(human_bytes(5 * 1024 * 1024), "5.0 MB");
+    // grows with the set, and history counts each generation on its own
+    let live = HashSet::from([(0, 0), (1, 0), (2, 0)]);
+    assert!(set_bytes(&live) >= 3 * std::mem::size_of::<Coord>());
+    let mut detector = PeriodDetector::default();
+    detector.record(live.clone());
+    detector.record(live.clone());
+    assert_eq!(detector.bytes(), 2 * set_bytes(&live));
+}
+
+fn toggle_memory_view(keys: Res<Input<KeyCode>>, mut view: ResMut<MemoryView>) {
+    if keys.just_pressed(KeyCode::F4) {
+        view.enabled = !view.enabled;
     }
+}
 
-    result
+fn estimate_memory(
+    mut view: ResMut<MemoryView>,
+    live: Res<LiveCells>,
+    detector: Res<PeriodDetector>,
+    timeline: Res<scrub::Timeline>,
+    comparison: Res<compare::Comparison>,
+) {
+    if view.enabled {
+        view.live = set_bytes(&live.0);
+        view.history = detector.bytes() + timeline.bytes() + comparison.bytes();
+    }
 }
 
-fn setup(mut commands: Commands) {
-    // cells
-    let mut cells_with_mm = vec![];
-    let rand_alives = seed();
+// the live bounding box on the dashboard
+#[derive(Resource, Default, Debug)]
+struct BoxView(bool);
 
-    for x in -HALF_LEN..HALF_LEN {
-        for y in -HALF_LEN..HALF_LEN {
-            let cell = Cell {
-                state: if rand_alives.contains(&(x, y)) {
-                    State::Alive
-                } else {
-                    State::Dead
-                },
-                index_xy: (x, y),
-            };
+fn toggle_box_view(keys: Res<Input<KeyCode>>, mut view: ResMut<BoxView>) {
+    if keys.just_pressed(KeyCode::F5) {
+        view.0 = !view.0;
+    }
+}
 
-            let pos = Vec3::new(x as f32 * (SIZE + GAP), y as f32 * (SIZE + GAP), 0.0);
-            // dbg!(&pos);
-            cells_with_mm.push((
-                cell,
-                SpriteBundle {
-                    sprite: Sprite {
-                        custom_size: Some(Vec2 { x: SIZE, y: SIZE }),
-                        ..Default::default()
-                    },
-                    transform: Transform::from_translation(pos),
-                    ..Default::default()
-                },
-            ));
+// live cells per quadrant around the origin on the dashboard, where a
+// symmetric seed that stops being balanced shows up
+#[derive(Resource, Default, Debug)]
+struct QuadrantView(bool);
+
+fn toggle_quadrant_view(keys: Res<Input<KeyCode>>, mut view: ResMut<QuadrantView>) {
+    if keys.just_pressed(KeyCode::F6) {
+        view.0 = !view.0;
+    }
+}
+
+// names of the objects on a board that has settled or repeats, like
+// "2 blinkers, block", or the labeled ones with `'`; only Conway's have names
+#[derive(Resource, Default, Debug)]
+struct Objects(Option<String>);
+
+fn name_objects(
+    detector: Res<PeriodDetector>,
+    rule: Res<Rule>,
+    config: Res<Config>,
+    live: Res<LiveCells>,
+    labels: Res<labels::ObjectLabels>,
+    mut objects: ResMut<Objects>,
+) {
+    if !detector.is_changed() && !labels.is_changed() {
+        return;
+    }
+    let settled = detector.period.is_some() || detector.spaceship.is_some();
+    let names = if labels.enabled {
+        // the labeled ones, whether or not the rest has settled
+        let shown = labels.shown.iter().map(|found| found.name);
+        labels::conway(&rule, &config).then(|| census(shown))
+    } else {
+        (settled && *rule == Rule::default()).then(|| identify(&live.0))
+    };
+    if objects.0 != names {
+        objects.0 = names;
+    }
+}
+
+// `WxH @ (x, y)` from the lowest corner, as counted from `origin`; `0x0` for an
+// empty board
+fn box_readout(alives: &HashSet<Coord>, origin: camera::DisplayOrigin) -> String {
+    match bounding_box(alives) {
+        Some(((x0, y0), (x1, y1))) => {
+            let (x, y) = origin.shown((x0, y0));
+            format!("{}x{} @ ({x}, {y})", x1 - x0 + 1, y1 - y0 + 1)
+        }
+        None => "0x0".to_string(),
+    }
+}
+
+#[test]
+fn test_box_readout() {
+    let origin = camera::DisplayOrigin::default();
+    assert_eq!(box_readout(&HashSet::new(), origin), "0x0");
+    let blinker = HashSet::from([(-1, 4), (0, 4), (1, 4)]);
+    assert_eq!(box_readout(&blinker, origin), "3x1 @ (-1, 4)");
+    let origin = camera::DisplayOrigin((-1, 2));
+    assert_eq!(box_readout(&blinker, origin), "3x1 @ (0, 2)");
+}
+
+// the optional readouts at the bottom of the dashboard
+#[derive(SystemParam)]
+struct Readouts<'w> {
+    timing: Res<'w, StepTiming>,
+    memory: Res<'w, MemoryView>,
+    bbox: Res<'w, BoxView>,
+    quadrants: Res<'w, QuadrantView>,
+    speed: Res<'w, AdaptiveSpeed>,
+    fixed_time: Res<'w, FixedTime>,
+    pending: Res<'w, PendingCells>,
+    origin: Res<'w, camera::DisplayOrigin>,
+    objects: Res<'w, Objects>,
+    throughput: Res<'w, Throughput>,
+    flat_out: Res<'w, FlatOut>,
+    recording: Res<'w, record::Recording>,
+    hashlife: Res<'w, HashLife>,
+}
+
+// paces the window title, which is slow to set on some platforms
+#[derive(Resource)]
+struct TitleRefresh(Timer);
+
+impl Default for TitleRefresh {
+    fn default() -> Self {
+        TitleRefresh(Timer::from_seconds(TITLE_REFRESH, TimerMode::Repeating))
+    }
+}
+
+// on the web the app draws into the page's `#bevy` canvas, sized to fill its
+// parent
+fn primary_window() -> Window {
+    let web = cfg!(target_arch = "wasm32");
+    Window {
+        canvas: web.then(|| "#bevy".to_string()),
+        fit_canvas_to_parent: web,
+        ..Default::default()
+    }
+}
+
+// rule, round and population, readable even with the dashboard off screen
+fn update_title(
+    time: Res<Time>,
+    db: Res<Dashboard>,
+    rule: Res<Rule>,
+    mut refresh: ResMut<TitleRefresh>,
+    mut windows: Query<&mut Window, With<PrimaryWindow>>,
+) {
+    if !refresh.0.tick(time.delta()).just_finished() {
+        return;
+    }
+    let title = format!("Life — {} — gen {} — pop {}", *rule, db.round, db.survival);
+    for mut window in windows.iter_mut() {
+        if window.title != title {
+            window.title = title.clone();
+        }
+    }
+}
+
+#[derive(Resource, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+struct Theme {
+    // shows through the gaps between cells
+    background: Color,
+    alive: Color,
+    dead: Color,
+    // dead cells that have never been alive
+    untouched: Color,
+    // frozen cells, alive and dead
+    wall: Color,
+    obstacle: Color,
+    // side of a cell in world units
+    size: f32,
+    // rounding of the cell corners as a fraction of the size, 0 is square
+    corner_radius: f32,
+    // between two cells, 0 makes one continuous field
+    gap: f32,
+    // births and deaths pop and crossfade (Z and I together), else cells just
+    // switch from one tick to the next
+    smooth: bool,
+    // live-neighbor counts, 0 at the start and 8 at the end
+    heat: ColorRamp,
+    // birth generation rings, wrapping around every 360 / RING_HUE_STEP generations
+    rings: ColorRamp,
+    // F2's live cells, newborn at the start and OLD_AGE at the end
+    age: ColorRamp,
+    // the dashboard's
+    text: Color,
+    // what F1 goes on from; other than classic, a --config file gets its colors
+    preset: themes::ThemePreset,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme {
+            background: Color::rgb(0.15, 0.15, 0.15),
+            alive: Color::WHITE,
+            dead: Color::GRAY,
+            untouched: Color::rgb(0.4, 0.4, 0.4),
+            wall: Color::rgb(0.55, 0.7, 0.95),
+            obstacle: Color::rgb(0.25, 0.1, 0.1),
+            size: SIZE,
+            corner_radius: 0.0,
+            gap: GAP,
+            smooth: false,
+            heat: ColorRamp::heat(),
+            rings: ColorRamp::rainbow(),
+            age: ColorRamp::even(
+                &(0..=10)
+                    .map(|i| age_color(OLD_AGE * i / 10))
+                    .collect::<Vec<_>>(),
+            ),
+            text: Color::WHITE,
+            preset: themes::ThemePreset::Classic,
+        }
+    }
+}
+
+impl Theme {
+    // from one cell center to the next
+    fn pitch(&self) -> f32 {
+        self.size + self.gap
+    }
+}
+
+// crossfade cells toward the generation that's coming up
+#[derive(Resource, Default, Debug)]
+struct Interpolation {
+    enabled: bool,
+    next: HashSet<Coord>,
+}
+
+// live-neighbor count of every cell, the pressure behind the next generation
+#[derive(Resource, Default, Debug)]
+struct NeighborView {
+    enabled: bool,
+    counts: HashMap<Coord, usize>,
+}
+
+// every live cell in its own color
+#[derive(Resource, Default, Debug)]
+struct Confetti(bool);
+
+// live cells colored by whether they were alive in the seed too (survivors)
+// or not (emergent)
+#[derive(Resource, Default, Debug)]
+struct SeedView(bool);
+
+// live cells colored by how many generations in a row they've been alive
+#[derive(Resource, Default, Debug)]
+struct AgeView(bool);
+
+// dead cells drawn fully transparent, only the live structure shows
+#[derive(Resource, Default, Debug)]
+struct HideDead(bool);
+
+// off, the board and dashboard stop being redrawn while the sim runs on; the
+// cells that changed meanwhile get their color once back on
+#[derive(Resource, Debug)]
+struct RenderEnabled(bool);
+
+impl Default for RenderEnabled {
+    fn default() -> Self {
+        RenderEnabled(true)
+    }
+}
+
+fn rendering(render: Res<RenderEnabled>) -> bool {
+    render.0
+}
+
+// sub-period of every cell that was alive at some point of the detected period
+#[derive(Resource, Default, Debug)]
+struct SpectrumView(Option<HashMap<Coord, usize>>);
+
+fn cell_bundle(cell: &Cell, theme: &Theme, topology: Topology) -> (SpriteBundle, pop::Pop) {
+    let pos = draw::cell_center(cell.index_xy, theme.pitch(), topology).extend(0.0);
+    (
+        SpriteBundle {
+            sprite: Sprite {
+                custom_size: Some(Vec2::splat(theme.size)),
+                ..Default::default()
+            },
+            transform: Transform::from_translation(pos),
+            ..Default::default()
+        },
+        pop::Pop::new(cell),
+    )
+}
+
+// the sim spawns bare cells, wherever they come from they get their sprite here
+fn dress_cells(
+    mut commands: Commands,
+    config: Res<Config>,
+    theme: Res<Theme>,
+    cells: Query<(Entity, &Cell), Added<Cell>>,
+) {
+    for (entity, cell) in cells.iter() {
+        commands
+            .entity(entity)
+            .insert(cell_bundle(cell, &theme, config.topology));
+    }
+}
+
+fn adjust_gap(keys: Res<Input<KeyCode>>, mut theme: ResMut<Theme>) {
+    let step = match () {
+        _ if keys.just_pressed(KeyCode::Comma) => -1.0,
+        _ if keys.just_pressed(KeyCode::Period) => 1.0,
+        _ => return,
+    };
+    // up to a cell's width
+    theme.gap = (theme.gap + step).clamp(0.0, theme.size);
+}
+
+// every sprite back on the grid after the gap changed, side worlds included
+fn reflow_cells(
+    config: Res<Config>,
+    theme: Res<Theme>,
+    mut cells: Query<(&mut Transform, &Cell)>,
+    mut side_cells: Query<(&mut Transform, &worlds::SideCell), Without<Cell>>,
+) {
+    let pitch = theme.pitch();
+    for (mut transform, cell) in cells.iter_mut() {
+        transform.translation =
+            draw::cell_center(cell.index_xy, pitch, config.topology).extend(0.0);
+    }
+    for (mut transform, cell) in side_cells.iter_mut() {
+        transform.translation = cell.position(&config, pitch).extend(0.0);
+    }
+}
+
+// swap the main board for `board`, frozen cells aside; in sparse mode dead cells
+// lose their entity and new live ones get one
+fn replace_board(
+    board: &HashSet<Coord>,
+    config: &Config,
+    commands: &mut Commands,
+    live: &mut LiveCells,
+    cells: &mut Query<(Entity, &mut Cell)>,
+) {
+    for (entity, mut cell) in cells.iter_mut() {
+        if cell.frozen {
+            continue;
+        }
+        let alive = board.contains(&cell.index_xy);
+        live.set(&mut cell, alive);
+        if !alive && config.sparse {
+            commands.entity(entity).despawn();
+        }
+    }
+    if config.sparse {
+        for xy in board.difference(&live.0.clone()) {
+            let mut cell = Cell::new(*xy, false);
+            live.set(&mut cell, true);
+            commands.spawn(cell);
         }
     }
-    // dbg!(&cells_with_mm.iter().map(|cm| &cm.0).collect::<Vec<_>>());
-    commands.spawn_batch(cells_with_mm);
-    // dashboard
+}
+
+// Ctrl+O lays the --pattern down again, read afresh from its file, in place of
+// the board; the round carries on as after drawing
+fn reload_pattern(
+    mut commands: Commands,
+    keys: Res<Input<KeyCode>>,
+    config: Res<Config>,
+    mut detector: ResMut<PeriodDetector>,
+    mut live: ResMut<LiveCells>,
+    mut cells: Query<(Entity, &mut Cell)>,
+) {
+    let ctrl = keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+    if !(ctrl && keys.just_pressed(KeyCode::O)) {
+        return;
+    }
+    let Some(path) = config.pattern.as_ref() else {
+        info!("no --pattern to load");
+        return;
+    };
+    if path == Path::new("-") {
+        info!("stdin can only be read once, at startup");
+        return;
+    }
+    match load_pattern(path) {
+        Ok(pattern) => {
+            let board = place_pattern(&pattern, config.pattern_at, &config);
+            replace_board(&board, &config, &mut commands, &mut live, &mut cells);
+            *detector = PeriodDetector::default();
+            info!("loaded {}", path.display());
+        }
+        Err(e) => error!("can't load pattern: {e}"),
+    }
+}
+
+fn setup(
+    mut commands: Commands,
+    config: Res<Config>,
+    theme: Res<Theme>,
+    panel: Res<camera::Panel>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+) {
+    // dashboard, anchored to the top-left corner of the window or wrapped
+    // inside the panel
     let ts = TextStyle {
         font_size: 30.0,
         ..Default::default()
     };
-    commands.spawn(Text2dBundle {
-        text: Text {
-            sections: vec![
-                TextSection {
-                    value: String::new(),
-                    style: ts.clone(),
+    let mut style = Style {
+        position_type: PositionType::Absolute,
+        top: Val::Px(10.0),
+        left: Val::Px(10.0),
+        ..Default::default()
+    };
+    if let Some(side) = panel.side {
+        style.max_width = Val::Px(panel.width - 20.0);
+        if side == camera::PanelSide::Right {
+            (style.left, style.right) = (Val::Auto, Val::Px(10.0));
+        }
+        commands.spawn(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                left: if side == camera::PanelSide::Left {
+                    Val::Px(0.0)
+                } else {
+                    Val::Auto
                 },
-                TextSection {
-                    value: String::new(),
-                    style: ts,
+                right: if side == camera::PanelSide::Right {
+                    Val::Px(0.0)
+                } else {
+                    Val::Auto
                 },
-            ],
+                width: Val::Px(panel.width),
+                height: Val::Percent(100.0),
+                ..Default::default()
+            },
+            background_color: Color::rgba(0.0, 0.0, 0.0, 0.3).into(),
+            ..Default::default()
+        });
+    }
+    commands.spawn((
+        DashboardText,
+        TextBundle::from_section(String::new(), ts).with_style(style),
+    ));
+    // sized to the board, behind the cells
+    let board_height = config.height as f32 * theme.pitch();
+    commands.spawn((
+        BoardCounter,
+        Text2dBundle {
+            text: Text::from_section(
+                String::new(),
+                TextStyle {
+                    font_size: board_height * 0.3,
+                    color: Color::rgba(1.0, 1.0, 1.0, 0.15),
+                    ..Default::default()
+                },
+            )
+            .with_alignment(TextAlignment::Center),
+            transform: Transform::from_xyz(0.0, 0.0, -1.0),
             ..Default::default()
         },
-        transform: Transform::from_xyz(0.0, (HALF_LEN as f32 + 1.0) * (SIZE + GAP), 0.0),
-        ..Default::default()
-    });
-    commands.spawn(Camera2dBundle::default());
-}
-
-// Any live cell with fewer than two live neighbours dies (referred to as underpopulation).
-// Any live cell with more than three live neighbours dies (referred to as overpopulation).
-// Any live cell with two or three live neighbours lives, unchanged, to the next generation.
-// Any dead cell with exactly three live neighbours comes to life.
-fn dead_or_alive(mut db: ResMut<Dashboard>, mut query: Query<&mut Cell>) {
-    let alive_coords: Vec<_> = query
-        .iter()
-        .filter(|c| c.state == State::Alive)
-        .map(|c| c.index_xy)
-        .collect();
-    if alive_coords.len() == 0 {
-        // end of the world
-        return;
-    }
-
-    db.survival = 0;
-    for mut cell in query.iter_mut() {
-        let live_count = alive_neighbor_count(&cell.index_xy, &alive_coords);
-        match cell.state {
-            State::Alive => {
-                if live_count < 2 || live_count > 3 {
-                    cell.state = State::Dead
-                }
-            }
-            State::Dead => {
-                if live_count == 3 {
-                    // println!("come to live");
-                    cell.state = State::Alive
-                }
-            }
+    ));
+    let mut camera = Camera2dBundle::default();
+    let z = camera.transform.translation.z;
+    camera.transform.translation = worlds::layout_center(&config, theme.pitch()).extend(z);
+    // with a panel the boards have to start out beside it, not under it
+    if let (Some(_), Ok(window)) = (panel.side, windows.get_single()) {
+        let (center, extent) = camera::boards_extent(&config, theme.pitch());
+        let window = Vec2::new(window.width(), window.height());
+        let (translation, scale) = panel.fit(center, extent, window);
+        camera.transform.translation = translation.extend(z);
+        camera.projection.scale = scale;
+    }
+    commands.spawn(camera);
+}
+
+// Shift+F3 is the diagnostics overlay
+fn toggle_step_timing(keys: Res<Input<KeyCode>>, mut timing: ResMut<StepTiming>) {
+    let shift = keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    if keys.just_pressed(KeyCode::F3) && !shift {
+        timing.enabled = !timing.enabled;
+    }
+}
+
+// percentiles change every tick, so only refresh what's shown now and then
+fn summarize_step_timing(time: Res<Time>, mut timing: ResMut<StepTiming>) {
+    if !timing.enabled || !timing.refresh.tick(time.delta()).just_finished() {
+        return;
+    }
+    timing.summary = percentiles(&timing.samples);
+}
+
+// the cell State is this crate's, the schedule one Bevy's
+type SimNow = bevy::ecs::schedule::State<SimState>;
+
+fn toggle_pause(
+    keys: Res<Input<KeyCode>>,
+    sim_state: Res<SimNow>,
+    mut next: ResMut<NextState<SimState>>,
+) {
+    if keys.just_pressed(KeyCode::Space) {
+        next.set(sim_state.get().toggled());
+    }
+}
+
+// Enter while paused: one generation, to go through it frame by frame
+fn single_step(
+    keys: Res<Input<KeyCode>>,
+    sim_state: Res<SimNow>,
+    pressed: Res<controls::StepPressed>,
+) -> bool {
+    *sim_state.get() == SimState::Paused && (keys.just_pressed(KeyCode::Return) || pressed.0)
+}
+
+// a sample consumer of the cell events
+fn count_cell_events(mut events: EventReader<CellEvent>) {
+    let mut per_generation: Vec<(usize, usize, usize)> = vec![];
+    for e in events.iter() {
+        if per_generation.last().map(|g| g.0) != Some(e.generation) {
+            per_generation.push((e.generation, 0, 0));
         }
-        // dbg!(&cell);
-        if cell.state == State::Alive {
-            db.survival += 1;
+        let counts = per_generation.last_mut().unwrap();
+        if e.to == State::Alive {
+            counts.1 += 1;
+        } else {
+            counts.2 += 1;
         }
     }
-    db.round += 1;
+    for (generation, births, deaths) in per_generation {
+        info!("generation {generation}: {births} births, {deaths} deaths");
+    }
 }
 
-fn alive_neighbor_count(me: &Coord, alives: &Vec<Coord>) -> usize {
-    let (x, y) = me.to_owned();
-    // surrounding 8
-    [
-        (x - 1, y - 1),
-        (x - 1, y),
-        (x - 1, y + 1),
-        (x, y - 1),
-        (x, y + 1),
-        (x + 1, y - 1),
-        (x + 1, y),
-        (x + 1, y + 1),
-    ]
-    .iter()
-    .filter(|(x, y)| {
-        let valid = *x >= -HALF_LEN && *y >= -HALF_LEN && *x <= HALF_LEN && *y <= HALF_LEN;
-        valid && alives.iter().any(|(ax, ay)| x == ax && y == ay)
-    })
-    .count()
+// smallest shift that maps the cyclic sequence onto itself
+fn sub_period(states: &[bool]) -> usize {
+    let p = states.len();
+    (1..p)
+        .filter(|d| p.is_multiple_of(*d))
+        .find(|d| (0..p).all(|t| states[t] == states[(t + d) % p]))
+        .unwrap_or(p)
 }
 
 #[test]
-fn test_alive_neighbor() {
-    let alives = vec![(-1, -1), (-1, 0), (0, -1)];
-    assert_eq!(alive_neighbor_count(&(0, 0), &alives), 3);
-    assert_eq!(alive_neighbor_count(&(-2, -1), &alives), 2);
+fn test_sub_period() {
+    assert_eq!(sub_period(&[true, true, true, true]), 1);
+    assert_eq!(sub_period(&[true, false, true, false]), 2);
+    assert_eq!(sub_period(&[true, false, false, true, false, false]), 3);
+    assert_eq!(sub_period(&[true, true, false]), 3);
+}
+
+fn spectrum(period: &[&HashSet<Coord>]) -> HashMap<Coord, usize> {
+    let touched: HashSet<Coord> = period.iter().flat_map(|s| s.iter().copied()).collect();
+    touched
+        .into_iter()
+        .map(|c| {
+            let states: Vec<_> = period.iter().map(|s| s.contains(&c)).collect();
+            (c, sub_period(&states))
+        })
+        .collect()
 }
 
-fn update_cell_color(mut query: Query<(&mut Sprite, &Cell)>) {
-    for (mut sprite, cell) in query.iter_mut() {
-        match cell.state {
-            State::Dead => sprite.color = Color::GRAY,
-            State::Alive => sprite.color = Color::WHITE,
+fn toggle_spectrum(
+    keys: Res<Input<KeyCode>>,
+    detector: Res<PeriodDetector>,
+    mut view: ResMut<SpectrumView>,
+) {
+    if detector.period.is_none() && view.0.is_some() {
+        // whatever we showed no longer describes the board
+        view.0 = None;
+    }
+    let ctrl = keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+    if ctrl || !keys.just_pressed(KeyCode::O) {
+        return;
+    }
+    if view.0.is_some() {
+        view.0 = None;
+    } else if let Some(period) = detector.last_period() {
+        view.0 = Some(spectrum(&period));
+    } else {
+        info!("no period detected yet");
+    }
+}
+
+fn spectrum_color(sub_period: usize) -> Color {
+    match sub_period {
+        1 => Color::rgb(0.3, 0.5, 0.9),
+        2 => Color::ORANGE,
+        3 => Color::LIME_GREEN,
+        4 => Color::YELLOW,
+        _ => Color::FUCHSIA,
+    }
+}
+
+fn toggle_edge_mode(keys: Res<Input<KeyCode>>, mut edge: ResMut<EdgeMode>) {
+    if keys.just_pressed(KeyCode::W) {
+        edge.cycle();
+    }
+}
+
+// the board border flashes on every generation, a metronome for slow runs
+#[derive(Resource, Default, Debug)]
+struct Pulse {
+    enabled: bool,
+    // seconds since the last generation, none before the first one
+    since: Option<f32>,
+    round: usize,
+}
+
+// how bright the pulse still is `since` seconds after a tick: from 1 down to 0
+// within a quarter of the tick, so at fast rates it never stays lit
+fn pulse_brightness(since: f32, period: f32) -> f32 {
+    let duration = PULSE_SECS.min(period / 4.0);
+    (1.0 - since / duration).clamp(0.0, 1.0)
+}
+
+#[test]
+fn test_pulse_brightness() {
+    assert_eq!(pulse_brightness(0.0, 1.0), 1.0);
+    assert!((pulse_brightness(PULSE_SECS / 2.0, 1.0) - 0.5).abs() < 1e-6);
+    assert_eq!(pulse_brightness(PULSE_SECS, 1.0), 0.0);
+    assert_eq!(pulse_brightness(5.0, 1.0), 0.0);
+    // ten ticks a second leave it 25ms
+    assert_eq!(pulse_brightness(0.025, 0.1), 0.0);
+}
+
+fn toggle_pulse(keys: Res<Input<KeyCode>>, mut pulse: ResMut<Pulse>) {
+    if keys.just_pressed(KeyCode::J) {
+        pulse.enabled = !pulse.enabled;
+    }
+}
+
+fn time_pulse(time: Res<Time>, db: Res<Dashboard>, mut pulse: ResMut<Pulse>) {
+    if db.round != pulse.round {
+        pulse.round = db.round;
+        pulse.since = Some(0.0);
+    } else if let Some(since) = pulse.since.as_mut() {
+        *since += time.delta_seconds();
+    }
+}
+
+// border around every board: red where cells fall off, blue where they wrap
+// around, green where the board is reflected
+fn draw_edges(
+    mut gizmos: Gizmos,
+    config: Res<Config>,
+    theme: Res<Theme>,
+    edge: Res<EdgeMode>,
+    pulse: Res<Pulse>,
+    fixed_time: Res<FixedTime>,
+) {
+    let pitch = theme.pitch();
+    let (xs, ys) = (config.x_range(), config.y_range());
+    // halfway into the gap around the outermost cells
+    let left = (xs.start as f32 - 0.5) * pitch;
+    let right = (xs.end as f32 - 0.5) * pitch;
+    let bottom = (ys.start as f32 - 0.5) * pitch;
+    let top = (ys.end as f32 - 0.5) * pitch;
+    let flash = match (pulse.enabled, pulse.since) {
+        (true, Some(since)) => pulse_brightness(since, fixed_time.period.as_secs_f32()),
+        _ => 0.0,
+    };
+    let color = |boundary: Boundary| {
+        let base = match boundary {
+            Boundary::Dead => Color::RED,
+            Boundary::Wrap => Color::BLUE,
+            Boundary::Mirror => Color::GREEN,
+            // just where the board started out
+            Boundary::Open => Color::rgba(1.0, 1.0, 1.0, 0.2),
+        };
+        mix(base, Color::WHITE, flash * 0.7)
+    };
+    for slot in 0..config.worlds {
+        let o = worlds::offset(slot, &config, pitch);
+        let corner = |x: f32, y: f32| o + Vec2::new(x, y);
+        let x_color = color(edge.x);
+        let y_color = color(edge.y);
+        gizmos.line_2d(corner(left, bottom), corner(left, top), x_color);
+        gizmos.line_2d(corner(right, bottom), corner(right, top), x_color);
+        gizmos.line_2d(corner(left, bottom), corner(right, bottom), y_color);
+        gizmos.line_2d(corner(left, top), corner(right, top), y_color);
+    }
+}
+
+fn toggle_smooth(keys: Res<Input<KeyCode>>, mut theme: ResMut<Theme>) {
+    // Ctrl+V pastes
+    let ctrl = keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+    if !ctrl && keys.just_pressed(KeyCode::V) {
+        theme.smooth = !theme.smooth;
+    }
+}
+
+// only when smooth mode itself flipped, so Z and I can still be set apart; off,
+// nothing is left halfway: sprites are set back to full size now and the colors
+// are redrawn without the crossfade on the next frame
+fn apply_smooth(
+    theme: Res<Theme>,
+    mut applied: Local<Option<bool>>,
+    mut interpolation: ResMut<Interpolation>,
+    mut pop: ResMut<pop::PopMode>,
+    mut sprites: Query<&mut Transform, With<pop::Pop>>,
+) {
+    if *applied == Some(theme.smooth) {
+        return;
+    }
+    *applied = Some(theme.smooth);
+    interpolation.enabled = theme.smooth;
+    pop.0 = theme.smooth;
+    if !theme.smooth {
+        pop::settle(&mut sprites);
+    }
+}
+
+fn toggle_interpolation(keys: Res<Input<KeyCode>>, mut interpolation: ResMut<Interpolation>) {
+    if keys.just_pressed(KeyCode::I) {
+        interpolation.enabled = !interpolation.enabled;
+    }
+}
+
+fn look_ahead(
+    mut interpolation: ResMut<Interpolation>,
+    live: Res<LiveCells>,
+    rule: Res<Rule>,
+    config: Res<Config>,
+    edge: Res<EdgeMode>,
+) {
+    let stale = live.is_changed() || rule.is_changed() || edge.is_changed();
+    if interpolation.enabled && (stale || interpolation.is_changed()) {
+        interpolation.next = step(&live.0, &rule, &config, *edge);
+    }
+}
+
+// switch between Conway and Life without Death
+fn toggle_life_without_death(
+    keys: Res<Input<KeyCode>>,
+    config: Res<Config>,
+    mut rule: ResMut<Rule>,
+) {
+    if !keys.just_pressed(KeyCode::L) {
+        return;
+    }
+    let lwd = Rule::life_without_death();
+    let next = if *rule == lwd { Rule::default() } else { lwd };
+    match config.runs(&next) {
+        Ok(()) => *rule = next,
+        Err(e) => warn!("{e}"),
+    }
+}
+
+// step through the presets this board runs, Shift going back
+fn cycle_rule(keys: Res<Input<KeyCode>>, config: Res<Config>, mut rule: ResMut<Rule>) {
+    if !keys.just_pressed(KeyCode::B) {
+        return;
+    }
+    let back = keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    *rule = config.next_preset(&rule, !back);
+    info!("rule {}", *rule);
+}
+
+// PageUp / PageDown: twice / half as many generations a tick under HashLife
+fn change_super_step(keys: Res<Input<KeyCode>>, mut hashlife: ResMut<HashLife>) {
+    let k = hashlife.super_step;
+    if keys.just_pressed(KeyCode::PageUp) && k < MAX_SUPER_STEP {
+        hashlife.super_step += 1;
+    } else if keys.just_pressed(KeyCode::PageDown) && k > 0 {
+        hashlife.super_step -= 1;
+    } else {
+        return;
+    }
+    info!("2^{} generations a tick", hashlife.super_step);
+}
+
+fn toggle_hide_dead(keys: Res<Input<KeyCode>>, mut hide: ResMut<HideDead>) {
+    if keys.just_pressed(KeyCode::T) {
+        hide.0 = !hide.0;
+    }
+}
+
+fn toggle_rendering(keys: Res<Input<KeyCode>>, mut render: ResMut<RenderEnabled>) {
+    if keys.just_pressed(KeyCode::N) {
+        render.0 = !render.0;
+        let state = if render.0 { "on" } else { "off" };
+        info!("rendering {state}");
+    }
+}
+
+fn toggle_confetti(keys: Res<Input<KeyCode>>, mut confetti: ResMut<Confetti>) {
+    if keys.just_pressed(KeyCode::H) {
+        confetti.0 = !confetti.0;
+    }
+}
+
+fn toggle_age_view(keys: Res<Input<KeyCode>>, mut view: ResMut<AgeView>) {
+    if keys.just_pressed(KeyCode::F2) {
+        view.0 = !view.0;
+    }
+}
+
+fn toggle_seed_view(keys: Res<Input<KeyCode>>, mut view: ResMut<SeedView>) {
+    if keys.just_pressed(KeyCode::F7) {
+        view.0 = !view.0;
+    }
+}
+
+fn toggle_neighbor_view(keys: Res<Input<KeyCode>>, mut view: ResMut<NeighborView>) {
+    // Ctrl+C copies
+    let ctrl = keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+    if !ctrl && keys.just_pressed(KeyCode::C) {
+        view.enabled = !view.enabled;
+    }
+}
+
+fn count_neighbors(
+    mut view: ResMut<NeighborView>,
+    live: Res<LiveCells>,
+    config: Res<Config>,
+    edge: Res<EdgeMode>,
+) {
+    let stale = live.is_changed() || edge.is_changed();
+    if view.enabled && (stale || view.is_changed()) {
+        view.counts = config
+            .coords()
+            .map(|c| (c, alive_neighbor_count(&c, &live.0, &config, *edge)))
+            .collect();
+    }
+}
+
+fn mix(from: Color, to: Color, t: f32) -> Color {
+    let [r0, g0, b0, a0] = from.as_rgba_f32();
+    let [r1, g1, b1, a1] = to.as_rgba_f32();
+    Color::rgba(
+        r0 + (r1 - r0) * t,
+        g0 + (g1 - g0) * t,
+        b0 + (b1 - b0) * t,
+        a0 + (a1 - a0) * t,
+    )
+}
+
+// the classic F2 gradient: newborns a bright yellow, darkening through orange to a deep red as they
+// reach OLD_AGE; still lifes end up dark, the churn stays bright
+fn age_color(age: usize) -> Color {
+    let t = (age as f32 / OLD_AGE as f32).min(1.0);
+    Color::hsl(55.0 * (1.0 - t), 1.0, 0.75 - 0.5 * t.sqrt())
+}
+
+#[test]
+fn test_age_color() {
+    let lightness = |age| age_color(age).as_hsla_f32()[2];
+    assert!(lightness(0) > lightness(1));
+    assert!(lightness(10) > lightness(50));
+    assert_eq!(age_color(OLD_AGE), age_color(10 * OLD_AGE));
+}
+
+// every Life without Death generation gets its own hue, so growth shows as rings
+fn ring_color(born: usize, theme: &Theme) -> Color {
+    theme
+        .rings
+        .sample((born as f32 * RING_HUE_STEP) % 360.0 / 360.0)
+}
+
+fn base_color(cell: &Cell, theme: &Theme, rule: &Rule, confetti: bool) -> Color {
+    if rule.wireworld && !cell.frozen {
+        return wire::wire_color(&cell.state, theme);
+    }
+    let rings = *rule == Rule::life_without_death();
+    match cell.state {
+        State::Alive if cell.frozen => theme.wall,
+        State::Dead if cell.frozen => theme.obstacle,
+        State::Dead if cell.ever_alive => theme.dead,
+        // a touch darker, so the historical footprint stands out
+        State::Dead => theme.untouched,
+        // only frozen ones draw here under Wireworld, and away from it a wire
+        // is gone the next tick
+        State::Wire => theme.obstacle,
+        State::Alive if confetti => Color::hsl(cell.hue, 0.8, 0.6),
+        State::Alive if rings => ring_color(cell.born, theme),
+        State::Alive => theme.alive,
+        // fading from alive towards dead, a step a stage
+        State::Dying(stage) => mix(
+            theme.alive,
+            theme.dead,
+            stage as f32 / rule.states.saturating_sub(1).max(1) as f32,
+        ),
+    }
+}
+
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+fn update_cell_color(
+    mut sprites: ParamSet<(
+        Query<(&mut Sprite, &Cell)>,
+        Query<(&mut Sprite, &Cell), Or<(Changed<Cell>, Added<Sprite>)>>,
+    )>,
+    view: Res<SpectrumView>,
+    neighbors: Res<NeighborView>,
+    theme: Res<Theme>,
+    rule: Res<Rule>,
+    confetti: Res<Confetti>,
+    hide_dead: Res<HideDead>,
+    seed_view: Res<SeedView>,
+    age_view: Res<AgeView>,
+    db: Res<Dashboard>,
+    comparison: Res<compare::Comparison>,
+    interpolation: Res<Interpolation>,
+    fixed_time: Res<FixedTime>,
+    config: Res<Config>,
+    (ants, lineage): (Res<Ants>, Res<Lineage>),
+    trail: Res<trail::TrailView>,
+) {
+    let colors = config.turmite.as_ref().map_or(2, |t| t.colors());
+    // frozen obstacles stay, they still shape what happens
+    let hidden = |cell: &Cell| hide_dead.0 && cell.state == State::Dead && !cell.frozen;
+    let dead = if hide_dead.0 { Color::NONE } else { theme.dead };
+    // how far we are between the last tick and the next one
+    let t = (fixed_time.accumulated().as_secs_f32() / fixed_time.period.as_secs_f32()).min(1.0);
+    let seed = comparison.seed().filter(|_| seed_view.0);
+    let paint = |sprite: &mut Sprite, cell: &Cell| {
+        if let Some(sub) = view.0.as_ref().and_then(|m| m.get(&cell.index_xy)) {
+            sprite.color = spectrum_color(*sub);
+            return;
+        }
+        if neighbors.enabled {
+            let count = neighbors.counts.get(&cell.index_xy).copied().unwrap_or(0);
+            sprite.color = theme.heat.sample(count.min(8) as f32 / 8.0);
+            return;
+        }
+        if let Some(seed) = seed.filter(|_| cell.state == State::Alive) {
+            sprite.color = if seed.contains(&cell.index_xy) {
+                Color::GOLD
+            } else {
+                Color::TURQUOISE
+            };
+            return;
+        }
+        if age_view.0 && cell.state == State::Alive {
+            let age = db.round.saturating_sub(cell.born);
+            sprite.color = theme.age.sample(age as f32 / OLD_AGE as f32);
+            return;
+        }
+        if trail.enabled && !cell.frozen {
+            sprite.color = trail.color(cell, &theme);
+            return;
+        }
+        sprite.color = if hidden(cell) {
+            Color::NONE
+        } else {
+            base_color(cell, &theme, &rule, confetti.0)
+        };
+        let lineage = lineage.colors.get(&cell.index_xy);
+        if let Some(i) = lineage.filter(|_| cell.state == State::Alive && !cell.frozen) {
+            sprite.color = LINEAGE_COLORS[*i as usize % LINEAGE_COLORS.len()];
+        }
+        let color = ants.color(cell.index_xy);
+        if colors > 2 && color > 1 {
+            sprite.color = turmite::ant_color(color, colors, &theme);
+        }
+        let alive = cell.state == State::Alive;
+        let decaying = matches!(cell.state, State::Dying(_));
+        if interpolation.enabled
+            && !decaying
+            && interpolation.next.contains(&cell.index_xy) != alive
+        {
+            let target = if alive { dead } else { theme.alive };
+            sprite.color = mix(sprite.color, target, t);
+        }
+    };
+    // most frames only the cells that flipped (or just got their sprite) need
+    // a color; a view, the theme or the rule switching, or colors that move on
+    // with the clock or the round, and every sprite is painted again
+    let repaint = view.is_changed()
+        || neighbors.is_changed()
+        || theme.is_changed()
+        || rule.is_changed()
+        || confetti.is_changed()
+        || hide_dead.is_changed()
+        || seed_view.is_changed()
+        || age_view.is_changed()
+        || (seed_view.0 && comparison.is_changed())
+        || config.is_changed()
+        || ants.is_changed()
+        || (rule.colors > 1 && lineage.is_changed())
+        || interpolation.enabled
+        || trail.is_changed()
+        || (age_view.0 && db.is_changed());
+    if repaint {
+        for (mut sprite, cell) in sprites.p0().iter_mut() {
+            paint(&mut sprite, cell);
+        }
+    } else {
+        for (mut sprite, cell) in sprites.p1().iter_mut() {
+            paint(&mut sprite, cell);
         }
     }
 }
 
-fn update_dashboard(db: Res<Dashboard>, mut query: Query<&mut Text>) {
+fn apply_theme_background(theme: Res<Theme>, mut clear: ResMut<ClearColor>) {
+    clear.0 = theme.background;
+}
+
+fn request_forecast(
+    keys: Res<Input<KeyCode>>,
+    config: Res<Config>,
+    rule: Res<Rule>,
+    edge: Res<EdgeMode>,
+    live: Res<LiveCells>,
+    mut db: ResMut<Dashboard>,
+) {
+    // Ctrl+X cuts
+    let ctrl = keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+    if ctrl || !keys.just_pressed(KeyCode::X) {
+        return;
+    }
+    let f = forecast(live.0.clone(), &rule, &config, *edge);
+    db.forecast = Some((db.round, f));
+}
+
+// slows the ticks down while a lot is going on, speeds them up when it's quiet
+#[derive(Resource, Debug)]
+struct AdaptiveSpeed {
+    enabled: bool,
+    // tick lengths in seconds, for a still and for the busiest board
+    fastest: f32,
+    slowest: f32,
+    // the tick length to go back to once it's off
+    normal: Option<Duration>,
+}
+
+// tick length for the share of the board that changed in the last tick
+fn adaptive_period(activity: f32, fastest: f32, slowest: f32) -> f32 {
+    let busy = (activity / BUSIEST_ACTIVITY).clamp(0.0, 1.0);
+    fastest + (slowest - fastest) * busy
+}
+
+#[test]
+fn test_adaptive_period() {
+    assert_eq!(adaptive_period(0.0, 0.1, 1.0), 0.1);
+    assert_eq!(adaptive_period(BUSIEST_ACTIVITY, 0.1, 1.0), 1.0);
+    assert_eq!(adaptive_period(0.9, 0.1, 1.0), 1.0);
+    assert!((adaptive_period(BUSIEST_ACTIVITY / 2.0, 0.2, 1.0) - 0.6).abs() < 1e-6);
+}
+
+fn toggle_adaptive_speed(
+    keys: Res<Input<KeyCode>>,
+    mut speed: ResMut<AdaptiveSpeed>,
+    mut fixed_time: ResMut<FixedTime>,
+) {
+    if !keys.just_pressed(KeyCode::P) {
+        return;
+    }
+    speed.enabled = !speed.enabled;
+    if speed.enabled {
+        speed.normal = Some(fixed_time.period);
+    } else if let Some(normal) = speed.normal.take() {
+        fixed_time.period = normal;
+    }
+}
+
+// `=` halves the tick, `-` doubles it, within MIN_TICK and MAX_TICK
+fn scaled_period(period: f32, faster: bool) -> f32 {
+    let scaled = if faster { period / 2.0 } else { period * 2.0 };
+    scaled.clamp(MIN_TICK, MAX_TICK)
+}
+
+#[test]
+fn test_scaled_period() {
+    assert_eq!(scaled_period(1.0, true), 0.5);
+    assert_eq!(scaled_period(1.0, false), 2.0);
+    assert_eq!(scaled_period(0.015, true), MIN_TICK);
+    assert_eq!(scaled_period(8.0, false), MAX_TICK);
+}
+
+// `=` / `-` speed the ticks up / down, left to the adaptive speed while that's
+// on; `0` runs a tick every frame instead
+fn change_speed(
+    keys: Res<Input<KeyCode>>,
+    speed: Res<AdaptiveSpeed>,
+    mut fixed_time: ResMut<FixedTime>,
+    mut flat_out: ResMut<FlatOut>,
+) {
+    if keys.just_pressed(KeyCode::Key0) {
+        flat_out.0 = !flat_out.0;
+    }
+    let faster = keys.any_just_pressed([KeyCode::Equals, KeyCode::NumpadAdd]);
+    let slower = keys.any_just_pressed([KeyCode::Minus, KeyCode::NumpadSubtract]);
+    if speed.enabled || faster == slower {
+        return;
+    }
+    let period = scaled_period(fixed_time.period.as_secs_f32(), faster);
+    fixed_time.period = Duration::from_secs_f32(period);
+}
+
+// generations actually run per second, whatever drives them
+#[derive(Resource, Default, Debug)]
+struct Throughput {
+    // when the current count started, in seconds since startup, and the round then
+    since: Option<(f32, usize)>,
+    rate: f32,
+}
+
+fn measure_throughput(time: Res<Time>, db: Res<Dashboard>, mut throughput: ResMut<Throughput>) {
+    let now = time.elapsed_seconds();
+    match throughput.since {
+        Some((start, round)) if now - start >= THROUGHPUT_WINDOW => {
+            // going back to an earlier round counts as none
+            throughput.rate = db.round.saturating_sub(round) as f32 / (now - start);
+            throughput.since = Some((now, db.round));
+        }
+        Some(_) => {}
+        None => throughput.since = Some((now, db.round)),
+    }
+}
+
+fn adapt_speed(
+    db: Res<Dashboard>,
+    config: Res<Config>,
+    speed: Res<AdaptiveSpeed>,
+    mut fixed_time: ResMut<FixedTime>,
+) {
+    // nothing to go by before the first tick, so the rate stays as it is
+    if !speed.enabled || db.history.is_empty() {
+        return;
+    }
+    let activity = (db.births + db.deaths) as f32 / config.area() as f32;
+    let period = adaptive_period(activity, speed.fastest, speed.slowest);
+    fixed_time.period = Duration::from_secs_f32(period);
+}
+
+fn toggle_board_counter(
+    keys: Res<Input<KeyCode>>,
+    mut query: Query<&mut Visibility, With<BoardCounter>>,
+) {
+    if !keys.just_pressed(KeyCode::K) {
+        return;
+    }
+    for mut v in query.iter_mut() {
+        *v = match *v {
+            Visibility::Hidden => Visibility::Inherited,
+            _ => Visibility::Hidden,
+        };
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn update_dashboard(
+    db: Res<Dashboard>,
+    readouts: Readouts,
+    side_worlds: Res<worlds::SideWorlds>,
+    detector: Res<PeriodDetector>,
+    config: Res<Config>,
+    rule: Res<Rule>,
+    edge: Res<EdgeMode>,
+    sim_state: Res<SimNow>,
+    drawing: DrawState,
+    live: Res<LiveCells>,
+    comparison: Res<compare::Comparison>,
+    theme: Res<Theme>,
+    lineage: Res<Lineage>,
+    mut query: Query<&mut Text, With<DashboardText>>,
+    mut counter: Query<&mut Text, (With<BoardCounter>, Without<DashboardText>)>,
+) {
+    for mut t in counter.iter_mut() {
+        t.sections[0].value = format!("{}\n{}", db.round, db.survival);
+        t.sections[0].style.color = theme.text.with_a(0.15);
+    }
+    let trend_color = match db.trend(config.trend_threshold) {
+        Trend::Growing => Some(Color::GREEN),
+        Trend::Shrinking => Some(Color::RED),
+        Trend::Stable => None,
+    };
+    let preset = if *rule == Rule::life_without_death() {
+        " (Life without Death)"
+    } else {
+        ""
+    };
+    // the first line keeps the default style, the rest may override the color
+    let mut lines = vec![
+        (format!("Round: {} ", db.round), None),
+        (format!("Survival: {} ", db.survival), trend_color),
+        (
+            format!(
+                "Activity: {:.1}% ",
+                // births and deaths against the whole board: near zero is settling, high is churn
+                100.0 * (db.births + db.deaths) as f32 / config.area() as f32
+            ),
+            None,
+        ),
+        (
+            format!(
+                "\nBirths: {} Deaths: {} Peak: {} Density: {:.1}% ",
+                db.births,
+                db.deaths,
+                db.peak,
+                100.0 * db.survival as f32 / config.area() as f32
+            ),
+            None,
+        ),
+        match &config.turmite {
+            Some(turmite) => (format!("\nTurmite: {turmite} "), None),
+            None => (format!("\nRule: {}{preset} ", *rule), None),
+        },
+        (format!("\nEdges: {} ", *edge), None),
+    ];
+    if rule.colors > 1 {
+        let mut counts = vec![0; rule.colors as usize];
+        for color in lineage.colors.values() {
+            counts[(*color % rule.colors) as usize] += 1;
+        }
+        lines.push(("\nColors: ".to_string(), None));
+        for (i, n) in counts.into_iter().enumerate() {
+            lines.push((
+                format!("{n} "),
+                Some(LINEAGE_COLORS[i % LINEAGE_COLORS.len()]),
+            ));
+        }
+    }
+    if config.backend == Backend::Hashlife {
+        let k = readouts.hashlife.super_step;
+        let nodes = readouts.hashlife.node_count();
+        lines.push((
+            format!("\nHashLife: 2^{k} generations a tick, {nodes} nodes "),
+            None,
+        ));
+    }
+    lines.push((format!("\nTool: {} ", *drawing.tool), None));
+    if *drawing.tool == DrawTool::Pen {
+        lines.push((format!("({}) ", *drawing.brush), None));
+    }
+    if rule.wireworld && *drawing.tool != DrawTool::Select {
+        lines.push((format!("painting {} (D) ", *drawing.wire), None));
+    }
+    if *drawing.tool == DrawTool::Stamp && !drawing.stamp.1.is_empty() {
+        lines.push((format!("({}) ", drawing.stamp.1), None));
+    }
+    if let Some((w, h)) = drawing.selection.size() {
+        lines.push((format!("({w}x{h} selected) "), None));
+    }
+    if *drawing.symmetry != Symmetry::None {
+        lines.push((format!("[{}] ", *drawing.symmetry), None));
+    }
+    if let Some((x, y)) = drawing.anchor.0.map(|c| readouts.origin.shown(c)) {
+        lines.push((format!("from ({x}, {y}) "), None));
+    }
+    for (i, world) in side_worlds.0.iter().enumerate() {
+        lines.push((
+            format!(
+                "\nWorld {}: round {}, survival {} ({})",
+                i + 2,
+                world.round,
+                world.live.len(),
+                world.rule
+            ),
+            None,
+        ));
+    }
+    if let (Some(previous), Some(diff)) = (
+        comparison.previous_rule(),
+        comparison.diff(db.round, &live.0),
+    ) {
+        lines.push((
+            format!(
+                "\nAgainst the run under {previous}: {} only then, {} only now, {} both",
+                diff.previous_only.len(),
+                diff.current_only.len(),
+                diff.both.len()
+            ),
+            None,
+        ));
+    }
+    if !readouts.pending.0.is_empty() {
+        let left = readouts.pending.0.len();
+        lines.push((
+            format!("\nBuilding grid... {left} cells to go"),
+            Some(Color::YELLOW),
+        ));
+    }
+    match db.settled {
+        Some((since, _)) if db.survival == 0 => {
+            lines.push((format!("\nDied out at round {since}"), Some(Color::CYAN)));
+        }
+        Some((since, period)) => {
+            lines.push((
+                format!("\nStabilized at round {since}, period {period}"),
+                Some(Color::CYAN),
+            ));
+        }
+        None => {}
+    }
+    if let Some(ship) = detector.spaceship {
+        lines.push((format!("\nSpaceship detected, {ship}"), Some(Color::CYAN)));
+    }
+    if let Some(names) = readouts.objects.0.as_ref().filter(|n| !n.is_empty()) {
+        lines.push((format!("\nObjects: {names}"), Some(Color::CYAN)));
+    }
+    let flat_out = if readouts.flat_out.0 {
+        ", flat out"
+    } else {
+        ""
+    };
+    lines.push((
+        format!("\nSpeed: {:.1} gen/s{flat_out}", readouts.throughput.rate),
+        None,
+    ));
+    if readouts.speed.enabled {
+        let rate = 1.0 / readouts.fixed_time.period.as_secs_f32();
+        lines.push((format!("\nAdaptive speed: {rate:.1} ticks/s"), None));
+    }
+    if readouts.origin.0 != (0, 0) {
+        let (x, y) = readouts.origin.0;
+        lines.push((format!("\nCounting from ({x}, {y})"), None));
+    }
+    if let Some(frames) = readouts.recording.frames() {
+        lines.push((format!("\nRecording: {frames} frames"), Some(Color::RED)));
+    }
+    if readouts.bbox.0 {
+        lines.push((
+            format!("\nBox: {}", box_readout(&live.0, *readouts.origin)),
+            None,
+        ));
+    }
+    if readouts.quadrants.0 {
+        let [ne, nw, sw, se] = quadrant_counts(&live.0);
+        lines.push((
+            format!("\nQuadrants: NW {nw} NE {ne} SW {sw} SE {se}"),
+            None,
+        ));
+    }
+    if let (true, Some([min, median, p95, max])) =
+        (readouts.timing.enabled, readouts.timing.summary)
+    {
+        lines.push((
+            format!("\nStep: min {min:.2?} median {median:.2?} p95 {p95:.2?} max {max:.2?}"),
+            None,
+        ));
+    }
+    if readouts.memory.enabled {
+        lines.push((
+            format!(
+                "\nMemory: live {}, history {}",
+                human_bytes(readouts.memory.live),
+                human_bytes(readouts.memory.history)
+            ),
+            None,
+        ));
+    }
+    if *sim_state.get() == SimState::Paused {
+        lines.push(("\nPaused (Space resumes)".to_string(), Some(Color::YELLOW)));
+    }
+    if let Some((round, f)) = &db.forecast {
+        lines.push((format!("\nForecast from round {round}: {f}"), None));
+    }
     for mut t in query.iter_mut() {
-        t.sections[0].value = format!("Round: {} ", db.round);
-        t.sections[1].value = format!("Survival: {} ", db.survival);
+        let style = t.sections[0].style.clone();
+        t.sections = lines
+            .iter()
+            .map(|(l, color)| {
+                let mut style = style.clone();
+                style.color = color.unwrap_or(theme.text);
+                TextSection::new(l.clone(), style)
+            })
+            .collect();
+    }
+}
+
+// the sim plugin without rendering or input, run flat out for `generations`
+// ticks once the board is built, 2^super_step generations each under
+// HashLife; prints what it got to and how long it took
+#[allow(clippy::too_many_arguments)]
+fn run_headless(
+    mut config: Config,
+    rule: Rule,
+    edge: EdgeMode,
+    seed: Option<u64>,
+    generations: usize,
+    super_step: u8,
+    output: Option<PathBuf>,
+    stats_out: Option<stats::StatsOut>,
+) {
+    // the GPU board needs a renderer
+    if config.backend == Backend::Gpu {
+        config.backend = Backend::Cpu;
+    }
+    let per_tick = match config.backend {
+        Backend::Hashlife => 1 << super_step.min(MAX_SUPER_STEP),
+        _ => 1,
+    };
+    let mut app = App::new();
+    app.insert_resource(HashLife::new(super_step));
+    if let Some(seed) = seed {
+        app.insert_resource(SimRng::seeded(seed));
+    }
+    if let Some(stats_out) = stats_out {
+        app.insert_resource(stats_out).add_systems(
+            FixedUpdate,
+            stats::write_stats
+                .after(SimSet)
+                .run_if(resource_exists::<stats::StatsOut>()),
+        );
+    }
+    app.add_plugins(MinimalPlugins)
+        .insert_resource(config)
+        .insert_resource(rule.clone())
+        .insert_resource(edge)
+        .add_plugins(SimPlugin);
+    app.update();
+    while !app.world.resource::<PendingCells>().0.is_empty() {
+        app.update();
+    }
+    let started = std::time::Instant::now();
+    for _ in 0..generations {
+        step_once(&mut app.world);
+    }
+    let elapsed = started.elapsed();
+    let generations = generations * per_tick;
+    let (round, population) = {
+        let db = app.world.resource::<Dashboard>();
+        (db.round, db.survival)
+    };
+    let per_sec = generations as f64 / elapsed.as_secs_f64().max(f64::EPSILON);
+    println!(
+        "{generations} generations in {elapsed:.2?} ({per_sec:.0}/s): round {round}, population {population}"
+    );
+    if let Some(path) = output {
+        let pattern = Pattern::from_board(&app.world.resource::<LiveCells>().0);
+        if let Err(e) = std::fs::write(&path, to_format(&path, &pattern, &rule)) {
+            eprintln!("can't write {}: {e}", path.display());
+            std::process::exit(1);
+        }
     }
 }
 
 fn main() {
-    App::new()
-        .insert_resource(FixedTime::new_from_secs(TICK))
-        .insert_resource(Dashboard::default())
-        .add_plugins(DefaultPlugins)
-        .add_systems(Startup, setup)
-        .add_systems(FixedUpdate, dead_or_alive)
-        .add_systems(Update, update_cell_color)
-        .add_systems(Update, update_dashboard)
-        .run();
+    let cli = Cli::parse();
+    let tune = cli.tune_density;
+    let assert_by = cli.assert_extinct_by;
+    let soup_search = cli.soup_search.then_some((cli.runs, cli.soup_cap));
+    let bench = cli.bench;
+    let headless = cli.generations.filter(|_| cli.headless);
+    let output = cli.output.clone();
+    let stats_path = cli.stats_out.clone();
+    let (host_addr, join_addr) = (cli.host.clone(), cli.join.clone());
+    let timeline = scrub::Timeline::with_history(cli.history);
+    let screenshot_width = screenshot::ScreenshotWidth(cli.screenshot_width);
+    let recording = record::Recording::new(cli.record_delay, cli.record_width);
+    let load = cli.load.as_ref().map(|path| {
+        save::SaveState::load(path).unwrap_or_else(|e| {
+            let e = format!("--load {}: {e}", path.display());
+            Cli::command().error(ErrorKind::ValueValidation, e).exit()
+        })
+    });
+    let print_config = cli.print_config;
+    let no_menu = cli.no_menu;
+    let log_diagnostics = cli.log_diagnostics;
+    let (life3d, cube_side) = (cli.life3d, cli.cube_side);
+    let super_step = cli.super_step;
+    let config_path = cli.config.clone().or_else(|| {
+        let mut defaults = session::DEFAULT_FILES.iter().map(PathBuf::from);
+        defaults.find(|p| p.exists())
+    });
+    let replay = cli.replay.as_ref().map(|path| {
+        replay::Replay::load(path).unwrap_or_else(|e| {
+            let e = format!("--replay {}: {e}", path.display());
+            Cli::command().error(ErrorKind::ValueValidation, e).exit()
+        })
+    });
+    // a replay is set up from the session it was recorded in
+    let config_path = config_path.filter(|_| replay.is_none());
+    let session = match (replay.as_ref(), &config_path) {
+        (Some(_), _) => Ok(session::Session::default()),
+        (None, Some(path)) => session::Session::load(path),
+        (None, None) => Ok(session::Session::default()),
+    };
+    let session = session.unwrap_or_else(|e| {
+        let e = format!("--config {}: {e}", config_path.as_ref().unwrap().display());
+        Cli::command().error(ErrorKind::ValueValidation, e).exit()
+    });
+    let (session, playback) = match replay {
+        Some(replay) => (replay.session, Some(replay::Playback::new(replay.edits))),
+        None => (session, None),
+    };
+    let from_file = config_path.is_some() || playback.is_some();
+    let record_edits = cli.record_edits.clone();
+    let seed = load.as_ref().map(|s| s.seed).or(cli.seed).or(session.seed);
+    let unbounded = cli.unbounded;
+    let texture_render = texture::TextureRender(cli.texture);
+    let panel = camera::Panel {
+        side: cli.panel,
+        width: cli.panel_width,
+    };
+    if panel.width <= 0.0 {
+        let e = "--panel-width has to be above 0";
+        Cli::command().error(ErrorKind::ValueValidation, e).exit();
+    }
+    if cli.texture && cli.topology == Topology::Hex {
+        let e = "--texture draws the square lattice only";
+        Cli::command().error(ErrorKind::ValueValidation, e).exit();
+    }
+    let rule = if from_file {
+        session.rule
+    } else {
+        cli.rule.clone()
+    };
+    let theme = if from_file {
+        session.theme
+    } else {
+        Theme {
+            size: cli.cell_size,
+            gap: GAP * cli.cell_size / SIZE,
+            corner_radius: cli.corner_radius,
+            smooth: cli.smooth,
+            ..Default::default()
+        }
+    };
+    let theme = match cli.theme {
+        Some(preset) => theme.with_preset(preset),
+        None => theme,
+    };
+    if !(cli.cell_size > 0.0 && cli.tick_ms > 0.0) {
+        let e = "--cell-size and --tick-ms have to be above 0";
+        Cli::command().error(ErrorKind::ValueValidation, e).exit();
+    }
+    let tick = match session.tick {
+        Some(tick) if from_file => tick,
+        _ => cli.tick_ms / 1000.0,
+    };
+    if tick <= 0.0 {
+        let e = "the tick in the --config file has to be above 0";
+        Cli::command().error(ErrorKind::ValueValidation, e).exit();
+    }
+    let sonification = sound::Sonification::new(cli.sound_scale, cli.sound_tempo);
+    let explorer = explore::Explorer::new(
+        cli.explore_every as usize,
+        cli.explore_reseed,
+        cli.explore_log.as_deref(),
+    )
+    .unwrap_or_else(|e| {
+        let e = format!("--explore-log: {e}");
+        Cli::command().error(ErrorKind::Io, e).exit()
+    });
+    let speed = AdaptiveSpeed {
+        enabled: false,
+        fastest: cli.fastest_tick,
+        slowest: cli.slowest_tick,
+        normal: None,
+    };
+    if !(0.0 < speed.fastest && speed.fastest <= speed.slowest) {
+        let e = "--fastest-tick has to be above 0 and at most --slowest-tick";
+        Cli::command().error(ErrorKind::ValueValidation, e).exit();
+    }
+    let mut config = if from_file {
+        session.config
+    } else {
+        Config::from(cli)
+    };
+    let rule = match &load {
+        Some(state) => {
+            // the saved board goes down on an empty one its size
+            (config.width, config.height) = (state.board.width, state.board.height);
+            config.empty = true;
+            state.rule.clone()
+        }
+        None => rule,
+    };
+    if let Err(e) = config.validate() {
+        Cli::command().error(ErrorKind::ValueValidation, e).exit();
+    }
+    if let Err(e) = config.runs(&rule) {
+        Cli::command().error(ErrorKind::ValueValidation, e).exit();
+    }
+    if config.backend == Backend::Hashlife {
+        if texture_render.0 {
+            let e = "the HashLife backend has no edges, so no --texture either";
+            Cli::command().error(ErrorKind::ValueValidation, e).exit();
+        }
+        config.sparse = true;
+    }
+    let unbounded = unbounded || config.backend == Backend::Hashlife;
+    let edge = if unbounded {
+        EdgeMode::open()
+    } else {
+        EdgeMode::default()
+    };
+    // with no edges the board is only where the cells start, so it grows to
+    // keep all of a pattern bigger than it, a Macrocell one say; stdin is read
+    // once, later
+    let pattern = config
+        .pattern
+        .as_ref()
+        .filter(|p| unbounded && **p != Path::new("-"));
+    if let Some(pattern) = pattern.and_then(|p| load_pattern(p).ok()) {
+        let (x, y) = config.pattern_at;
+        config.width = config.width.max(pattern.width + 2 * x.abs() + 2);
+        config.height = config.height.max(pattern.height + 2 * y.abs() + 2);
+    }
+    // a soup of electrons with no wires under them is gone in a tick
+    if rule.wireworld && config.pattern.is_none() {
+        config.empty = true;
+    }
+    let network_error = |e: std::io::Error| -> ! {
+        let e = format!(
+            "can't reach {}: {e}",
+            host_addr.as_ref().or(join_addr.as_ref()).unwrap()
+        );
+        Cli::command().error(ErrorKind::Io, e).exit()
+    };
+    let host = host_addr
+        .as_deref()
+        .map(|addr| net::Host::bind(addr).unwrap_or_else(|e| network_error(e)));
+    let client = join_addr
+        .as_deref()
+        .map(|addr| net::Client::connect(addr).unwrap_or_else(|e| network_error(e)));
+    // the host's board comes in over the connection
+    config.empty |= client.is_some();
+    if let Some(target) = tune {
+        let (density, population) = tune_density(target, &config, &rule, edge);
+        println!("--density {density:.4} averages {population:.1} live cells early on");
+        return;
+    }
+    if let Some(by) = assert_by {
+        if let Err(e) = forecasts(&rule) {
+            let e = format!("--assert-extinct-by: {e}");
+            Cli::command().error(ErrorKind::ValueValidation, e).exit();
+        }
+        let seed = seed.unwrap_or_else(rand::random);
+        let start = starting_cells(&config, &rule, edge, &mut SimRng::seeded(seed).0);
+        // rounds count from the end of the warmup, as on the dashboard
+        let cap = by.saturating_sub(config.warmup);
+        match forecast_within(start, &rule, &config, edge, cap) {
+            Forecast::Extinct(n) => {
+                println!("seed {seed}: extinct at generation {}", config.warmup + n);
+            }
+            Forecast::Settles { after, period } => {
+                let at = config.warmup + after;
+                println!("seed {seed}: settled with period {period} at generation {at}");
+            }
+            Forecast::Unknown => {
+                eprintln!("seed {seed}: still changing at generation {by}, expected it to die out or settle by then");
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
+    if bench {
+        // the same soup every time unless --seed says otherwise
+        bench::run(seed.unwrap_or(0));
+        return;
+    }
+
+    if let Some((runs, cap)) = soup_search {
+        if let Err(e) = forecasts(&rule) {
+            let e = format!("--soup-search: {e}");
+            Cli::command().error(ErrorKind::ValueValidation, e).exit();
+        }
+        let base = seed.unwrap_or_else(rand::random);
+        soups::search(&config, &rule, edge, base, runs, cap);
+        return;
+    }
+
+    let stats_out = stats_path.map(|path| {
+        stats::StatsOut::create(&path).unwrap_or_else(|e| {
+            let e = format!("--stats-out {}: {e}", path.display());
+            Cli::command().error(ErrorKind::ValueValidation, e).exit()
+        })
+    });
+    if let Some(generations) = headless {
+        run_headless(
+            config,
+            rule,
+            edge,
+            seed,
+            generations,
+            super_step,
+            output,
+            stats_out,
+        );
+        return;
+    }
+
+    if let Some(rule) = life3d {
+        cube::run(rule, cube_side, tick, seed);
+        return;
+    }
+
+    let stamp = Stamp::new(&config);
+    let gpu = config.backend == Backend::Gpu;
+    let mut app = App::new();
+    if let Some(seed) = seed {
+        app.insert_resource(SimRng::seeded(seed));
+    }
+    if print_config {
+        app.add_systems(Startup, session::print_config.after(SimSet));
+    }
+    if no_menu {
+        app.insert_resource(bevy::ecs::schedule::State::new(menu::Screen::Simulation));
+    }
+    if log_diagnostics {
+        app.add_plugins(LogDiagnosticsPlugin::filtered(diagnostics::SHOWN.to_vec()));
+    }
+    if let Some(state) = load {
+        app.insert_resource(save::PendingLoad(state)).add_systems(
+            Update,
+            save::apply_pending_load.run_if(resource_exists::<save::PendingLoad>()),
+        );
+    }
+    if let Some(path) = config_path {
+        app.insert_resource(session::Watched::new(path))
+            .add_systems(Update, session::reload_session);
+    }
+    if rule.script.is_some() {
+        app.add_systems(Update, session::reload_script);
+    }
+    if unbounded {
+        app.insert_resource(EdgeMode::open());
+    }
+    if let Some(stats_out) = stats_out {
+        app.insert_resource(stats_out);
+    }
+    if let Some(path) = record_edits {
+        app.insert_resource(replay::EditLog::new(path))
+            .add_systems(Startup, replay::start_log.after(SimSet))
+            .add_systems(
+                FixedUpdate,
+                (
+                    replay::note_edits.in_set(SimSet).before(dead_or_alive),
+                    replay::note_tick.in_set(SimSet).after(explore::explore),
+                ),
+            );
+    }
+    if let Some(playback) = playback {
+        app.insert_resource(playback).add_systems(
+            FixedUpdate,
+            replay::play_edits.in_set(SimSet).before(dead_or_alive),
+        );
+    }
+    if let Some(host) = host {
+        app.insert_resource(host)
+            .add_systems(Update, net::serve.after(draw::draw));
+    }
+    if let Some(client) = client {
+        app.insert_resource(client)
+            .add_systems(Update, net::follow_host.after(draw::draw));
+    }
+    app.insert_resource(FixedTime::new_from_secs(tick))
+        .insert_resource(config)
+        .insert_resource(rule)
+        .insert_resource(theme)
+        .init_resource::<corners::CellTexture>()
+        .init_resource::<TitleRefresh>()
+        .init_resource::<MemoryView>()
+        // where the cells went is the thing to know without a border
+        .insert_resource(BoxView(unbounded))
+        .insert_resource(HashLife::new(super_step))
+        .init_resource::<QuadrantView>()
+        .init_resource::<Objects>()
+        .init_resource::<Throughput>()
+        .insert_resource(explorer)
+        .insert_resource(speed)
+        .init_resource::<SpectrumView>()
+        .init_resource::<Interpolation>()
+        .init_resource::<NeighborView>()
+        .init_resource::<Confetti>()
+        .init_resource::<HideDead>()
+        .init_resource::<SeedView>()
+        .init_resource::<AgeView>()
+        .insert_resource(texture_render)
+        .init_resource::<heat::HeatMap>()
+        .init_resource::<trail::TrailView>()
+        .init_resource::<Pulse>()
+        .init_resource::<RenderEnabled>()
+        .init_resource::<pop::PopMode>()
+        .init_resource::<inspect::Inspector>()
+        .init_resource::<inspect::GridOverlay>()
+        .init_resource::<labels::ObjectLabels>()
+        .insert_resource(timeline)
+        .init_resource::<ribbon::Ribbon>()
+        .init_resource::<minimap::Minimap>()
+        .init_resource::<compare::Comparison>()
+        .init_resource::<lifespan::Lifespans>()
+        .init_resource::<DrawTool>()
+        .init_resource::<draw::DrawAnchor>()
+        .init_resource::<draw::EraseDrag>()
+        .init_resource::<draw::Brush>()
+        .init_resource::<wire::WirePaint>()
+        .init_resource::<select::Selection>()
+        .init_resource::<controls::Controls>()
+        .init_resource::<controls::StepPressed>()
+        .init_resource::<controls::RuleField>()
+        .init_resource::<graph::PopulationGraph>()
+        .init_resource::<touch::TouchGesture>()
+        .insert_resource(screenshot_width)
+        .insert_resource(recording)
+        .init_resource::<select::Clipboard>()
+        .init_resource::<Symmetry>()
+        .insert_resource(stamp)
+        .init_resource::<worlds::SideWorlds>()
+        .insert_resource(sonification)
+        .add_plugins(DefaultPlugins.set(WindowPlugin {
+            primary_window: Some(primary_window()),
+            ..Default::default()
+        }))
+        .add_audio_source::<sound::Tone>()
+        // after the log plugin, so the seed gets printed
+        .add_plugins(SimPlugin)
+        .add_plugins((
+            FrameTimeDiagnosticsPlugin,
+            diagnostics::SimDiagnosticsPlugin,
+        ))
+        .init_resource::<diagnostics::DiagnosticsOverlay>()
+        .add_systems(Startup, diagnostics::spawn_overlay)
+        .add_systems(
+            FixedUpdate,
+            diagnostics::measure_step
+                .in_set(SimSet)
+                .after(cap_population),
+        )
+        .add_systems(
+            Update,
+            diagnostics::measure_coloring.after(update_cell_color),
+        )
+        .add_systems(
+            Update,
+            (diagnostics::toggle_overlay, diagnostics::update_overlay)
+                .chain()
+                .in_set(menu::OnBoard),
+        )
+        .add_state::<menu::Screen>()
+        .init_resource::<menu::Menu>()
+        .configure_set(
+            FixedUpdate,
+            SimSet.run_if(in_state(menu::Screen::Simulation)),
+        )
+        .configure_set(
+            Update,
+            menu::OnBoard.run_if(in_state(menu::Screen::Simulation)),
+        )
+        .add_systems(OnEnter(menu::Screen::Menu), menu::spawn_main_menu)
+        .add_systems(OnEnter(menu::Screen::Patterns), menu::spawn_patterns)
+        .add_systems(OnEnter(menu::Screen::Settings), menu::spawn_settings)
+        .add_systems(OnExit(menu::Screen::Menu), menu::despawn_screen)
+        .add_systems(OnExit(menu::Screen::Patterns), menu::despawn_screen)
+        .add_systems(OnExit(menu::Screen::Settings), menu::despawn_screen)
+        .add_systems(OnEnter(menu::Screen::Simulation), menu::start_board)
+        .add_systems(
+            Update,
+            (menu::go_back, menu::press_menu, menu::update_settings).chain(),
+        )
+        .add_systems(
+            Startup,
+            (
+                corners::load_cell_texture,
+                setup,
+                worlds::spawn_side_worlds,
+                worlds::spawn_world_labels,
+                inspect::spawn_inspector,
+                scrub::spawn_scrubber,
+                scrub::record_keyframe,
+                compare::record_generation,
+                lifespan::count_lifespans,
+                graph::record_population,
+            )
+                .chain()
+                .after(SimSet),
+        )
+        .add_systems(
+            FixedUpdate,
+            (
+                worlds::step_side_worlds,
+                explore::explore,
+                adapt_speed,
+                scrub::record_keyframe,
+                compare::record_generation,
+                lifespan::count_lifespans,
+                graph::record_population,
+                trail::track_activity,
+                record::capture_frame,
+                stats::write_stats.run_if(resource_exists::<stats::StatsOut>()),
+            )
+                .chain()
+                .in_set(SimSet)
+                .after(cap_population),
+        )
+        .add_systems(
+            Update,
+            (
+                toggle_spectrum,
+                request_forecast,
+                explore::toggle_explore,
+                toggle_edge_mode,
+                toggle_board_counter,
+                toggle_interpolation,
+                toggle_smooth,
+                toggle_pulse,
+                toggle_neighbor_view,
+                toggle_pause,
+                toggle_confetti,
+                toggle_hide_dead,
+                toggle_seed_view,
+                toggle_rendering,
+                toggle_step_timing,
+                toggle_memory_view,
+                toggle_box_view,
+                toggle_quadrant_view,
+                toggle_adaptive_speed,
+                themes::cycle_theme,
+            )
+                .in_set(menu::OnBoard),
+        )
+        .add_systems(
+            Update,
+            (apply_theme_background, reflow_cells.after(adjust_gap))
+                .run_if(resource_changed::<Theme>()),
+        )
+        .add_systems(Update, adjust_gap)
+        .add_systems(
+            Update,
+            (
+                toggle_life_without_death,
+                cycle_rule,
+                toggle_age_view,
+                change_super_step.run_if(hashlife_backend),
+            )
+                .in_set(menu::OnBoard),
+        )
+        .add_systems(
+            Update,
+            step_once
+                .after(toggle_pause)
+                .after(controls::press_controls)
+                .run_if(single_step)
+                .in_set(menu::OnBoard),
+        )
+        .add_systems(Startup, controls::spawn_controls)
+        .add_systems(
+            Update,
+            (graph::toggle_graph, graph::draw_graph)
+                .chain()
+                .in_set(menu::OnBoard),
+        )
+        .add_systems(
+            Update,
+            (
+                touch::touch_gestures,
+                touch::touch_paint.run_if(controls::pointer_free),
+            )
+                .chain()
+                .in_set(menu::OnBoard),
+        )
+        .add_systems(
+            Update,
+            (
+                controls::toggle_controls,
+                controls::press_controls,
+                controls::update_controls,
+                controls::show_rule_field,
+            )
+                .chain()
+                .in_set(menu::OnBoard),
+        )
+        .add_systems(
+            PreUpdate,
+            controls::type_rule.after(bevy::input::InputSystem),
+        )
+        .add_systems(
+            Update,
+            resize::resize_board
+                .after(controls::press_controls)
+                .after(resize::resize_on_key)
+                .before(texture::update_board_texture)
+                .before(heat::update_heat_map),
+        )
+        .add_systems(
+            Update,
+            (
+                draw::select_tool,
+                draw::adjust_brush,
+                draw::cycle_symmetry,
+                draw::pick_stamp,
+                draw::orient_stamp,
+                controls::reseed_on_key,
+                draw::draw
+                    .run_if(controls::pointer_free)
+                    .run_if(not(wire::wireworld)),
+                wire::draw_wires
+                    .run_if(controls::pointer_free)
+                    .run_if(wire::wireworld),
+                wire::cycle_wire_paint.run_if(wire::wireworld),
+                draw::erase_rect.run_if(controls::pointer_free),
+                select::select_cells.run_if(controls::pointer_free),
+                select::use_clipboard,
+            )
+                .chain()
+                .in_set(menu::OnBoard),
+        )
+        .add_systems(
+            Update,
+            (
+                draw::preview_brush,
+                draw::preview_stamp,
+                draw::preview_erase,
+                select::preview_selection,
+                turmite::draw_ants,
+            ),
+        )
+        .add_systems(
+            Update,
+            (
+                export::export_svg,
+                export::export_pattern,
+                lifespan::export_lifespans,
+                reload_pattern,
+                save::save_or_load,
+                save::load_dropped,
+                screenshot::take_screenshot,
+                record::toggle_recording,
+            )
+                .in_set(menu::OnBoard),
+        )
+        .add_systems(Update, (time_pulse, draw_edges).chain())
+        .add_systems(
+            Update,
+            (compare::replay_seed, compare::draw_comparison).chain(),
+        )
+        .add_systems(
+            Update,
+            (
+                scrub::scrub.before(draw::draw),
+                scrub::rewind,
+                scrub::update_scrubber,
+            )
+                .chain()
+                .in_set(menu::OnBoard),
+        )
+        .add_systems(
+            Update,
+            (ribbon::toggle_ribbon, ribbon::update_ribbon)
+                .chain()
+                .in_set(menu::OnBoard),
+        )
+        .add_systems(Startup, minimap::spawn_minimap)
+        .add_systems(
+            Update,
+            (
+                minimap::toggle_minimap,
+                minimap::jump_from_minimap,
+                minimap::update_minimap,
+            )
+                .chain()
+                .after(camera::pan_camera)
+                .in_set(menu::OnBoard),
+        )
+        .add_event::<CellEvent>()
+        .add_systems(
+            Update,
+            count_cell_events.run_if(|config: Res<Config>| config.cell_events),
+        )
+        .add_systems(
+            Update,
+            (inspect::toggle_inspector, inspect::update_inspector)
+                .chain()
+                .in_set(menu::OnBoard),
+        )
+        .add_systems(
+            Update,
+            (inspect::toggle_grid, inspect::draw_grid)
+                .chain()
+                .in_set(menu::OnBoard),
+        )
+        .add_systems(
+            Update,
+            (
+                apply_smooth.after(toggle_smooth),
+                pop::toggle_pop,
+                pop::animate_pops,
+            )
+                .chain()
+                .in_set(menu::OnBoard),
+        )
+        .init_resource::<camera::Bookmarks>()
+        .init_resource::<camera::DisplayOrigin>()
+        .insert_resource(panel)
+        .add_systems(
+            Update,
+            (
+                camera::fit_to_view,
+                camera::refit_on_resize,
+                camera::bookmarks,
+                camera::recenter_origin,
+                camera::pan_camera,
+                resize::resize_on_key,
+                camera::zoom_camera,
+            )
+                .in_set(menu::OnBoard),
+        )
+        .add_systems(
+            Update,
+            (worlds::color_side_worlds, worlds::update_world_labels).run_if(rendering),
+        )
+        .add_systems(
+            Update,
+            (
+                heat::toggle_heat_map,
+                trail::toggle_trail_view,
+                heat::update_heat_map.run_if(rendering),
+            )
+                .chain(),
+        )
+        .add_systems(
+            Update,
+            (dress_cells, corners::round_new_cells)
+                .chain()
+                .run_if(not(texture::texture_render)),
+        )
+        .add_systems(
+            Startup,
+            texture::spawn_board_texture
+                .after(SimSet)
+                .run_if(texture::texture_render),
+        )
+        .add_systems(
+            Update,
+            texture::update_board_texture
+                .run_if(texture::texture_render)
+                .run_if(rendering),
+        )
+        .add_systems(
+            Update,
+            ((look_ahead, count_neighbors), update_cell_color)
+                .chain()
+                .run_if(rendering),
+        )
+        .add_systems(
+            Update,
+            (summarize_step_timing, estimate_memory, update_dashboard)
+                .chain()
+                .run_if(rendering),
+        )
+        .add_systems(
+            Update,
+            (labels::toggle_labels, labels::find_objects, name_objects)
+                .chain()
+                .before(update_dashboard)
+                .in_set(menu::OnBoard),
+        )
+        .add_systems(Update, labels::draw_labels.after(labels::find_objects))
+        .add_systems(
+            Update,
+            (change_speed, measure_throughput.before(update_dashboard)).in_set(menu::OnBoard),
+        )
+        .add_systems(Update, update_title)
+        .add_systems(
+            Update,
+            (sound::toggle_sound, sound::play_sound)
+                .chain()
+                .in_set(menu::OnBoard),
+        );
+    if gpu {
+        app.add_plugins(gpu::GpuPlugin)
+            .add_systems(Startup, gpu::spawn_gpu_board.after(SimSet))
+            .add_systems(
+                FixedUpdate,
+                gpu::tick_gpu_board
+                    .run_if(in_state(menu::Screen::Simulation))
+                    .run_if(in_state(SimState::Running)),
+            );
+    }
+    app.run();
 }
@@ -0,0 +1,365 @@
+use bevy::app::AppExit;
+use bevy::prelude::*;
+use bevy_game_of_life::pattern::{load_pattern, place_pattern, LIBRARY};
+use bevy_game_of_life::{Cell, Config, Dashboard, LiveCells, PeriodDetector, Rule, SimRng};
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::controls::{reseed, restart};
+use crate::resize::resized;
+use crate::{scaled_period, Theme};
+
+// pattern files from the working directory the Load Pattern screen lists,
+// after the built-in ones
+const MAX_PATTERN_FILES: usize = 12;
+
+// what the window shows: the main menu it opens on (unless --no-menu), the
+// patterns to start from, the settings and the board itself; Escape goes
+// back one, from the board to the menu. The board stays put behind the
+// menus, not a tick going by
+#[derive(States, Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Screen {
+    #[default]
+    Menu,
+    Patterns,
+    Settings,
+    Simulation,
+}
+
+impl Screen {
+    fn back(self) -> Screen {
+        match self {
+            Screen::Menu => Screen::Simulation,
+            _ => Screen::Menu,
+        }
+    }
+}
+
+// the systems taking keys and clicks meant for the board, left out while a
+// menu is up
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct OnBoard;
+
+// a settings change the board starts over for, on Start
+#[derive(Resource, Default, Debug)]
+pub struct Menu {
+    fresh_board: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Setting {
+    Width,
+    Height,
+    Rule,
+    Theme,
+    Speed,
+}
+
+const SETTINGS: [(Setting, &str); 5] = [
+    (Setting::Width, "Width"),
+    (Setting::Height, "Height"),
+    (Setting::Rule, "Rule"),
+    (Setting::Theme, "Theme"),
+    (Setting::Speed, "Tick"),
+];
+
+#[derive(Component, Debug, Clone, PartialEq)]
+pub enum MenuButton {
+    Start,
+    Patterns,
+    Settings,
+    Quit,
+    Back,
+    // a built-in name or a file
+    Load(PathBuf),
+    // up or down
+    Change(Setting, bool),
+}
+
+// the root of the screen's nodes, gone with it
+#[derive(Component)]
+pub struct MenuNode;
+
+#[derive(Component)]
+pub struct SettingValue(Setting);
+
+// the pattern files in `dir`, by name
+fn pattern_files(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<_> = entries
+        .filter_map(|e| Some(e.ok()?.path()))
+        .filter(|p| {
+            let extension = p.extension().and_then(|e| e.to_str());
+            matches!(extension, Some("rle" | "cells" | "mc" | "lif" | "life"))
+        })
+        .collect();
+    files.sort();
+    files.truncate(MAX_PATTERN_FILES);
+    files
+}
+
+#[test]
+fn test_menu() {
+    let dir = std::env::temp_dir().join("gol-test-menu");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    for name in ["b.cells", "notes.txt", "a.rle", "big.mc"] {
+        std::fs::write(dir.join(name), "").unwrap();
+    }
+    let names: Vec<_> = pattern_files(&dir)
+        .iter()
+        .map(|p| p.file_name().unwrap().to_str().unwrap().to_string())
+        .collect();
+    assert_eq!(names, ["a.rle", "b.cells", "big.mc"]);
+    assert!(pattern_files(&dir.join("missing")).is_empty());
+    assert_eq!(Screen::Settings.back(), Screen::Menu);
+    assert_eq!(Screen::Simulation.back(), Screen::Menu);
+    assert_eq!(Screen::Menu.back(), Screen::Simulation);
+}
+
+pub fn go_back(
+    keys: Res<Input<KeyCode>>,
+    screen: Res<State<Screen>>,
+    mut next: ResMut<NextState<Screen>>,
+) {
+    if keys.just_pressed(KeyCode::Escape) {
+        next.set(screen.get().back());
+    }
+}
+
+fn label(text: &str, font_size: f32) -> TextBundle {
+    TextBundle::from_section(
+        text,
+        TextStyle {
+            font_size,
+            ..Default::default()
+        },
+    )
+}
+
+// the whole window, dimming the board, with `fill` down the middle
+fn spawn_screen(commands: &mut Commands, title: &str, fill: impl FnOnce(&mut ChildBuilder)) {
+    commands
+        .spawn((
+            MenuNode,
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    row_gap: Val::Px(8.0),
+                    ..Default::default()
+                },
+                background_color: Color::rgba(0.0, 0.0, 0.0, 0.85).into(),
+                // over the panel and the minimap
+                z_index: ZIndex::Global(10),
+                ..Default::default()
+            },
+        ))
+        .with_children(|root| {
+            root.spawn(label(title, 40.0));
+            fill(root);
+        });
+}
+
+fn spawn_button(parent: &mut ChildBuilder, button: MenuButton, text: &str) {
+    parent
+        .spawn((
+            button,
+            ButtonBundle {
+                style: Style {
+                    min_width: Val::Px(40.0),
+                    padding: UiRect::axes(Val::Px(16.0), Val::Px(6.0)),
+                    justify_content: JustifyContent::Center,
+                    ..Default::default()
+                },
+                background_color: Color::DARK_GRAY.into(),
+                ..Default::default()
+            },
+        ))
+        .with_children(|button| {
+            button.spawn(label(text, 24.0));
+        });
+}
+
+pub fn spawn_main_menu(mut commands: Commands) {
+    spawn_screen(&mut commands, "Game of Life", |root| {
+        spawn_button(root, MenuButton::Start, "Start");
+        spawn_button(root, MenuButton::Patterns, "Load Pattern");
+        spawn_button(root, MenuButton::Settings, "Settings");
+        spawn_button(root, MenuButton::Quit, "Quit");
+    });
+}
+
+pub fn spawn_patterns(mut commands: Commands) {
+    spawn_screen(&mut commands, "Load Pattern", |root| {
+        for (name, _) in LIBRARY {
+            spawn_button(root, MenuButton::Load(PathBuf::from(name)), name);
+        }
+        for path in pattern_files(Path::new(".")) {
+            let name = path.file_name().map(|n| n.to_string_lossy().to_string());
+            spawn_button(root, MenuButton::Load(path), &name.unwrap_or_default());
+        }
+        spawn_button(root, MenuButton::Back, "Back");
+    });
+}
+
+pub fn spawn_settings(mut commands: Commands) {
+    spawn_screen(&mut commands, "Settings", |root| {
+        for (setting, text) in SETTINGS {
+            root.spawn(NodeBundle {
+                style: Style {
+                    column_gap: Val::Px(8.0),
+                    align_items: AlignItems::Center,
+                    ..Default::default()
+                },
+                ..Default::default()
+            })
+            .with_children(|row| {
+                let mut name = label(text, 24.0);
+                name.style.width = Val::Px(80.0);
+                row.spawn(name);
+                spawn_button(row, MenuButton::Change(setting, false), "-");
+                let mut value = label("", 24.0);
+                value.style.width = Val::Px(200.0);
+                row.spawn((SettingValue(setting), value));
+                spawn_button(row, MenuButton::Change(setting, true), "+");
+            });
+        }
+        spawn_button(root, MenuButton::Back, "Back");
+    });
+}
+
+pub fn despawn_screen(mut commands: Commands, nodes: Query<Entity, With<MenuNode>>) {
+    for node in nodes.iter() {
+        commands.entity(node).despawn_recursive();
+    }
+}
+
+// size changes go through resize_board, as the panel's do
+#[allow(clippy::too_many_arguments)]
+pub fn press_menu(
+    mut commands: Commands,
+    mut next: ResMut<NextState<Screen>>,
+    mut exit: EventWriter<AppExit>,
+    mut menu: ResMut<Menu>,
+    mut config: ResMut<Config>,
+    mut rule: ResMut<Rule>,
+    mut theme: ResMut<Theme>,
+    mut fixed_time: ResMut<FixedTime>,
+    mut db: ResMut<Dashboard>,
+    mut detector: ResMut<PeriodDetector>,
+    mut live: ResMut<LiveCells>,
+    mut cells: Query<(Entity, &mut Cell)>,
+    mut buttons: Query<(&Interaction, &MenuButton, &mut BackgroundColor), Changed<Interaction>>,
+) {
+    for (interaction, button, mut color) in buttons.iter_mut() {
+        *color = match interaction {
+            Interaction::Pressed => Color::GRAY,
+            Interaction::Hovered => Color::rgb(0.4, 0.4, 0.4),
+            Interaction::None => Color::DARK_GRAY,
+        }
+        .into();
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        match button {
+            MenuButton::Start => next.set(Screen::Simulation),
+            MenuButton::Patterns => next.set(Screen::Patterns),
+            MenuButton::Settings => next.set(Screen::Settings),
+            MenuButton::Quit => exit.send(AppExit),
+            MenuButton::Back => next.set(Screen::Menu),
+            MenuButton::Load(path) => match load_pattern(path) {
+                Ok(pattern) => {
+                    let board = place_pattern(&pattern, config.pattern_at, &config);
+                    restart(
+                        &board,
+                        &config,
+                        &mut db,
+                        &mut detector,
+                        &mut commands,
+                        &mut live,
+                        &mut cells,
+                    );
+                    menu.fresh_board = false;
+                    info!("loaded {}", path.display());
+                    next.set(Screen::Simulation);
+                }
+                Err(e) => error!("can't load pattern: {e}"),
+            },
+            MenuButton::Change(setting, up) => match setting {
+                Setting::Width => {
+                    config.width = resized(config.width, *up);
+                    menu.fresh_board = true;
+                }
+                Setting::Height => {
+                    config.height = resized(config.height, *up);
+                    menu.fresh_board = true;
+                }
+                Setting::Rule => {
+                    *rule = config.next_preset(&rule, *up);
+                    menu.fresh_board = true;
+                }
+                Setting::Theme => *theme = theme.with_preset(theme.preset.next()),
+                Setting::Speed => {
+                    let period = scaled_period(fixed_time.period.as_secs_f32(), !*up);
+                    fixed_time.period = Duration::from_secs_f32(period);
+                }
+            },
+        }
+    }
+}
+
+pub fn update_settings(
+    config: Res<Config>,
+    rule: Res<Rule>,
+    theme: Res<Theme>,
+    fixed_time: Res<FixedTime>,
+    mut values: Query<(&mut Text, &SettingValue)>,
+) {
+    for (mut text, value) in values.iter_mut() {
+        let shown = match value.0 {
+            Setting::Width => config.width.to_string(),
+            Setting::Height => config.height.to_string(),
+            Setting::Rule => rule.to_string(),
+            Setting::Theme => theme.preset.name(),
+            Setting::Speed => format!("{:.3}s", fixed_time.period.as_secs_f32()),
+        };
+        if text.sections[0].value != shown {
+            text.sections[0].value = shown;
+        }
+    }
+}
+
+// back on the board after a size or rule change, a fresh soup for it
+#[allow(clippy::too_many_arguments)]
+pub fn start_board(
+    mut commands: Commands,
+    config: Res<Config>,
+    mut menu: ResMut<Menu>,
+    mut rng: ResMut<SimRng>,
+    mut db: ResMut<Dashboard>,
+    mut detector: ResMut<PeriodDetector>,
+    mut live: ResMut<LiveCells>,
+    mut cells: Query<(Entity, &mut Cell)>,
+) {
+    if !menu.fresh_board {
+        return;
+    }
+    menu.fresh_board = false;
+    reseed(
+        &config,
+        &mut rng,
+        &mut db,
+        &mut detector,
+        &mut commands,
+        &mut live,
+        &mut cells,
+    );
+}
@@ -0,0 +1,247 @@
+use bevy::prelude::*;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use bevy::render::texture::ImageSampler;
+use bevy::window::PrimaryWindow;
+use bevy_game_of_life::{bounding_box, Config, Coord, LiveCells};
+
+use std::collections::HashSet;
+
+use crate::Theme;
+
+// the longer side has at most this many pixels, bigger areas get blocks of
+// cells folded into one pixel
+const MINIMAP_PIXELS: i32 = 160;
+// on screen size of the longer side, in logical pixels
+const MINIMAP_SIZE: f32 = 160.0;
+// seconds between two redraws, the camera moving in between
+const MINIMAP_REFRESH: f32 = 0.1;
+
+// the whole population in the bottom-right corner, with the part in view
+// outlined, whenever the view doesn't take it all in; a click or a drag on
+// it moves the camera there. On unless turned off with the backquote key
+#[derive(Resource, Debug)]
+pub struct Minimap {
+    enabled: bool,
+    timer: Timer,
+}
+
+impl Default for Minimap {
+    fn default() -> Minimap {
+        Minimap {
+            enabled: true,
+            timer: Timer::from_seconds(MINIMAP_REFRESH, TimerMode::Repeating),
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct MinimapNode;
+
+// the cells the minimap covers, lowest and highest corner: the board, and the
+// live cells past it on an open board
+fn area(config: &Config, alives: &HashSet<Coord>) -> (Coord, Coord) {
+    let (xs, ys) = (config.x_range(), config.y_range());
+    let board = ((xs.start, ys.start), (xs.end - 1, ys.end - 1));
+    match bounding_box(alives) {
+        Some(((x0, y0), (x1, y1))) => (
+            (x0.min(board.0 .0), y0.min(board.0 .1)),
+            (x1.max(board.1 .0), y1.max(board.1 .1)),
+        ),
+        None => board,
+    }
+}
+
+// cells per pixel edge, and the size in pixels
+fn scale((low, high): (Coord, Coord)) -> (i32, (i32, i32)) {
+    let (w, h) = (high.0 - low.0 + 1, high.1 - low.1 + 1);
+    let scale = (w.max(h) + MINIMAP_PIXELS - 1) / MINIMAP_PIXELS;
+    (scale, ((w + scale - 1) / scale, (h + scale - 1) / scale))
+}
+
+// 0 for nothing, 1 for a live cell in the pixel's block, 2 for the view's
+// outline, rows from the top; `view` is the cells in view, corners as `area`
+fn pixels(area: (Coord, Coord), alives: &HashSet<Coord>, view: (Coord, Coord)) -> Vec<u8> {
+    let (scale, (w, h)) = scale(area);
+    let ((x0, _), (_, y1)) = area;
+    let mut out = vec![0; (w * h) as usize];
+    let pixel = |(x, y): Coord| ((x - x0) / scale, (y1 - y) / scale);
+    for &c in alives {
+        let (px, py) = pixel(c);
+        if (0..w).contains(&px) && (0..h).contains(&py) {
+            out[(py * w + px) as usize] = 1;
+        }
+    }
+    // the view's corners, as pixels, kept on the minimap
+    let clamp = |(px, py): (i32, i32)| (px.clamp(0, w - 1), py.clamp(0, h - 1));
+    let (left, bottom) = clamp(pixel(view.0));
+    let (right, top) = clamp(pixel(view.1));
+    for px in left..=right {
+        out[(top * w + px) as usize] = 2;
+        out[(bottom * w + px) as usize] = 2;
+    }
+    for py in top..=bottom {
+        out[(py * w + left) as usize] = 2;
+        out[(py * w + right) as usize] = 2;
+    }
+    out
+}
+
+#[test]
+fn test_minimap() {
+    let config = Config {
+        width: 4,
+        height: 4,
+        ..Default::default()
+    };
+    // past the board on an open one, the area takes the live cells in
+    let alives = HashSet::from([(-2, 1), (5, -2)]);
+    let covered = area(&config, &alives);
+    assert_eq!(covered, ((-2, -2), (5, 1)));
+    assert_eq!(scale(covered), (1, (8, 4)));
+    assert_eq!(scale(((0, 0), (319, 9))), (2, (160, 5)));
+    // a view of the middle two by two cells, outlined around them
+    let lit = pixels(covered, &alives, ((0, -1), (1, 0)));
+    let at = |x: usize, y: usize| lit[y * 8 + x];
+    assert_eq!((at(0, 0), at(7, 3)), (1, 1));
+    assert_eq!((at(2, 1), at(3, 2)), (2, 2));
+    assert_eq!(at(4, 1), 0);
+    assert_eq!(lit.iter().filter(|p| **p == 2).count(), 4);
+}
+
+fn image(w: i32, h: i32, pixels: &[u8], theme: &Theme) -> Image {
+    let colors = [theme.background.with_a(0.85), theme.alive, theme.text].map(|c| c.as_rgba_u8());
+    let data = pixels.iter().flat_map(|p| colors[*p as usize]).collect();
+    let mut image = Image::new(
+        Extent3d {
+            width: w as u32,
+            height: h as u32,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+    );
+    image.sampler_descriptor = ImageSampler::nearest();
+    image
+}
+
+pub fn spawn_minimap(mut commands: Commands, mut images: ResMut<Assets<Image>>) {
+    let blank = images.add(Image::default());
+    commands.spawn((
+        MinimapNode,
+        ButtonBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                bottom: Val::Px(10.0),
+                right: Val::Px(10.0),
+                ..Default::default()
+            },
+            image: blank.into(),
+            visibility: Visibility::Hidden,
+            ..Default::default()
+        },
+    ));
+}
+
+pub fn toggle_minimap(keys: Res<Input<KeyCode>>, mut minimap: ResMut<Minimap>) {
+    if keys.just_pressed(KeyCode::Grave) {
+        minimap.enabled = !minimap.enabled;
+    }
+}
+
+// the cells the camera shows, corners as `area`
+fn in_view(
+    transform: &Transform,
+    projection: &OrthographicProjection,
+    window: Vec2,
+    pitch: f32,
+) -> (Coord, Coord) {
+    let half = window / 2.0 * projection.scale;
+    let center = transform.translation.truncate();
+    let cell = |v: Vec2| ((v.x / pitch).round() as i32, (v.y / pitch).round() as i32);
+    (cell(center - half), cell(center + half))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn update_minimap(
+    time: Res<Time>,
+    config: Res<Config>,
+    theme: Res<Theme>,
+    live: Res<LiveCells>,
+    mut minimap: ResMut<Minimap>,
+    mut images: ResMut<Assets<Image>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    cameras: Query<(&Transform, &OrthographicProjection)>,
+    mut nodes: Query<(&mut Style, &mut UiImage, &mut Visibility), With<MinimapNode>>,
+) {
+    if !minimap.timer.tick(time.delta()).just_finished() && !minimap.is_changed() {
+        return;
+    }
+    let (Ok(window), Some((transform, projection))) = (windows.get_single(), cameras.iter().next())
+    else {
+        return;
+    };
+    let window = Vec2::new(window.width(), window.height());
+    let covered = area(&config, &live.0);
+    let view = in_view(transform, projection, window, theme.pitch());
+    let seen = view.0 .0 <= covered.0 .0
+        && view.0 .1 <= covered.0 .1
+        && view.1 .0 >= covered.1 .0
+        && view.1 .1 >= covered.1 .1;
+    for (mut style, mut ui_image, mut visibility) in nodes.iter_mut() {
+        let shown = if minimap.enabled && !seen {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+        if *visibility != shown {
+            *visibility = shown;
+        }
+        if shown == Visibility::Hidden {
+            continue;
+        }
+        let (_, (w, h)) = scale(covered);
+        let size = MINIMAP_SIZE / w.max(h) as f32;
+        style.width = Val::Px(w as f32 * size);
+        style.height = Val::Px(h as f32 * size);
+        let drawn = image(w, h, &pixels(covered, &live.0, view), &theme);
+        match images.get_mut(&ui_image.texture) {
+            Some(old) => *old = drawn,
+            None => ui_image.texture = images.add(drawn),
+        }
+    }
+}
+
+// pressed, the camera centers on the cell under the cursor
+pub fn jump_from_minimap(
+    config: Res<Config>,
+    theme: Res<Theme>,
+    live: Res<LiveCells>,
+    mut minimap: ResMut<Minimap>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    nodes: Query<(&Interaction, &Node, &GlobalTransform, &Visibility), With<MinimapNode>>,
+    mut cameras: Query<&mut Transform, With<OrthographicProjection>>,
+) {
+    let Some(cursor) = windows.get_single().ok().and_then(|w| w.cursor_position()) else {
+        return;
+    };
+    for (interaction, node, global, visibility) in nodes.iter() {
+        if *interaction != Interaction::Pressed || *visibility == Visibility::Hidden {
+            continue;
+        }
+        let size = node.size();
+        let corner = global.translation().truncate() - size / 2.0;
+        let at = ((cursor - corner) / size).clamp(Vec2::ZERO, Vec2::ONE);
+        let ((x0, y0), (x1, y1)) = area(&config, &live.0);
+        let cell = Vec2::new(
+            x0 as f32 + at.x * (x1 - x0 + 1) as f32,
+            y1 as f32 + 1.0 - at.y * (y1 - y0 + 1) as f32,
+        ) - Vec2::splat(0.5);
+        for mut transform in cameras.iter_mut() {
+            transform.translation.x = cell.x * theme.pitch();
+            transform.translation.y = cell.y * theme.pitch();
+        }
+        // the outline follows right away
+        minimap.set_changed();
+    }
+}
@@ -0,0 +1,436 @@
+use bevy::prelude::*;
+use bevy_game_of_life::{Cell, Config, Coord, Dashboard, LiveCells, Rule, SimState};
+
+use std::collections::HashSet;
+use std::io::{ErrorKind, Read, Write};
+use std::net::{TcpListener, TcpStream};
+
+use crate::replace_board;
+
+// one line each way, fields split by spaces and cells as x,y:
+//   hello <width> <height> <rule>   host to a new client, before its first diff
+//   diff <round> +x,y.. -x,y..      host to clients, the cells born and died
+//   paint +x,y.. -x,y..             client to host, cells drawn in or wiped
+#[derive(Debug, PartialEq)]
+pub enum Message {
+    Hello {
+        width: i32,
+        height: i32,
+        rule: Rule,
+    },
+    Diff {
+        round: usize,
+        born: Vec<Coord>,
+        died: Vec<Coord>,
+    },
+    Paint {
+        born: Vec<Coord>,
+        died: Vec<Coord>,
+    },
+}
+
+fn cells(born: &[Coord], died: &[Coord]) -> String {
+    let signed = |sign: char, cells: &[Coord]| {
+        cells
+            .iter()
+            .map(|(x, y)| format!(" {sign}{x},{y}"))
+            .collect::<String>()
+    };
+    format!("{}{}", signed('+', born), signed('-', died))
+}
+
+impl Message {
+    pub fn to_line(&self) -> String {
+        match self {
+            Message::Hello {
+                width,
+                height,
+                rule,
+            } => format!("hello {width} {height} {rule}\n"),
+            Message::Diff { round, born, died } => {
+                format!("diff {round}{}\n", cells(born, died))
+            }
+            Message::Paint { born, died } => format!("paint{}\n", cells(born, died)),
+        }
+    }
+
+    pub fn parse(line: &str) -> Result<Message, String> {
+        let mut words = line.split_whitespace();
+        let kind = words.next().ok_or("empty line")?;
+        let number = |word: Option<&str>| -> Result<i64, String> {
+            let word = word.ok_or(format!("{kind} ended early"))?;
+            word.parse().map_err(|e| format!("{word:?}: {e}"))
+        };
+        match kind {
+            "hello" => {
+                let (width, height) = (number(words.next())?, number(words.next())?);
+                let rule = words.collect::<Vec<_>>().join(" ");
+                // a script would be read off this end's disk, at a path the
+                // other end picked
+                if rule.trim().ends_with(".rhai") {
+                    return Err(format!("{rule:?}: script rules aren't shared"));
+                }
+                let rule = Rule::named(&rule)?;
+                Ok(Message::Hello {
+                    width: width as i32,
+                    height: height as i32,
+                    rule,
+                })
+            }
+            "diff" | "paint" => {
+                let round = if kind == "diff" {
+                    number(words.next())? as usize
+                } else {
+                    0
+                };
+                let (mut born, mut died) = (vec![], vec![]);
+                for word in words {
+                    let (list, cell) = match word.split_at(1) {
+                        ("+", cell) => (&mut born, cell),
+                        ("-", cell) => (&mut died, cell),
+                        _ => return Err(format!("{word:?} is neither +x,y nor -x,y")),
+                    };
+                    list.push(crate::parse_coord(cell)?);
+                }
+                Ok(if kind == "diff" {
+                    Message::Diff { round, born, died }
+                } else {
+                    Message::Paint { born, died }
+                })
+            }
+            _ => Err(format!("unknown message {kind:?}")),
+        }
+    }
+}
+
+#[test]
+fn test_message() {
+    let messages = [
+        Message::Hello {
+            width: 40,
+            height: 30,
+            rule: Rule::named("highlife").unwrap(),
+        },
+        Message::Diff {
+            round: 12,
+            born: vec![(1, -2), (0, 0)],
+            died: vec![(-5, 3)],
+        },
+        Message::Paint {
+            born: vec![],
+            died: vec![(7, 7)],
+        },
+    ];
+    for message in messages {
+        let line = message.to_line();
+        assert!(!line.trim_end().contains('\n'));
+        assert_eq!(Message::parse(&line), Ok(message));
+    }
+    assert_eq!(
+        Message::parse("diff 3 +1,2"),
+        Ok(Message::Diff {
+            round: 3,
+            born: vec![(1, 2)],
+            died: vec![]
+        })
+    );
+    assert!(Message::parse("diff x").is_err());
+    assert!(Message::parse("paint 1,2").is_err());
+    assert!(Message::parse("goodbye").is_err());
+    assert!(Message::parse("hello 4 4 ../../rule.rhai").is_err());
+}
+
+// a line longer than this, or this much waiting to go out, is a peer that
+// stopped making sense or stopped reading
+const MAX_LINE: usize = 16 << 20;
+const MAX_QUEUED: usize = 64 << 20;
+
+fn gone(why: &str) -> std::io::Error {
+    std::io::Error::other(why)
+}
+
+// the whole lines in `pending`, taken out of it; what's left is the start of
+// the next one
+fn take_lines(pending: &mut Vec<u8>) -> std::io::Result<Vec<String>> {
+    let mut lines = vec![];
+    while let Some(end) = pending.iter().position(|b| *b == b'\n') {
+        let line: Vec<u8> = pending.drain(..=end).collect();
+        lines.push(String::from_utf8_lossy(&line).trim().to_string());
+    }
+    if pending.len() > MAX_LINE {
+        return Err(gone("a line too long"));
+    }
+    Ok(lines)
+}
+
+#[test]
+fn test_take_lines() {
+    let mut pending = b"paint +1,2\ndiff 3\npai".to_vec();
+    let lines = take_lines(&mut pending).unwrap();
+    assert_eq!(lines, ["paint +1,2", "diff 3"]);
+    assert_eq!(pending, b"pai");
+    let mut endless = vec![b'+'; MAX_LINE + 1];
+    assert!(take_lines(&mut endless).is_err());
+}
+
+// a socket that's never waited on, what's come in of a line so far and what
+// hasn't gone out yet
+pub struct Peer {
+    stream: TcpStream,
+    read: Vec<u8>,
+    write: Vec<u8>,
+}
+
+impl Peer {
+    fn new(stream: TcpStream) -> std::io::Result<Peer> {
+        stream.set_nonblocking(true)?;
+        stream.set_nodelay(true)?;
+        Ok(Peer {
+            stream,
+            read: vec![],
+            write: vec![],
+        })
+    }
+
+    // queued, and as much of the queue as the socket takes now
+    fn send(&mut self, message: &Message) -> std::io::Result<()> {
+        self.write.extend_from_slice(message.to_line().as_bytes());
+        self.flush()
+    }
+
+    // the rest goes on the next frames; a peer that lets too much pile up
+    // is dropped
+    fn flush(&mut self) -> std::io::Result<()> {
+        while !self.write.is_empty() {
+            match self.stream.write(&self.write) {
+                Ok(0) => return Err(ErrorKind::WriteZero.into()),
+                Ok(n) => drop(self.write.drain(..n)),
+                Err(e) if e.kind() == ErrorKind::WouldBlock => break,
+                Err(e) => return Err(e),
+            }
+        }
+        if self.write.len() > MAX_QUEUED {
+            return Err(gone("too far behind"));
+        }
+        Ok(())
+    }
+
+    // the whole lines that came in since the last look; Err once the other
+    // end hangs up
+    fn receive(&mut self) -> std::io::Result<Vec<String>> {
+        let mut buffer = [0; 4096];
+        loop {
+            match self.stream.read(&mut buffer) {
+                Ok(0) => return Err(ErrorKind::UnexpectedEof.into()),
+                Ok(n) => self.read.extend_from_slice(&buffer[..n]),
+                Err(e) if e.kind() == ErrorKind::WouldBlock => break,
+                Err(e) => return Err(e),
+            }
+            if self.read.len() > MAX_LINE {
+                take_lines(&mut self.read)?;
+            }
+        }
+        take_lines(&mut self.read)
+    }
+
+    // the lines that came in, once what was waiting has gone out
+    fn exchange(&mut self) -> std::io::Result<Vec<String>> {
+        self.flush()?;
+        self.receive()
+    }
+}
+
+fn diff(from: &HashSet<Coord>, to: &HashSet<Coord>) -> (Vec<Coord>, Vec<Coord>) {
+    let mut born: Vec<_> = to.difference(from).copied().collect();
+    let mut died: Vec<_> = from.difference(to).copied().collect();
+    born.sort();
+    died.sort();
+    (born, died)
+}
+
+// --host: the simulation runs here, every client is sent what changed as it
+// changes, and what they paint lands on this board
+#[derive(Resource)]
+pub struct Host {
+    listener: TcpListener,
+    clients: Vec<Peer>,
+    // the board as the clients have it
+    sent: HashSet<Coord>,
+}
+
+impl Host {
+    pub fn bind(addr: &str) -> std::io::Result<Host> {
+        let listener = TcpListener::bind(addr)?;
+        listener.set_nonblocking(true)?;
+        Ok(Host {
+            listener,
+            clients: vec![],
+            sent: HashSet::new(),
+        })
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn serve(
+    mut commands: Commands,
+    config: Res<Config>,
+    rule: Res<Rule>,
+    db: Res<Dashboard>,
+    mut host: ResMut<Host>,
+    mut live: ResMut<LiveCells>,
+    mut cells: Query<(Entity, &mut Cell)>,
+) {
+    let host = &mut *host;
+    while let Ok((stream, addr)) = host.listener.accept() {
+        let hello = Message::Hello {
+            width: config.width,
+            height: config.height,
+            rule: rule.clone(),
+        };
+        let (born, _) = diff(&HashSet::new(), &live.0);
+        let board = Message::Diff {
+            round: db.round,
+            born,
+            died: vec![],
+        };
+        let joined = Peer::new(stream).and_then(|mut peer| {
+            peer.send(&hello)?;
+            peer.send(&board)?;
+            Ok(peer)
+        });
+        match joined {
+            Ok(peer) => {
+                info!("{addr} joined");
+                host.clients.push(peer);
+            }
+            Err(e) => warn!("{addr} couldn't join: {e}"),
+        }
+    }
+    // their paint first, so it goes out with this frame's diff
+    let mut board = live.0.clone();
+    host.clients.retain_mut(|peer| match peer.exchange() {
+        Ok(lines) => {
+            for line in lines {
+                match Message::parse(&line) {
+                    Ok(Message::Paint { born, died }) => {
+                        board.extend(born.into_iter().filter(|c| config.contains(*c)));
+                        for c in died {
+                            board.remove(&c);
+                        }
+                    }
+                    Ok(other) => warn!("a client sent {other:?}, only paint is taken"),
+                    Err(e) => warn!("a client sent {line:?}: {e}"),
+                }
+            }
+            true
+        }
+        Err(e) => {
+            info!("a client left: {e}");
+            false
+        }
+    });
+    if board != live.0 {
+        replace_board(&board, &config, &mut commands, &mut live, &mut cells);
+    }
+    if live.0 == host.sent {
+        return;
+    }
+    let (born, died) = diff(&host.sent, &live.0);
+    let message = Message::Diff {
+        round: db.round,
+        born,
+        died,
+    };
+    host.clients.retain_mut(|peer| peer.send(&message).is_ok());
+    host.sent = live.0.clone();
+}
+
+// --join: the board shows the host's and never runs by itself; what's drawn
+// here is sent to the host, and stays while it comes back with the diffs
+#[derive(Resource)]
+pub struct Client {
+    peer: Option<Peer>,
+    // the board as of the last look, drawing since then aside
+    shown: HashSet<Coord>,
+}
+
+impl Client {
+    pub fn connect(addr: &str) -> std::io::Result<Client> {
+        Ok(Client {
+            peer: Some(Peer::new(TcpStream::connect(addr)?)?),
+            shown: HashSet::new(),
+        })
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn follow_host(
+    mut commands: Commands,
+    config: Res<Config>,
+    mut rule: ResMut<Rule>,
+    mut db: ResMut<Dashboard>,
+    mut sim_state: ResMut<NextState<SimState>>,
+    mut client: ResMut<Client>,
+    mut live: ResMut<LiveCells>,
+    mut cells: Query<(Entity, &mut Cell)>,
+) {
+    let client = &mut *client;
+    let Some(peer) = client.peer.as_mut() else {
+        return;
+    };
+    sim_state.set(SimState::Paused);
+    // drawn here since the last look
+    if live.0 != client.shown {
+        let (born, died) = diff(&client.shown, &live.0);
+        if let Err(e) = peer.send(&Message::Paint { born, died }) {
+            warn!("lost the host: {e}");
+            client.peer = None;
+            return;
+        }
+        client.shown = live.0.clone();
+    }
+    let lines = match peer.exchange() {
+        Ok(lines) => lines,
+        Err(e) => {
+            warn!("lost the host: {e}");
+            client.peer = None;
+            return;
+        }
+    };
+    let mut changed = false;
+    for line in lines {
+        match Message::parse(&line) {
+            Ok(Message::Hello {
+                width,
+                height,
+                rule: hosted,
+            }) => {
+                if (width, height) != (config.width, config.height) {
+                    warn!(
+                        "the host's board is {width}x{height}, this one {}x{}: start with the same --width and --height",
+                        config.width, config.height
+                    );
+                }
+                match config.runs(&hosted) {
+                    Ok(()) if hosted != *rule => *rule = hosted,
+                    Ok(()) => {}
+                    Err(e) => warn!("the host's rule: {e}"),
+                }
+            }
+            Ok(Message::Diff { round, born, died }) => {
+                client.shown.extend(born);
+                for c in died {
+                    client.shown.remove(&c);
+                }
+                db.round = round;
+                changed = true;
+            }
+            Ok(other) => warn!("the host sent {other:?}"),
+            Err(e) => warn!("the host sent {line:?}: {e}"),
+        }
+    }
+    if changed {
+        replace_board(&client.shown, &config, &mut commands, &mut live, &mut cells);
+        db.survival = live.0.len();
+    }
+}
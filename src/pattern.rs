@@ -0,0 +1,603 @@
+use std::collections::HashSet;
+use std::io::Read;
+use std::path::Path;
+
+use crate::{Config, Coord, Rule};
+
+// live cells in file order: x to the right, rows counting down from the top
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Pattern {
+    pub width: i32,
+    pub height: i32,
+    pub cells: Vec<Coord>,
+}
+
+impl Pattern {
+    fn from_cells(cells: Vec<Coord>) -> Pattern {
+        let width = cells.iter().map(|c| c.0 + 1).max().unwrap_or(0);
+        let height = cells.iter().map(|c| c.1 + 1).max().unwrap_or(0);
+        Pattern {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    // board cells, y growing up, as a pattern cropped to them
+    pub fn from_board(alives: &HashSet<Coord>) -> Pattern {
+        let min_x = alives.iter().map(|c| c.0).min().unwrap_or(0);
+        let max_y = alives.iter().map(|c| c.1).max().unwrap_or(0);
+        let mut cells: Vec<_> = alives.iter().map(|(x, y)| (x - min_x, max_y - y)).collect();
+        cells.sort_by_key(|(x, y)| (*y, *x));
+        Pattern::from_cells(cells)
+    }
+
+    // a quarter turn clockwise as seen on screen
+    pub fn rotate(&self) -> Pattern {
+        Pattern {
+            width: self.height,
+            height: self.width,
+            cells: self
+                .cells
+                .iter()
+                .map(|(x, y)| (self.height - 1 - y, *x))
+                .collect(),
+        }
+    }
+
+    // mirrored left to right
+    pub fn flip(&self) -> Pattern {
+        Pattern {
+            cells: self
+                .cells
+                .iter()
+                .map(|(x, y)| (self.width - 1 - x, *y))
+                .collect(),
+            ..self.clone()
+        }
+    }
+}
+
+// classics that ship in the binary, by the name `--pattern` and the stamp take
+pub const LIBRARY: [(&str, &str); 6] = [
+    ("glider", "x = 3, y = 3\nbo$2bo$3o!"),
+    ("lwss", "x = 5, y = 4\nbo2bo$o4b$o3bo$4o!"),
+    (
+        "pulsar",
+        "x = 13, y = 13\n2b3o3b3o2$o4bobo4bo$o4bobo4bo$o4bobo4bo$2b3o3b3o2$2b3o3b3o$\
+         o4bobo4bo$o4bobo4bo$o4bobo4bo2$2b3o3b3o!",
+    ),
+    (
+        "gosper",
+        "x = 36, y = 9\n24bo$22bobo$12b2o6b2o12b2o$11bo3bo4b2o12b2o$2o8bo5bo3b2o$\
+         2o8bo3bob2o4bobo$10bo5bo7bo$11bo3bo$12b2o!",
+    ),
+    ("rpentomino", "x = 3, y = 3\nb2o$2o$bo!"),
+    ("acorn", "x = 7, y = 3\nbo$3bo$2o2b3o!"),
+];
+// the random soup, which is what a run starts from without a pattern
+pub const SOUP: &str = "soup";
+
+pub fn builtin(name: &str) -> Option<Pattern> {
+    let (_, rle) = LIBRARY.iter().find(|(n, _)| n.eq_ignore_ascii_case(name))?;
+    Some(parse_rle(rle).expect("the library parses"))
+}
+
+#[test]
+fn test_library() {
+    let counts: Vec<_> = LIBRARY
+        .iter()
+        .map(|(name, _)| builtin(name).unwrap().cells.len())
+        .collect();
+    assert_eq!(counts, [5, 9, 48, 36, 5, 7]);
+    let gun = builtin("Gosper").unwrap();
+    assert_eq!((gun.width, gun.height), (36, 9));
+    assert_eq!(builtin("glider"), parse_cells(".O.\n..O\nOOO").ok());
+    // the soup is seeded, not a pattern
+    assert!(builtin("soup").is_none());
+}
+
+// Run Length Encoded, as used by Golly and the LifeWiki:
+// `#` comment lines, an `x = 3, y = 3` header, then `b`/`o` runs, `$` rows, `!` end
+pub fn parse_rle(text: &str) -> Result<Pattern, String> {
+    let mut lines = text.lines().map(str::trim).filter(|l| !l.starts_with('#'));
+    let header = lines.by_ref().find(|l| !l.is_empty()).ok_or("empty RLE")?;
+    let (mut width, mut height) = (None, None);
+    for field in header.split(',') {
+        let (key, value) = field.split_once('=').ok_or("bad RLE header")?;
+        let value = value.trim();
+        match key.trim() {
+            "x" => width = Some(value.parse::<i32>().map_err(|e| e.to_string())?),
+            "y" => height = Some(value.parse::<i32>().map_err(|e| e.to_string())?),
+            _ => {}
+        }
+    }
+
+    let mut cells = vec![];
+    let (mut x, mut y) = (0, 0);
+    let mut run = 0;
+    'body: for line in lines {
+        for c in line.chars() {
+            match c {
+                '0'..='9' => run = run * 10 + c.to_digit(10).unwrap() as i32,
+                'b' | '.' => {
+                    x += run.max(1);
+                    run = 0;
+                }
+                '$' => {
+                    y += run.max(1);
+                    x = 0;
+                    run = 0;
+                }
+                '!' => break 'body,
+                c if c.is_ascii_alphabetic() => {
+                    for _ in 0..run.max(1) {
+                        cells.push((x, y));
+                        x += 1;
+                    }
+                    run = 0;
+                }
+                c if c.is_whitespace() => {}
+                _ => return Err(format!("unexpected {c:?} in RLE")),
+            }
+        }
+    }
+
+    let mut pattern = Pattern::from_cells(cells);
+    pattern.width = pattern.width.max(width.ok_or("RLE header without x")?);
+    pattern.height = pattern.height.max(height.ok_or("RLE header without y")?);
+    Ok(pattern)
+}
+
+// plaintext: `!` comment lines, then rows of `.` (dead) and `O` (alive)
+pub fn parse_cells(text: &str) -> Result<Pattern, String> {
+    let mut cells = vec![];
+    let rows = text.lines().filter(|l| !l.starts_with('!'));
+    for (y, row) in rows.enumerate() {
+        for (x, c) in row.trim_end().chars().enumerate() {
+            match c {
+                'O' | 'o' | '*' => cells.push((x as i32, y as i32)),
+                '.' => {}
+                _ => return Err(format!("unexpected {c:?} in plaintext pattern")),
+            }
+        }
+    }
+    Ok(Pattern::from_cells(cells))
+}
+
+// Life 1.06: a `#Life 1.06` header, then one `x y` pair per live cell, y growing down
+pub fn parse_life106(text: &str) -> Result<Pattern, String> {
+    let mut cells = vec![];
+    for line in text.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let mut numbers = line.split_whitespace().map(|n| {
+            n.parse::<i32>()
+                .map_err(|e| format!("bad coordinate {n:?}: {e}"))
+        });
+        match (numbers.next(), numbers.next(), numbers.next()) {
+            (Some(x), Some(y), None) => cells.push((x?, y?)),
+            _ => return Err(format!("expected `x y`, got {line:?}")),
+        }
+    }
+    // the file may be centered anywhere, patterns start at 0, 0
+    let min_x = cells.iter().map(|c| c.0).min().unwrap_or(0);
+    let min_y = cells.iter().map(|c| c.1).min().unwrap_or(0);
+    let cells = cells
+        .into_iter()
+        .map(|(x, y)| (x - min_x, y - min_y))
+        .collect();
+    Ok(Pattern::from_cells(cells))
+}
+
+// what parse_rle reads, lines kept under 70 characters as Golly writes them
+pub fn to_rle(pattern: &Pattern, rule: &Rule) -> String {
+    let mut rows = vec![vec![false; pattern.width as usize]; pattern.height as usize];
+    for (x, y) in &pattern.cells {
+        rows[*y as usize][*x as usize] = true;
+    }
+    // (count, tag) runs, dead cells at the end of a row left out
+    let mut runs: Vec<(usize, char)> = vec![];
+    let mut push = |tag: char, n: usize| match runs.last_mut() {
+        Some((count, last)) if *last == tag => *count += n,
+        _ => runs.push((n, tag)),
+    };
+    for (i, row) in rows.iter().enumerate() {
+        if i > 0 {
+            push('$', 1);
+        }
+        let len = row
+            .iter()
+            .rposition(|alive| *alive)
+            .map_or(0, |last| last + 1);
+        for alive in &row[..len] {
+            push(if *alive { 'o' } else { 'b' }, 1);
+        }
+    }
+    let mut text = format!(
+        "x = {}, y = {}, rule = {rule}\n",
+        pattern.width, pattern.height
+    );
+    let mut line = String::new();
+    for (n, tag) in runs.into_iter().chain([(1, '!')]) {
+        let run = if n > 1 {
+            format!("{n}{tag}")
+        } else {
+            tag.to_string()
+        };
+        if line.len() + run.len() > 70 {
+            text += &line;
+            text.push('\n');
+            line.clear();
+        }
+        line += &run;
+    }
+    text += &line;
+    text.push('\n');
+    text
+}
+
+// what parse_cells reads, the rule as a comment; rows end at their last live
+// cell and a blank one is a lone `.`
+pub fn to_cells(pattern: &Pattern, rule: &Rule) -> String {
+    let mut rows = vec![vec![]; pattern.height as usize];
+    for (x, y) in &pattern.cells {
+        rows[*y as usize].push(*x as usize);
+    }
+    let mut text = format!("!Rule: {rule}\n");
+    for row in rows {
+        let len = row.iter().max().map_or(1, |last| last + 1);
+        let mut line = vec!['.'; len];
+        for x in row {
+            line[x] = 'O';
+        }
+        text.extend(line);
+        text.push('\n');
+    }
+    text
+}
+
+// the pattern in the format `path`'s extension names, RLE unless it's .cells
+// or .lif
+pub fn to_format(path: &Path, pattern: &Pattern, rule: &Rule) -> String {
+    match path.extension().and_then(|e| e.to_str()) {
+        Some("cells") => to_cells(pattern, rule),
+        Some("lif" | "life") => to_life106(pattern),
+        _ => to_rle(pattern, rule),
+    }
+}
+
+pub fn to_life106(pattern: &Pattern) -> String {
+    let mut text = "#Life 1.06\n".to_string();
+    for (x, y) in &pattern.cells {
+        text += &format!("{x} {y}\n");
+    }
+    text
+}
+
+// past this many live cells a Macrocell file is too big to load as cells
+const MAX_MACROCELL_CELLS: u64 = 1 << 24;
+
+// a Macrocell node as read: the live cells of an 8x8 leaf, from its top-left
+// corner, or the nw, ne, sw and se children by their line numbers, 0 being
+// empty
+enum Macro {
+    Leaf(Vec<Coord>),
+    Branch([usize; 4]),
+}
+
+// Golly's Macrocell: an `[M2]` header and `#` comments, then a quadtree a node
+// a line, children before their parents and the last one the root. A leaf is
+// an 8x8 square as `.` and `*` rows ending in `$`, a node above it `level nw
+// ne sw se`; square `2^level` cells across
+pub fn parse_macrocell(text: &str) -> Result<Pattern, String> {
+    let mut lines = text.lines().map(str::trim);
+    if !lines.next().is_some_and(|l| l.starts_with("[M2]")) {
+        return Err("Macrocell without its [M2] header".to_string());
+    }
+    // (level, population, node), line numbers counting from 1
+    let mut nodes: Vec<(u32, u64, Macro)> = vec![];
+    for line in lines.filter(|l| !l.is_empty() && !l.starts_with('#')) {
+        if line.starts_with(['.', '*', '$']) {
+            let mut cells = vec![];
+            for (y, row) in line.split('$').take(8).enumerate() {
+                for (x, c) in row.chars().enumerate() {
+                    match c {
+                        '*' if x < 8 => cells.push((x as i32, y as i32)),
+                        '.' if x < 8 => {}
+                        _ => return Err(format!("unexpected {c:?} in Macrocell leaf {line:?}")),
+                    }
+                }
+            }
+            nodes.push((3, cells.len() as u64, Macro::Leaf(cells)));
+            continue;
+        }
+        let numbers = line
+            .split_whitespace()
+            .map(|n| n.parse::<usize>().map_err(|e| format!("{n:?}: {e}")))
+            .collect::<Result<Vec<_>, _>>()?;
+        let [level, nw, ne, sw, se] = numbers[..] else {
+            return Err(format!("bad Macrocell node {line:?}"));
+        };
+        let level = level as u32;
+        if !(1..=63).contains(&level) {
+            return Err(format!("bad Macrocell level in {line:?}"));
+        }
+        let children = [nw, ne, sw, se];
+        if level == 1 {
+            // a 2x2 of cell states, the multi-state files' leaves
+            let cells: Vec<_> = (0..4)
+                .filter(|i| children[*i] > 0)
+                .map(|i| ((i % 2) as i32, (i / 2) as i32))
+                .collect();
+            nodes.push((1, cells.len() as u64, Macro::Leaf(cells)));
+            continue;
+        }
+        let mut population = 0u64;
+        for child in children.into_iter().filter(|c| *c > 0) {
+            match nodes.get(child - 1) {
+                Some((l, p, _)) if l + 1 == level => population = population.saturating_add(*p),
+                _ => return Err(format!("bad child {child} in Macrocell node {line:?}")),
+            }
+        }
+        nodes.push((level, population, Macro::Branch(children)));
+    }
+    let Some(root) = nodes.len().checked_sub(1) else {
+        return Ok(Pattern::default());
+    };
+    if nodes[root].1 > MAX_MACROCELL_CELLS {
+        return Err(format!(
+            "{} live cells, more than the {MAX_MACROCELL_CELLS} a Macrocell file can load",
+            nodes[root].1
+        ));
+    }
+    fn collect(
+        nodes: &[(u32, u64, Macro)],
+        id: usize,
+        (x, y): (i64, i64),
+        out: &mut Vec<(i64, i64)>,
+    ) {
+        let (level, population, node) = &nodes[id];
+        if *population == 0 {
+            return;
+        }
+        match node {
+            Macro::Leaf(cells) => out.extend(
+                cells
+                    .iter()
+                    .map(|(cx, cy)| (x + *cx as i64, y + *cy as i64)),
+            ),
+            Macro::Branch(children) => {
+                let half = 1i64 << (level - 1);
+                for (i, child) in children.iter().enumerate() {
+                    if *child > 0 {
+                        let at = (x + half * (i % 2) as i64, y + half * (i / 2) as i64);
+                        collect(nodes, child - 1, at, out);
+                    }
+                }
+            }
+        }
+    }
+    let mut cells = vec![];
+    collect(&nodes, root, (0, 0), &mut cells);
+    // cropped to the live cells, which have to fit the board's coordinates
+    let min_x = cells.iter().map(|c| c.0).min().unwrap_or(0);
+    let min_y = cells.iter().map(|c| c.1).min().unwrap_or(0);
+    let mut cells = cells
+        .into_iter()
+        .map(|(x, y)| {
+            let fit =
+                |v: i64| i32::try_from(v).map_err(|_| "Macrocell pattern too wide".to_string());
+            Ok((fit(x - min_x)?, fit(y - min_y)?))
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    cells.sort_by_key(|(x, y)| (*y, *x));
+    Ok(Pattern::from_cells(cells))
+}
+
+// the format told from the text alone: the Life 1.06 or Macrocell header, an
+// RLE `x = ..` header after any comments, else plaintext
+pub fn sniff_pattern(text: &str) -> Result<Pattern, String> {
+    if text.trim().is_empty() {
+        return Err("no pattern, the input is empty".to_string());
+    }
+    if text.starts_with("#Life 1.06") {
+        return parse_life106(text);
+    }
+    if text.starts_with("[M2]") {
+        return parse_macrocell(text);
+    }
+    let first = text
+        .lines()
+        .map(str::trim)
+        .find(|l| !l.is_empty() && !l.starts_with('#'));
+    if first.is_some_and(|l| l.starts_with('x') && l.contains('=')) {
+        parse_rle(text)
+    } else {
+        parse_cells(text)
+    }
+}
+
+// `-` is whatever is piped to stdin, and a LIBRARY name that isn't a file is
+// that pattern
+pub fn load_pattern(path: &Path) -> Result<Pattern, String> {
+    let named = path.to_str().filter(|_| !path.exists()).and_then(builtin);
+    if let Some(pattern) = named {
+        return Ok(pattern);
+    }
+    if path == Path::new("-") {
+        let mut text = String::new();
+        std::io::stdin()
+            .read_to_string(&mut text)
+            .map_err(|e| format!("stdin: {e}"))?;
+        return sniff_pattern(&text);
+    }
+    let text = std::fs::read_to_string(path).map_err(|e| format!("{}: {e}", path.display()))?;
+    match path.extension().and_then(|e| e.to_str()) {
+        Some("cells") => parse_cells(&text),
+        Some("mc") => parse_macrocell(&text),
+        _ if text.starts_with("#Life 1.06") => parse_life106(&text),
+        _ => parse_rle(&text),
+    }
+}
+
+// board coords of the pattern centered on `center`, dropping whatever falls off the board
+pub fn place_pattern(pattern: &Pattern, center: Coord, config: &Config) -> HashSet<Coord> {
+    let left = center.0 - pattern.width / 2;
+    let top = center.1 + pattern.height / 2;
+    pattern
+        .cells
+        .iter()
+        .map(|(x, y)| (left + x, top - y))
+        .filter(|c| config.contains(*c))
+        .collect()
+}
+
+#[test]
+fn test_parse_rle() {
+    let glider = parse_rle("#N Glider\nx = 3, y = 3, rule = B3/S23\nbob$2bo$3o!").unwrap();
+    assert_eq!((glider.width, glider.height), (3, 3));
+    assert_eq!(glider.cells, vec![(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)]);
+    // runs across line breaks, blank rows via `2$`
+    let split = parse_rle("x = 2, y = 3\n2o2$\no!").unwrap();
+    assert_eq!(split.cells, vec![(0, 0), (1, 0), (0, 2)]);
+    assert!(parse_rle("bob$2bo$3o!").is_err());
+}
+
+#[test]
+fn test_parse_cells() {
+    let glider = parse_cells("!Name: Glider\n.O.\n..O\nOOO\n").unwrap();
+    assert_eq!((glider.width, glider.height), (3, 3));
+    assert_eq!(glider.cells, vec![(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)]);
+    assert!(parse_cells(".X.").is_err());
+    // and back, blank rows and all
+    let split = parse_cells("O..O\n\n.O").unwrap();
+    let text = to_cells(&split, &Rule::default());
+    assert_eq!(text, "!Rule: B3/S23\nO..O\n.\n.O\n");
+    assert_eq!(parse_cells(&text).unwrap(), split);
+    assert_eq!(sniff_pattern(&text).unwrap(), split);
+    let path = Path::new("glider.cells");
+    assert_eq!(
+        parse_cells(&to_format(path, &glider, &Rule::default())).unwrap(),
+        glider
+    );
+}
+
+#[test]
+fn test_life106() {
+    let glider = parse_life106("#Life 1.06\n0 -1\n1 0\n-1 1\n0 1\n1 1\n").unwrap();
+    assert_eq!(glider, parse_cells(".O.\n..O\nOOO").unwrap());
+    assert_eq!(parse_life106(&to_life106(&glider)).unwrap(), glider);
+    // any whitespace, comments and a missing header are fine
+    let spaced = parse_life106("#D a glider\n  0\t-1\n1   0\n\n-1 1\n0 1\n1 1").unwrap();
+    assert_eq!(spaced, glider);
+    assert!(parse_life106("#Life 1.06\n1 2 3").is_err());
+    assert!(parse_life106("#Life 1.06\n1 x").is_err());
+}
+
+#[test]
+fn test_parse_macrocell() {
+    let glider = parse_cells(".O.\n..O\nOOO").unwrap();
+    let one = parse_macrocell("[M2] (golly 4.2)\n#R B3/S23\n.*$..*$***$\n").unwrap();
+    assert_eq!(one, glider);
+    // a glider in the nw and the se of a 16x16, cropped to them
+    let two = parse_macrocell("[M2]\n.*$..*$***$\n4 1 0 0 1\n").unwrap();
+    let mut cells = glider.cells.clone();
+    cells.extend(glider.cells.iter().map(|(x, y)| (x + 8, y + 8)));
+    assert_eq!(two, Pattern::from_cells(cells));
+    assert_eq!(
+        sniff_pattern("[M2]\n$$$..*$\n").unwrap().cells,
+        vec![(0, 0)]
+    );
+    // a node two levels up from its children, or one that isn't there
+    assert!(parse_macrocell("[M2]\n.*$\n5 1 0 0 0\n").is_err());
+    assert!(parse_macrocell("[M2]\n.*$\n4 2 0 0 0\n").is_err());
+    assert!(parse_macrocell(".*$\n").is_err());
+    // the multi-state files' 2x2 leaves
+    let block = parse_macrocell("[M2]\n1 1 1 1 1\n").unwrap();
+    assert_eq!(block.cells, vec![(0, 0), (1, 0), (0, 1), (1, 1)]);
+}
+
+#[test]
+fn test_to_rle() {
+    // a glider on the board, y up: .O. / ..O / OOO from the top
+    let board = HashSet::from([(0, 1), (1, 0), (-1, -1), (0, -1), (1, -1)]);
+    let glider = Pattern::from_board(&board);
+    assert_eq!(glider, parse_cells(".O.\n..O\nOOO").unwrap());
+    let rle = to_rle(&glider, &Rule::default());
+    assert_eq!(rle, "x = 3, y = 3, rule = B3/S23\nbo$2bo$3o!\n");
+    assert_eq!(parse_rle(&rle).unwrap(), glider);
+    // blank rows fold into one run, long rows wrap
+    let mut wide: Vec<_> = (0..100).step_by(2).map(|x| (x, 0)).collect();
+    wide.push((0, 3));
+    let wide = Pattern::from_cells(wide);
+    let rle = to_rle(&wide, &Rule::default());
+    assert!(rle.lines().all(|l| l.len() <= 70));
+    assert!(rle.contains("3$o!"));
+    let mut back = parse_rle(&rle).unwrap();
+    back.cells.sort_by_key(|(x, y)| (*y, *x));
+    assert_eq!(back, wide);
+}
+
+#[test]
+fn test_sniff_pattern() {
+    let glider = parse_cells(".O.\n..O\nOOO").unwrap();
+    assert_eq!(
+        sniff_pattern("!Name: Glider\n.O.\n..O\nOOO\n"),
+        Ok(glider.clone())
+    );
+    let life106 = sniff_pattern("#Life 1.06\n0 -1\n1 0\n-1 1\n0 1\n1 1\n");
+    assert_eq!(life106, Ok(glider.clone()));
+    assert_eq!(
+        sniff_pattern("#N Glider\nx = 3, y = 3\nbo$2bo$3o!"),
+        Ok(glider)
+    );
+    assert!(sniff_pattern(" \n\n").is_err());
+    assert!(sniff_pattern("not a pattern").is_err());
+}
+
+#[test]
+fn test_rotate_and_flip() {
+    let sorted = |p: Pattern| {
+        let mut cells = p.cells;
+        cells.sort();
+        cells
+    };
+    // .O.
+    // ..O
+    // OOO
+    let glider = parse_cells(".O.\n..O\nOOO").unwrap();
+    // O..
+    // O.O
+    // OO.
+    let turned = parse_cells("O..\nO.O\nOO.").unwrap();
+    assert_eq!(sorted(glider.rotate()), sorted(turned));
+    let four = glider.rotate().rotate().rotate().rotate();
+    assert_eq!(sorted(four), sorted(glider.clone()));
+    assert_eq!(sorted(glider.flip().flip()), sorted(glider.clone()));
+
+    // non-square: a 3x1 row stands up as 1x3
+    let row = parse_cells("OO.").unwrap();
+    let row = Pattern { width: 3, ..row };
+    let column = row.rotate();
+    assert_eq!((column.width, column.height), (1, 3));
+    assert_eq!(sorted(column), vec![(0, 0), (0, 1)]);
+    assert_eq!(sorted(row.flip()), vec![(1, 0), (2, 0)]);
+}
+
+#[test]
+fn test_place_pattern_centered() {
+    let config = Config::default();
+    let glider = parse_cells(".O.\n..O\nOOO").unwrap();
+    assert_eq!(
+        place_pattern(&glider, (0, 0), &config),
+        HashSet::from([(0, 1), (1, 0), (-1, -1), (0, -1), (1, -1)])
+    );
+
+    // a 30 wide line on a 20 wide board keeps only its middle
+    let line = parse_rle("x = 30, y = 1\n30o!").unwrap();
+    let placed = place_pattern(&line, (0, 0), &config);
+    assert_eq!(placed.len(), config.width as usize);
+    assert!(placed.iter().all(|c| config.contains(*c) && c.1 == 0));
+}
@@ -0,0 +1,836 @@
+use bevy::prelude::*;
+// std's Instant panics on the web
+use bevy::utils::Instant;
+use rand::{rngs::StdRng, SeedableRng};
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::Path;
+use std::time::Duration;
+
+use crate::ant::{move_ants, place_ants, turmite_mode, Ants};
+use crate::hashlife::{hashlife_backend, hashlife_tick, HashLife};
+use crate::pattern::{load_pattern, place_pattern, SOUP};
+use crate::{
+    next_lineage, seed, seed_colors, step, step_counted, Backend, Cell, CellEvent, Config, Coord,
+    EdgeMode, Forecast, LiveCells, Stepping,
+};
+use crate::{PeriodDetector, Rule, State};
+
+// generations the population trend is judged over
+pub(crate) const TREND_WINDOW: usize = 6;
+// ticks the step timing percentiles are taken over
+const TIMING_WINDOW: usize = 120;
+// seconds between two refreshes of the shown percentiles
+const TIMING_REFRESH: f32 = 0.5;
+// most cell entities spawned in one frame; bigger boards are built over
+// several frames, the sim holding off until they're complete
+pub const SPAWN_CHUNK: usize = 40_000;
+// most ticks Stepping::Manual catches up on in one frame, past that the
+// backlog is dropped rather than the frames falling ever further behind
+const MAX_CATCH_UP: usize = 8;
+
+// where the main board stands, as of the last tick
+#[derive(Resource, Default, Debug)]
+pub struct Dashboard {
+    pub round: usize,
+    pub survival: usize,
+    // round it was computed at, and the outcome
+    pub forecast: Option<(usize, Forecast)>,
+    // survival over the last TREND_WINDOW rounds, newest at the back
+    pub history: VecDeque<usize>,
+    // cells that came alive / died in the last tick
+    pub births: usize,
+    pub deaths: usize,
+    // round the board started repeating at, and the period, once it does
+    pub settled: Option<(usize, usize)>,
+    // the most cells alive at once since the board was seeded
+    pub peak: usize,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Trend {
+    Growing,
+    Shrinking,
+    Stable,
+}
+
+impl Dashboard {
+    // newer half of the window against the older half, which evens out short oscillations
+    pub fn trend(&self, threshold: usize) -> Trend {
+        let half = self.history.len() / 2;
+        if half == 0 {
+            return Trend::Stable;
+        }
+        let older: usize = self.history.iter().take(half).sum();
+        let newer: usize = self.history.iter().rev().take(half).sum();
+        let (older, newer) = (older / half, newer / half);
+        if newer > older + threshold {
+            Trend::Growing
+        } else if older > newer + threshold {
+            Trend::Shrinking
+        } else {
+            Trend::Stable
+        }
+    }
+}
+
+#[test]
+fn test_trend() {
+    let with = |history: &[usize]| Dashboard {
+        history: history.iter().copied().collect(),
+        ..Default::default()
+    };
+    assert_eq!(with(&[]).trend(2), Trend::Stable);
+    assert_eq!(with(&[10, 12, 15, 18, 21, 25]).trend(2), Trend::Growing);
+    assert_eq!(with(&[25, 21, 18, 15, 12, 10]).trend(2), Trend::Shrinking);
+    // a period 2 oscillator swinging between 10 and 14
+    assert_eq!(with(&[10, 14, 10, 14, 10, 14]).trend(2), Trend::Stable);
+    assert_eq!(with(&[10, 11, 12, 13, 14, 15]).trend(2), Trend::Growing);
+    assert_eq!(with(&[10, 11, 12, 13, 14, 15]).trend(3), Trend::Stable);
+}
+
+// every bit of randomness comes from here, so a seed reproduces a run; the
+// second field is that seed
+#[derive(Resource)]
+pub struct SimRng(pub StdRng, pub u64);
+
+impl SimRng {
+    // insert one before adding the plugin to replay a run
+    pub fn seeded(seed: u64) -> SimRng {
+        SimRng(StdRng::seed_from_u64(seed), seed)
+    }
+}
+
+impl Default for SimRng {
+    fn default() -> Self {
+        let seed = rand::random();
+        info!("rng seed: {seed}");
+        SimRng::seeded(seed)
+    }
+}
+
+// how long the last TIMING_WINDOW ticks of dead_or_alive took
+#[derive(Resource, Debug)]
+pub struct StepTiming {
+    pub enabled: bool,
+    pub samples: VecDeque<Duration>,
+    // min, median, p95, max as last shown
+    pub summary: Option<[Duration; 4]>,
+    pub refresh: Timer,
+}
+
+impl Default for StepTiming {
+    fn default() -> Self {
+        StepTiming {
+            enabled: false,
+            samples: VecDeque::with_capacity(TIMING_WINDOW),
+            summary: None,
+            refresh: Timer::from_seconds(TIMING_REFRESH, TimerMode::Repeating),
+        }
+    }
+}
+
+impl StepTiming {
+    fn record(&mut self, sample: Duration) {
+        if self.samples.len() == TIMING_WINDOW {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(sample);
+    }
+}
+
+// min, median, p95 and max, nearest rank
+pub fn percentiles(samples: &VecDeque<Duration>) -> Option<[Duration; 4]> {
+    let mut sorted: Vec<_> = samples.iter().copied().collect();
+    sorted.sort();
+    let rank = |p: usize| sorted[(p * sorted.len()).div_ceil(100).max(1) - 1];
+    Some([*sorted.first()?, rank(50), rank(95), *sorted.last()?])
+}
+
+#[test]
+fn test_percentiles() {
+    let ms =
+        |v: &[u64]| -> VecDeque<Duration> { v.iter().map(|m| Duration::from_millis(*m)).collect() };
+    assert_eq!(percentiles(&ms(&[])), None);
+    let one = Duration::from_millis(3);
+    assert_eq!(percentiles(&ms(&[3])), Some([one; 4]));
+    let spread: Vec<_> = (1..=100).rev().collect();
+    assert_eq!(
+        percentiles(&ms(&spread)),
+        Some([1, 50, 95, 100].map(Duration::from_millis))
+    );
+    // a single stutter shows in p95 and max but not the median
+    let stutter = ms(&[2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 40]);
+    let [_, median, p95, max] = percentiles(&stutter).unwrap();
+    assert_eq!(median, Duration::from_millis(2));
+    assert_eq!(
+        (p95, max),
+        (Duration::from_millis(2), Duration::from_millis(40))
+    );
+}
+
+// the fixed update stops ticking while paused
+#[derive(States, Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SimState {
+    #[default]
+    Running,
+    Paused,
+}
+
+impl SimState {
+    pub fn toggled(self) -> SimState {
+        match self {
+            SimState::Running => SimState::Paused,
+            SimState::Paused => SimState::Running,
+        }
+    }
+}
+
+// the pattern or a random soup, run through the warmup; what seed_board lays
+// down, and what a headless run starts from
+pub fn starting_cells(
+    config: &Config,
+    rule: &Rule,
+    edge: EdgeMode,
+    rng: &mut StdRng,
+) -> HashSet<Coord> {
+    let pattern = config.pattern.as_ref().filter(|p| **p != Path::new(SOUP));
+    let loaded = pattern.and_then(|path| {
+        load_pattern(path)
+            .map_err(|e| warn!("can't load pattern, seeding randomly: {e}"))
+            .ok()
+    });
+    let picture = config.image.as_ref().and_then(|image| {
+        image
+            .seed(config)
+            .map_err(|e| warn!("can't load image, seeding randomly: {e}"))
+            .ok()
+    });
+    let mut alives = match loaded {
+        // the ants start on an empty board
+        _ if config.empty || config.turmite.is_some() => HashSet::new(),
+        _ if picture.is_some() => picture.unwrap(),
+        Some(p) => place_pattern(&p, config.pattern_at, config),
+        None => match rule.script.as_ref().and_then(|s| s.seed(config, rng)) {
+            Some(Ok(board)) => board,
+            Some(Err(e)) => {
+                warn!("the rule script can't seed, seeding randomly: {e}");
+                seed(rng, config)
+            }
+            None => seed(rng, config),
+        },
+    };
+    for _ in 0..config.warmup {
+        alives = step(&alives, rule, config, edge);
+    }
+    alives
+}
+
+// the starting cells as entities; only the cells, the app decides how they look
+#[allow(clippy::too_many_arguments)]
+pub fn seed_board(
+    mut commands: Commands,
+    config: Res<Config>,
+    rule: Res<Rule>,
+    edge: Res<EdgeMode>,
+    mut rng: ResMut<SimRng>,
+    mut live: ResMut<LiveCells>,
+    mut db: ResMut<Dashboard>,
+    mut sim_state: ResMut<NextState<SimState>>,
+    mut pending: ResMut<PendingCells>,
+) {
+    // cells
+    let mut cells_with_mm = vec![];
+    let rand_alives = starting_cells(&config, &rule, *edge, &mut rng.0);
+    // nothing would happen anyway until something is drawn
+    if config.empty {
+        sim_state.set(SimState::Paused);
+    }
+    db.round = config.warmup;
+    db.survival = rand_alives.len();
+    db.peak = db.survival;
+    if config.backend == Backend::Gpu {
+        // the GPU board is seeded from LiveCells, there are no cells to step
+        live.0 = rand_alives;
+        return;
+    }
+
+    for x in config.x_range() {
+        for y in config.y_range() {
+            let alive = rand_alives.contains(&(x, y));
+            if alive || !config.sparse {
+                cells_with_mm.push(Cell::new((x, y), alive));
+            }
+        }
+    }
+    if cells_with_mm.len() > SPAWN_CHUNK {
+        pending.0 = cells_with_mm;
+    } else {
+        commands.spawn_batch(cells_with_mm);
+    }
+    live.0 = rand_alives;
+}
+
+// cells of a big board still waiting for their entity
+#[derive(Resource, Default, Debug)]
+pub struct PendingCells(pub Vec<Cell>);
+
+pub fn spawn_pending_cells(mut commands: Commands, mut pending: ResMut<PendingCells>) {
+    let start = pending.0.len().saturating_sub(SPAWN_CHUNK);
+    let chunk: Vec<_> = pending.0.drain(start..).collect();
+    commands.spawn_batch(chunk);
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn dead_or_alive(
+    mut commands: Commands,
+    mut db: ResMut<Dashboard>,
+    config: Res<Config>,
+    rule: Res<Rule>,
+    edge: Res<EdgeMode>,
+    mut live: ResMut<LiveCells>,
+    mut timing: ResMut<StepTiming>,
+    mut events: EventWriter<CellEvent>,
+    mut query: Query<(Entity, &mut Cell)>,
+) {
+    // end of the world, unless there are dying cells still to decay or
+    // electron tails to turn back into wire
+    if live.0.is_empty() && rule.states <= 2 && !rule.wireworld {
+        return;
+    }
+
+    let started = Instant::now();
+    let next = if config.step_stats {
+        let (next, stats) = step_counted(&live.0, &rule, &config, *edge, false);
+        debug!(
+            "round {}: {} cells evaluated, {} neighbor lookups, {:?}",
+            db.round + 1,
+            stats.evaluated,
+            stats.lookups,
+            started.elapsed()
+        );
+        next
+    } else {
+        step(&live.0, &rule, &config, *edge)
+    };
+    follow_generation(
+        &next,
+        1,
+        &mut commands,
+        &mut db,
+        &config,
+        &rule,
+        &mut live,
+        &mut events,
+        &mut query,
+    );
+    timing.record(started.elapsed());
+}
+
+// the cells and LiveCells moved on to `next`, `generations` on from where they
+// were; whatever worked it out
+#[allow(clippy::too_many_arguments)]
+pub fn follow_generation(
+    next: &HashSet<Coord>,
+    generations: usize,
+    commands: &mut Commands,
+    db: &mut Dashboard,
+    config: &Config,
+    rule: &Rule,
+    live: &mut LiveCells,
+    events: &mut EventWriter<CellEvent>,
+    query: &mut Query<(Entity, &mut Cell)>,
+) {
+    db.round += generations;
+    (db.births, db.deaths) = (0, 0);
+    let mut unborn = next.clone();
+    for (entity, mut cell) in query.iter_mut() {
+        unborn.remove(&cell.index_xy);
+        if cell.frozen {
+            continue;
+        }
+        let from = cell.state.clone();
+        let to = if rule.wireworld {
+            from.next_wire(next.contains(&cell.index_xy))
+        } else {
+            from.next(next.contains(&cell.index_xy), rule.states)
+        };
+        if to == from {
+            // left alone, so whatever watches Changed<Cell> skips it
+            if to == State::Dead && config.sparse {
+                commands.entity(entity).despawn();
+            }
+            continue;
+        }
+        let alive = to == State::Alive;
+        match (from == State::Alive, alive) {
+            (false, true) => {
+                cell.born = db.round;
+                db.births += 1;
+            }
+            (true, false) => db.deaths += 1,
+            _ => {}
+        }
+        live.set(&mut cell, alive);
+        // decaying cells are out of LiveCells, the cell alone knows the stage
+        cell.state = to;
+        if config.cell_events && cell.state != from {
+            events.send(CellEvent {
+                coord: cell.index_xy,
+                from,
+                to: cell.state.clone(),
+                generation: db.round,
+            });
+        }
+        if cell.state == State::Dead && config.sparse {
+            commands.entity(entity).despawn();
+        }
+    }
+    // only in sparse mode are there births without an entity yet, and never
+    // under Wireworld, where only wires are born
+    if rule.wireworld {
+        unborn.clear();
+    }
+    for xy in unborn {
+        let mut cell = Cell::new(xy, false);
+        cell.born = db.round;
+        db.births += 1;
+        live.set(&mut cell, true);
+        if config.cell_events {
+            events.send(CellEvent {
+                coord: xy,
+                from: State::Dead,
+                to: State::Alive,
+                generation: db.round,
+            });
+        }
+        commands.spawn(cell);
+    }
+    db.survival = live.0.len();
+    let survival = db.survival;
+    db.peak = db.peak.max(survival);
+    db.history.push_back(survival);
+    if db.history.len() > TREND_WINDOW {
+        db.history.pop_front();
+    }
+}
+
+// every live cell's color under a multi-color rule, and the round they're for
+#[derive(Resource, Default, Debug)]
+pub struct Lineage {
+    pub colors: HashMap<Coord, u8>,
+    round: Option<usize>,
+}
+
+pub fn colored(rule: Res<Rule>) -> bool {
+    rule.colors > 1
+}
+
+// a generation on, newborns take their parents' colors; in the same round, as
+// when painting, the new cells take their neighbors'. Any other jump, a reseed,
+// a rewind or a few generations at once, and the board is colored afresh by
+// `config.color_init`
+pub fn follow_lineage(
+    config: Res<Config>,
+    rule: Res<Rule>,
+    edge: Res<EdgeMode>,
+    db: Res<Dashboard>,
+    live: Res<LiveCells>,
+    mut rng: ResMut<SimRng>,
+    mut lineage: ResMut<Lineage>,
+) {
+    let fresh = lineage
+        .round
+        .is_none_or(|r| db.round != r && db.round != r + 1);
+    lineage.colors = if fresh {
+        seed_colors(&mut rng.0, &config, &live.0, rule.colors)
+    } else {
+        next_lineage(&lineage.colors, &live.0, rule.colors, &config, *edge)
+    };
+    lineage.round = Some(db.round);
+}
+
+// an explicit cap, else half the board for rules that never let a cell die
+pub fn population_cap(config: &Config, rule: &Rule) -> Option<usize> {
+    let immortal = rule.survival.iter().all(|s| *s);
+    config
+        .population_cap
+        .or_else(|| immortal.then(|| config.area() / 2))
+}
+
+pub fn cap_population(
+    db: Res<Dashboard>,
+    config: Res<Config>,
+    rule: Res<Rule>,
+    mut sim_state: ResMut<NextState<SimState>>,
+) {
+    let Some(cap) = population_cap(&config, &rule) else {
+        return;
+    };
+    // only when crossing it, so resuming carries on past the cap
+    let before = db.history.iter().rev().nth(1).copied().unwrap_or(0);
+    if before < cap && db.survival >= cap {
+        sim_state.set(SimState::Paused);
+        info!("round {}: population reached {cap}, paused", db.round);
+    }
+}
+
+// flags the round a cycle started at, the first tick it shows up, and pauses
+// then with stop_when_settled; resuming runs on through the cycle
+pub fn note_settled(
+    detector: Res<PeriodDetector>,
+    config: Res<Config>,
+    mut db: ResMut<Dashboard>,
+    mut sim_state: ResMut<NextState<SimState>>,
+) {
+    let Some(period) = detector.period else {
+        db.settled = None;
+        return;
+    };
+    if db.settled.is_some() {
+        return;
+    }
+    let since = db.round.saturating_sub(period);
+    db.settled = Some((since, period));
+    if config.stop_when_settled {
+        sim_state.set(SimState::Paused);
+        info!(
+            "round {}: settled at round {since}, period {period}, paused",
+            db.round
+        );
+    }
+}
+
+// one tick every frame, however short the frames, instead of one a period
+#[derive(Resource, Default, Debug)]
+pub struct FlatOut(pub bool);
+
+// set while step_once runs a tick of its own
+#[derive(Resource, Default, Debug)]
+pub struct SingleStep(bool);
+
+fn single_step(single: Res<SingleStep>) -> bool {
+    single.0
+}
+
+// along with the state: not while a big board is still being built, nor on
+// the GPU board, nor once a pause is set for the next frame, so the frame's
+// other ticks don't run on past it
+pub fn ready(
+    config: Res<Config>,
+    next: Res<NextState<SimState>>,
+    pending: Res<PendingCells>,
+    single: Res<SingleStep>,
+) -> bool {
+    let pausing = next.0 == Some(SimState::Paused) && !single.0;
+    !pausing && pending.0.is_empty() && config.backend != Backend::Gpu
+}
+
+// frame time not yet spent on ticks, under Stepping::Manual
+#[derive(Resource, Default, Debug)]
+pub struct ManualClock {
+    pub accumulated: Duration,
+    // set while step_manually runs the FixedUpdate schedule
+    ticking: bool,
+}
+
+// the FixedUpdate schedule Bevy runs itself only ticks under Stepping::Fixed
+fn on_schedule(
+    config: Res<Config>,
+    clock: Res<ManualClock>,
+    single: Res<SingleStep>,
+    flat_out: Res<FlatOut>,
+) -> bool {
+    single.0 || (!flat_out.0 && (config.stepping == Stepping::Fixed || clock.ticking))
+}
+
+// one tick right away, paused or not, the app's per-tick systems included; for
+// stepping through a paused board, as the fixed loop ticks a running one too
+pub fn step_once(world: &mut World) {
+    world.resource_mut::<SingleStep>().0 = true;
+    world.run_schedule(FixedUpdate);
+    world.resource_mut::<SingleStep>().0 = false;
+}
+
+// Stepping::Manual: adds up the frame time and runs FixedUpdate for every
+// whole period in it, the period read again after each tick as adaptive
+// speed may change it
+pub fn step_manually(world: &mut World) {
+    let delta = world.resource::<Time>().delta();
+    world.resource_mut::<ManualClock>().accumulated += delta;
+    for _ in 0..MAX_CATCH_UP {
+        let period = world.resource::<FixedTime>().period;
+        let mut clock = world.resource_mut::<ManualClock>();
+        if clock.accumulated < period {
+            return;
+        }
+        clock.accumulated -= period;
+        clock.ticking = true;
+        world.run_schedule(FixedUpdate);
+        world.resource_mut::<ManualClock>().ticking = false;
+    }
+    world.resource_mut::<ManualClock>().accumulated = Duration::ZERO;
+}
+
+pub fn detect_period(mut detector: ResMut<PeriodDetector>, live: Res<LiveCells>) {
+    detector.record(live.0.clone());
+}
+
+// the seeding at startup and the tick itself; an app puts its own per-tick
+// systems in it, after cap_population, so they pause along
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SimSet;
+
+// the board and its rules with nothing to look at: cells are bare `Cell`s, and
+// the app inserts `Config`, and `SimRng` when it wants a set seed, beforehand
+pub struct SimPlugin;
+
+impl Plugin for SimPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Dashboard>()
+            .init_resource::<Rule>()
+            .init_resource::<EdgeMode>()
+            .add_state::<SimState>()
+            .init_resource::<StepTiming>()
+            .init_resource::<LiveCells>()
+            .init_resource::<PeriodDetector>()
+            .init_resource::<SimRng>()
+            .init_resource::<PendingCells>()
+            .init_resource::<ManualClock>()
+            .init_resource::<SingleStep>()
+            .init_resource::<FlatOut>()
+            .init_resource::<Ants>()
+            .init_resource::<HashLife>()
+            .init_resource::<Lineage>()
+            .add_event::<CellEvent>()
+            // checked once a tick for the whole set, so a pause halfway through
+            // doesn't skip the rest of that tick
+            .configure_set(
+                FixedUpdate,
+                SimSet
+                    .run_if(in_state(SimState::Running).or_else(single_step))
+                    .run_if(ready)
+                    .run_if(on_schedule),
+            )
+            .add_systems(Startup, (seed_board, place_ants).chain().in_set(SimSet))
+            .add_systems(
+                Update,
+                spawn_pending_cells.run_if(|pending: Res<PendingCells>| !pending.0.is_empty()),
+            )
+            // cells drawn in between ticks
+            .add_systems(
+                Update,
+                follow_lineage
+                    .run_if(colored)
+                    .run_if(resource_changed::<LiveCells>()),
+            )
+            .add_systems(
+                Update,
+                step_manually.run_if(|config: Res<Config>| config.stepping == Stepping::Manual),
+            )
+            .add_systems(
+                Update,
+                step_once
+                    .run_if(|flat_out: Res<FlatOut>| flat_out.0)
+                    .run_if(in_state(SimState::Running))
+                    .run_if(ready),
+            )
+            .add_systems(
+                FixedUpdate,
+                (
+                    dead_or_alive
+                        .run_if(not(turmite_mode))
+                        .run_if(not(hashlife_backend)),
+                    hashlife_tick.run_if(hashlife_backend),
+                    move_ants.run_if(turmite_mode),
+                    follow_lineage.run_if(colored),
+                    detect_period,
+                    note_settled,
+                    cap_population,
+                )
+                    .chain()
+                    .in_set(SimSet),
+            );
+    }
+}
+
+// `cells` on a board of their own, and a schedule ticking it
+#[cfg(test)]
+fn sim_world(
+    config: Config,
+    cells: impl IntoIterator<Item = Cell>,
+    live: HashSet<Coord>,
+) -> (World, Schedule) {
+    let mut world = World::new();
+    for cell in cells {
+        world.spawn(cell);
+    }
+    world.insert_resource(config);
+    world.insert_resource(LiveCells(live));
+    world.init_resource::<Dashboard>();
+    world.init_resource::<Rule>();
+    world.init_resource::<EdgeMode>();
+    world.init_resource::<StepTiming>();
+    world.init_resource::<Events<CellEvent>>();
+    let mut schedule = Schedule::default();
+    schedule.add_systems(dead_or_alive);
+    (world, schedule)
+}
+
+#[test]
+fn test_live_cells_stay_in_sync() {
+    let config = Config {
+        width: 8,
+        height: 8,
+        cell_events: true,
+        ..Default::default()
+    };
+    let blinker = HashSet::from([(-1, 0), (0, 0), (1, 0)]);
+    let cells: Vec<_> = config
+        .coords()
+        .map(|xy| Cell::new(xy, blinker.contains(&xy)))
+        .collect();
+    let (mut world, mut schedule) = sim_world(config, cells, blinker);
+    let check = |world: &mut World| {
+        let from_cells: HashSet<_> = world
+            .query::<&Cell>()
+            .iter(world)
+            .filter(|c| c.state == State::Alive)
+            .map(|c| c.index_xy)
+            .collect();
+        assert_eq!(from_cells, world.resource::<LiveCells>().0);
+    };
+    for _ in 0..3 {
+        schedule.run(&mut world);
+        check(&mut world);
+        let db = world.resource::<Dashboard>();
+        // the blinker swaps its two ends each tick
+        assert_eq!((db.births, db.deaths), (2, 2));
+        let round = db.round;
+        let mut events = world.resource_mut::<Events<CellEvent>>();
+        let sent: Vec<_> = events.drain().collect();
+        assert_eq!(sent.len(), 4);
+        assert!(sent.iter().all(|e| e.generation == round && e.from != e.to));
+    }
+    // drop a block into a corner, then keep ticking
+    world.resource_scope(|world, mut live: Mut<LiveCells>| {
+        for mut cell in world.query::<&mut Cell>().iter_mut(world) {
+            if [(2, 2), (2, 3), (3, 2), (3, 3)].contains(&cell.index_xy) {
+                live.set(&mut cell, true);
+            }
+        }
+    });
+    check(&mut world);
+    for _ in 0..3 {
+        schedule.run(&mut world);
+        check(&mut world);
+    }
+    assert_eq!(world.resource::<LiveCells>().0.len(), 7);
+}
+
+#[test]
+fn test_last_dying_cell_decays() {
+    let config = Config {
+        width: 4,
+        height: 4,
+        ..Default::default()
+    };
+    let lone = HashSet::from([(0, 0)]);
+    let cells: Vec<_> = config
+        .coords()
+        .map(|xy| Cell::new(xy, lone.contains(&xy)))
+        .collect();
+    let (mut world, mut schedule) = sim_world(config, cells, lone);
+    // Brian's Brain: alive, dying, dead
+    world.insert_resource(Rule::named("brianbrain").unwrap());
+    let state = |world: &mut World| {
+        let mut cells = world.query::<&Cell>();
+        let cell = cells.iter(world).find(|c| c.index_xy == (0, 0));
+        cell.unwrap().state.clone()
+    };
+    schedule.run(&mut world);
+    assert_eq!(state(&mut world), State::Dying(1));
+    assert!(world.resource::<LiveCells>().0.is_empty());
+    schedule.run(&mut world);
+    assert_eq!(state(&mut world), State::Dead);
+    assert_eq!(world.resource::<Dashboard>().round, 2);
+}
+
+#[test]
+fn test_only_flipped_cells_change() {
+    let config = Config {
+        width: 8,
+        height: 8,
+        ..Default::default()
+    };
+    let blinker = HashSet::from([(-1, 0), (0, 0), (1, 0)]);
+    let cells: Vec<_> = config
+        .coords()
+        .map(|xy| Cell::new(xy, blinker.contains(&xy)))
+        .collect();
+    let (mut world, mut schedule) = sim_world(config, cells, blinker);
+    for _ in 0..3 {
+        world.clear_trackers();
+        schedule.run(&mut world);
+        // the two ends and the two births, the middle and the rest untouched
+        let mut changed = world.query_filtered::<&Cell, Changed<Cell>>();
+        assert_eq!(changed.iter(&world).count(), 4);
+    }
+}
+
+#[test]
+fn test_frozen_cells() {
+    let config = Config {
+        width: 8,
+        height: 8,
+        ..Default::default()
+    };
+    // a blinker whose top would-be birth is blocked, and an L whose corner is a wall
+    let alives = HashSet::from([(-1, 0), (0, 0), (1, 0), (-3, -3), (-3, -2), (-2, -3)]);
+    let cells: Vec<_> = config
+        .coords()
+        .map(|xy| {
+            let mut cell = Cell::new(xy, alives.contains(&xy));
+            cell.frozen = xy == (0, 1) || xy == (-3, -3);
+            cell
+        })
+        .collect();
+    let (mut world, mut schedule) = sim_world(config, cells, alives);
+    schedule.run(&mut world);
+    let live = &world.resource::<LiveCells>().0;
+    // the bottom birth still happens, and the wall took part in turning the L into a block
+    assert!(live.contains(&(0, -1)));
+    assert!(live.contains(&(-2, -2)));
+    for _ in 0..4 {
+        let live = &world.resource::<LiveCells>().0;
+        // the obstacle never comes alive, the wall never dies
+        assert!(!live.contains(&(0, 1)));
+        assert!(live.contains(&(-3, -3)));
+        schedule.run(&mut world);
+    }
+}
+
+#[test]
+fn test_sparse_entities_match_live_cells() {
+    let config = Config {
+        width: 16,
+        height: 16,
+        sparse: true,
+        ..Default::default()
+    };
+    // the r-pentomino churns for a long while
+    let r = HashSet::from([(0, 1), (1, 1), (-1, 0), (0, 0), (0, -1)]);
+    let cells: Vec<_> = r.iter().map(|xy| Cell::new(*xy, true)).collect();
+    let (mut world, mut schedule) = sim_world(config, cells, r);
+    world.insert_resource(EdgeMode::wrapping(true, true));
+    for _ in 0..60 {
+        schedule.run(&mut world);
+        let cells: Vec<_> = world.query::<&Cell>().iter(&world).cloned().collect();
+        // nothing dead hangs around, and nothing alive is missing
+        assert!(cells.iter().all(|c| c.state == State::Alive));
+        let from_cells: HashSet<_> = cells.iter().map(|c| c.index_xy).collect();
+        assert_eq!(from_cells.len(), cells.len());
+        assert_eq!(from_cells, world.resource::<LiveCells>().0);
+    }
+    assert!(!world.resource::<LiveCells>().0.is_empty());
+}
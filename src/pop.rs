@@ -0,0 +1,101 @@
+use bevy::prelude::*;
+use bevy_game_of_life::{Cell, State};
+
+// seconds a birth or death takes to play out, at most
+const POP_SECS: f32 = 0.25;
+// how small dead cells sit while popping is on
+const DEAD_SCALE: f32 = 0.4;
+
+// births grow in, deaths shrink out; only ever touches the sprite's scale
+#[derive(Resource, Default, Debug)]
+pub struct PopMode(pub bool);
+
+#[derive(Component, Debug)]
+pub struct Pop {
+    // the state the cell is heading to
+    alive: bool,
+    // 0 just changed, 1 done
+    progress: f32,
+}
+
+impl Pop {
+    pub fn new(cell: &Cell) -> Pop {
+        Pop {
+            alive: cell.state == State::Alive,
+            progress: 1.0,
+        }
+    }
+
+    fn scale(&self) -> f32 {
+        let (from, to) = if self.alive {
+            (DEAD_SCALE, 1.0)
+        } else {
+            (1.0, DEAD_SCALE)
+        };
+        from + (to - from) * self.progress
+    }
+}
+
+#[test]
+fn test_pop_scale() {
+    let mut pop = Pop {
+        alive: true,
+        progress: 0.0,
+    };
+    assert_eq!(pop.scale(), DEAD_SCALE);
+    pop.progress = 1.0;
+    assert_eq!(pop.scale(), 1.0);
+    pop.alive = false;
+    assert!((pop.scale() - DEAD_SCALE).abs() < 1e-6);
+    pop.progress = 0.5;
+    assert!((pop.scale() - 0.7).abs() < 1e-6);
+}
+
+pub fn toggle_pop(
+    keys: Res<Input<KeyCode>>,
+    mut mode: ResMut<PopMode>,
+    mut query: Query<&mut Transform, With<Pop>>,
+) {
+    if !keys.just_pressed(KeyCode::Z) {
+        return;
+    }
+    mode.0 = !mode.0;
+    if !mode.0 {
+        settle(&mut query);
+    }
+}
+
+// every sprite back to full size, wherever its pop was
+pub fn settle(query: &mut Query<&mut Transform, With<Pop>>) {
+    for mut transform in query.iter_mut() {
+        transform.scale = Vec3::ONE;
+    }
+}
+
+pub fn animate_pops(
+    mode: Res<PopMode>,
+    time: Res<Time>,
+    fixed_time: Res<FixedTime>,
+    mut query: Query<(&Cell, &mut Pop, &mut Transform)>,
+) {
+    // done by the middle of the tick, and not at all when ticks come faster than frames
+    let duration = POP_SECS.min(fixed_time.period.as_secs_f32() / 2.0);
+    let snap = duration <= time.delta_seconds();
+    for (cell, mut pop, mut transform) in query.iter_mut() {
+        let alive = cell.state == State::Alive;
+        if pop.alive != alive {
+            pop.alive = alive;
+            pop.progress = 0.0;
+        }
+        if !mode.0 {
+            pop.progress = 1.0;
+            continue;
+        }
+        pop.progress = if snap {
+            1.0
+        } else {
+            (pop.progress + time.delta_seconds() / duration).min(1.0)
+        };
+        transform.scale = Vec3::splat(pop.scale());
+    }
+}
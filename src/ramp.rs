@@ -0,0 +1,103 @@
+use bevy::prelude::Color;
+use serde::{Deserialize, Serialize};
+
+// a gradient over 0..=1 through the given stops, blended in linear rgb so the
+// midpoints don't come out muddy; outside its stops it holds the end colors
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ColorRamp(Vec<(f32, Color)>);
+
+impl ColorRamp {
+    pub fn new(mut stops: Vec<(f32, Color)>) -> ColorRamp {
+        stops.sort_by(|a, b| a.0.total_cmp(&b.0));
+        ColorRamp(stops)
+    }
+
+    // the same spacing between all the colors
+    pub fn even(colors: &[Color]) -> ColorRamp {
+        let last = colors.len().saturating_sub(1).max(1) as f32;
+        ColorRamp::new(
+            colors
+                .iter()
+                .enumerate()
+                .map(|(i, c)| (i as f32 / last, *c))
+                .collect(),
+        )
+    }
+
+    pub fn sample(&self, t: f32) -> Color {
+        let (Some(first), Some(last)) = (self.0.first(), self.0.last()) else {
+            return Color::NONE;
+        };
+        if t <= first.0 {
+            return first.1;
+        }
+        if t >= last.0 {
+            return last.1;
+        }
+        let i = self.0.iter().position(|(s, _)| *s > t).unwrap_or(0);
+        let ((t0, c0), (t1, c1)) = (self.0[i - 1], self.0[i]);
+        let f = (t - t0) / (t1 - t0);
+        let [r0, g0, b0, a0] = c0.as_linear_rgba_f32();
+        let [r1, g1, b1, a1] = c1.as_linear_rgba_f32();
+        Color::rgba_linear(
+            r0 + (r1 - r0) * f,
+            g0 + (g1 - g0) * f,
+            b0 + (b1 - b0) * f,
+            a0 + (a1 - a0) * f,
+        )
+    }
+
+    // cold to hot, the colors of 0 through 8 live neighbors
+    pub fn heat() -> ColorRamp {
+        ColorRamp::even(&[
+            Color::rgb(0.1, 0.1, 0.2),
+            Color::rgb(0.1, 0.2, 0.5),
+            Color::rgb(0.1, 0.5, 0.8),
+            Color::rgb(0.2, 0.8, 0.4),
+            Color::rgb(0.8, 0.8, 0.1),
+            Color::rgb(0.9, 0.6, 0.1),
+            Color::rgb(0.9, 0.3, 0.1),
+            Color::rgb(0.8, 0.1, 0.2),
+            Color::rgb(0.6, 0.0, 0.4),
+        ])
+    }
+
+    // once around the hue circle, ending where it starts
+    pub fn rainbow() -> ColorRamp {
+        let hues: Vec<_> = (0..=6)
+            .map(|i| Color::hsl(i as f32 * 60.0, 0.7, 0.6))
+            .collect();
+        ColorRamp::even(&hues)
+    }
+}
+
+#[test]
+fn test_color_ramp() {
+    let close = |a: Color, b: Color| {
+        let (a, b) = (a.as_rgba_f32(), b.as_rgba_f32());
+        a.iter().zip(b).all(|(x, y)| (x - y).abs() < 1e-3)
+    };
+    let ramp = ColorRamp::new(vec![
+        (1.0, Color::WHITE),
+        (0.0, Color::BLACK),
+        (0.5, Color::RED),
+    ]);
+    // exactly the stops on the stops, and the ends past them
+    assert!(close(ramp.sample(0.0), Color::BLACK));
+    assert!(close(ramp.sample(0.5), Color::RED));
+    assert!(close(ramp.sample(1.0), Color::WHITE));
+    assert!(close(ramp.sample(-3.0), Color::BLACK));
+    assert!(close(ramp.sample(7.0), Color::WHITE));
+    // halfway in linear light, which is brighter than halfway in srgb
+    let mid = ramp.sample(0.75).as_linear_rgba_f32();
+    assert!((mid[0] - 1.0).abs() < 1e-3 && (mid[1] - 0.5).abs() < 1e-3);
+    assert!(ramp.sample(0.25).r() > 0.5);
+    // the heat ramp hits every neighbor count's color
+    let heat = ColorRamp::heat();
+    assert!(close(heat.sample(4.0 / 8.0), Color::rgb(0.8, 0.8, 0.1)));
+    assert!(close(
+        ColorRamp::rainbow().sample(1.0),
+        Color::hsl(0.0, 0.7, 0.6)
+    ));
+    assert_eq!(ColorRamp::new(vec![]).sample(0.5), Color::NONE);
+}
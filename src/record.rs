@@ -0,0 +1,143 @@
+use bevy::prelude::*;
+use bevy_game_of_life::{Config, Coord, LiveCells};
+
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::BufWriter;
+
+use crate::screenshot::{board_region, render_region};
+use crate::select::Selection;
+use crate::{Dashboard, Theme};
+
+// a take stops on its own past this many frames, they're all kept in memory
+pub const MAX_FRAMES: usize = 1000;
+
+// F10 starts recording a frame every generation and F10 again writes them as
+// an animated PNG, looping; with a selection only that rectangle is recorded
+#[derive(Resource, Debug)]
+pub struct Recording {
+    pub delay_ms: u16,
+    pub width: u32,
+    take: Option<Take>,
+}
+
+impl Recording {
+    pub fn new(delay_ms: u16, width: u32) -> Recording {
+        Recording {
+            delay_ms,
+            width,
+            take: None,
+        }
+    }
+
+    // frames so far, while recording
+    pub fn frames(&self) -> Option<usize> {
+        self.take.as_ref().map(|take| take.frames.len())
+    }
+}
+
+#[derive(Debug)]
+struct Take {
+    region: (Coord, Coord),
+    start: usize,
+    // the live cells in the region, a set per generation
+    frames: Vec<HashSet<Coord>>,
+}
+
+impl Take {
+    fn capture(&mut self, alives: &HashSet<Coord>) {
+        let ((x0, y0), (x1, y1)) = self.region;
+        let inside = |(x, y): &&Coord| (x0..=x1).contains(x) && (y0..=y1).contains(y);
+        self.frames
+            .push(alives.iter().filter(inside).copied().collect());
+    }
+}
+
+fn write_apng(
+    path: &str,
+    take: &Take,
+    sparse: bool,
+    theme: &Theme,
+    width: u32,
+    delay_ms: u16,
+) -> Result<(), png::EncodingError> {
+    let canvases: Vec<_> = take
+        .frames
+        .iter()
+        .map(|alives| render_region(take.region, sparse, alives, theme, width, None))
+        .collect();
+    let (w, h) = (canvases[0].width, canvases[0].height);
+    let mut encoder = png::Encoder::new(BufWriter::new(File::create(path)?), w, h);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    // no end to the loop
+    encoder.set_animated(canvases.len() as u32, 0)?;
+    encoder.set_frame_delay(delay_ms, 1000)?;
+    let mut writer = encoder.write_header()?;
+    for canvas in canvases {
+        writer.write_image_data(&canvas.data)?;
+    }
+    writer.finish()
+}
+
+#[test]
+fn test_write_apng() {
+    let mut take = Take {
+        region: ((-1, -1), (0, 0)),
+        start: 0,
+        frames: vec![],
+    };
+    take.capture(&HashSet::from([(0, 0), (1, 1)]));
+    take.capture(&HashSet::new());
+    assert_eq!(take.frames[0], HashSet::from([(0, 0)]));
+    let path = std::env::temp_dir().join("gol-test-run.png");
+    let path = path.to_str().unwrap();
+    write_apng(path, &take, false, &Theme::default(), 40, 100).unwrap();
+    let bytes = std::fs::read(path).unwrap();
+    // the animation control chunk, with the 2 frames in it
+    let actl = bytes.windows(4).position(|w| w == b"acTL").unwrap();
+    assert_eq!(bytes[actl + 4..actl + 8], 2u32.to_be_bytes());
+    std::fs::remove_file(path).unwrap();
+}
+
+pub fn toggle_recording(
+    keys: Res<Input<KeyCode>>,
+    config: Res<Config>,
+    theme: Res<Theme>,
+    db: Res<Dashboard>,
+    live: Res<LiveCells>,
+    selection: Res<Selection>,
+    mut recording: ResMut<Recording>,
+) {
+    let full = recording.frames().is_some_and(|n| n >= MAX_FRAMES);
+    if !keys.just_pressed(KeyCode::F10) && !full {
+        return;
+    }
+    let Some(take) = recording.take.take() else {
+        let region = selection.rect.map_or(board_region(&config), |(a, b)| {
+            ((a.0.min(b.0), a.1.min(b.1)), (a.0.max(b.0), a.1.max(b.1)))
+        });
+        let mut take = Take {
+            region,
+            start: db.round,
+            frames: vec![],
+        };
+        // the board as it is now is the first frame
+        take.capture(&live.0);
+        recording.take = Some(take);
+        info!("recording from round {}", db.round);
+        return;
+    };
+    let path = format!("run-{}-{}.png", take.start, db.round);
+    let (width, delay) = (recording.width, recording.delay_ms);
+    match write_apng(&path, &take, config.sparse, &theme, width, delay) {
+        Ok(()) => info!("exported {path}, {} frames", take.frames.len()),
+        Err(e) => error!("can't write {path}: {e}"),
+    }
+}
+
+pub fn capture_frame(live: Res<LiveCells>, mut recording: ResMut<Recording>) {
+    if let Some(take) = recording.take.as_mut() {
+        take.capture(&live.0);
+    }
+}
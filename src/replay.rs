@@ -0,0 +1,256 @@
+use bevy::prelude::*;
+use bevy_game_of_life::{Cell, Config, Coord, Dashboard, LiveCells, PeriodDetector, Rule, SimRng};
+use serde::{Deserialize, Serialize};
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::session::Session;
+use crate::{replace_board, Theme};
+
+// whatever changed the board, the rule or the speed in between two ticks:
+// paint, stamps, pastes, a reseed, a loaded pattern, all of them as the cells
+// they turned on and off
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Default)]
+#[serde(default)]
+pub struct Edit {
+    // the generation it came in on, before that tick
+    pub round: usize,
+    // the generation count it set, for the edits that start over, like a reseed
+    pub restart: Option<usize>,
+    pub born: Vec<Coord>,
+    pub died: Vec<Coord>,
+    // seconds a tick takes from here on, when that changed
+    pub tick: Option<f32>,
+    pub rule: Option<Rule>,
+}
+
+impl Edit {
+    pub fn apply(&self, board: &HashSet<Coord>) -> HashSet<Coord> {
+        let mut board = board.clone();
+        board.extend(self.born.iter().copied());
+        for c in &self.died {
+            board.remove(c);
+        }
+        board
+    }
+}
+
+fn changes(from: &HashSet<Coord>, to: &HashSet<Coord>) -> (Vec<Coord>, Vec<Coord>) {
+    let mut born: Vec<_> = to.difference(from).copied().collect();
+    let mut died: Vec<_> = from.difference(to).copied().collect();
+    born.sort();
+    died.sort();
+    (born, died)
+}
+
+// `--record-edits` writes one, `--replay` plays it back: the run set up as
+// `--config` would, seed included, and the edits on top in order
+#[derive(Serialize, Deserialize, Debug, PartialEq, Default)]
+#[serde(default)]
+pub struct Replay {
+    pub session: Session,
+    pub edits: Vec<Edit>,
+}
+
+impl Replay {
+    pub fn to_ron(&self) -> String {
+        let pretty = ron::ser::PrettyConfig::default().compact_arrays(true);
+        ron::ser::to_string_pretty(self, pretty).expect("a replay always serializes")
+    }
+
+    pub fn load(path: &Path) -> Result<Replay, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+        let mut replay: Replay = ron::from_str(&text).map_err(|e| e.to_string())?;
+        // the theme's preset colors, as --config has them
+        replay.session = Session::from_ron(&replay.session.to_ron())?;
+        Ok(replay)
+    }
+}
+
+#[test]
+fn test_replay() {
+    let before = HashSet::from([(0, 0), (1, 0)]);
+    let after = HashSet::from([(1, 0), (5, -5)]);
+    let (born, died) = changes(&before, &after);
+    let edit = Edit {
+        round: 12,
+        born,
+        died,
+        tick: Some(0.5),
+        ..Default::default()
+    };
+    assert_eq!(
+        (edit.born.as_slice(), edit.died.as_slice()),
+        (&[(5, -5)][..], &[(0, 0)][..])
+    );
+    assert_eq!(edit.apply(&before), after);
+
+    let replay = Replay {
+        session: Session {
+            rule: Rule::parse("B36/S23").unwrap(),
+            seed: Some(9),
+            ..Default::default()
+        },
+        edits: vec![
+            edit,
+            Edit {
+                round: 30,
+                restart: Some(0),
+                rule: Some(Rule::life_without_death()),
+                ..Default::default()
+            },
+        ],
+    };
+    let path = std::env::temp_dir().join("gol-test-replay.ron");
+    std::fs::write(&path, replay.to_ron()).unwrap();
+    assert_eq!(Replay::load(&path), Ok(replay));
+}
+
+// --record-edits: the board as the last tick left it, to tell the edits from
+// the generations
+#[derive(Resource, Debug)]
+pub struct EditLog {
+    path: PathBuf,
+    replay: Replay,
+    board: HashSet<Coord>,
+    round: usize,
+    tick: Duration,
+    rule: Rule,
+}
+
+impl EditLog {
+    pub fn new(path: PathBuf) -> EditLog {
+        EditLog {
+            path,
+            replay: Replay::default(),
+            board: HashSet::new(),
+            round: 0,
+            tick: Duration::ZERO,
+            rule: Rule::default(),
+        }
+    }
+
+    fn save(&self) {
+        if let Err(e) = std::fs::write(&self.path, self.replay.to_ron()) {
+            warn!("can't write the edits to {}: {e}", self.path.display());
+        }
+    }
+}
+
+// once the board is seeded, what the run was set up from
+#[allow(clippy::too_many_arguments)]
+pub fn start_log(
+    config: Res<Config>,
+    rule: Res<Rule>,
+    theme: Res<Theme>,
+    rng: Res<SimRng>,
+    fixed_time: Res<FixedTime>,
+    db: Res<Dashboard>,
+    live: Res<LiveCells>,
+    mut log: ResMut<EditLog>,
+) {
+    log.replay.session = Session {
+        config: config.clone(),
+        rule: rule.clone(),
+        theme: theme.clone(),
+        seed: Some(rng.1),
+        tick: Some(fixed_time.period.as_secs_f32()),
+    };
+    log.board = live.0.clone();
+    log.round = db.round;
+    log.tick = fixed_time.period;
+    log.rule = rule.clone();
+    log.save();
+}
+
+// ahead of the tick, whatever is different from how the last one left things
+pub fn note_edits(
+    fixed_time: Res<FixedTime>,
+    rule: Res<Rule>,
+    db: Res<Dashboard>,
+    live: Res<LiveCells>,
+    mut log: ResMut<EditLog>,
+) {
+    let tick = (fixed_time.period != log.tick).then(|| fixed_time.period.as_secs_f32());
+    let new_rule = (*rule != log.rule).then(|| rule.clone());
+    let unchanged = live.0 == log.board && db.round == log.round;
+    if unchanged && tick.is_none() && new_rule.is_none() {
+        return;
+    }
+    let (born, died) = changes(&log.board, &live.0);
+    let edit = Edit {
+        round: log.round,
+        restart: (db.round != log.round).then_some(db.round),
+        born,
+        died,
+        tick,
+        rule: new_rule,
+    };
+    log.replay.edits.push(edit);
+    log.tick = fixed_time.period;
+    log.rule = rule.clone();
+    log.save();
+}
+
+// after it, the board the next edits are told from
+pub fn note_tick(db: Res<Dashboard>, live: Res<LiveCells>, mut log: ResMut<EditLog>) {
+    if live.is_changed() {
+        log.board = live.0.clone();
+    }
+    log.round = db.round;
+}
+
+// --replay: the recorded edits, each ahead of the tick it came in on
+#[derive(Resource, Debug)]
+pub struct Playback {
+    edits: Vec<Edit>,
+    next: usize,
+}
+
+impl Playback {
+    pub fn new(edits: Vec<Edit>) -> Playback {
+        Playback { edits, next: 0 }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn play_edits(
+    mut commands: Commands,
+    config: Res<Config>,
+    mut fixed_time: ResMut<FixedTime>,
+    mut rule: ResMut<Rule>,
+    mut db: ResMut<Dashboard>,
+    mut detector: ResMut<PeriodDetector>,
+    mut playback: ResMut<Playback>,
+    mut live: ResMut<LiveCells>,
+    mut cells: Query<(Entity, &mut Cell)>,
+) {
+    while let Some(edit) = playback.edits.get(playback.next) {
+        if edit.round > db.round {
+            return;
+        }
+        let edit = edit.clone();
+        playback.next += 1;
+        if !edit.born.is_empty() || !edit.died.is_empty() {
+            let board = edit.apply(&live.0);
+            replace_board(&board, &config, &mut commands, &mut live, &mut cells);
+            *detector = PeriodDetector::default();
+        }
+        if let Some(round) = edit.restart {
+            db.round = round;
+            db.history.clear();
+            db.forecast = None;
+        }
+        if let Some(tick) = edit.tick {
+            fixed_time.period = Duration::from_secs_f32(tick);
+        }
+        if let Some(next) = edit.rule {
+            *rule = next;
+        }
+        if playback.next == playback.edits.len() {
+            info!("round {}: replayed all {} edits", db.round, playback.next);
+        }
+    }
+}
@@ -0,0 +1,130 @@
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use bevy_game_of_life::{
+    Backend, Cell, Config, Coord, Dashboard, LiveCells, PendingCells, PeriodDetector,
+};
+
+use std::collections::HashSet;
+
+use crate::camera::{boards_extent, Panel};
+use crate::Theme;
+
+// cells a press adds or takes off, one each side so the board stays centered
+pub const RESIZE_STEP: i32 = 2;
+// never smaller than this each way
+const MIN_SIDE: i32 = 2;
+
+pub fn resized(side: i32, grow: bool) -> i32 {
+    if grow {
+        side + RESIZE_STEP
+    } else {
+        (side - RESIZE_STEP).max(MIN_SIDE)
+    }
+}
+
+// the cells of the old board that the new one drops, and the ones it adds
+fn difference(old: &Config, new: &Config) -> (Vec<Coord>, Vec<Coord>) {
+    let dropped = old.coords().filter(|c| !new.contains(*c)).collect();
+    let added = new.coords().filter(|c| !old.contains(*c)).collect();
+    (dropped, added)
+}
+
+#[test]
+fn test_resize() {
+    let board = |width, height| Config {
+        width,
+        height,
+        ..Default::default()
+    };
+    assert_eq!(resized(20, true), 22);
+    assert_eq!(resized(3, false), MIN_SIDE);
+    let (dropped, added) = difference(&board(4, 4), &board(6, 2));
+    // two rows off the top and bottom, a column on either side
+    assert_eq!((dropped.len(), added.len()), (8, 4));
+    assert!(dropped.iter().all(|c| c.1 == -2 || c.1 == 1));
+    assert!(added.iter().all(|c| c.0 == -3 || c.0 == 2));
+    assert_eq!(difference(&board(5, 5), &board(5, 5)), (vec![], vec![]));
+}
+
+// Alt+arrows: wider / narrower, taller / shorter
+pub fn resize_on_key(keys: Res<Input<KeyCode>>, mut config: ResMut<Config>) {
+    if !keys.any_pressed([KeyCode::AltLeft, KeyCode::AltRight]) {
+        return;
+    }
+    let (mut width, mut height) = (config.width, config.height);
+    for (key, grow) in [(KeyCode::Right, true), (KeyCode::Left, false)] {
+        if keys.just_pressed(key) {
+            width = resized(width, grow);
+        }
+    }
+    for (key, grow) in [(KeyCode::Up, true), (KeyCode::Down, false)] {
+        if keys.just_pressed(key) {
+            height = resized(height, grow);
+        }
+    }
+    if (width, height) != (config.width, config.height) {
+        (config.width, config.height) = (width, height);
+    }
+}
+
+// whatever changed the board's size, the live cells that still fit stay,
+// cells come and go at the edges and the camera frames the new board
+#[allow(clippy::too_many_arguments)]
+pub fn resize_board(
+    mut commands: Commands,
+    mut config: ResMut<Config>,
+    theme: Res<Theme>,
+    panel: Res<Panel>,
+    mut db: ResMut<Dashboard>,
+    mut detector: ResMut<PeriodDetector>,
+    mut live: ResMut<LiveCells>,
+    mut pending: ResMut<PendingCells>,
+    mut applied: Local<Option<Config>>,
+    cells: Query<(Entity, &Cell)>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    mut cameras: Query<(&mut Transform, &mut OrthographicProjection)>,
+) {
+    let old = applied.get_or_insert_with(|| config.clone());
+    if (old.width, old.height) == (config.width, config.height) {
+        return;
+    }
+    // the GPU textures and the side boards' layout are sized at startup
+    if config.backend == Backend::Gpu || config.worlds > 1 {
+        warn!("the board can't change size with the GPU backend or side worlds");
+        (config.width, config.height) = (old.width, old.height);
+        return;
+    }
+    let (dropped, added) = difference(old, &config);
+    *old = config.clone();
+    let dropped: HashSet<_> = dropped.into_iter().collect();
+    for (entity, cell) in cells.iter() {
+        if dropped.contains(&cell.index_xy) {
+            commands.entity(entity).despawn();
+        }
+    }
+    pending.0.retain(|cell| config.contains(cell.index_xy));
+    if !config.sparse {
+        commands.spawn_batch(
+            added
+                .into_iter()
+                .map(|c| Cell::new(c, false))
+                .collect::<Vec<_>>(),
+        );
+    }
+    live.0.retain(|c| config.contains(*c));
+    db.survival = live.0.len();
+    *detector = PeriodDetector::default();
+    info!("the board is {}x{} now", config.width, config.height);
+
+    let Ok(window) = windows.get_single() else {
+        return;
+    };
+    let (center, extent) = boards_extent(&config, theme.pitch());
+    let window = Vec2::new(window.width(), window.height());
+    let (translation, scale) = panel.fit(center, extent, window);
+    for (mut transform, mut projection) in cameras.iter_mut() {
+        transform.translation.x = translation.x;
+        transform.translation.y = translation.y;
+        projection.scale = scale;
+    }
+}
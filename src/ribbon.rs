@@ -0,0 +1,161 @@
+use bevy::prelude::*;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use bevy::render::texture::ImageSampler;
+use bevy_game_of_life::{Config, Coord};
+
+use std::collections::HashSet;
+
+use crate::scrub::Timeline;
+use crate::Theme;
+
+// latest keyframes shown, oldest on the left
+const MAX_THUMBNAILS: usize = 6;
+// a thumbnail has at most this many pixels each way, bigger boards get blocks
+// of cells folded into one pixel
+const THUMBNAIL_PIXELS: i32 = 64;
+// on screen size of a thumbnail's longer side, in logical pixels
+const THUMBNAIL_SIZE: f32 = 64.0;
+
+// the last keyframes along the top right; off by default since every new
+// keyframe means new textures
+#[derive(Resource, Default, Debug)]
+pub struct Ribbon {
+    enabled: bool,
+    // generation of the newest keyframe the thumbnails were made from, which
+    // also moves when scrubbing back forks the history
+    shown: Option<usize>,
+}
+
+#[derive(Component)]
+pub struct RibbonStrip;
+
+// cells per pixel edge, and the thumbnail size in pixels
+fn thumbnail_scale(config: &Config) -> (i32, (i32, i32)) {
+    let scale = (config.width.max(config.height) + THUMBNAIL_PIXELS - 1) / THUMBNAIL_PIXELS;
+    let pixels = |cells: i32| (cells + scale - 1) / scale;
+    (scale, (pixels(config.width), pixels(config.height)))
+}
+
+// a pixel is lit when any cell of its block is alive, rows from the top
+fn thumbnail_pixels(config: &Config, alives: &HashSet<Coord>) -> (i32, i32, Vec<bool>) {
+    let (scale, (w, h)) = thumbnail_scale(config);
+    let (xs, ys) = (config.x_range(), config.y_range());
+    let mut lit = vec![false; (w * h) as usize];
+    for &(x, y) in alives.iter().filter(|c| config.contains(**c)) {
+        let px = (x - xs.start) / scale;
+        let py = (ys.end - 1 - y) / scale;
+        lit[(py * w + px) as usize] = true;
+    }
+    (w, h, lit)
+}
+
+#[test]
+fn test_thumbnail_pixels() {
+    let config = Config {
+        width: 4,
+        height: 2,
+        ..Default::default()
+    };
+    // one pixel per cell, the top row first
+    let (w, h, lit) = thumbnail_pixels(&config, &HashSet::from([(-2, 0), (1, -1)]));
+    assert_eq!((w, h), (4, 2));
+    assert_eq!(lit, [true, false, false, false, false, false, false, true]);
+    // 130 cells across fold three to a pixel
+    let wide = Config {
+        width: 130,
+        height: 10,
+        ..Default::default()
+    };
+    let (w, h, lit) = thumbnail_pixels(&wide, &HashSet::from([(64, -5), (-65, 4)]));
+    assert_eq!((w, h), (44, 4));
+    assert_eq!(lit.iter().filter(|l| **l).count(), 2);
+    assert!(lit[0] && lit[(3 * w + 43) as usize]);
+}
+
+fn thumbnail(config: &Config, alives: &HashSet<Coord>, theme: &Theme) -> Image {
+    let (w, h, lit) = thumbnail_pixels(config, alives);
+    let [on, off] = [theme.alive, theme.background].map(|c| c.as_rgba_u8());
+    let data = lit
+        .into_iter()
+        .flat_map(|l| if l { on } else { off })
+        .collect();
+    let mut image = Image::new(
+        Extent3d {
+            width: w as u32,
+            height: h as u32,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+    );
+    // blocky cells rather than a blur
+    image.sampler_descriptor = ImageSampler::nearest();
+    image
+}
+
+pub fn toggle_ribbon(
+    mut commands: Commands,
+    keys: Res<Input<KeyCode>>,
+    mut ribbon: ResMut<Ribbon>,
+    strips: Query<Entity, With<RibbonStrip>>,
+) {
+    if !keys.just_pressed(KeyCode::U) {
+        return;
+    }
+    ribbon.enabled = !ribbon.enabled;
+    ribbon.shown = None;
+    for strip in strips.iter() {
+        commands.entity(strip).despawn_recursive();
+    }
+}
+
+// only when a keyframe was added or dropped since the last time
+pub fn update_ribbon(
+    mut commands: Commands,
+    config: Res<Config>,
+    theme: Res<Theme>,
+    timeline: Res<Timeline>,
+    mut ribbon: ResMut<Ribbon>,
+    mut images: ResMut<Assets<Image>>,
+    strips: Query<Entity, With<RibbonStrip>>,
+) {
+    let newest = timeline.newest();
+    if !ribbon.enabled || newest == ribbon.shown {
+        return;
+    }
+    ribbon.shown = newest;
+    // the old textures go with their handles
+    for strip in strips.iter() {
+        commands.entity(strip).despawn_recursive();
+    }
+    let (_, (w, h)) = thumbnail_scale(&config);
+    let size = THUMBNAIL_SIZE / w.max(h) as f32;
+    commands
+        .spawn((
+            RibbonStrip,
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(10.0),
+                    right: Val::Px(10.0),
+                    column_gap: Val::Px(4.0),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        ))
+        .with_children(|strip| {
+            for alives in timeline.recent(MAX_THUMBNAILS) {
+                strip.spawn(ImageBundle {
+                    style: Style {
+                        width: Val::Px(w as f32 * size),
+                        height: Val::Px(h as f32 * size),
+                        ..Default::default()
+                    },
+                    image: images.add(thumbnail(&config, alives, &theme)).into(),
+                    ..Default::default()
+                });
+            }
+        });
+}
@@ -0,0 +1,338 @@
+use bevy::prelude::Resource;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use std::collections::HashSet;
+use std::path::Path;
+
+use crate::{Config, EdgeMode, Larger, Script};
+
+// outer-totalistic life-like rule, indexed by live neighbor count; serialized
+// as its rulestring
+#[derive(Resource, Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(try_from = "String", into = "String")]
+pub struct Rule {
+    pub birth: [bool; 9],
+    pub survival: [bool; 9],
+    // dead and alive, plus the decay stages of a Generations rule, where a cell
+    // that dies fades through them before it can be born again
+    pub states: u8,
+    // Wireworld, see State::next_wire; births are wires next to one or two
+    // electron heads
+    pub wireworld: bool,
+    // a Larger than Life rule in place of the birth and survival digits
+    pub larger: Option<Larger>,
+    // a script's next_state in their place, or its seed in place of the soup
+    pub script: Option<Script>,
+    // more than 1 for Life in colors, see next_lineage: Immigration's 2 or
+    // QuadLife's 4
+    pub colors: u8,
+}
+
+// well known rules by the name `--rule` takes, as rulestrings
+pub const PRESETS: [(&str, &str); 13] = [
+    ("life", "B3/S23"),
+    ("highlife", "B36/S23"),
+    ("daynight", "B3678/S34678"),
+    ("seeds", "B2/S"),
+    ("lifewithoutdeath", "B3/S012345678"),
+    ("replicator", "B1357/S1357"),
+    ("2x2", "B36/S125"),
+    ("maze", "B3/S12345"),
+    ("brianbrain", "B2/S/3"),
+    ("starwars", "B2/S345/4"),
+    ("bugs", "R5,C0,M1,S34..58,B34..45,NM"),
+    ("immigration", "Immigration"),
+    ("quadlife", "QuadLife"),
+];
+
+impl Default for Rule {
+    fn default() -> Self {
+        Rule::parse("B3/S23").unwrap()
+    }
+}
+
+impl Rule {
+    // "B36/S23" style rulestrings, with the state count after another '/' for
+    // a Generations rule ("B2/S/3")
+    pub fn parse(s: &str) -> Result<Rule, String> {
+        if s.trim().ends_with(".rhai") {
+            return Ok(Rule::scripted(Script::load(Path::new(s.trim()))?));
+        }
+        if s.trim().eq_ignore_ascii_case("wireworld") {
+            return Ok(Rule::wireworld());
+        }
+        if s.trim().eq_ignore_ascii_case("immigration") {
+            return Ok(Rule::colored(2));
+        }
+        if s.trim().eq_ignore_ascii_case("quadlife") {
+            return Ok(Rule::colored(4));
+        }
+        if s.contains(',') {
+            let (larger, states) = Larger::parse(s)?;
+            return Ok(Rule {
+                states,
+                larger: Some(larger),
+                ..Rule::parse("B/S").unwrap()
+            });
+        }
+        let mut parts = s.trim().split('/');
+        let b = parts.next().unwrap_or_default();
+        let survival = parts
+            .next()
+            .ok_or_else(|| format!("missing '/' in rule {s:?}"))?;
+        let states = match parts.next() {
+            None => 2,
+            Some(n) => match n.trim_start_matches(['C', 'c']).parse() {
+                Ok(n) if n >= 2 => n,
+                _ => return Err(format!("bad state count {n:?}, expected 2 or more")),
+            },
+        };
+        if parts.next().is_some() {
+            return Err(format!("too many '/' in rule {s:?}"));
+        }
+        let digits = |part: &str, prefix: char| -> Result<[bool; 9], String> {
+            let part = part
+                .strip_prefix(prefix)
+                .or_else(|| part.strip_prefix(prefix.to_ascii_lowercase()))
+                .ok_or_else(|| format!("expected {prefix} in {part:?}"))?;
+            let mut set = [false; 9];
+            for c in part.chars() {
+                match c.to_digit(10) {
+                    Some(d) if d <= 8 => set[d as usize] = true,
+                    _ => return Err(format!("bad neighbor count {c:?}")),
+                }
+            }
+            Ok(set)
+        };
+        Ok(Rule {
+            birth: digits(b, 'B')?,
+            survival: digits(survival, 'S')?,
+            states,
+            wireworld: false,
+            larger: None,
+            script: None,
+            colors: 1,
+        })
+    }
+
+    // Conway where the script has no next_state; B0 where it has a cell with
+    // nothing around it born, so the whole board is looked at
+    pub fn scripted(script: Script) -> Rule {
+        let mut rule = Rule::default();
+        let empty = HashSet::new();
+        let config = Config::default();
+        rule.birth[0] =
+            script.next_alive((0, 0), &empty, &config, EdgeMode::default()) == Some(true);
+        rule.script = Some(script);
+        rule
+    }
+
+    // a preset name from PRESETS, in any case, or else a rulestring
+    pub fn named(s: &str) -> Result<Rule, String> {
+        let script = s.trim().ends_with(".rhai");
+        if script || s.contains(['/', ',']) || s.trim().eq_ignore_ascii_case("wireworld") {
+            return Rule::parse(s);
+        }
+        let name = s.trim().to_ascii_lowercase();
+        match PRESETS.iter().find(|(n, _)| *n == name) {
+            Some((_, rulestring)) => Rule::parse(rulestring),
+            None => {
+                let names: Vec<_> = PRESETS.iter().map(|(n, _)| *n).collect();
+                Err(format!(
+                    "unknown rule preset {s:?}, try one of {} or a rulestring like B36/S23",
+                    names.join(", ")
+                ))
+            }
+        }
+    }
+
+    // the preset after (or before) this one in PRESETS, round the ends; a rule
+    // that isn't a preset goes back to Conway
+    pub fn next_preset(&self, forward: bool) -> Rule {
+        let rulestring = self.to_string();
+        let step = if forward { 1 } else { PRESETS.len() - 1 };
+        let next = PRESETS
+            .iter()
+            .position(|(_, r)| *r == rulestring)
+            .map_or(0, |i| (i + step) % PRESETS.len());
+        Rule::parse(PRESETS[next].1).unwrap()
+    }
+
+    // empty, head, tail and wire: B12 is when a wire gets a head, and no head
+    // survives
+    pub fn wireworld() -> Rule {
+        Rule {
+            wireworld: true,
+            ..Rule::parse("B12/S/3").unwrap()
+        }
+    }
+
+    // Conway with every live cell in one of `colors`, a newborn taking its
+    // parents'
+    pub fn colored(colors: u8) -> Rule {
+        Rule {
+            colors,
+            ..Rule::default()
+        }
+    }
+
+    // a.k.a. Inkspot: nothing ever dies, so the board only grows into mazes
+    pub fn life_without_death() -> Rule {
+        Rule::parse("B3/S012345678").unwrap()
+    }
+
+    // counts past 8 (possible when the center is counted) match no digit
+    pub fn next_alive(&self, alive: bool, live_count: usize) -> bool {
+        let set = if alive { &self.survival } else { &self.birth };
+        set.get(live_count).copied().unwrap_or(false)
+    }
+
+    // two states out of the 8 around each cell, nothing but the birth and
+    // survival digits to it
+    pub fn life_like(&self) -> bool {
+        self.states <= 2
+            && self.larger.is_none()
+            && self.script.is_none()
+            && self.colors <= 1
+            && !self.wireworld
+    }
+
+    // flip one birth or survival digit, never turning on B0
+    pub fn mutate(&mut self, rng: &mut impl Rng) {
+        if rng.gen_bool(0.5) {
+            let d = rng.gen_range(1..9);
+            self.birth[d] = !self.birth[d];
+        } else {
+            let d = rng.gen_range(0..9);
+            self.survival[d] = !self.survival[d];
+        }
+    }
+}
+
+impl std::fmt::Display for Rule {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        if self.wireworld {
+            return write!(f, "Wireworld");
+        }
+        if let Some(script) = &self.script {
+            return write!(f, "{}", script.path.display());
+        }
+        match self.colors {
+            2 => return write!(f, "Immigration"),
+            4 => return write!(f, "QuadLife"),
+            _ => {}
+        }
+        if let Some(larger) = &self.larger {
+            return write!(f, "{}", larger.to_rulestring(self.states));
+        }
+        let digits = |set: &[bool; 9]| -> String {
+            (0..9).filter(|d| set[*d]).map(|d| d.to_string()).collect()
+        };
+        write!(f, "B{}/S{}", digits(&self.birth), digits(&self.survival))?;
+        if self.states > 2 {
+            write!(f, "/{}", self.states)?;
+        }
+        Ok(())
+    }
+}
+
+impl TryFrom<String> for Rule {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Rule, String> {
+        Rule::named(&s)
+    }
+}
+
+impl From<Rule> for String {
+    fn from(rule: Rule) -> String {
+        rule.to_string()
+    }
+}
+
+#[test]
+fn test_rule_parse() {
+    let highlife = Rule::parse("B36/S23").unwrap();
+    assert!(highlife.birth[3] && highlife.birth[6] && !highlife.birth[2]);
+    assert!(highlife.survival[2] && highlife.survival[3]);
+    assert_eq!(highlife.to_string(), "B36/S23");
+    assert_eq!(Rule::parse("b2/s").unwrap().to_string(), "B2/S");
+    assert!(Rule::parse("B9/S23").is_err());
+    assert!(Rule::parse("B3S23").is_err());
+    let brain = Rule::parse("B2/S/3").unwrap();
+    assert_eq!((brain.states, brain.survival), (3, [false; 9]));
+    assert_eq!(brain.to_string(), "B2/S/3");
+    assert_eq!(Rule::parse("B2/S345/C4").unwrap().to_string(), "B2/S345/4");
+    assert_eq!(highlife.states, 2);
+    assert!(Rule::parse("B2/S/1").is_err());
+    let e = Rule::parse("B2/S/3/4").unwrap_err();
+    assert!(e.contains("\"B2/S/3/4\""), "{e}");
+    let wireworld = Rule::named("WireWorld").unwrap();
+    assert_eq!(wireworld, Rule::wireworld());
+    assert_eq!(Rule::parse(&wireworld.to_string()).unwrap(), wireworld);
+    assert_ne!(wireworld, Rule::parse("B12/S/3").unwrap());
+    let bugs = Rule::named("bugs").unwrap();
+    assert_eq!(bugs.larger.as_ref().unwrap().radius, 5);
+    assert_eq!(Rule::parse("R2,C3,M0,S1..2,B2,NN").unwrap().states, 3);
+}
+
+#[test]
+fn test_rule_presets() {
+    let sets = |rule: Rule| {
+        let digits = |set: [bool; 9]| (0..9).filter(|d| set[*d]).collect::<Vec<_>>();
+        (digits(rule.birth), digits(rule.survival))
+    };
+    assert_eq!(sets(Rule::named("life").unwrap()), (vec![3], vec![2, 3]));
+    assert_eq!(
+        sets(Rule::named("HighLife").unwrap()),
+        (vec![3, 6], vec![2, 3])
+    );
+    assert_eq!(
+        sets(Rule::named("daynight").unwrap()),
+        (vec![3, 6, 7, 8], vec![3, 4, 6, 7, 8])
+    );
+    assert_eq!(sets(Rule::named("seeds").unwrap()), (vec![2], vec![]));
+    assert_eq!(
+        Rule::named("lifewithoutdeath").unwrap(),
+        Rule::life_without_death()
+    );
+    // Conway's digits, in colors
+    let quadlife = Rule::named("QuadLife").unwrap();
+    assert_eq!(
+        (quadlife.colors, quadlife.birth),
+        (4, Rule::default().birth)
+    );
+    assert_eq!(Rule::parse("immigration").unwrap(), Rule::colored(2));
+    for (name, rulestring) in PRESETS {
+        assert_eq!(Rule::named(name).unwrap().to_string(), rulestring);
+    }
+    let mut rule = Rule::default();
+    for (_, rulestring) in PRESETS.iter().skip(1) {
+        rule = rule.next_preset(true);
+        assert_eq!(rule.to_string(), *rulestring);
+    }
+    assert_eq!(rule.next_preset(true), Rule::default());
+    assert_eq!(Rule::default().next_preset(false), rule);
+    let custom = Rule::parse("B1/S1").unwrap();
+    assert_eq!(custom.next_preset(false), Rule::default());
+    // rulestrings still go straight through, unknown names list the presets
+    assert_eq!(Rule::named("B2/S").unwrap().to_string(), "B2/S");
+    let e = Rule::named("lief").unwrap_err();
+    assert!(e.contains("highlife") && e.contains("seeds"));
+}
+
+#[test]
+fn test_rule_mutate() {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    let mut rng = StdRng::seed_from_u64(7);
+    let mut rule = Rule::default();
+    for _ in 0..1000 {
+        let before = rule.clone();
+        rule.mutate(&mut rng);
+        assert!(!rule.birth[0]);
+        assert_ne!(before, rule);
+        assert_eq!(Rule::parse(&rule.to_string()).unwrap(), rule);
+    }
+}
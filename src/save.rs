@@ -0,0 +1,268 @@
+use bevy::prelude::*;
+use bevy_game_of_life::pattern::{load_pattern, Pattern};
+use bevy_game_of_life::{
+    Cell, Config, Dashboard, LiveCells, PendingCells, PeriodDetector, Rule, SimRng, SimState,
+    Snapshot,
+};
+use serde::{Deserialize, Serialize};
+
+use std::path::Path;
+
+use crate::draw::{DrawTool, Stamp};
+use crate::replace_board;
+
+// where Shift+F9 saves and F9 loads
+pub const SAVE_FILE: &str = "state.ron";
+
+// a run to pick up again: the board, the round it got to, the seed the rest of
+// its randomness comes from and the rule
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct SaveState {
+    pub round: usize,
+    pub seed: u64,
+    pub rule: Rule,
+    pub board: Snapshot,
+}
+
+impl SaveState {
+    pub fn load(path: &Path) -> Result<SaveState, String> {
+        let s = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+        let state: SaveState = ron::from_str(&s).map_err(|e| e.to_string())?;
+        // a broken board shows now rather than once it's laid down
+        state.board.alives()?;
+        Ok(state)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let pretty = ron::ser::PrettyConfig::default();
+        let s = ron::ser::to_string_pretty(self, pretty).map_err(|e| e.to_string())?;
+        std::fs::write(path, s).map_err(|e| e.to_string())
+    }
+}
+
+#[test]
+fn test_save_round_trip() {
+    let config = Config {
+        width: 5,
+        height: 4,
+        ..Default::default()
+    };
+    let state = SaveState {
+        round: 1234,
+        seed: 99,
+        rule: Rule::named("highlife").unwrap(),
+        board: Snapshot::new(&config, &[(-1, 0), (0, 0), (1, 0)].into()),
+    };
+    let path = std::env::temp_dir().join("gol-test-save.ron");
+    state.save(&path).unwrap();
+    assert_eq!(SaveState::load(&path), Ok(state));
+    std::fs::write(
+        &path,
+        "(round: 1, seed: 2, rule: \"B3/S23\", board: (width: 2, height: 1, rows: [\"O\"]))",
+    )
+    .unwrap();
+    assert!(SaveState::load(&path).unwrap_err().contains("isn't 2 wide"));
+    std::fs::remove_file(path).unwrap();
+}
+
+// a --load state waiting for the board to be built, then laid down on it
+#[derive(Resource, Debug)]
+pub struct PendingLoad(pub SaveState);
+
+// everything about the run, in place; the board has to be the same size
+#[allow(clippy::too_many_arguments)]
+fn restore(
+    state: &SaveState,
+    config: &Config,
+    commands: &mut Commands,
+    db: &mut Dashboard,
+    rule: &mut Rule,
+    rng: &mut SimRng,
+    detector: &mut PeriodDetector,
+    live: &mut LiveCells,
+    cells: &mut Query<(Entity, &mut Cell)>,
+) -> Result<(), String> {
+    if (state.board.width, state.board.height) != (config.width, config.height) {
+        return Err(format!(
+            "the saved board is {}x{}, this one {}x{}",
+            state.board.width, state.board.height, config.width, config.height
+        ));
+    }
+    config.runs(&state.rule)?;
+    let board = state.board.alives()?;
+    replace_board(&board, config, commands, live, cells);
+    db.round = state.round;
+    db.survival = board.len();
+    if *rule != state.rule {
+        *rule = state.rule.clone();
+    }
+    *rng = SimRng::seeded(state.seed);
+    *detector = PeriodDetector::default();
+    Ok(())
+}
+
+// Shift+F9 saves the run to SAVE_FILE, F9 goes back to it
+#[allow(clippy::too_many_arguments)]
+pub fn save_or_load(
+    mut commands: Commands,
+    keys: Res<Input<KeyCode>>,
+    config: Res<Config>,
+    mut db: ResMut<Dashboard>,
+    mut rule: ResMut<Rule>,
+    mut rng: ResMut<SimRng>,
+    mut detector: ResMut<PeriodDetector>,
+    mut live: ResMut<LiveCells>,
+    mut cells: Query<(Entity, &mut Cell)>,
+) {
+    if !keys.just_pressed(KeyCode::F9) {
+        return;
+    }
+    let path = Path::new(SAVE_FILE);
+    if keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]) {
+        let state = SaveState {
+            round: db.round,
+            seed: rng.1,
+            rule: rule.clone(),
+            board: Snapshot::new(&config, &live.0),
+        };
+        match state.save(path) {
+            Ok(()) => info!("saved round {} to {SAVE_FILE}", db.round),
+            Err(e) => error!("can't save to {SAVE_FILE}: {e}"),
+        }
+        return;
+    }
+    let loaded = SaveState::load(path).and_then(|state| {
+        restore(
+            &state,
+            &config,
+            &mut commands,
+            &mut db,
+            &mut rule,
+            &mut rng,
+            &mut detector,
+            &mut live,
+            &mut cells,
+        )
+    });
+    match loaded {
+        Ok(()) => info!("loaded round {} from {SAVE_FILE}", db.round),
+        Err(e) => error!("can't load {SAVE_FILE}: {e}"),
+    }
+}
+
+// the --load state, once the (empty) board has its cells; it runs on from there
+#[allow(clippy::too_many_arguments)]
+pub fn apply_pending_load(
+    mut commands: Commands,
+    pending: Res<PendingLoad>,
+    building: Res<PendingCells>,
+    config: Res<Config>,
+    mut db: ResMut<Dashboard>,
+    mut rule: ResMut<Rule>,
+    mut rng: ResMut<SimRng>,
+    mut detector: ResMut<PeriodDetector>,
+    mut live: ResMut<LiveCells>,
+    mut sim_state: ResMut<NextState<SimState>>,
+    mut cells: Query<(Entity, &mut Cell)>,
+) {
+    if !building.0.is_empty() {
+        return;
+    }
+    let loaded = restore(
+        &pending.0,
+        &config,
+        &mut commands,
+        &mut db,
+        &mut rule,
+        &mut rng,
+        &mut detector,
+        &mut live,
+        &mut cells,
+    );
+    if let Err(e) = loaded {
+        error!("can't load the saved state: {e}");
+    }
+    sim_state.set(SimState::Running);
+    commands.remove_resource::<PendingLoad>();
+}
+
+// a file dropped on the window: a saved run by its .ron, else a pattern
+#[derive(Debug, PartialEq)]
+enum Dropped {
+    Run(SaveState),
+    Pattern(Pattern),
+}
+
+fn read_dropped(path: &Path) -> Result<Dropped, String> {
+    match path.extension().and_then(|e| e.to_str()) {
+        Some("ron") => SaveState::load(path).map(Dropped::Run),
+        _ => load_pattern(path).map(Dropped::Pattern),
+    }
+}
+
+#[test]
+fn test_read_dropped() {
+    let dir = std::env::temp_dir();
+    let rle = dir.join("gol-test-drop.rle");
+    std::fs::write(&rle, "x = 3, y = 1\n3o!").unwrap();
+    let Ok(Dropped::Pattern(pattern)) = read_dropped(&rle) else {
+        panic!("the RLE file didn't load as a pattern");
+    };
+    assert_eq!(pattern.cells, vec![(0, 0), (1, 0), (2, 0)]);
+    let ron = dir.join("gol-test-drop.ron");
+    std::fs::write(&ron, "not a saved run").unwrap();
+    assert!(read_dropped(&ron).is_err());
+    std::fs::remove_file(rle).unwrap();
+    std::fs::remove_file(ron).unwrap();
+}
+
+// a pattern file dropped on the window becomes the stamp, to place with a
+// click; a run saved with Shift+F9 is laid down as F9 would. Either way the
+// board pauses
+#[allow(clippy::too_many_arguments)]
+pub fn load_dropped(
+    mut commands: Commands,
+    mut drops: EventReader<FileDragAndDrop>,
+    config: Res<Config>,
+    mut db: ResMut<Dashboard>,
+    mut rule: ResMut<Rule>,
+    mut rng: ResMut<SimRng>,
+    mut detector: ResMut<PeriodDetector>,
+    mut live: ResMut<LiveCells>,
+    mut sim_state: ResMut<NextState<SimState>>,
+    mut tool: ResMut<DrawTool>,
+    mut stamp: ResMut<Stamp>,
+    mut cells: Query<(Entity, &mut Cell)>,
+) {
+    for drop in drops.iter() {
+        let FileDragAndDrop::DroppedFile { path_buf: path, .. } = drop else {
+            continue;
+        };
+        let loaded = read_dropped(path).and_then(|dropped| match dropped {
+            Dropped::Run(state) => restore(
+                &state,
+                &config,
+                &mut commands,
+                &mut db,
+                &mut rule,
+                &mut rng,
+                &mut detector,
+                &mut live,
+                &mut cells,
+            ),
+            Dropped::Pattern(pattern) => {
+                let name = path.file_stem().map(|s| s.to_string_lossy().into_owned());
+                *stamp = Stamp(pattern, name.unwrap_or_default());
+                *tool = DrawTool::Stamp;
+                Ok(())
+            }
+        });
+        match loaded {
+            Ok(()) => {
+                sim_state.set(SimState::Paused);
+                info!("loaded {}", path.display());
+            }
+            Err(e) => error!("can't load {}: {e}", path.display()),
+        }
+    }
+}
@@ -0,0 +1,207 @@
+use bevy::prelude::*;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use bevy_game_of_life::{Config, Coord, LiveCells};
+
+use std::collections::HashSet;
+
+use crate::{Dashboard, Theme};
+
+// how wide F12 draws the board, in pixels; the height follows the board
+#[derive(Resource, Debug)]
+pub struct ScreenshotWidth(pub u32);
+
+// 3x5 glyphs for the caption, a row per byte with the leftmost pixel the
+// highest of the three bits
+fn glyph(c: char) -> [u8; 5] {
+    match c {
+        '0' | 'O' => [0b111, 0b101, 0b101, 0b101, 0b111],
+        '1' => [0b010, 0b110, 0b010, 0b010, 0b111],
+        '2' => [0b111, 0b001, 0b111, 0b100, 0b111],
+        '3' => [0b111, 0b001, 0b111, 0b001, 0b111],
+        '4' => [0b101, 0b101, 0b111, 0b001, 0b001],
+        '5' => [0b111, 0b100, 0b111, 0b001, 0b111],
+        '6' => [0b111, 0b100, 0b111, 0b101, 0b111],
+        '7' => [0b111, 0b001, 0b001, 0b001, 0b001],
+        '8' => [0b111, 0b101, 0b111, 0b101, 0b111],
+        '9' => [0b111, 0b101, 0b111, 0b001, 0b111],
+        'R' => [0b110, 0b101, 0b110, 0b101, 0b101],
+        'U' => [0b101, 0b101, 0b101, 0b101, 0b111],
+        'N' => [0b101, 0b111, 0b111, 0b101, 0b101],
+        'D' => [0b110, 0b101, 0b101, 0b101, 0b110],
+        'P' => [0b111, 0b101, 0b111, 0b100, 0b100],
+        _ => [0; 5],
+    }
+}
+
+pub struct Canvas {
+    pub width: u32,
+    pub height: u32,
+    // rgba rows from the top
+    pub data: Vec<u8>,
+}
+
+impl Canvas {
+    // the format from the extension
+    pub fn save(self, path: &str) -> Result<(), String> {
+        let image = Image::new(
+            Extent3d {
+                width: self.width,
+                height: self.height,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            self.data,
+            TextureFormat::Rgba8UnormSrgb,
+        );
+        let image = image.try_into_dynamic().map_err(|e| e.to_string())?;
+        image.save(path).map_err(|e| e.to_string())
+    }
+
+    // clipped to the canvas
+    fn fill(&mut self, left: u32, top: u32, w: u32, h: u32, color: [u8; 4]) {
+        for y in top..(top + h).min(self.height) {
+            for x in left..(left + w).min(self.width) {
+                let i = (y * self.width + x) as usize * 4;
+                self.data[i..i + 4].copy_from_slice(&color);
+            }
+        }
+    }
+}
+
+// the cells from the `low` to the `high` corner, both included, drawn
+// `width` pixels across with the gap between cells kept to scale, and
+// `caption` in the top-left corner
+pub fn render_region(
+    (low, high): (Coord, Coord),
+    sparse: bool,
+    alives: &HashSet<Coord>,
+    theme: &Theme,
+    width: u32,
+    caption: Option<&str>,
+) -> Canvas {
+    let (columns, rows) = (high.0 - low.0 + 1, high.1 - low.1 + 1);
+    let cell = (width as f32 / columns as f32).max(1.0);
+    let mut canvas = Canvas {
+        width: (cell * columns as f32).round() as u32,
+        height: (cell * rows as f32).round() as u32,
+        data: vec![],
+    };
+    canvas.data = theme
+        .background
+        .as_rgba_u8()
+        .repeat((canvas.width * canvas.height) as usize);
+    // at least a pixel, however small the cells get
+    let side = (cell * theme.size / theme.pitch()).round().max(1.0) as u32;
+    let inset = ((cell - side as f32) / 2.0).max(0.0);
+    let (alive, dead) = (theme.alive.as_rgba_u8(), theme.dead.as_rgba_u8());
+    let coords = (low.0..=high.0).flat_map(|x| (low.1..=high.1).map(move |y| (x, y)));
+    for (x, y) in coords {
+        let color = if alives.contains(&(x, y)) {
+            alive
+        } else if sparse {
+            // as on screen, dead cells without a sprite show the background
+            continue;
+        } else {
+            dead
+        };
+        let left = ((x - low.0) as f32 * cell + inset) as u32;
+        let top = ((high.1 - y) as f32 * cell + inset) as u32;
+        canvas.fill(left, top, side, side, color);
+    }
+    if let Some(caption) = caption {
+        let scale = (canvas.width / 200).max(2);
+        let advance = 4 * scale;
+        let box_w = advance * caption.chars().count() as u32 + scale;
+        canvas.fill(0, 0, box_w, 7 * scale, theme.background.as_rgba_u8());
+        for (i, c) in caption.chars().enumerate() {
+            for (row, bits) in glyph(c).into_iter().enumerate() {
+                for col in 0..3 {
+                    if bits & (0b100 >> col) != 0 {
+                        let left = scale + i as u32 * advance + col * scale;
+                        let top = scale + row as u32 * scale;
+                        canvas.fill(left, top, scale, scale, alive);
+                    }
+                }
+            }
+        }
+    }
+    canvas
+}
+
+pub fn board_region(config: &Config) -> (Coord, Coord) {
+    let (xs, ys) = (config.x_range(), config.y_range());
+    ((xs.start, ys.start), (xs.end - 1, ys.end - 1))
+}
+
+fn render_board(
+    config: &Config,
+    alives: &HashSet<Coord>,
+    theme: &Theme,
+    width: u32,
+    caption: Option<&str>,
+) -> Canvas {
+    let region = board_region(config);
+    render_region(region, config.sparse, alives, theme, width, caption)
+}
+
+#[test]
+fn test_render_board() {
+    let config = Config {
+        width: 2,
+        height: 1,
+        ..Default::default()
+    };
+    let theme = Theme::default();
+    let board = render_board(&config, &HashSet::from([(-1, 0)]), &theme, 200, None);
+    assert_eq!((board.width, board.height), (200, 100));
+    let at = |canvas: &Canvas, x: u32, y: u32| {
+        let i = (y * canvas.width + x) as usize * 4;
+        [
+            canvas.data[i],
+            canvas.data[i + 1],
+            canvas.data[i + 2],
+            canvas.data[i + 3],
+        ]
+    };
+    assert_eq!(at(&board, 50, 50), theme.alive.as_rgba_u8());
+    assert_eq!(at(&board, 150, 50), theme.dead.as_rgba_u8());
+    // the gap around a cell is left as the background
+    assert_eq!(at(&board, 0, 0), theme.background.as_rgba_u8());
+    let captioned = render_board(&config, &HashSet::new(), &theme, 200, Some("1"));
+    // the top of the 1 is its middle column
+    assert_eq!(at(&captioned, 2 + 2 + 1, 2 + 1), theme.alive.as_rgba_u8());
+    assert_eq!(at(&captioned, 2 + 1, 2 + 1), theme.background.as_rgba_u8());
+    let path = std::env::temp_dir().join("gol-test-board.png");
+    captioned.save(path.to_str().unwrap()).unwrap();
+    assert!(std::fs::read(&path).unwrap().starts_with(b"\x89PNG"));
+    std::fs::remove_file(path).unwrap();
+}
+
+// F12 writes the board as board-<round>.png, Shift+F12 with the round and the
+// population over it
+pub fn take_screenshot(
+    keys: Res<Input<KeyCode>>,
+    config: Res<Config>,
+    theme: Res<Theme>,
+    live: Res<LiveCells>,
+    db: Res<Dashboard>,
+    width: Res<ScreenshotWidth>,
+) {
+    if !keys.just_pressed(KeyCode::F12) {
+        return;
+    }
+    let shift = keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    let caption = format!("ROUND {} POP {}", db.round, db.survival);
+    let canvas = render_board(
+        &config,
+        &live.0,
+        &theme,
+        width.0,
+        shift.then_some(caption.as_str()),
+    );
+    let path = format!("board-{}.png", db.round);
+    match canvas.save(&path) {
+        Ok(()) => info!("exported {path}"),
+        Err(e) => error!("can't write {path}: {e}"),
+    }
+}
@@ -0,0 +1,236 @@
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use rhai::{Array, Dynamic, Engine, Scope, AST};
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use crate::{Config, Coord, EdgeMode};
+
+// the neighbors in the order next_state gets them: the row above, left to
+// right, the two beside, then the row below
+pub const NEIGHBORS: [Coord; 8] = [
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+    (-1, 0),
+    (1, 0),
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+];
+
+// a script stuck in a loop gives up rather than hanging the board
+const MAX_OPERATIONS: u64 = 50_000_000;
+
+// a Rhai script as a rule, `fn next_state(cell, neighbors)` being whether the
+// cell is alive next from its own state, 0 or 1, and its NEIGHBORS'; and as
+// a seeder, `fn seed(width, height)` the live cells of a fresh board as [x, y]
+// pairs from its bottom-left corner, with `random()` for a float in 0..1.
+// Either is optional. next_state is run for each of the 512 neighborhoods
+// once, as it's loaded, so a generation is table lookups
+#[derive(Clone)]
+pub struct Script {
+    pub path: PathBuf,
+    source: Arc<str>,
+    // whether the middle cell lives on, by neighborhood as `neighborhood` numbers them
+    table: Option<Arc<[bool]>>,
+    ast: Arc<AST>,
+    seeds: bool,
+}
+
+impl std::fmt::Debug for Script {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "Script({})", self.path.display())
+    }
+}
+
+impl PartialEq for Script {
+    fn eq(&self, other: &Script) -> bool {
+        self.path == other.path && self.source == other.source
+    }
+}
+
+fn engine() -> Engine {
+    let mut engine = Engine::new();
+    engine.set_max_operations(MAX_OPERATIONS);
+    // debug builds nest less deep by default than release ones
+    engine.set_max_expr_depths(64, 64);
+    engine
+}
+
+impl Script {
+    pub fn load(path: &Path) -> Result<Script, String> {
+        let source =
+            std::fs::read_to_string(path).map_err(|e| format!("{}: {e}", path.display()))?;
+        Script::from_source(path, &source).map_err(|e| format!("{}: {e}", path.display()))
+    }
+
+    pub fn from_source(path: &Path, source: &str) -> Result<Script, String> {
+        let engine = engine();
+        let ast = engine.compile(source).map_err(|e| e.to_string())?;
+        let defines = |name: &str, params: usize| {
+            ast.iter_functions()
+                .any(|f| f.name == name && f.params.len() == params)
+        };
+        let (steps, seeds) = (defines("next_state", 2), defines("seed", 2));
+        if !steps && !seeds {
+            return Err(
+                "defines neither next_state(cell, neighbors) nor seed(width, height)".into(),
+            );
+        }
+        let table = if steps {
+            let mut table = Vec::with_capacity(512);
+            for index in 0..512usize {
+                let cell = (index >> 8) as i64;
+                let neighbors: Array = (0..8)
+                    .map(|i| Dynamic::from((index >> i) as i64 & 1))
+                    .collect();
+                let next: Dynamic = engine
+                    .call_fn(&mut Scope::new(), &ast, "next_state", (cell, neighbors))
+                    .map_err(|e| format!("next_state: {e}"))?;
+                let alive = match (next.as_bool(), next.as_int()) {
+                    (Ok(alive), _) => alive,
+                    (_, Ok(state)) => state != 0,
+                    _ => {
+                        return Err(format!(
+                            "next_state returned {next}, not a bool or a state number"
+                        ))
+                    }
+                };
+                table.push(alive);
+            }
+            Some(table.into())
+        } else {
+            None
+        };
+        Ok(Script {
+            path: path.to_path_buf(),
+            source: source.into(),
+            table,
+            ast: Arc::new(ast),
+            seeds,
+        })
+    }
+
+    pub fn steps(&self) -> bool {
+        self.table.is_some()
+    }
+
+    // whether `me` is alive next, if the script says
+    pub fn next_alive(
+        &self,
+        me: Coord,
+        alives: &HashSet<Coord>,
+        config: &Config,
+        edge: EdgeMode,
+    ) -> Option<bool> {
+        let table = self.table.as_ref()?;
+        Some(table[neighborhood(me, alives, config, edge)])
+    }
+
+    // a board seeded by the script, random() drawing from `rng`; None without
+    // a seed function
+    pub fn seed(
+        &self,
+        config: &Config,
+        rng: &mut StdRng,
+    ) -> Option<Result<HashSet<Coord>, String>> {
+        if !self.seeds {
+            return None;
+        }
+        let mut engine = engine();
+        let rng = Arc::new(Mutex::new(StdRng::from_rng(rng).unwrap()));
+        engine.register_fn("random", move || rng.lock().unwrap().gen::<f64>());
+        let args = (config.width as i64, config.height as i64);
+        let cells: Result<Array, _> = engine.call_fn(&mut Scope::new(), &self.ast, "seed", args);
+        let cells = match cells {
+            Ok(cells) => cells,
+            Err(e) => return Some(Err(format!("seed: {e}"))),
+        };
+        let (left, bottom) = (config.x_range().start, config.y_range().start);
+        let mut board = HashSet::new();
+        for cell in cells {
+            let pair = cell.try_cast::<Array>().and_then(|pair| match &pair[..] {
+                [x, y] => Some((x.as_int().ok()?, y.as_int().ok()?)),
+                _ => None,
+            });
+            let Some((x, y)) = pair else {
+                return Some(Err(
+                    "seed has to return [x, y] pairs of whole numbers".into()
+                ));
+            };
+            let c = (left + x as i32, bottom + y as i32);
+            if config.contains(c) {
+                board.insert(c);
+            }
+        }
+        Some(Ok(board))
+    }
+}
+
+// the cell's own state in bit 8, its NEIGHBORS' in bits 0 to 7; off the board
+// past a dead edge is dead
+fn neighborhood((x, y): Coord, alives: &HashSet<Coord>, config: &Config, edge: EdgeMode) -> usize {
+    let alive = |c: Coord| edge.resolve(c, config).is_some_and(|c| alives.contains(&c));
+    let mut index = (alive((x, y)) as usize) << 8;
+    for (i, (dx, dy)) in NEIGHBORS.iter().enumerate() {
+        index |= (alive((x + dx, y + dy)) as usize) << i;
+    }
+    index
+}
+
+#[test]
+fn test_script() {
+    let path = Path::new("life.rhai");
+    let life = Script::from_source(
+        path,
+        "fn next_state(cell, neighbors) {
+            let n = 0;
+            for c in neighbors { n += c; }
+            n == 3 || (cell == 1 && n == 2)
+        }",
+    )
+    .unwrap();
+    // a script Conway runs as the built-in one does
+    let config = Config::default();
+    let soup = crate::seed(&mut StdRng::seed_from_u64(5), &config);
+    let edge = EdgeMode::default();
+    let expected = crate::step(&soup, &crate::Rule::default(), &config, edge);
+    let next: HashSet<_> = config
+        .coords()
+        .filter(|c| life.next_alive(*c, &soup, &config, edge).unwrap())
+        .collect();
+    assert_eq!(next, expected);
+    assert!(life.seed(&config, &mut StdRng::seed_from_u64(1)).is_none());
+
+    // non-totalistic: born with just the cell above alive, so a cell falls
+    let falling = Script::from_source(path, "fn next_state(cell, n) { n[1] == 1 }").unwrap();
+    let dot = HashSet::from([(0, 5)]);
+    assert_eq!(falling.next_alive((0, 4), &dot, &config, edge), Some(true));
+    assert_eq!(falling.next_alive((0, 5), &dot, &config, edge), Some(false));
+
+    // a seeder, the same from the same rng
+    let row = Script::from_source(
+        path,
+        "fn seed(w, h) { let cells = []; for x in 0..w { if random() < 0.5 { cells.push([x, 0]); } } cells }",
+    )
+    .unwrap();
+    assert!(!row.steps());
+    let seeded = |n| {
+        row.seed(&config, &mut StdRng::seed_from_u64(n))
+            .unwrap()
+            .unwrap()
+    };
+    assert_eq!(seeded(3), seeded(3));
+    assert!(seeded(3).iter().all(|c| c.1 == config.y_range().start));
+
+    assert!(Script::from_source(path, "fn other() { 1 }").is_err());
+    assert!(Script::from_source(path, "fn next_state(cell, n) { \"yes\" }").is_err());
+    assert!(Script::from_source(path, "fn next_state(cell, n) {").is_err());
+    let broken = Script::from_source(path, "fn seed(w, h) { [1, 2] }").unwrap();
+    assert!(broken
+        .seed(&config, &mut StdRng::seed_from_u64(1))
+        .unwrap()
+        .is_err());
+}
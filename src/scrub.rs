@@ -0,0 +1,291 @@
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use bevy_game_of_life::{
+    set_bytes, step, Cell, Config, Coord, EdgeMode, LiveCells, Rule, SimState,
+};
+
+use std::collections::{HashSet, VecDeque};
+
+use crate::{replace_board, Dashboard};
+
+// generations between two keyframes
+const KEYFRAME_EVERY: usize = 10;
+// keyframes kept by default, older ones fall off the start of the timeline
+const MAX_KEYFRAMES: usize = 100;
+// pixels of the scrubber bar along the bottom of the window
+const BAR_HEIGHT: f32 = 16.0;
+
+// snapshots of the main board to scrub back through; anything in between is
+// replayed with the current rule and edges, so drawing or switching rules
+// midway makes the replay diverge from what actually happened
+#[derive(Resource, Debug)]
+pub struct Timeline {
+    keyframes: VecDeque<(usize, HashSet<Coord>)>,
+    // furthest generation the sim itself has reached
+    latest: usize,
+    pub scrubbing: bool,
+    capacity: usize,
+}
+
+impl Default for Timeline {
+    fn default() -> Self {
+        Timeline {
+            keyframes: VecDeque::new(),
+            latest: 0,
+            scrubbing: false,
+            capacity: MAX_KEYFRAMES,
+        }
+    }
+}
+
+impl Timeline {
+    // about the last `generations` to go back through, a keyframe at least
+    pub fn with_history(generations: usize) -> Timeline {
+        Timeline {
+            capacity: generations.div_ceil(KEYFRAME_EVERY).max(1),
+            ..Default::default()
+        }
+    }
+
+    // generations the timeline reaches back with a full set of keyframes
+    pub fn history() -> usize {
+        MAX_KEYFRAMES * KEYFRAME_EVERY
+    }
+
+    fn record(&mut self, round: usize, alives: &HashSet<Coord>) {
+        // running again from a scrubbed-to point forks a new history
+        while self.keyframes.back().is_some_and(|(g, _)| *g > round) {
+            self.keyframes.pop_back();
+        }
+        self.latest = round;
+        let due = self
+            .keyframes
+            .back()
+            .is_none_or(|(g, _)| round >= g + KEYFRAME_EVERY);
+        if due {
+            self.keyframes.push_back((round, alives.clone()));
+            if self.keyframes.len() > self.capacity {
+                self.keyframes.pop_front();
+            }
+        }
+    }
+
+    pub fn bytes(&self) -> usize {
+        self.keyframes.iter().map(|(_, k)| set_bytes(k)).sum()
+    }
+
+    fn first(&self) -> Option<usize> {
+        self.keyframes.front().map(|(g, _)| *g)
+    }
+
+    pub fn newest(&self) -> Option<usize> {
+        self.keyframes.back().map(|(g, _)| *g)
+    }
+
+    // the last `n` keyframes, oldest first
+    pub fn recent(&self, n: usize) -> impl Iterator<Item = &HashSet<Coord>> {
+        let skip = self.keyframes.len().saturating_sub(n);
+        self.keyframes.iter().skip(skip).map(|(_, k)| k)
+    }
+
+    // the board at `generation`, clamped to what was recorded
+    fn board_at(
+        &self,
+        generation: usize,
+        rule: &Rule,
+        config: &Config,
+        edge: EdgeMode,
+    ) -> Option<(usize, HashSet<Coord>)> {
+        let generation = generation.clamp(self.first()?, self.latest);
+        let (from, keyframe) = self
+            .keyframes
+            .iter()
+            .rev()
+            .find(|(g, _)| *g <= generation)?;
+        let mut board = keyframe.clone();
+        for _ in *from..generation {
+            board = step(&board, rule, config, edge);
+        }
+        Some((generation, board))
+    }
+}
+
+#[test]
+fn test_timeline() {
+    let config = Config::default();
+    let rule = Rule::default();
+    let edge = EdgeMode::default();
+    let mut timeline = Timeline::default();
+    let glider = HashSet::from([(0, 1), (1, 0), (-1, -1), (0, -1), (1, -1)]);
+    let mut boards = vec![glider];
+    for round in 0..25 {
+        if round > 0 {
+            boards.push(step(&boards[round - 1], &rule, &config, edge));
+        }
+        timeline.record(round, &boards[round]);
+    }
+    assert_eq!(timeline.keyframes.len(), 3);
+    for generation in [0, 7, 10, 24] {
+        let (g, board) = timeline.board_at(generation, &rule, &config, edge).unwrap();
+        assert_eq!((g, &board), (generation, &boards[generation]));
+    }
+    // past the end clamps to the latest generation
+    let (g, _) = timeline.board_at(99, &rule, &config, edge).unwrap();
+    assert_eq!(g, 24);
+
+    // resuming from generation 6 drops the keyframes after it
+    timeline.record(6, &boards[6]);
+    assert_eq!(timeline.keyframes.len(), 1);
+    assert_eq!(timeline.latest, 6);
+
+    // a short history keeps only the latest keyframes
+    let mut short = Timeline::with_history(15);
+    for (round, board) in boards.iter().enumerate() {
+        short.record(round, board);
+    }
+    assert_eq!(short.first(), Some(10));
+    assert_eq!(short.board_at(5, &rule, &config, edge).unwrap().0, 10);
+}
+
+#[derive(Component)]
+pub struct ScrubBar;
+
+#[derive(Component)]
+pub struct ScrubHandle;
+
+pub fn spawn_scrubber(mut commands: Commands) {
+    commands
+        .spawn((
+            ScrubBar,
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    bottom: Val::Px(0.0),
+                    width: Val::Percent(100.0),
+                    height: Val::Px(BAR_HEIGHT),
+                    ..Default::default()
+                },
+                background_color: Color::rgba(1.0, 1.0, 1.0, 0.1).into(),
+                visibility: Visibility::Hidden,
+                ..Default::default()
+            },
+        ))
+        .with_children(|bar| {
+            bar.spawn((
+                ScrubHandle,
+                NodeBundle {
+                    style: Style {
+                        position_type: PositionType::Absolute,
+                        width: Val::Px(6.0),
+                        height: Val::Percent(100.0),
+                        ..Default::default()
+                    },
+                    background_color: Color::YELLOW.into(),
+                    ..Default::default()
+                },
+            ));
+        });
+}
+
+// at startup and after every tick, never while scrubbing
+pub fn record_keyframe(db: Res<Dashboard>, live: Res<LiveCells>, mut timeline: ResMut<Timeline>) {
+    timeline.record(db.round, &live.0);
+}
+
+// press on the bar and drag to scrub; the sim stays paused afterwards
+#[allow(clippy::too_many_arguments)]
+pub fn scrub(
+    mut commands: Commands,
+    buttons: Res<Input<MouseButton>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    config: Res<Config>,
+    rule: Res<Rule>,
+    edge: Res<EdgeMode>,
+    mut timeline: ResMut<Timeline>,
+    mut db: ResMut<Dashboard>,
+    mut sim_state: ResMut<NextState<SimState>>,
+    mut live: ResMut<LiveCells>,
+    mut cells: Query<(Entity, &mut Cell)>,
+) {
+    let (Ok(window), Some(first)) = (windows.get_single(), timeline.first()) else {
+        return;
+    };
+    let Some(cursor) = window.cursor_position() else {
+        return;
+    };
+    if buttons.just_pressed(MouseButton::Left) && cursor.y >= window.height() - BAR_HEIGHT {
+        timeline.scrubbing = true;
+    }
+    if !buttons.pressed(MouseButton::Left) {
+        timeline.scrubbing = false;
+    }
+    if !timeline.scrubbing {
+        return;
+    }
+    sim_state.set(SimState::Paused);
+    let t = (cursor.x / window.width()).clamp(0.0, 1.0);
+    let wanted = first + ((timeline.latest - first) as f32 * t).round() as usize;
+    if wanted == db.round {
+        return;
+    }
+    let Some((generation, board)) = timeline.board_at(wanted, &rule, &config, *edge) else {
+        return;
+    };
+    replace_board(&board, &config, &mut commands, &mut live, &mut cells);
+    db.round = generation;
+    db.survival = live.0.len();
+}
+
+// Backspace goes back a generation while paused, Enter forward again
+#[allow(clippy::too_many_arguments)]
+pub fn rewind(
+    mut commands: Commands,
+    keys: Res<Input<KeyCode>>,
+    sim_state: Res<State<SimState>>,
+    config: Res<Config>,
+    rule: Res<Rule>,
+    edge: Res<EdgeMode>,
+    timeline: Res<Timeline>,
+    mut db: ResMut<Dashboard>,
+    mut live: ResMut<LiveCells>,
+    mut cells: Query<(Entity, &mut Cell)>,
+) {
+    if *sim_state.get() != SimState::Paused || !keys.just_pressed(KeyCode::Back) {
+        return;
+    }
+    let Some(first) = timeline.first().filter(|f| *f < db.round) else {
+        info!("nothing recorded before round {}", db.round);
+        return;
+    };
+    let wanted = (db.round - 1).max(first);
+    let Some((generation, board)) = timeline.board_at(wanted, &rule, &config, *edge) else {
+        return;
+    };
+    replace_board(&board, &config, &mut commands, &mut live, &mut cells);
+    db.round = generation;
+    db.survival = live.0.len();
+}
+
+pub fn update_scrubber(
+    db: Res<Dashboard>,
+    timeline: Res<Timeline>,
+    mut bar: Query<&mut Visibility, With<ScrubBar>>,
+    mut handle: Query<&mut Style, With<ScrubHandle>>,
+) {
+    let first = timeline.first().unwrap_or(0);
+    let span = timeline.latest.saturating_sub(first);
+    for mut v in bar.iter_mut() {
+        *v = if span > 0 {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
+    if span == 0 {
+        return;
+    }
+    for mut style in handle.iter_mut() {
+        let t = db.round.saturating_sub(first) as f32 / span as f32;
+        style.left = Val::Percent(t * 100.0);
+    }
+}
@@ -0,0 +1,229 @@
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use bevy_game_of_life::pattern::{sniff_pattern, to_rle, Pattern};
+use bevy_game_of_life::{Cell, Config, Coord, LiveCells, Rule};
+
+use std::collections::HashSet;
+
+use crate::draw::{cell_center, clamp_to_board, cursor_cell, paint, rect, DrawTool, Stamp};
+use crate::scrub::Timeline;
+use crate::{Dashboard, Theme};
+
+// where Ctrl+Shift+C writes the clipboard
+pub const CLIPBOARD_FILE: &str = "clipboard.rle";
+
+// the select tool's rectangle: the corner a drag started from while it's on,
+// both corners once it's let go
+#[derive(Resource, Default, Debug)]
+pub struct Selection {
+    pub drag: Option<Coord>,
+    pub rect: Option<(Coord, Coord)>,
+}
+
+impl Selection {
+    pub fn size(&self) -> Option<(i32, i32)> {
+        let (a, b) = self.rect?;
+        Some(((a.0 - b.0).abs() + 1, (a.1 - b.1).abs() + 1))
+    }
+}
+
+// the last copy or cut, kept until the next one
+#[derive(Resource, Default, Debug)]
+pub struct Clipboard(pub Option<Pattern>);
+
+// the live cells between two corners as a pattern the size of the whole
+// rectangle, so blank rows and columns at its sides are kept
+fn copy_rect(alives: &HashSet<Coord>, a: Coord, b: Coord) -> Pattern {
+    let (x0, x1) = (a.0.min(b.0), a.0.max(b.0));
+    let (y0, y1) = (a.1.min(b.1), a.1.max(b.1));
+    let mut cells: Vec<_> = alives
+        .iter()
+        .filter(|(x, y)| (x0..=x1).contains(x) && (y0..=y1).contains(y))
+        .map(|(x, y)| (x - x0, y1 - y))
+        .collect();
+    cells.sort_by_key(|(x, y)| (*y, *x));
+    Pattern {
+        width: x1 - x0 + 1,
+        height: y1 - y0 + 1,
+        cells,
+    }
+}
+
+#[test]
+fn test_copy_rect() {
+    let alives = HashSet::from([(1, 1), (2, 0), (5, 5)]);
+    let copied = copy_rect(&alives, (3, -1), (0, 1));
+    assert_eq!((copied.width, copied.height), (4, 3));
+    // rows from the top, so (1, 1) is on the first one
+    assert_eq!(copied.cells, vec![(1, 0), (2, 1)]);
+    assert!(copy_rect(&alives, (8, 8), (9, 9)).cells.is_empty());
+}
+
+// the text on the OS clipboard
+#[cfg(not(target_arch = "wasm32"))]
+fn system_clipboard() -> Result<String, String> {
+    arboard::Clipboard::new()
+        .and_then(|mut c| c.get_text())
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(target_arch = "wasm32")]
+fn system_clipboard() -> Result<String, String> {
+    Err("the browser's clipboard can't be read".to_string())
+}
+
+// a pattern pasted as text, RLE as forum posts have it, or plaintext or Life
+// 1.06
+fn parse_pasted(text: &str) -> Result<Pattern, String> {
+    let pattern = sniff_pattern(text)?;
+    if pattern.cells.is_empty() {
+        return Err("no live cells in it".to_string());
+    }
+    Ok(pattern)
+}
+
+#[test]
+fn test_parse_pasted() {
+    let glider = "#N Glider\nx = 3, y = 3, rule = B3/S23\nbo$2bo$3o!\n";
+    assert_eq!(parse_pasted(glider).unwrap().cells.len(), 5);
+    assert!(parse_pasted("").is_err());
+    assert!(parse_pasted("a sentence copied by mistake").is_err());
+    assert!(parse_pasted("x = 0, y = 0\n!").is_err());
+}
+
+// with the select tool, a left drag picks the rectangle between press and
+// release, clamped to the board; Shift-drags still erase
+#[allow(clippy::too_many_arguments)]
+pub fn select_cells(
+    keys: Res<Input<KeyCode>>,
+    buttons: Res<Input<MouseButton>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    config: Res<Config>,
+    theme: Res<Theme>,
+    tool: Res<DrawTool>,
+    timeline: Res<Timeline>,
+    mut selection: ResMut<Selection>,
+) {
+    if *tool != DrawTool::Select || timeline.scrubbing {
+        selection.drag = None;
+        return;
+    }
+    let cursor = cursor_cell(&windows, &cameras, theme.pitch(), config.topology)
+        .map(|c| clamp_to_board(c, &config));
+    let shift = keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    if buttons.just_pressed(MouseButton::Left) && !shift {
+        selection.drag = cursor;
+    }
+    if !buttons.just_released(MouseButton::Left) {
+        return;
+    }
+    if let (Some(start), Some(end)) = (selection.drag.take(), cursor) {
+        selection.rect = Some((start, end));
+    }
+}
+
+// Ctrl+C copies the selection, Ctrl+X cuts it; Ctrl+V makes the clipboard the
+// stamp, so R / F turn and mirror it and a click drops it; Ctrl+Shift+C also
+// writes it to CLIPBOARD_FILE, and Ctrl+Shift+V stamps the pattern on the OS
+// clipboard instead
+#[allow(clippy::too_many_arguments)]
+pub fn use_clipboard(
+    mut commands: Commands,
+    keys: Res<Input<KeyCode>>,
+    rule: Res<Rule>,
+    db: Res<Dashboard>,
+    selection: Res<Selection>,
+    mut clipboard: ResMut<Clipboard>,
+    mut tool: ResMut<DrawTool>,
+    mut stamp: ResMut<Stamp>,
+    mut live: ResMut<LiveCells>,
+    mut cells: Query<&mut Cell>,
+) {
+    if !keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]) {
+        return;
+    }
+    let shift = keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    if keys.just_pressed(KeyCode::V) && shift {
+        match system_clipboard().and_then(|text| parse_pasted(&text)) {
+            Ok(pattern) => {
+                *stamp = Stamp(pattern, "pasted".to_string());
+                *tool = DrawTool::Stamp;
+            }
+            Err(e) => warn!("can't paste a pattern from the clipboard: {e}"),
+        }
+        return;
+    }
+    if keys.just_pressed(KeyCode::V) {
+        let Some(pattern) = &clipboard.0 else {
+            info!("nothing copied yet");
+            return;
+        };
+        *stamp = Stamp(pattern.clone(), "clipboard".to_string());
+        *tool = DrawTool::Stamp;
+        return;
+    }
+    let cut = keys.just_pressed(KeyCode::X);
+    if !cut && !keys.just_pressed(KeyCode::C) {
+        return;
+    }
+    let Some((a, b)) = selection.rect else {
+        info!("nothing selected");
+        return;
+    };
+    let copied = copy_rect(&live.0, a, b);
+    if shift {
+        match std::fs::write(CLIPBOARD_FILE, to_rle(&copied, &rule)) {
+            Ok(()) => info!("exported {CLIPBOARD_FILE}"),
+            Err(e) => error!("can't write {CLIPBOARD_FILE}: {e}"),
+        }
+    }
+    clipboard.0 = Some(copied);
+    if cut {
+        let targets = rect(a, b, true).into_iter().collect();
+        paint(
+            &targets,
+            false,
+            db.round,
+            &mut commands,
+            &mut live,
+            &mut cells,
+        );
+    }
+}
+
+// the rectangle being dragged out, or the one picked
+pub fn preview_selection(
+    mut gizmos: Gizmos,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    config: Res<Config>,
+    theme: Res<Theme>,
+    selection: Res<Selection>,
+) {
+    let dragged = selection.drag.and_then(|start| {
+        let cursor = cursor_cell(&windows, &cameras, theme.pitch(), config.topology)?;
+        Some((start, clamp_to_board(cursor, &config)))
+    });
+    let Some((start, end)) = dragged.or(selection.rect) else {
+        return;
+    };
+    let (low, high) = (
+        cell_center(start, theme.pitch(), config.topology).min(cell_center(
+            end,
+            theme.pitch(),
+            config.topology,
+        )),
+        cell_center(start, theme.pitch(), config.topology).max(cell_center(
+            end,
+            theme.pitch(),
+            config.topology,
+        )),
+    );
+    gizmos.rect_2d(
+        (low + high) / 2.0,
+        0.0,
+        high - low + Vec2::splat(theme.size),
+        Color::rgba(0.4, 1.0, 0.4, 0.8),
+    );
+}
@@ -0,0 +1,288 @@
+use bevy::prelude::*;
+use bevy_game_of_life::{Config, Rule, Script, SimRng};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use crate::themes::ThemePreset;
+use crate::Theme;
+
+// read at startup when there's no --config, the first one there
+pub const DEFAULT_FILES: [&str; 2] = ["gol.toml", "gol.ron"];
+// seconds between two looks at whether the file changed
+const WATCH_EVERY: f32 = 1.0;
+
+// everything a run is set up from, as `--print-config` writes it and `--config`
+// reads it back; left out parts take their defaults
+#[derive(Serialize, Deserialize, Debug, PartialEq, Default)]
+#[serde(default)]
+pub struct Session {
+    pub config: Config,
+    pub rule: Rule,
+    pub theme: Theme,
+    // a fresh random one when missing
+    pub seed: Option<u64>,
+    // seconds a tick takes, else --tick-ms
+    pub tick: Option<f32>,
+}
+
+impl Session {
+    pub fn to_ron(&self) -> String {
+        let pretty = ron::ser::PrettyConfig::default();
+        ron::ser::to_string_pretty(self, pretty).expect("a session always serializes")
+    }
+
+    pub fn from_ron(s: &str) -> Result<Session, String> {
+        let mut session: Session = ron::from_str(s).map_err(|e| e.to_string())?;
+        // a named preset's colors over any the file spells out
+        if session.theme.preset != ThemePreset::Classic {
+            session.theme = session.theme.with_preset(session.theme.preset);
+        }
+        Ok(session)
+    }
+
+    // the short form: the board size, density, rule, seed and tick at the top
+    // and a [theme] table of a preset and #rrggbb colors, the colors written
+    // out over the preset's
+    pub fn from_toml(s: &str) -> Result<Session, String> {
+        let doc: toml_edit::Document =
+            s.parse().map_err(|e: toml_edit::TomlError| e.to_string())?;
+        let mut session = Session::default();
+        for (key, item) in doc.iter() {
+            match key {
+                "width" => session.config.width = toml_int(key, item)?,
+                "height" => session.config.height = toml_int(key, item)?,
+                "density" => session.config.density = Some(toml_number(key, item)? as f32),
+                "rule" => session.rule = Rule::named(toml_str(key, item)?)?,
+                "seed" => session.seed = Some(toml_int(key, item)?),
+                "tick" => session.tick = Some(toml_number(key, item)? as f32),
+                "theme" => session.theme = toml_theme(item)?,
+                _ => return Err(format!("unknown key {key}")),
+            }
+        }
+        Ok(session)
+    }
+
+    // TOML for a .toml file, RON for the rest
+    pub fn load(path: &Path) -> Result<Session, String> {
+        let s = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("toml") => Session::from_toml(&s),
+            _ => Session::from_ron(&s),
+        }
+    }
+}
+
+fn toml_str<'a>(key: &str, item: &'a toml_edit::Item) -> Result<&'a str, String> {
+    item.as_str()
+        .ok_or_else(|| format!("{key} should be a string"))
+}
+
+fn toml_int<T: TryFrom<i64>>(key: &str, item: &toml_edit::Item) -> Result<T, String> {
+    let out_of_range = || format!("{key} should be a whole number in range");
+    item.as_integer()
+        .and_then(|i| T::try_from(i).ok())
+        .ok_or_else(out_of_range)
+}
+
+fn toml_number(key: &str, item: &toml_edit::Item) -> Result<f64, String> {
+    item.as_float()
+        .or_else(|| item.as_integer().map(|i| i as f64))
+        .ok_or_else(|| format!("{key} should be a number"))
+}
+
+fn toml_theme(item: &toml_edit::Item) -> Result<Theme, String> {
+    let table = item.as_table_like().ok_or("theme should be a table")?;
+    let mut theme = Theme::default();
+    if let Some(preset) = table.get("preset") {
+        let name = toml_str("theme.preset", preset)?;
+        let preset = ThemePreset::from_str(name, true).map_err(|_| format!("no {name} theme"))?;
+        theme = theme.with_preset(preset);
+    }
+    for (key, item) in table.iter().filter(|(key, _)| *key != "preset") {
+        let hex = toml_str(key, item)?;
+        let color = Color::hex(hex.trim_start_matches('#'))
+            .map_err(|_| format!("{key}: not a #rrggbb color"))?;
+        match key {
+            "background" => theme.background = color,
+            "alive" => theme.alive = color,
+            "dead" => theme.dead = color,
+            "untouched" => theme.untouched = color,
+            "wall" => theme.wall = color,
+            "obstacle" => theme.obstacle = color,
+            "text" => theme.text = color,
+            _ => return Err(format!("unknown key theme.{key}")),
+        }
+    }
+    Ok(theme)
+}
+
+#[test]
+fn test_session_round_trip() {
+    let session = Session {
+        config: Config {
+            width: 64,
+            density: Some(0.3),
+            world_rules: vec![Rule::life_without_death()],
+            worlds: 2,
+            ..Default::default()
+        },
+        rule: Rule::parse("B36/S23").unwrap(),
+        theme: Theme {
+            corner_radius: 0.25,
+            ..Default::default()
+        },
+        seed: Some(42),
+        tick: Some(0.25),
+    };
+    let printed = session.to_ron();
+    assert!(printed.contains("\"B36/S23\""));
+    assert_eq!(Session::from_ron(&printed), Ok(session));
+    // hand-written ones only need what differs from the defaults
+    let short = Session::from_ron("(rule: \"B2/S\", config: (height: 8))").unwrap();
+    assert_eq!((short.config.height, short.config.width), (8, 20));
+    assert_eq!(short.rule.to_string(), "B2/S");
+    assert!(Session::from_ron("(rule: \"B9/S\")").is_err());
+    let solarized = Session::from_ron("(theme: (preset: Solarized, size: 5.0))").unwrap();
+    // a preset in the file brings its colors, the rest of the theme as written
+    let expected = Theme::default().with_preset(ThemePreset::Solarized);
+    assert_eq!(
+        (solarized.theme.alive, solarized.theme.size),
+        (expected.alive, 5.0)
+    );
+}
+
+#[test]
+fn test_session_toml() {
+    let toml = "width = 64\nrule = \"highlife\"\nseed = 7\ntick = 0.5\n\n[theme]\npreset = \"paper\"\nalive = \"#ff0000\"\n";
+    let session = Session::from_toml(toml).unwrap();
+    assert_eq!((session.config.width, session.config.height), (64, 20));
+    assert_eq!(session.rule, Rule::named("highlife").unwrap());
+    assert_eq!((session.seed, session.tick), (Some(7), Some(0.5)));
+    // the preset's colors but the one written out
+    let paper = Theme::default().with_preset(ThemePreset::Paper);
+    assert_eq!(session.theme.alive, Color::rgb(1.0, 0.0, 0.0));
+    assert_eq!(session.theme.background, paper.background);
+    assert_eq!(Session::from_toml(""), Ok(Session::default()));
+    for wrong in [
+        "widht = 3",
+        "seed = -1",
+        "rule = 3",
+        "[theme]\nalive = \"red\"",
+        "width =",
+    ] {
+        assert!(Session::from_toml(wrong).is_err(), "{wrong}");
+    }
+}
+
+// once the plugin has picked the seed, so a random one gets written down too
+pub fn print_config(
+    config: Res<Config>,
+    rule: Res<Rule>,
+    theme: Res<Theme>,
+    rng: Res<SimRng>,
+    fixed_time: Res<FixedTime>,
+) {
+    let session = Session {
+        config: config.clone(),
+        rule: rule.clone(),
+        theme: theme.clone(),
+        seed: Some(rng.1),
+        tick: Some(fixed_time.period.as_secs_f32()),
+    };
+    println!("{}", session.to_ron());
+}
+
+// the file the run was set up from, read again whenever it changes on disk
+#[derive(Resource, Debug)]
+pub struct Watched {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    timer: Timer,
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+impl Watched {
+    pub fn new(path: PathBuf) -> Watched {
+        Watched {
+            modified: modified(&path),
+            path,
+            timer: Timer::from_seconds(WATCH_EVERY, TimerMode::Repeating),
+        }
+    }
+}
+
+// a --rule script picks up its edits as they're saved, like the session file;
+// one that no longer loads leaves the last one running
+pub fn reload_script(time: Res<Time>, mut rule: ResMut<Rule>, mut watched: Local<Option<Watched>>) {
+    let Some(path) = rule.script.as_ref().map(|s| s.path.clone()) else {
+        return;
+    };
+    let watched = match &mut *watched {
+        Some(w) if w.path == path => w,
+        w => w.insert(Watched::new(path.clone())),
+    };
+    if !watched.timer.tick(time.delta()).just_finished() {
+        return;
+    }
+    let modified = modified(&path);
+    if modified == watched.modified {
+        return;
+    }
+    watched.modified = modified;
+    match Script::load(&path) {
+        Ok(script) => {
+            *rule = Rule::scripted(script);
+            info!("reloaded {}", path.display());
+        }
+        Err(e) => warn!("{e}, keeping the script as it was"),
+    }
+}
+
+// colors, rule and tick apply right away; the board, its size and the seed
+// only on the next start
+pub fn reload_session(
+    time: Res<Time>,
+    config: Res<Config>,
+    mut watched: ResMut<Watched>,
+    mut rule: ResMut<Rule>,
+    mut theme: ResMut<Theme>,
+    mut fixed_time: ResMut<FixedTime>,
+) {
+    if !watched.timer.tick(time.delta()).just_finished() {
+        return;
+    }
+    let modified = modified(&watched.path);
+    if modified == watched.modified {
+        return;
+    }
+    watched.modified = modified;
+    let path = watched.path.display();
+    let session = match Session::load(&watched.path) {
+        Ok(session) => session,
+        Err(e) => {
+            warn!("{path}: {e}, keeping the current setup");
+            return;
+        }
+    };
+    if session.theme != *theme {
+        *theme = session.theme;
+    }
+    match config.runs(&session.rule) {
+        Ok(()) if session.rule != *rule => *rule = session.rule,
+        Ok(()) => {}
+        Err(e) => warn!("{path}: {e}, keeping {}", *rule),
+    }
+    if let Some(tick) = session.tick.filter(|t| *t > 0.0) {
+        fixed_time.period = Duration::from_secs_f32(tick);
+    }
+    if session.config != *config {
+        info!("{path}: the board changes take a restart");
+    }
+    info!("reloaded {path}");
+}
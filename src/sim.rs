@@ -0,0 +1,1072 @@
+use bevy::prelude::Resource;
+use bevy::tasks::{ComputeTaskPool, TaskPool};
+use rand::Rng;
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::{set_bytes, step_larger, Blob, ColorInit, Config, Coord, EdgeMode, Rule};
+
+// longest oscillation we look back for
+pub const MAX_PERIOD: usize = 30;
+// how far ahead the forecast is allowed to simulate
+pub const FORECAST_CAP: usize = 1000;
+// fewer cells to look at than this and a generation is worked out on one
+// thread, handing the bands out would cost more than it saves
+const PARALLEL_CUTOFF: usize = 20_000;
+
+// the blobs when there are any, a soup over the seed region otherwise
+pub fn seed(rng: &mut impl Rng, config: &Config) -> HashSet<Coord> {
+    if !config.blobs.is_empty() {
+        return seed_blobs(rng, &config.blobs, config);
+    }
+    let mut result = HashSet::new();
+
+    let count = config.seed_count();
+    let (xs, ys) = config.seed_ranges();
+    while result.len() < count {
+        let x = rng.gen_range(xs.clone());
+        let y = rng.gen_range(ys.clone());
+        result.insert((x, y));
+    }
+
+    result
+}
+
+// every blob drawn from the same rng in turn, cut to the board; where they
+// overlap a cell gets a chance from each
+pub fn seed_blobs(rng: &mut impl Rng, blobs: &[Blob], config: &Config) -> HashSet<Coord> {
+    let mut result = HashSet::new();
+    for blob in blobs {
+        let (cx, cy) = blob.center;
+        let r = blob.radius;
+        for x in cx - r..=cx + r {
+            for y in cy - r..=cy + r {
+                let inside = (x - cx).pow(2) + (y - cy).pow(2) <= r * r;
+                if inside && config.contains((x, y)) && rng.gen_bool(blob.density as f64) {
+                    result.insert((x, y));
+                }
+            }
+        }
+    }
+    result
+}
+
+#[test]
+fn test_seed_blobs() {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    let blobs = [
+        Blob {
+            center: (-5, -5),
+            radius: 3,
+            density: 0.6,
+        },
+        Blob {
+            center: (6, 4),
+            radius: 2,
+            density: 1.0,
+        },
+    ];
+    let config = Config {
+        blobs: blobs.to_vec(),
+        ..Default::default()
+    };
+    let within = |(x, y): Coord, b: &Blob| {
+        (x - b.center.0).pow(2) + (y - b.center.1).pow(2) <= b.radius.pow(2)
+    };
+    for s in 0..10 {
+        let alives = seed(&mut StdRng::seed_from_u64(s), &config);
+        assert!(alives.iter().all(|c| blobs.iter().any(|b| within(*c, b))));
+        // a full blob is the whole disk of radius 2
+        let full = alives.iter().filter(|c| within(**c, &blobs[1])).count();
+        assert_eq!(full, 13);
+    }
+    // cut to the board, and nothing from an empty blob
+    let off = [Blob {
+        center: (9, 9),
+        radius: 1,
+        density: 1.0,
+    }];
+    assert_eq!(seed_blobs(&mut rand::thread_rng(), &off, &config).len(), 3);
+    let none = [Blob {
+        density: 0.0,
+        ..blobs[0]
+    }];
+    assert!(seed_blobs(&mut rand::thread_rng(), &none, &config).is_empty());
+}
+
+// a color in 0..colors for every seeded cell, by `config.color_init`; visits the
+// cells in sorted order so the same rng seed always hands out the same colors
+pub fn seed_colors(
+    rng: &mut impl Rng,
+    config: &Config,
+    alives: &HashSet<Coord>,
+    colors: u8,
+) -> HashMap<Coord, u8> {
+    let mut sorted: Vec<_> = alives.iter().copied().collect();
+    sorted.sort();
+    let four = colors >= 4;
+    sorted
+        .into_iter()
+        .map(|(x, y)| {
+            let color = match config.color_init {
+                ColorInit::Random => rng.gen_range(0..colors),
+                ColorInit::Checkerboard if four => (x.rem_euclid(2) + 2 * y.rem_euclid(2)) as u8,
+                ColorInit::Checkerboard => (x + y).rem_euclid(2) as u8,
+                ColorInit::Halves if four => (x >= 0) as u8 + 2 * (y >= 0) as u8,
+                ColorInit::Halves => (x >= 0) as u8,
+            };
+            ((x, y), color % colors)
+        })
+        .collect()
+}
+
+#[test]
+fn test_seed_colors() {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    let alives = seed(&mut StdRng::seed_from_u64(1), &Config::default());
+    for color_init in [
+        ColorInit::Random,
+        ColorInit::Checkerboard,
+        ColorInit::Halves,
+    ] {
+        let config = Config {
+            color_init,
+            ..Default::default()
+        };
+        for colors in [2, 4] {
+            let colored =
+                |seed| seed_colors(&mut StdRng::seed_from_u64(seed), &config, &alives, colors);
+            let first = colored(9);
+            assert_eq!(first, colored(9));
+            assert_eq!(first.len(), alives.len());
+            assert!(first.values().all(|c| *c < colors));
+        }
+    }
+    let halves = Config {
+        color_init: ColorInit::Halves,
+        ..Default::default()
+    };
+    let split = seed_colors(&mut rand::thread_rng(), &halves, &alives, 2);
+    assert!(split.iter().all(|((x, _), c)| (*x >= 0) == (*c == 1)));
+}
+
+// the color a cell born of `parents` takes under a rule in `colors`: the one
+// most of them have, or under QuadLife, three parents all different, the
+// fourth
+pub fn inherited_color(parents: &[u8], colors: u8) -> u8 {
+    let mut counts = vec![0; colors.max(1) as usize];
+    for p in parents {
+        counts[(p % colors.max(1)) as usize] += 1;
+    }
+    let most = counts.iter().copied().max().unwrap_or(0);
+    let pick = if colors >= 4 && most == 1 && parents.len() == 3 {
+        counts.iter().position(|n| *n == 0)
+    } else {
+        counts.iter().position(|n| *n == most)
+    };
+    pick.unwrap_or(0) as u8
+}
+
+// the colors of `live` from the colors of the board before it: survivors keep
+// theirs and newborns inherit from the colored cells around them, the first
+// color for one with none
+pub fn next_lineage(
+    previous: &HashMap<Coord, u8>,
+    live: &HashSet<Coord>,
+    colors: u8,
+    config: &Config,
+    edge: EdgeMode,
+) -> HashMap<Coord, u8> {
+    live.iter()
+        .map(|&(x, y)| {
+            let color = previous.get(&(x, y)).copied().unwrap_or_else(|| {
+                let parents: Vec<u8> = config
+                    .topology
+                    .offsets(y)
+                    .iter()
+                    .filter_map(|(dx, dy)| edge.resolve((x + dx, y + dy), config))
+                    .filter_map(|c| previous.get(&c).copied())
+                    .collect();
+                inherited_color(&parents, colors)
+            });
+            ((x, y), color)
+        })
+        .collect()
+}
+
+#[test]
+fn test_next_lineage() {
+    assert_eq!(inherited_color(&[1, 0, 1], 2), 1);
+    assert_eq!(inherited_color(&[3, 3, 0], 4), 3);
+    // QuadLife's three different parents
+    assert_eq!(inherited_color(&[0, 2, 3], 4), 1);
+    assert_eq!(inherited_color(&[], 2), 0);
+
+    let config = Config::default();
+    let edge = EdgeMode::default();
+    // a blinker's middle stays red, the new tips take the majority, blue
+    let before = HashMap::from([((-1, 0), 1), ((0, 0), 0), ((1, 0), 1)]);
+    let after = step(
+        &before.keys().copied().collect(),
+        &Rule::colored(2),
+        &config,
+        edge,
+    );
+    let colors = next_lineage(&before, &after, 2, &config, edge);
+    assert_eq!(colors.len(), 3);
+    assert_eq!(colors[&(0, 0)], 0);
+    assert_eq!((colors[&(0, 1)], colors[&(0, -1)]), (1, 1));
+}
+
+#[test]
+fn test_seed_uniform() {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    let mut rng = StdRng::seed_from_u64(42);
+    let config = Config::default();
+    let mut quadrants = [0usize; 4];
+    let rounds = 500;
+    for _ in 0..rounds {
+        for (x, y) in seed(&mut rng, &config) {
+            assert!(config.contains((x, y)));
+            quadrants[(x >= 0) as usize * 2 + (y >= 0) as usize] += 1;
+        }
+    }
+    let expected = (rounds * config.seed_count() / 4) as f32;
+    for q in quadrants {
+        assert!(
+            (q as f32 - expected).abs() / expected < 0.05,
+            "{quadrants:?}"
+        );
+    }
+}
+
+// lowest and highest corner of the live cells, both included
+pub fn bounding_box(alives: &HashSet<Coord>) -> Option<(Coord, Coord)> {
+    let min_x = alives.iter().map(|c| c.0).min()?;
+    let max_x = alives.iter().map(|c| c.0).max()?;
+    let min_y = alives.iter().map(|c| c.1).min()?;
+    let max_y = alives.iter().map(|c| c.1).max()?;
+    Some(((min_x, min_y), (max_x, max_y)))
+}
+
+#[test]
+fn test_bounding_box() {
+    assert_eq!(bounding_box(&HashSet::new()), None);
+    assert_eq!(
+        bounding_box(&HashSet::from([(2, -3)])),
+        Some(((2, -3), (2, -3)))
+    );
+    let glider = HashSet::from([(0, 1), (1, 0), (-1, -1), (0, -1), (1, -1)]);
+    assert_eq!(bounding_box(&glider), Some(((-1, -1), (1, 1))));
+}
+
+// live cells to the north-east, north-west, south-west and south-east of the
+// origin in one pass, the axes counting toward the positive side
+pub fn quadrant_counts(alives: &HashSet<Coord>) -> [usize; 4] {
+    let mut counts = [0; 4];
+    for (x, y) in alives {
+        let i = match (*x >= 0, *y >= 0) {
+            (true, true) => 0,
+            (false, true) => 1,
+            (false, false) => 2,
+            (true, false) => 3,
+        };
+        counts[i] += 1;
+    }
+    counts
+}
+
+#[test]
+fn test_quadrant_counts() {
+    assert_eq!(quadrant_counts(&HashSet::new()), [0; 4]);
+    let blinker = HashSet::from([(-1, 0), (0, 0), (1, 0)]);
+    assert_eq!(quadrant_counts(&blinker), [2, 1, 0, 0]);
+    // a symmetric soup stays balanced
+    let corners = HashSet::from([(3, 3), (-4, 3), (-4, -4), (3, -4)]);
+    assert_eq!(quadrant_counts(&corners), [1; 4]);
+}
+
+// halvings of the density range tried before settling for the closest
+const TUNE_STEPS: usize = 16;
+// soups run at every density, and how many generations each
+const TUNE_TRIALS: u64 = 8;
+const TUNE_GENERATIONS: usize = 10;
+
+// average population over the first TUNE_GENERATIONS of the same TUNE_TRIALS
+// soups, so the candidate densities are compared on equal footing
+fn average_population(density: f32, config: &Config, rule: &Rule, edge: EdgeMode) -> f32 {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    let config = Config {
+        density: Some(density),
+        ..config.clone()
+    };
+    let mut total = 0;
+    for trial in 0..TUNE_TRIALS {
+        let mut alives = seed(&mut StdRng::seed_from_u64(trial), &config);
+        for _ in 0..TUNE_GENERATIONS {
+            alives = step(&alives, rule, &config, edge);
+            total += alives.len();
+        }
+    }
+    total as f32 / (TUNE_TRIALS as usize * TUNE_GENERATIONS) as f32
+}
+
+// the density whose soups average closest to `target` live cells early on, and
+// that average. Bisects as if more density always meant more cells, which stops
+// holding once soups get crowded enough to choke, so it keeps the best seen.
+pub fn tune_density(target: f32, config: &Config, rule: &Rule, edge: EdgeMode) -> (f32, f32) {
+    let (mut low, mut high) = (0.0, 1.0);
+    let mut best = (0.0, average_population(0.0, config, rule, edge));
+    for _ in 0..TUNE_STEPS {
+        let mid = (low + high) / 2.0;
+        let population = average_population(mid, config, rule, edge);
+        if (population - target).abs() < (best.1 - target).abs() {
+            best = (mid, population);
+        }
+        if population < target {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+    best
+}
+
+#[test]
+fn test_tune_density() {
+    let config = Config::default();
+    let rule = Rule::default();
+    let edge = EdgeMode::default();
+    let (density, population) = tune_density(40.0, &config, &rule, edge);
+    assert!((population - 40.0).abs() < 2.0, "{density} {population}");
+    assert_eq!(
+        average_population(density, &config, &rule, edge),
+        population
+    );
+    // nothing to find in an empty board
+    assert_eq!(tune_density(0.0, &config, &rule, edge), (0.0, 0.0));
+}
+
+// With the default B3/S23:
+// Any live cell with fewer than two live neighbours dies (referred to as underpopulation).
+// Any live cell with more than three live neighbours dies (referred to as overpopulation).
+// Any live cell with two or three live neighbours lives, unchanged, to the next generation.
+// Any dead cell with exactly three live neighbours comes to life.
+pub fn step(
+    alives: &HashSet<Coord>,
+    rule: &Rule,
+    config: &Config,
+    edge: EdgeMode,
+) -> HashSet<Coord> {
+    let pool = ComputeTaskPool::init(TaskPool::default);
+    step_in_bands(alives, rule, config, edge, pool.thread_num())
+}
+
+// `step` with the cells to look at split into `bands`, each worked out on a
+// thread of the compute pool; they only read the live set, so the bands can't
+// get in each other's way. One band, or a small board, stays on this thread
+pub fn step_in_bands(
+    alives: &HashSet<Coord>,
+    rule: &Rule,
+    config: &Config,
+    edge: EdgeMode,
+    bands: usize,
+) -> HashSet<Coord> {
+    if let Some(larger) = &rule.larger {
+        return step_larger(alives, larger, config, edge);
+    }
+    let script = rule.script.as_ref().filter(|s| s.steps());
+    let decide = |me: &Coord| {
+        if let Some(script) = script {
+            return script.next_alive(*me, alives, config, edge) == Some(true);
+        }
+        let live_count = alive_neighbor_count(me, alives, config, edge);
+        rule.next_alive(alives.contains(me), live_count)
+    };
+    let cells: Vec<Coord> = match active_set(alives, rule, config, edge) {
+        Some(active) => active.into_iter().collect(),
+        None => config.coords().collect(),
+    };
+    if bands < 2 || cells.len() < PARALLEL_CUTOFF {
+        return cells.into_iter().filter(decide).collect();
+    }
+    let decide = &decide;
+    let pool = ComputeTaskPool::init(TaskPool::default);
+    let born = pool.scope(|scope| {
+        for band in cells.chunks(cells.len().div_ceil(bands)) {
+            scope.spawn(async move { band.iter().copied().filter(decide).collect::<Vec<_>>() });
+        }
+    });
+    born.into_iter().flatten().collect()
+}
+
+#[test]
+fn test_step_in_bands() {
+    let config = Config {
+        width: 200,
+        height: 200,
+        density: Some(0.3),
+        ..Default::default()
+    };
+    let soup = seed(&mut rand::thread_rng(), &config);
+    for rule in ["B3/S23", "B0/S8"] {
+        let rule = Rule::parse(rule).unwrap();
+        let alone = step_in_bands(&soup, &rule, &config, EdgeMode::default(), 1);
+        for bands in [2, 3, 8] {
+            let split = step_in_bands(&soup, &rule, &config, EdgeMode::default(), bands);
+            assert_eq!(split, alone);
+        }
+    }
+}
+
+// the only cells that can be alive next: the live ones and their neighbors,
+// since anything else has no live neighbor; with B0 that's no help, those
+// cells get born, and the whole board has to be looked at
+fn active_set(
+    alives: &HashSet<Coord>,
+    rule: &Rule,
+    config: &Config,
+    edge: EdgeMode,
+) -> Option<HashSet<Coord>> {
+    if rule.birth[0] {
+        return None;
+    }
+    let mut active = HashSet::with_capacity(alives.len() * 9);
+    for &(x, y) in alives {
+        active.extend(edge.resolve((x, y), config));
+        for (dx, dy) in config.topology.offsets(y) {
+            active.extend(edge.resolve((x + dx, y + dy), config));
+        }
+    }
+    Some(active)
+}
+
+// the work a generation takes: cells whose next state was worked out, and
+// lookups of a neighbor (or the cell itself, with include_center) in the live set
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct StepStats {
+    pub evaluated: usize,
+    pub lookups: usize,
+}
+
+// `step` with its work counted; `full_scan` looks at every cell of the board
+// instead of just the active set, to compare against
+pub fn step_counted(
+    alives: &HashSet<Coord>,
+    rule: &Rule,
+    config: &Config,
+    edge: EdgeMode,
+    full_scan: bool,
+) -> (HashSet<Coord>, StepStats) {
+    // every cell looked at, none of them looked up
+    if let Some(larger) = &rule.larger {
+        let next = step_larger(alives, larger, config, edge);
+        let evaluated = config.area();
+        return (
+            next,
+            StepStats {
+                evaluated,
+                lookups: 0,
+            },
+        );
+    }
+    let cells: Vec<Coord> = match active_set(alives, rule, config, edge) {
+        Some(active) if !full_scan => active.into_iter().collect(),
+        _ => config.coords().collect(),
+    };
+    let mut stats = StepStats {
+        evaluated: cells.len(),
+        lookups: 0,
+    };
+    // the cell and its 8 neighbors each, whatever the script makes of them
+    if let Some(script) = rule.script.as_ref().filter(|s| s.steps()) {
+        stats.lookups = cells.len() * 9;
+        let next = cells
+            .into_iter()
+            .filter(|c| script.next_alive(*c, alives, config, edge) == Some(true))
+            .collect();
+        return (next, stats);
+    }
+    let next = cells
+        .into_iter()
+        .filter(|&(x, y)| {
+            let center = config.include_center.then_some((0, 0));
+            let neighbors = config
+                .topology
+                .offsets(y)
+                .iter()
+                .copied()
+                .chain(center)
+                .filter_map(|(dx, dy)| edge.resolve((x + dx, y + dy), config));
+            let mut live_count = 0;
+            for c in neighbors {
+                stats.lookups += 1;
+                live_count += alives.contains(&c) as usize;
+            }
+            rule.next_alive(alives.contains(&(x, y)), live_count)
+        })
+        .collect();
+    (next, stats)
+}
+
+#[test]
+fn test_step_counted() {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    // a few cells on a big board
+    let config = Config {
+        width: 100,
+        height: 100,
+        density: Some(0.01),
+        ..Default::default()
+    };
+    let rule = Rule::default();
+    let edge = EdgeMode::default();
+    let soup = seed(&mut StdRng::seed_from_u64(5), &config);
+    let (active, lean) = step_counted(&soup, &rule, &config, edge, false);
+    let (full, scan) = step_counted(&soup, &rule, &config, edge, true);
+    assert_eq!(active, full);
+    assert_eq!(active, step(&soup, &rule, &config, edge));
+    assert_eq!(scan.evaluated, config.area());
+    assert!(lean.evaluated <= 9 * soup.len() && lean.evaluated < scan.evaluated / 5);
+    assert!(lean.lookups < scan.lookups / 5);
+    // the border cells of a bounded board have fewer than 8 neighbors
+    assert!(scan.lookups < 8 * config.area());
+    // with B0 the empty space counts too, and the active set can't be used
+    let b0 = Rule::parse("B0/S").unwrap();
+    let (_, all) = step_counted(&soup, &b0, &config, edge, false);
+    assert_eq!(all.evaluated, config.area());
+    let torus = EdgeMode::wrapping(true, true);
+    assert_eq!(
+        step_counted(&soup, &rule, &config, torus, false).0,
+        step_counted(&soup, &rule, &config, torus, true).0
+    );
+}
+
+pub fn alive_neighbor_count(
+    me: &Coord,
+    alives: &HashSet<Coord>,
+    config: &Config,
+    edge: EdgeMode,
+) -> usize {
+    let (x, y) = me.to_owned();
+    // surrounding 8, or 6 on the hex lattice
+    config
+        .topology
+        .offsets(y)
+        .iter()
+        .filter_map(|(dx, dy)| edge.resolve((x + dx, y + dy), config))
+        .filter(|c| alives.contains(c))
+        .count()
+        + (config.include_center && alives.contains(me)) as usize
+}
+
+#[test]
+fn test_alive_neighbor() {
+    let alives = HashSet::from([(-1, -1), (-1, 0), (0, -1)]);
+    let config = Config::default();
+    assert_eq!(
+        alive_neighbor_count(&(0, 0), &alives, &config, EdgeMode::default()),
+        3
+    );
+    assert_eq!(
+        alive_neighbor_count(&(-2, -1), &alives, &config, EdgeMode::default()),
+        2
+    );
+}
+
+#[test]
+fn test_dead_boundary() {
+    // a full 5x5 board, -2..3 both ways
+    let config = Config {
+        width: 5,
+        height: 5,
+        ..Default::default()
+    };
+    let full: HashSet<_> = config.coords().collect();
+    let edge = EdgeMode::default();
+    assert_eq!(edge.resolve((3, 0), &config), None);
+    assert_eq!(edge.resolve((-3, -3), &config), None);
+    // the dead ring makes up the rest of the 8
+    assert_eq!(alive_neighbor_count(&(-2, -2), &full, &config, edge), 3);
+    assert_eq!(alive_neighbor_count(&(2, 2), &full, &config, edge), 3);
+    assert_eq!(alive_neighbor_count(&(0, 2), &full, &config, edge), 5);
+    assert_eq!(alive_neighbor_count(&(-2, 1), &full, &config, edge), 5);
+    assert_eq!(alive_neighbor_count(&(0, 0), &full, &config, edge), 8);
+    // only the corners survive, and nothing is born beyond the board
+    let next = step(&full, &Rule::default(), &config, edge);
+    assert_eq!(next, HashSet::from([(-2, -2), (-2, 2), (2, -2), (2, 2)]));
+}
+
+#[test]
+fn test_hex_neighbors() {
+    use crate::Topology;
+
+    let hex = Config {
+        topology: Topology::Hex,
+        ..Default::default()
+    };
+    let edge = EdgeMode::default();
+    let block: HashSet<_> = (-1..=1)
+        .flat_map(|x| (-1..=1).map(move |y| (x, y)))
+        .collect();
+    // the even row 0 touches (-1, 1) and (0, 1) above, not (1, 1)
+    assert_eq!(alive_neighbor_count(&(0, 0), &block, &hex, edge), 6);
+    let above = HashSet::from([(1, 1)]);
+    assert_eq!(alive_neighbor_count(&(0, 0), &above, &hex, edge), 0);
+    assert_eq!(alive_neighbor_count(&(1, 0), &above, &hex, edge), 1);
+    // the odd row 1 the other way round
+    assert_eq!(alive_neighbor_count(&(0, 1), &above, &hex, edge), 1);
+    assert_eq!(alive_neighbor_count(&(1, 2), &above, &hex, edge), 1);
+    assert_eq!(alive_neighbor_count(&(2, 2), &above, &hex, edge), 1);
+    assert_eq!(alive_neighbor_count(&(0, 2), &above, &hex, edge), 0);
+    // the active set follows the lattice, counted or not
+    let rule = Rule::parse("B2/S34").unwrap();
+    let pair = HashSet::from([(0, 0), (1, 0)]);
+    let next = step(&pair, &rule, &hex, edge);
+    assert_eq!(next, HashSet::from([(0, 1), (0, -1)]));
+    assert_eq!(step_counted(&pair, &rule, &hex, edge, true).0, next);
+}
+
+#[test]
+fn test_include_center() {
+    let alives = HashSet::from([(-1, -1), (-1, 0), (0, -1), (0, 0)]);
+    let outer = Config::default();
+    let total = Config {
+        include_center: true,
+        ..Default::default()
+    };
+    let edge = EdgeMode::default();
+    assert_eq!(alive_neighbor_count(&(0, 0), &alives, &outer, edge), 3);
+    assert_eq!(alive_neighbor_count(&(0, 0), &alives, &total, edge), 4);
+    // dead cells count the same either way
+    assert_eq!(alive_neighbor_count(&(1, 1), &alives, &total, edge), 1);
+
+    // totalistic B3/S34 is Conway: a live cell's count is one higher
+    let mut rng = rand::thread_rng();
+    let soup = seed(&mut rng, &outer);
+    let conway = step(&soup, &Rule::default(), &outer, edge);
+    let shifted = step(&soup, &Rule::parse("B3/S34").unwrap(), &total, edge);
+    assert_eq!(conway, shifted);
+    // a full 3x3 block centre counts 9, past any digit, and dies
+    let full: HashSet<_> = (-1..=1)
+        .flat_map(|x| (-1..=1).map(move |y| (x, y)))
+        .collect();
+    assert_eq!(alive_neighbor_count(&(0, 0), &full, &total, edge), 9);
+    let all = Rule::parse("B/S012345678").unwrap();
+    assert!(!step(&full, &all, &total, edge).contains(&(0, 0)));
+}
+
+#[test]
+fn test_neighbor_bounds_rectangular() {
+    let config = Config {
+        width: 40,
+        height: 4,
+        ..Default::default()
+    };
+    // (15, 2) is past the top edge, (16, 0) is well inside the width
+    let alives = HashSet::from([(15, 2), (14, 1), (16, 0)]);
+    assert_eq!(
+        alive_neighbor_count(&(15, 1), &alives, &config, EdgeMode::default()),
+        2
+    );
+}
+
+#[test]
+fn test_glider_on_wide_board() {
+    let config = Config {
+        width: 40,
+        height: 10,
+        ..Default::default()
+    };
+    let rule = Rule::default();
+    // heads up-right, one cell diagonally every 4 generations
+    let glider = [(0, 2), (1, 2), (2, 2), (2, 1), (1, 0)];
+    let at = |dx: i32, dy: i32| -> HashSet<Coord> {
+        glider.iter().map(|(x, y)| (x + dx, y + dy)).collect()
+    };
+    let mut alives = at(8, -5);
+    for shift in 1..=6 {
+        for _ in 0..4 {
+            alives = step(&alives, &rule, &config, EdgeMode::default());
+        }
+        assert_eq!(alives, at(8 + shift, -5 + shift));
+    }
+}
+
+#[test]
+fn test_cylinder_edges() {
+    let config = Config {
+        width: 10,
+        height: 10,
+        ..Default::default()
+    };
+    let edge = EdgeMode::wrapping(true, false);
+    assert_eq!(edge.resolve((5, 0), &config), Some((-5, 0)));
+    assert_eq!(edge.resolve((-6, -1), &config), Some((4, -1)));
+    assert_eq!(edge.resolve((0, 5), &config), None);
+
+    let alives = HashSet::from([(4, 0), (0, 4)]);
+    assert_eq!(alive_neighbor_count(&(-5, 0), &alives, &config, edge), 1);
+    assert_eq!(alive_neighbor_count(&(0, -5), &alives, &config, edge), 0);
+
+    let rule = Rule::default();
+    // a blinker straddling the x seam keeps blinking
+    let across_x = HashSet::from([(4, 0), (-5, 0), (-4, 0)]);
+    let next = step(&across_x, &rule, &config, edge);
+    assert_eq!(next, HashSet::from([(-5, -1), (-5, 0), (-5, 1)]));
+    // the same blinker cut by the y border dies
+    let across_y = HashSet::from([(0, 4), (0, -5), (0, -4)]);
+    assert!(step(&across_y, &rule, &config, edge).is_empty());
+}
+
+#[test]
+fn test_mirror_edges() {
+    use crate::Boundary;
+
+    let config = Config {
+        width: 10,
+        height: 10,
+        ..Default::default()
+    };
+    let mirror = EdgeMode {
+        x: Boundary::Mirror,
+        y: Boundary::Mirror,
+    };
+    assert_eq!(mirror.resolve((5, 0), &config), Some((4, 0)));
+    assert_eq!(mirror.resolve((-6, -6), &config), Some((-5, -5)));
+    let rule = Rule::default();
+    // half a block against the border: its reflection makes it whole, where
+    // the dead ring lets it die
+    let domino = HashSet::from([(4, 0), (4, 1)]);
+    assert_eq!(step(&domino, &rule, &config, mirror), domino);
+    assert!(step(&domino, &rule, &config, EdgeMode::default()).is_empty());
+    let mut edge = EdgeMode::wrapping(true, true);
+    edge.cycle();
+    assert_eq!((edge, edge.to_string()), (mirror, "mirror".to_string()));
+    edge.cycle();
+    assert_eq!(edge, EdgeMode::default());
+}
+
+#[test]
+fn test_open_edges() {
+    let config = Config::default();
+    let open = EdgeMode::open();
+    assert_eq!(open.resolve((100, -100), &config), Some((100, -100)));
+    // a glider flies off the board and on, whole, a cell every 4 generations
+    let glider = HashSet::from([(0, 1), (1, 0), (-1, -1), (0, -1), (1, -1)]);
+    let mut alives = glider.clone();
+    for _ in 0..4 * 30 {
+        alives = step(&alives, &Rule::default(), &config, open);
+    }
+    let moved: HashSet<_> = glider.iter().map(|(x, y)| (x + 30, y - 30)).collect();
+    assert_eq!(alives, moved);
+    // once bounded again, what's past the border dies off
+    let bounded = step(&alives, &Rule::default(), &config, EdgeMode::default());
+    assert!(bounded.is_empty());
+}
+
+#[test]
+fn test_tiny_boards() {
+    let tiny = |width, height| Config {
+        width,
+        height,
+        ..Default::default()
+    };
+    let rule = Rule::default();
+    let edge = EdgeMode::default();
+    // the smallest board a blinker fits on
+    let config = tiny(3, 3);
+    let horizontal = HashSet::from([(-1, 0), (0, 0), (1, 0)]);
+    let vertical = HashSet::from([(0, -1), (0, 0), (0, 1)]);
+    let next = step(&horizontal, &rule, &config, edge);
+    assert_eq!(next, vertical);
+    assert_eq!(step(&next, &rule, &config, edge), horizontal);
+
+    // seeding can't ask for more cells than there are
+    let mut rng = rand::thread_rng();
+    assert_eq!(seed(&mut rng, &tiny(1, 1)), HashSet::from([(0, 0)]));
+    assert_eq!(seed(&mut rng, &config).len(), 9);
+}
+
+#[test]
+fn test_life_without_death_never_kills() {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    let config = Config::default();
+    let rule = Rule::life_without_death();
+    let mut rng = StdRng::seed_from_u64(3);
+    let mut alives = seed(&mut rng, &config);
+    for _ in 0..20 {
+        let next = step(&alives, &rule, &config, EdgeMode::default());
+        assert!(next.is_superset(&alives));
+        alives = next;
+    }
+}
+
+// FNV-1a over the sorted live coords, stable across runs and platforms
+pub fn live_set_hash(alives: &HashSet<Coord>) -> u64 {
+    let mut sorted: Vec<_> = alives.iter().collect();
+    sorted.sort();
+    let mut hash = 0xcbf2_9ce4_8422_2325u64;
+    for (x, y) in sorted {
+        for b in x.to_le_bytes().into_iter().chain(y.to_le_bytes()) {
+            hash ^= b as u64;
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+    hash
+}
+
+#[test]
+fn test_live_set_hash() {
+    let a = HashSet::from([(0, 0), (1, -1), (-3, 2)]);
+    let b = HashSet::from([(-3, 2), (0, 0), (1, -1)]);
+    assert_eq!(live_set_hash(&a), live_set_hash(&b));
+    assert_ne!(live_set_hash(&a), live_set_hash(&HashSet::from([(0, 0)])));
+    assert_eq!(live_set_hash(&HashSet::new()), 0xcbf2_9ce4_8422_2325);
+}
+
+// last few live sets, newest at the back
+#[derive(Resource, Default, Debug)]
+pub struct PeriodDetector {
+    pub history: VecDeque<HashSet<Coord>>,
+    pub period: Option<usize>,
+    // the whole live set reappeared elsewhere
+    pub spaceship: Option<Spaceship>,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Spaceship {
+    // cells moved per period
+    pub velocity: Coord,
+    pub period: usize,
+}
+
+impl std::fmt::Display for Spaceship {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let (dx, dy) = self.velocity;
+        write!(f, "velocity ({dx}, {dy})/{}", self.period)
+    }
+}
+
+fn coord_sum(alives: &HashSet<Coord>) -> (i64, i64) {
+    alives
+        .iter()
+        .fold((0, 0), |(sx, sy), (x, y)| (sx + *x as i64, sy + *y as i64))
+}
+
+// the earliest set in `history` that `alives` is a shifted copy of; the centroid
+// shift is the only candidate, so a mismatch is cheap. Several ships only count
+// when they all fly the same way, otherwise it's no single translation.
+fn find_spaceship(
+    history: &VecDeque<HashSet<Coord>>,
+    alives: &HashSet<Coord>,
+) -> Option<Spaceship> {
+    let n = alives.len() as i64;
+    if n == 0 {
+        return None;
+    }
+    let now = coord_sum(alives);
+    history.iter().rev().enumerate().find_map(|(i, then)| {
+        if then.len() != alives.len() {
+            return None;
+        }
+        let before = coord_sum(then);
+        let (dx, dy) = (now.0 - before.0, now.1 - before.1);
+        if (dx, dy) == (0, 0) || dx % n != 0 || dy % n != 0 {
+            return None;
+        }
+        let velocity = ((dx / n) as i32, (dy / n) as i32);
+        then.iter()
+            .all(|(x, y)| alives.contains(&(x + velocity.0, y + velocity.1)))
+            .then_some(Spaceship {
+                velocity,
+                period: i + 1,
+            })
+    })
+}
+
+impl PeriodDetector {
+    pub fn record(&mut self, alives: HashSet<Coord>) {
+        self.period = self
+            .history
+            .iter()
+            .rev()
+            .position(|h| *h == alives)
+            .map(|i| i + 1);
+        self.spaceship = find_spaceship(&self.history, &alives);
+        self.history.push_back(alives);
+        if self.history.len() > MAX_PERIOD {
+            self.history.pop_front();
+        }
+    }
+
+    pub fn bytes(&self) -> usize {
+        self.history.iter().map(set_bytes).sum()
+    }
+
+    // one full detected period, oldest first
+    pub fn last_period(&self) -> Option<Vec<&HashSet<Coord>>> {
+        let period = self.period?;
+        let skip = self.history.len().checked_sub(period)?;
+        Some(self.history.iter().skip(skip).collect())
+    }
+}
+
+#[test]
+fn test_spaceship() {
+    let config = Config {
+        width: 40,
+        height: 40,
+        ..Default::default()
+    };
+    let rule = Rule::default();
+    let edge = EdgeMode::default();
+    // heading down-right
+    let glider = [(0, 1), (1, 0), (-1, -1), (0, -1), (1, -1)];
+    let mut detector = PeriodDetector::default();
+    let mut current = HashSet::from(glider);
+    for _ in 0..4 {
+        detector.record(current.clone());
+        assert_eq!(detector.spaceship, None);
+        current = step(&current, &rule, &config, edge);
+    }
+    detector.record(current.clone());
+    let ship = Spaceship {
+        velocity: (1, -1),
+        period: 4,
+    };
+    assert_eq!(detector.spaceship, Some(ship));
+    assert_eq!(ship.to_string(), "velocity (1, -1)/4");
+
+    // a blinker is a period, not a ship
+    let mut detector = PeriodDetector::default();
+    let mut blinker = HashSet::from([(-1, 0), (0, 0), (1, 0)]);
+    for _ in 0..4 {
+        detector.record(blinker.clone());
+        blinker = step(&blinker, &rule, &config, edge);
+    }
+    assert!(detector.period.is_some() && detector.spaceship.is_none());
+
+    // two gliders flying apart aren't one translation
+    let mut pair: HashSet<_> = glider.into_iter().collect();
+    pair.extend(glider.iter().map(|(x, y)| (-x - 10, -y + 10)));
+    let mut detector = PeriodDetector::default();
+    for _ in 0..8 {
+        detector.record(pair.clone());
+        pair = step(&pair, &rule, &config, edge);
+    }
+    assert_eq!(detector.spaceship, None);
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum Forecast {
+    Extinct(usize),
+    // enters a cycle `after` generations from now
+    Settles { after: usize, period: usize },
+    Unknown,
+}
+
+impl std::fmt::Display for Forecast {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Forecast::Extinct(n) => write!(f, "extinct in {n}"),
+            Forecast::Settles { after, period } => {
+                write!(f, "settles in {after} (period {period})")
+            }
+            Forecast::Unknown => write!(f, "unknown > {FORECAST_CAP}"),
+        }
+    }
+}
+
+pub fn forecast(alives: HashSet<Coord>, rule: &Rule, config: &Config, edge: EdgeMode) -> Forecast {
+    forecast_within(alives, rule, config, edge, FORECAST_CAP)
+}
+
+// the same, giving up as Unknown after `cap` generations
+pub fn forecast_within(
+    alives: HashSet<Coord>,
+    rule: &Rule,
+    config: &Config,
+    edge: EdgeMode,
+    cap: usize,
+) -> Forecast {
+    forecast_with_ash(alives, rule, config, edge, cap).0
+}
+
+// forecast_within along with the board it stopped on, the ash a settled soup
+// leaves behind
+pub fn forecast_with_ash(
+    alives: HashSet<Coord>,
+    rule: &Rule,
+    config: &Config,
+    edge: EdgeMode,
+    cap: usize,
+) -> (Forecast, HashSet<Coord>) {
+    let mut detector = PeriodDetector::default();
+    detector.record(alives.clone());
+    let mut current = alives;
+    for generation in 1..=cap {
+        current = step(&current, rule, config, edge);
+        if current.is_empty() {
+            return (Forecast::Extinct(generation), current);
+        }
+        detector.record(current.clone());
+        if let Some(period) = detector.period {
+            let settled = Forecast::Settles {
+                after: generation - period,
+                period,
+            };
+            return (settled, current);
+        }
+    }
+    (Forecast::Unknown, current)
+}
+
+// the forecasts follow the bare live set with `step`, which has no dying
+// states to keep and no wires to run
+pub fn forecasts(rule: &Rule) -> Result<(), String> {
+    if rule.wireworld || rule.states > 2 {
+        return Err(format!("can't forecast {rule}, only two-state rules"));
+    }
+    Ok(())
+}
+
+#[test]
+fn test_forecast() {
+    let config = Config::default();
+    let rule = Rule::default();
+    let edge = EdgeMode::default();
+    let lonely = HashSet::from([(0, 0)]);
+    assert_eq!(forecast(lonely, &rule, &config, edge), Forecast::Extinct(1));
+    let blinker = HashSet::from([(-1, 0), (0, 0), (1, 0)]);
+    assert_eq!(
+        forecast(blinker, &rule, &config, edge),
+        Forecast::Settles {
+            after: 0,
+            period: 2
+        }
+    );
+    // dies back into a block after a couple of generations
+    let corner = HashSet::from([(0, 0), (1, 0), (0, 1)]);
+    assert_eq!(
+        forecast(corner.clone(), &rule, &config, edge),
+        Forecast::Settles {
+            after: 1,
+            period: 1
+        }
+    );
+    // the block is only seen to repeat on generation 2
+    assert_eq!(
+        forecast_within(corner, &rule, &config, edge, 1),
+        Forecast::Unknown
+    );
+    assert!(forecasts(&rule).is_ok());
+    assert!(forecasts(&Rule::named("brianbrain").unwrap()).is_err());
+    assert!(forecasts(&Rule::named("wireworld").unwrap()).is_err());
+}
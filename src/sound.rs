@@ -0,0 +1,267 @@
+use bevy::audio::{Decodable, Source};
+use bevy::prelude::*;
+use bevy::reflect::{TypePath, TypeUuid};
+use bevy_game_of_life::{Config, Coord, Dashboard, LiveCells};
+
+use std::collections::HashSet;
+use std::f32::consts::TAU;
+use std::time::Duration;
+
+const SAMPLE_RATE: u32 = 44_100;
+// the scale's lowest note, an A
+const ROOT: f32 = 220.0;
+// scale notes the population and the rows spread over, two octaves of the pentatonic
+const DEGREES: usize = 10;
+// how long a tick / a note rings, in seconds
+const TICK: f32 = 0.03;
+const NOTE: f32 = 0.25;
+// births played at one beat, so a busy column doesn't turn into noise
+const MAX_NOTES: usize = 4;
+
+#[derive(clap::ValueEnum, Debug, Default, PartialEq, Clone, Copy)]
+pub enum Scale {
+    #[default]
+    Pentatonic,
+    Major,
+    Minor,
+    Chromatic,
+}
+
+impl Scale {
+    // semitones above the octave's root
+    fn steps(self) -> &'static [u32] {
+        match self {
+            Scale::Pentatonic => &[0, 2, 4, 7, 9],
+            Scale::Major => &[0, 2, 4, 5, 7, 9, 11],
+            Scale::Minor => &[0, 2, 3, 5, 7, 8, 10],
+            Scale::Chromatic => &[0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11],
+        }
+    }
+
+    // the scale's `degree`th note up from ROOT, in Hz
+    fn frequency(self, degree: usize) -> f32 {
+        let steps = self.steps();
+        let semitones = 12 * (degree / steps.len()) as u32 + steps[degree % steps.len()];
+        ROOT * 2f32.powf(semitones as f32 / 12.0)
+    }
+}
+
+// a sine wave dying away, as a sound asset bevy_audio plays
+#[derive(TypePath, TypeUuid, Debug, Clone, Copy)]
+#[uuid = "5b0f3d7e-2a61-4c8e-9f0a-3d1e6c7b8a42"]
+pub struct Tone {
+    frequency: f32,
+    seconds: f32,
+    volume: f32,
+}
+
+pub struct ToneDecoder {
+    tone: Tone,
+    sample: u32,
+    samples: u32,
+}
+
+impl Iterator for ToneDecoder {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        if self.sample >= self.samples {
+            return None;
+        }
+        let t = self.sample as f32 / SAMPLE_RATE as f32;
+        let left = 1.0 - self.sample as f32 / self.samples as f32;
+        self.sample += 1;
+        Some((TAU * self.tone.frequency * t).sin() * self.tone.volume * left * left)
+    }
+}
+
+impl Source for ToneDecoder {
+    fn current_frame_len(&self) -> Option<usize> {
+        Some((self.samples - self.sample) as usize)
+    }
+
+    fn channels(&self) -> u16 {
+        1
+    }
+
+    fn sample_rate(&self) -> u32 {
+        SAMPLE_RATE
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        Some(Duration::from_secs_f32(self.tone.seconds))
+    }
+}
+
+impl Decodable for Tone {
+    type DecoderItem = f32;
+    type Decoder = ToneDecoder;
+
+    fn decoder(&self) -> ToneDecoder {
+        ToneDecoder {
+            tone: *self,
+            sample: 0,
+            samples: (self.seconds * SAMPLE_RATE as f32) as u32,
+        }
+    }
+}
+
+// `;`: a tick every generation, pitched by the live share of the board, and
+// a step sequencer whose playhead sweeps a column a beat across the board,
+// playing the cells born in that column since the last beat, higher up the
+// board higher up the scale
+#[derive(Resource, Debug)]
+pub struct Sonification {
+    pub enabled: bool,
+    scale: Scale,
+    beat: Timer,
+    // the column under the playhead, from the board's left
+    playhead: i32,
+    // the live cells at the last beat
+    previous: HashSet<Coord>,
+    last_round: Option<usize>,
+}
+
+impl Sonification {
+    pub fn new(scale: Scale, tempo: f32) -> Sonification {
+        Sonification {
+            enabled: false,
+            scale,
+            beat: Timer::from_seconds(60.0 / tempo, TimerMode::Repeating),
+            playhead: 0,
+            previous: HashSet::new(),
+            last_round: None,
+        }
+    }
+}
+
+pub fn parse_tempo(s: &str) -> Result<f32, String> {
+    match s.parse::<f32>() {
+        Ok(bpm) if bpm > 0.0 && bpm.is_finite() => Ok(bpm),
+        Ok(_) => Err("has to be more than 0".into()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+// the tick's pitch: up the scale as the board fills
+fn tick_frequency(scale: Scale, population: usize, config: &Config) -> f32 {
+    let area = (config.width * config.height).max(1) as f32;
+    let degree = (population as f32 / area * DEGREES as f32).round() as usize;
+    // an octave over the notes, to stay clear of them
+    scale.frequency(degree.min(DEGREES) + scale.steps().len())
+}
+
+// the notes for the cells born in `column` between `previous` and `live`,
+// low to high, no note twice
+fn column_notes(
+    scale: Scale,
+    column: i32,
+    previous: &HashSet<Coord>,
+    live: &HashSet<Coord>,
+    config: &Config,
+) -> Vec<f32> {
+    let (x, bottom) = (config.x_range().start + column, config.y_range().start);
+    let mut degrees: Vec<usize> = live
+        .difference(previous)
+        .filter(|c| c.0 == x)
+        .map(|c| (c.1 - bottom) as usize * DEGREES / config.height.max(1) as usize)
+        .collect();
+    degrees.sort_unstable();
+    degrees.dedup();
+    degrees
+        .into_iter()
+        .take(MAX_NOTES)
+        .map(|d| scale.frequency(d))
+        .collect()
+}
+
+#[test]
+fn test_sonification() {
+    let config = Config {
+        width: 10,
+        height: 10,
+        ..Default::default()
+    };
+    let scale = Scale::Pentatonic;
+    // an octave up is twice the frequency, whatever the scale
+    assert_eq!(Scale::Major.frequency(7), 2.0 * ROOT);
+    assert_eq!(scale.frequency(5), 2.0 * ROOT);
+    assert!(scale.frequency(1) > ROOT);
+    assert!(tick_frequency(scale, 50, &config) > tick_frequency(scale, 5, &config));
+
+    let (left, bottom) = (config.x_range().start, config.y_range().start);
+    let previous = HashSet::from([(left + 2, bottom)]);
+    let live = HashSet::from([
+        (left + 2, bottom),
+        (left + 2, bottom + 1),
+        (left + 2, bottom + 9),
+        (left + 3, bottom + 5),
+    ]);
+    // the still cell and the one in the next column don't sound
+    let notes = column_notes(scale, 2, &previous, &live, &config);
+    assert_eq!(notes, vec![scale.frequency(1), scale.frequency(9)]);
+    assert!(column_notes(scale, 0, &previous, &live, &config).is_empty());
+    // a full column is capped
+    let column: HashSet<_> = (0..10).map(|y| (left, bottom + y)).collect();
+    let full = column_notes(scale, 0, &HashSet::new(), &column, &config);
+    assert_eq!(full.len(), MAX_NOTES);
+}
+
+fn play(
+    commands: &mut Commands,
+    tones: &mut Assets<Tone>,
+    frequency: f32,
+    seconds: f32,
+    volume: f32,
+) {
+    commands.spawn(AudioSourceBundle {
+        source: tones.add(Tone {
+            frequency,
+            seconds,
+            volume,
+        }),
+        settings: PlaybackSettings::DESPAWN,
+    });
+}
+
+pub fn toggle_sound(
+    keys: Res<Input<KeyCode>>,
+    live: Res<LiveCells>,
+    mut sound: ResMut<Sonification>,
+) {
+    if keys.just_pressed(KeyCode::Semicolon) {
+        sound.enabled = !sound.enabled;
+        // the sequencer starts over from the left, hearing only what's born from now
+        sound.playhead = 0;
+        sound.previous = live.0.clone();
+        sound.beat.reset();
+    }
+}
+
+pub fn play_sound(
+    mut commands: Commands,
+    time: Res<Time>,
+    config: Res<Config>,
+    db: Res<Dashboard>,
+    live: Res<LiveCells>,
+    mut tones: ResMut<Assets<Tone>>,
+    mut sound: ResMut<Sonification>,
+) {
+    if !sound.enabled {
+        return;
+    }
+    let scale = sound.scale;
+    if sound.last_round != Some(db.round) {
+        sound.last_round = Some(db.round);
+        let frequency = tick_frequency(scale, live.0.len(), &config);
+        play(&mut commands, &mut tones, frequency, TICK, 0.1);
+    }
+    if !sound.beat.tick(time.delta()).just_finished() {
+        return;
+    }
+    for frequency in column_notes(scale, sound.playhead, &sound.previous, &live.0, &config) {
+        play(&mut commands, &mut tones, frequency, NOTE, 0.2);
+    }
+    sound.playhead = (sound.playhead + 1) % config.width.max(1);
+    sound.previous = live.0.clone();
+}
@@ -0,0 +1,154 @@
+use bevy_game_of_life::pattern::{to_rle, Pattern};
+use bevy_game_of_life::{
+    forecast_with_ash, starting_cells, Config, EdgeMode, Forecast, Rule, SimRng,
+};
+
+// how far past the mean lifetime, in standard deviations, a transient is
+// unusually long
+const OUTLIER: f64 = 3.0;
+
+// what one soup came to
+#[derive(Debug, PartialEq, Clone)]
+pub struct Soup {
+    pub seed: u64,
+    pub forecast: Forecast,
+    // cells alive once it settled
+    pub ash: usize,
+}
+
+impl Soup {
+    // generations to die out or settle into its cycle, None if it didn't in time
+    fn lifetime(&self) -> Option<usize> {
+        match self.forecast {
+            Forecast::Extinct(n) => Some(n),
+            Forecast::Settles { after, .. } => Some(after),
+            Forecast::Unknown => None,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Report {
+    pub runs: usize,
+    pub extinct: usize,
+    pub settled: usize,
+    pub unknown: usize,
+    // over the soups that died out or settled
+    pub mean_lifetime: f64,
+    // live share of the board at the end, over the same soups
+    pub ash_density: f64,
+    // seed and lifetime
+    pub longest: Option<(u64, usize)>,
+    // the seeds still changing at the cap, or settling OUTLIER standard
+    // deviations past the mean
+    pub outliers: Vec<u64>,
+}
+
+pub fn report(soups: &[Soup], area: usize) -> Report {
+    let done: Vec<_> = soups
+        .iter()
+        .filter_map(|s| s.lifetime().map(|n| (s, n as f64)))
+        .collect();
+    let count = done.len().max(1) as f64;
+    let mean = done.iter().map(|(_, n)| n).sum::<f64>() / count;
+    let deviation = (done.iter().map(|(_, n)| (n - mean).powi(2)).sum::<f64>() / count).sqrt();
+    let ash = done.iter().map(|(s, _)| s.ash).sum::<usize>() as f64;
+    let extinct = soups
+        .iter()
+        .filter(|s| matches!(s.forecast, Forecast::Extinct(_)))
+        .count();
+    let unknown = soups.len() - done.len();
+    Report {
+        runs: soups.len(),
+        extinct,
+        settled: done.len() - extinct,
+        unknown,
+        mean_lifetime: mean,
+        ash_density: ash / count / area.max(1) as f64,
+        longest: done
+            .iter()
+            .max_by_key(|(_, n)| *n as usize)
+            .map(|(s, n)| (s.seed, *n as usize)),
+        outliers: soups
+            .iter()
+            .filter(|s| {
+                s.lifetime()
+                    .is_none_or(|n| n as f64 > mean + OUTLIER * deviation)
+            })
+            .map(|s| s.seed)
+            .collect(),
+    }
+}
+
+#[test]
+fn test_report() {
+    let soup = |seed, forecast, ash| Soup {
+        seed,
+        forecast,
+        ash,
+    };
+    let mut soups: Vec<_> = (0..20)
+        .map(|seed| {
+            let after = 100 + seed as usize;
+            soup(seed, Forecast::Settles { after, period: 2 }, 10)
+        })
+        .collect();
+    soups.push(soup(20, Forecast::Extinct(110), 0));
+    soups.push(soup(
+        21,
+        Forecast::Settles {
+            after: 900,
+            period: 1,
+        },
+        42,
+    ));
+    soups.push(soup(22, Forecast::Unknown, 500));
+    let stats = report(&soups, 100);
+    assert_eq!((stats.runs, stats.extinct), (23, 1));
+    assert_eq!((stats.settled, stats.unknown), (21, 1));
+    assert_eq!(stats.longest, Some((21, 900)));
+    // the one long transient and the one that never settled
+    assert_eq!(stats.outliers, vec![21, 22]);
+    let ash = (20 * 10 + 42) as f64 / 22.0 / 100.0;
+    assert!((stats.ash_density - ash).abs() < 1e-9);
+    // nothing to go on
+    assert_eq!(report(&[], 100).longest, None);
+}
+
+// --soup-search: `runs` soups, soup i from seed `base + i` so `--seed` brings
+// it back in the app, each run until it settles or `cap` generations. Prints
+// the report and saves the outliers' starting soups as soup-<seed>.rle
+pub fn search(config: &Config, rule: &Rule, edge: EdgeMode, base: u64, runs: usize, cap: usize) {
+    let start = |seed| starting_cells(config, rule, edge, &mut SimRng::seeded(seed).0);
+    let soups: Vec<_> = (0..runs as u64)
+        .map(|i| {
+            let seed = base.wrapping_add(i);
+            let (forecast, ash) = forecast_with_ash(start(seed), rule, config, edge, cap);
+            Soup {
+                seed,
+                forecast,
+                ash: ash.len(),
+            }
+        })
+        .collect();
+    let area = (config.width * config.height) as usize;
+    let report = report(&soups, area);
+    println!(
+        "{} soups from seed {base}: {} died out, {} settled, {} still changing at generation {cap}",
+        report.runs, report.extinct, report.settled, report.unknown
+    );
+    println!(
+        "mean lifetime {:.1} generations, ash density {:.4}",
+        report.mean_lifetime, report.ash_density
+    );
+    if let Some((seed, lifetime)) = report.longest {
+        println!("longest lived: seed {seed}, {lifetime} generations");
+    }
+    for &seed in &report.outliers {
+        let path = format!("soup-{seed}.rle");
+        match std::fs::write(&path, to_rle(&Pattern::from_board(&start(seed)), rule)) {
+            Ok(()) => println!("long transient: seed {seed}, saved to {path}"),
+            Err(e) => eprintln!("can't write {path}: {e}"),
+        }
+    }
+}
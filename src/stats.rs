@@ -0,0 +1,104 @@
+use bevy::prelude::*;
+use bevy_game_of_life::{bounding_box, Coord, Dashboard, LiveCells};
+
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+// rows written between flushes, so a plot of a running file is never far behind
+const FLUSH_EVERY: usize = 100;
+
+// --stats-out: a row per generation, CSV, or JSON lines for a .json file
+#[derive(Resource)]
+pub struct StatsOut {
+    writer: BufWriter<File>,
+    json: bool,
+    last_round: Option<usize>,
+    unflushed: usize,
+}
+
+impl StatsOut {
+    pub fn create(path: &Path) -> Result<StatsOut, String> {
+        let file = File::create(path).map_err(|e| e.to_string())?;
+        let json = path.extension().is_some_and(|e| e == "json");
+        let mut stats = StatsOut {
+            writer: BufWriter::new(file),
+            json,
+            last_round: None,
+            unflushed: 0,
+        };
+        if !json {
+            writeln!(
+                stats.writer,
+                "round,population,births,deaths,min_x,min_y,max_x,max_y"
+            )
+            .map_err(|e| e.to_string())?;
+        }
+        Ok(stats)
+    }
+
+    // once per round; a rewind writes the rounds again as they're run again
+    fn record(&mut self, db: &Dashboard, live: &LiveCells) -> std::io::Result<()> {
+        if self.last_round == Some(db.round) {
+            return Ok(());
+        }
+        self.last_round = Some(db.round);
+        let row = format_row(
+            self.json,
+            (db.round, db.survival, db.births, db.deaths),
+            bounding_box(&live.0),
+        );
+        writeln!(self.writer, "{row}")?;
+        self.unflushed += 1;
+        if self.unflushed >= FLUSH_EVERY {
+            self.unflushed = 0;
+            self.writer.flush()?;
+        }
+        Ok(())
+    }
+}
+
+// round, population, births and deaths, and the live bounding box, its
+// fields empty / null on an empty board
+fn format_row(
+    json: bool,
+    (round, population, births, deaths): (usize, usize, usize, usize),
+    bbox: Option<(Coord, Coord)>,
+) -> String {
+    let corners = bbox.map(|((x0, y0), (x1, y1))| [x0, y0, x1, y1]);
+    if json {
+        let bbox = corners.map_or("null".to_string(), |c| format!("{c:?}"));
+        format!(
+            "{{\"round\":{round},\"population\":{population},\"births\":{births},\"deaths\":{deaths},\"bbox\":{bbox}}}"
+        )
+    } else {
+        let bbox = corners.map_or(",,,".to_string(), |c| c.map(|v| v.to_string()).join(","));
+        format!("{round},{population},{births},{deaths},{bbox}")
+    }
+}
+
+#[test]
+fn test_format_row() {
+    let counts = (12, 5, 2, 1);
+    let bbox = Some(((-1, 0), (3, 2)));
+    assert_eq!(format_row(false, counts, bbox), "12,5,2,1,-1,0,3,2");
+    assert_eq!(format_row(false, (13, 0, 0, 5), None), "13,0,0,5,,,,");
+    assert_eq!(
+        format_row(true, counts, bbox),
+        r#"{"round":12,"population":5,"births":2,"deaths":1,"bbox":[-1, 0, 3, 2]}"#
+    );
+    assert!(format_row(true, counts, None).ends_with(r#""bbox":null}"#));
+}
+
+// after every tick; a file that can't be written any more is given up on
+pub fn write_stats(
+    mut commands: Commands,
+    db: Res<Dashboard>,
+    live: Res<LiveCells>,
+    mut stats: ResMut<StatsOut>,
+) {
+    if let Err(e) = stats.record(&db, &live) {
+        error!("can't write the --stats-out file, stopping: {e}");
+        commands.remove_resource::<StatsOut>();
+    }
+}
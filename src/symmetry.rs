@@ -0,0 +1,112 @@
+use bevy::prelude::Resource;
+
+use crate::{Config, Coord};
+
+// the ways a cell can be mirrored or turned about the middle of the board
+#[derive(Resource, Default, Debug, PartialEq, Clone, Copy)]
+pub enum Symmetry {
+    #[default]
+    None,
+    // left half mirrors the right
+    MirrorX,
+    // top half mirrors the bottom
+    MirrorY,
+    // both, four images
+    MirrorXY,
+    // half turn
+    Rotate2,
+    // quarter turns, four images
+    Rotate4,
+}
+
+impl Symmetry {
+    pub fn cycle(&mut self) {
+        *self = match self {
+            Symmetry::None => Symmetry::MirrorX,
+            Symmetry::MirrorX => Symmetry::MirrorY,
+            Symmetry::MirrorY => Symmetry::MirrorXY,
+            Symmetry::MirrorXY => Symmetry::Rotate2,
+            Symmetry::Rotate2 => Symmetry::Rotate4,
+            Symmetry::Rotate4 => Symmetry::None,
+        };
+    }
+
+    // `c` and all its counterparts on the board, `c` first. Works in doubled
+    // coordinates so the middle of an even board, between two cells, is integral;
+    // quarter turns on a non-square board can land off the grid or between cells,
+    // and those images are dropped.
+    pub fn images(&self, c: Coord, config: &Config) -> Vec<Coord> {
+        let (xs, ys) = (config.x_range(), config.y_range());
+        let (mx, my) = (xs.start + xs.end - 1, ys.start + ys.end - 1);
+        let (dx, dy) = (2 * c.0 - mx, 2 * c.1 - my);
+        let doubled = match self {
+            Symmetry::None => vec![(dx, dy)],
+            Symmetry::MirrorX => vec![(dx, dy), (-dx, dy)],
+            Symmetry::MirrorY => vec![(dx, dy), (dx, -dy)],
+            Symmetry::MirrorXY => vec![(dx, dy), (-dx, dy), (dx, -dy), (-dx, -dy)],
+            Symmetry::Rotate2 => vec![(dx, dy), (-dx, -dy)],
+            Symmetry::Rotate4 => vec![(dx, dy), (-dy, dx), (-dx, -dy), (dy, -dx)],
+        };
+        let mut images = vec![];
+        for (x, y) in doubled {
+            if (x + mx) % 2 != 0 || (y + my) % 2 != 0 {
+                continue;
+            }
+            let image = ((x + mx) / 2, (y + my) / 2);
+            if config.contains(image) && !images.contains(&image) {
+                images.push(image);
+            }
+        }
+        images
+    }
+}
+
+impl std::fmt::Display for Symmetry {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let name = match self {
+            Symmetry::None => "none",
+            Symmetry::MirrorX => "mirror left/right",
+            Symmetry::MirrorY => "mirror top/bottom",
+            Symmetry::MirrorXY => "mirror both ways",
+            Symmetry::Rotate2 => "half turn",
+            Symmetry::Rotate4 => "quarter turns",
+        };
+        write!(f, "{name}")
+    }
+}
+
+#[test]
+fn test_symmetry_images() {
+    // cells -10..10, the middle between -1 and 0
+    let config = Config::default();
+    assert_eq!(Symmetry::None.images((3, 4), &config), vec![(3, 4)]);
+    assert_eq!(
+        Symmetry::MirrorX.images((3, 4), &config),
+        vec![(3, 4), (-4, 4)]
+    );
+    assert_eq!(
+        Symmetry::Rotate4.images((3, 4), &config),
+        vec![(3, 4), (-5, 3), (-4, -5), (4, -4)]
+    );
+    // the very middle of an odd board is its own image
+    let odd = Config {
+        width: 5,
+        height: 5,
+        ..Default::default()
+    };
+    assert_eq!(Symmetry::MirrorXY.images((0, 0), &odd), vec![(0, 0)]);
+    assert_eq!(
+        Symmetry::Rotate2.images((2, 1), &odd),
+        vec![(2, 1), (-2, -1)]
+    );
+    // a wide board can't turn a quarter without leaving it
+    let wide = Config {
+        width: 10,
+        height: 4,
+        ..Default::default()
+    };
+    assert_eq!(
+        Symmetry::Rotate4.images((4, 0), &wide),
+        vec![(4, 0), (-5, -1)]
+    );
+}
@@ -0,0 +1,146 @@
+use bevy::prelude::*;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use bevy::render::texture::ImageSampler;
+use bevy_game_of_life::{Config, Coord, LiveCells};
+
+use std::collections::HashSet;
+
+use crate::Theme;
+
+// the main board drawn as one image, a texel per cell, instead of a sprite per
+// cell; cells get no sprite then, so the views that color them don't apply
+#[derive(Resource, Default, Debug)]
+pub struct TextureRender(pub bool);
+
+pub fn texture_render(render: Res<TextureRender>) -> bool {
+    render.0
+}
+
+#[derive(Resource)]
+pub struct BoardTexture {
+    image: Handle<Image>,
+    sprite: Entity,
+}
+
+// rows from the top, `alive` where a cell is and `dead` everywhere else
+pub fn board_texels(
+    config: &Config,
+    alives: &HashSet<Coord>,
+    alive: Color,
+    dead: Color,
+) -> Vec<u8> {
+    let (xs, ys) = (config.x_range(), config.y_range());
+    let mut data = dead
+        .as_rgba_u8()
+        .repeat((config.width * config.height) as usize);
+    let texel = alive.as_rgba_u8();
+    for &(x, y) in alives.iter().filter(|c| config.contains(**c)) {
+        let i = ((ys.end - 1 - y) * config.width + x - xs.start) as usize * 4;
+        data[i..i + 4].copy_from_slice(&texel);
+    }
+    data
+}
+
+#[test]
+fn test_board_texels() {
+    let config = Config {
+        width: 4,
+        height: 3,
+        ..Default::default()
+    };
+    let texels = board_texels(
+        &config,
+        &HashSet::from([(1, -1), (9, 9)]),
+        Color::WHITE,
+        Color::BLACK,
+    );
+    assert_eq!(texels.len(), 4 * 3 * 4);
+    // (1, -1) is the bottom row, third from the left
+    let at = |x: usize, y: usize| &texels[(y * 4 + x) * 4..(y * 4 + x) * 4 + 4];
+    assert_eq!(at(3, 2), [255; 4]);
+    assert_eq!(at(0, 0), [0, 0, 0, 255]);
+    assert_eq!(texels.chunks(4).filter(|t| t[0] == 255).count(), 1);
+}
+
+fn board_image(config: &Config, alives: &HashSet<Coord>, theme: &Theme) -> Image {
+    let mut image = Image::new(
+        Extent3d {
+            width: config.width as u32,
+            height: config.height as u32,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        board_texels(config, alives, theme.alive, theme.dead),
+        TextureFormat::Rgba8UnormSrgb,
+    );
+    // crisp cells, however far zoomed in
+    image.sampler_descriptor = ImageSampler::nearest();
+    image
+}
+
+// the quad's middle and size, over the cells and the gaps between them
+fn placement(config: &Config, pitch: f32) -> (Vec2, Vec2) {
+    let (xs, ys) = (config.x_range(), config.y_range());
+    let center = Vec2::new(
+        (xs.start + xs.end - 1) as f32 / 2.0,
+        (ys.start + ys.end - 1) as f32 / 2.0,
+    ) * pitch;
+    let size = Vec2::new(config.width as f32, config.height as f32) * pitch;
+    (center, size)
+}
+
+// one quad over the main board
+pub fn spawn_board_texture(
+    mut commands: Commands,
+    config: Res<Config>,
+    theme: Res<Theme>,
+    live: Res<LiveCells>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    let image = images.add(board_image(&config, &live.0, &theme));
+    let (center, size) = placement(&config, theme.pitch());
+    let sprite = commands
+        .spawn(SpriteBundle {
+            sprite: Sprite {
+                custom_size: Some(size),
+                ..Default::default()
+            },
+            texture: image.clone(),
+            transform: Transform::from_translation(center.extend(0.0)),
+            ..Default::default()
+        })
+        .id();
+    commands.insert_resource(BoardTexture { image, sprite });
+}
+
+// the texels written over in place, only on a frame the cells or colors
+// changed; a new size gets a new image, and the quad is fitted to it
+pub fn update_board_texture(
+    config: Res<Config>,
+    theme: Res<Theme>,
+    live: Res<LiveCells>,
+    texture: Option<Res<BoardTexture>>,
+    mut images: ResMut<Assets<Image>>,
+    mut sprites: Query<(&mut Sprite, &mut Transform)>,
+) {
+    let Some(texture) = texture else {
+        return;
+    };
+    if !live.is_changed() && !theme.is_changed() && !config.is_changed() {
+        return;
+    }
+    let Some(image) = images.get_mut(&texture.image) else {
+        return;
+    };
+    let size = image.texture_descriptor.size;
+    if (size.width, size.height) == (config.width as u32, config.height as u32) {
+        image.data = board_texels(&config, &live.0, theme.alive, theme.dead);
+        return;
+    }
+    *image = board_image(&config, &live.0, &theme);
+    if let Ok((mut sprite, mut transform)) = sprites.get_mut(texture.sprite) {
+        let (center, size) = placement(&config, theme.pitch());
+        sprite.custom_size = Some(size);
+        transform.translation = center.extend(transform.translation.z);
+    }
+}
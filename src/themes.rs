@@ -0,0 +1,126 @@
+use bevy::prelude::*;
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+use crate::ramp::ColorRamp;
+use crate::Theme;
+
+// the color sets `--theme`, F1 and the panel's Palette button pick from; the
+// cell size, gap, corners and smoothing stay as they are
+#[derive(ValueEnum, Serialize, Deserialize, Debug, Default, PartialEq, Clone, Copy)]
+pub enum ThemePreset {
+    #[default]
+    Classic,
+    Solarized,
+    HighContrast,
+    Paper,
+    Phosphor,
+    Midnight,
+}
+
+impl ThemePreset {
+    pub fn next(self) -> ThemePreset {
+        let all = ThemePreset::value_variants();
+        let i = all.iter().position(|p| *p == self).unwrap_or(0);
+        all[(i + 1) % all.len()]
+    }
+
+    pub fn name(self) -> String {
+        self.to_possible_value()
+            .map_or(String::new(), |v| v.get_name().to_string())
+    }
+}
+
+impl Theme {
+    pub fn with_preset(&self, preset: ThemePreset) -> Theme {
+        let rgb = |hex: u32| {
+            let channel = |shift: u32| ((hex >> shift) & 0xff) as f32 / 255.0;
+            Color::rgb(channel(16), channel(8), channel(0))
+        };
+        let classic = Theme::default();
+        let mut theme = match preset {
+            ThemePreset::Classic => classic,
+            ThemePreset::Solarized => Theme {
+                background: rgb(0x002b36),
+                alive: rgb(0xb58900),
+                dead: rgb(0x073642),
+                untouched: rgb(0x04303c),
+                wall: rgb(0x268bd2),
+                obstacle: rgb(0x3a2a2a),
+                text: rgb(0x93a1a1),
+                age: ColorRamp::even(&[rgb(0xb58900), rgb(0xcb4b16), rgb(0xdc322f), rgb(0xd33682)]),
+                ..classic
+            },
+            ThemePreset::HighContrast => Theme {
+                background: Color::rgb(0.35, 0.35, 0.35),
+                alive: Color::rgb(1.0, 1.0, 0.0),
+                dead: Color::BLACK,
+                untouched: Color::BLACK,
+                wall: Color::CYAN,
+                obstacle: Color::rgb(0.6, 0.0, 0.0),
+                text: Color::WHITE,
+                age: ColorRamp::even(&[Color::WHITE, Color::YELLOW, Color::RED]),
+                ..classic
+            },
+            ThemePreset::Paper => Theme {
+                background: Color::rgb(0.95, 0.95, 0.9),
+                alive: Color::rgb(0.1, 0.1, 0.1),
+                dead: Color::rgb(0.8, 0.8, 0.75),
+                untouched: Color::rgb(0.88, 0.88, 0.83),
+                text: Color::rgb(0.1, 0.1, 0.1),
+                ..classic
+            },
+            ThemePreset::Phosphor => Theme {
+                background: Color::rgb(0.0, 0.05, 0.0),
+                alive: Color::rgb(0.2, 1.0, 0.3),
+                dead: Color::rgb(0.0, 0.2, 0.05),
+                untouched: Color::rgb(0.0, 0.12, 0.02),
+                text: Color::rgb(0.2, 1.0, 0.3),
+                ..classic
+            },
+            ThemePreset::Midnight => Theme {
+                background: Color::rgb(0.05, 0.05, 0.2),
+                alive: Color::rgb(1.0, 0.8, 0.2),
+                dead: Color::rgb(0.15, 0.15, 0.4),
+                untouched: Color::rgb(0.1, 0.1, 0.3),
+                text: Color::rgb(1.0, 0.8, 0.2),
+                ..classic
+            },
+        };
+        theme.preset = preset;
+        theme.size = self.size;
+        theme.corner_radius = self.corner_radius;
+        theme.gap = self.gap;
+        theme.smooth = self.smooth;
+        theme
+    }
+}
+
+#[test]
+fn test_theme_presets() {
+    let mine = Theme {
+        size: 7.0,
+        smooth: true,
+        ..Default::default()
+    };
+    let solarized = mine.with_preset(ThemePreset::Solarized);
+    // the colors change, the geometry doesn't
+    assert_ne!(solarized.alive, mine.alive);
+    assert_eq!((solarized.size, solarized.smooth), (7.0, true));
+    assert_eq!(solarized.with_preset(ThemePreset::Classic), mine);
+    // every preset once, then back around
+    let mut preset = ThemePreset::Classic;
+    for _ in 1..ThemePreset::value_variants().len() {
+        preset = preset.next();
+        assert_ne!(preset, ThemePreset::Classic);
+    }
+    assert_eq!(preset.next(), ThemePreset::Classic);
+    assert_eq!(ThemePreset::HighContrast.name(), "high-contrast");
+}
+
+// F1: the next preset's colors
+pub fn cycle_theme(keys: Res<Input<KeyCode>>, mut theme: ResMut<Theme>) {
+    if keys.just_pressed(KeyCode::F1) {
+        *theme = theme.with_preset(theme.preset.next());
+    }
+}
@@ -0,0 +1,122 @@
+use bevy::input::touch::Touch;
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use bevy_game_of_life::{Cell, Config, LiveCells, SimState, Symmetry};
+
+use std::collections::HashSet;
+
+use crate::camera::zoom_about;
+use crate::draw::{paint, world_to_cell, Brush};
+use crate::scrub::Timeline;
+use crate::{Dashboard, Theme};
+
+// a two-finger touch this short that hardly moved is a tap, which pauses
+const TAP_SECS: f32 = 0.3;
+const TAP_SLOP: f32 = 20.0;
+
+// one finger paints with the pen brush; two pan and pinch-zoom, and a quick
+// two-finger tap pauses / resumes
+#[derive(Resource, Default, Debug)]
+pub struct TouchGesture {
+    // when the second finger came down, and how far the fingers went since
+    started: Option<f32>,
+    moved: f32,
+    // a second finger came down, so no more painting until they're all up
+    multi: bool,
+}
+
+fn offset_from_center(p: Vec2, window: &Window) -> Vec2 {
+    // screen y points down
+    Vec2::new(p.x - window.width() / 2.0, window.height() / 2.0 - p.y)
+}
+
+pub fn touch_gestures(
+    touches: Res<Touches>,
+    time: Res<Time>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    mut gesture: ResMut<TouchGesture>,
+    sim_state: Res<State<SimState>>,
+    mut next: ResMut<NextState<SimState>>,
+    mut cameras: Query<(&mut Transform, &mut OrthographicProjection)>,
+) {
+    let fingers: Vec<&Touch> = touches.iter().collect();
+    if fingers.is_empty() {
+        if let Some(started) = gesture.started.take() {
+            let quick = time.elapsed_seconds() - started < TAP_SECS;
+            if quick && gesture.moved < TAP_SLOP {
+                next.set(sim_state.get().toggled());
+            }
+        }
+        gesture.multi = false;
+        return;
+    }
+    let [a, b, ..] = fingers[..] else {
+        return;
+    };
+    if gesture.started.is_none() {
+        gesture.started = Some(time.elapsed_seconds());
+        gesture.moved = 0.0;
+    }
+    gesture.multi = true;
+    let (Ok(window), Ok((mut transform, mut projection))) =
+        (windows.get_single(), cameras.get_single_mut())
+    else {
+        return;
+    };
+    let before = a.previous_position().distance(b.previous_position());
+    let now = a.position().distance(b.position());
+    let dragged = (a.delta() + b.delta()) / 2.0;
+    gesture.moved += dragged.length() + (now - before).abs();
+    let pan = Vec2::new(dragged.x, -dragged.y) * projection.scale;
+    let mut translation = transform.translation.truncate() - pan;
+    if before > 0.0 && now > 0.0 {
+        let middle = offset_from_center((a.position() + b.position()) / 2.0, window);
+        (translation, projection.scale) =
+            zoom_about(translation, projection.scale, middle, before / now);
+    }
+    transform.translation = translation.extend(transform.translation.z);
+}
+
+// the finger's cells come alive, mirrored as the mouse pen is; the first one of
+// a pan can dab a little before the second comes down
+#[allow(clippy::too_many_arguments)]
+pub fn touch_paint(
+    mut commands: Commands,
+    touches: Res<Touches>,
+    gesture: Res<TouchGesture>,
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    config: Res<Config>,
+    theme: Res<Theme>,
+    db: Res<Dashboard>,
+    brush: Res<Brush>,
+    symmetry: Res<Symmetry>,
+    timeline: Res<Timeline>,
+    mut live: ResMut<LiveCells>,
+    mut cells: Query<&mut Cell>,
+) {
+    if gesture.multi || timeline.scrubbing {
+        return;
+    }
+    let Some(finger) = touches.first_pressed_position() else {
+        return;
+    };
+    let Ok((camera, transform)) = cameras.get_single() else {
+        return;
+    };
+    let Some(world) = camera.viewport_to_world_2d(transform, finger) else {
+        return;
+    };
+    let targets: HashSet<_> = brush
+        .cells(world_to_cell(world, theme.pitch(), config.topology))
+        .into_iter()
+        .flat_map(|c| symmetry.images(c, &config))
+        .collect();
+    paint(
+        &targets,
+        true,
+        db.round,
+        &mut commands,
+        &mut live,
+        &mut cells,
+    );
+}
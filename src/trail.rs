@@ -0,0 +1,133 @@
+use bevy::prelude::*;
+use bevy_game_of_life::{Cell, Coord, Dashboard, LiveCells, State};
+
+use std::collections::{HashMap, HashSet};
+
+use crate::{mix, Theme};
+
+// generations a dead cell takes to fade from the live color back to dead
+const TRAIL: usize = 8;
+// what's left of a cell's activity a generation on
+const DECAY: f32 = 0.9;
+// activity that glows fully: a cell flipping every generation settles at 10
+const HOT: f32 = 6.0;
+// how far the glow tints a cell
+const GLOW: f32 = 0.8;
+
+// F11: cells that just died fade out over TRAIL generations, and every flip
+// warms its cell up, cooling off again by DECAY a generation, so glider
+// streams leave a wake and oscillator cores burn
+#[derive(Resource, Default, Debug)]
+pub struct TrailView {
+    pub enabled: bool,
+    // the live cells the last generation it saw
+    previous: HashSet<Coord>,
+    round: usize,
+    activity: HashMap<Coord, f32>,
+    // the round each cell last died in
+    died: HashMap<Coord, usize>,
+}
+
+impl TrailView {
+    // `live` being the board at `round`
+    fn record(&mut self, round: usize, live: &HashSet<Coord>) {
+        if round == self.round {
+            return;
+        }
+        self.activity.retain(|_, a| {
+            *a *= DECAY;
+            *a > 0.01
+        });
+        for &c in live.symmetric_difference(&self.previous) {
+            *self.activity.entry(c).or_default() += 1.0;
+            if live.contains(&c) {
+                self.died.remove(&c);
+            } else {
+                self.died.insert(c, round);
+            }
+        }
+        self.died.retain(|_, at| round.abs_diff(*at) < TRAIL);
+        self.previous = live.clone();
+        self.round = round;
+    }
+
+    // 0 for a cell that's been still a while, 1 for a hot one
+    fn heat(&self, c: Coord) -> f32 {
+        self.activity.get(&c).map_or(0.0, |a| (a / HOT).min(1.0))
+    }
+
+    pub fn color(&self, cell: &Cell, theme: &Theme) -> Color {
+        let c = cell.index_xy;
+        let base = match (&cell.state, self.died.get(&c)) {
+            (State::Alive, _) => theme.alive,
+            (State::Dead, Some(at)) => {
+                let age = self.round.abs_diff(*at) + 1;
+                mix(theme.alive, theme.dead, age as f32 / TRAIL as f32)
+            }
+            _ => theme.dead,
+        };
+        let heat = self.heat(c);
+        if heat == 0.0 {
+            return base;
+        }
+        mix(base, theme.heat.sample(heat), heat * GLOW)
+    }
+}
+
+#[test]
+fn test_trail_view() {
+    let mut trail = TrailView::default();
+    let blinker = |up: bool| -> HashSet<Coord> {
+        let line = [(-1, 0), (0, 0), (1, 0)];
+        line.iter()
+            .map(|&(a, b)| if up { (b, a) } else { (a, b) })
+            .collect()
+    };
+    trail.previous = blinker(false);
+    for round in 1..=40 {
+        trail.record(round, &blinker(round % 2 == 0));
+    }
+    // the tips flip every generation and glow, the middle never does
+    assert_eq!(trail.heat((0, 0)), 0.0);
+    assert_eq!(trail.heat((1, 0)), 1.0);
+    // the same round again changes nothing
+    let before = trail.activity.clone();
+    trail.record(40, &HashSet::new());
+    assert_eq!(trail.activity, before);
+    // everything dies: the cells fade out and are forgotten after TRAIL rounds
+    let theme = Theme::default();
+    let cell = Cell::new((0, 0), false);
+    trail.record(41, &HashSet::new());
+    let fresh = trail.color(&cell, &theme);
+    assert_ne!(fresh, theme.dead);
+    for round in 42..=41 + TRAIL {
+        trail.record(round, &HashSet::new());
+    }
+    assert!(trail.died.is_empty());
+    assert!(trail.heat((1, 0)) < 1.0);
+}
+
+pub fn toggle_trail_view(
+    keys: Res<Input<KeyCode>>,
+    live: Res<LiveCells>,
+    db: Res<Dashboard>,
+    mut trail: ResMut<TrailView>,
+) {
+    if !keys.just_pressed(KeyCode::F11) {
+        return;
+    }
+    // from a clean slate, the board as it is now the starting point
+    *trail = TrailView {
+        enabled: !trail.enabled,
+        previous: live.0.clone(),
+        round: db.round,
+        ..Default::default()
+    };
+}
+
+// once a generation; off, nothing is kept
+pub fn track_activity(db: Res<Dashboard>, live: Res<LiveCells>, mut trail: ResMut<TrailView>) {
+    if trail.enabled {
+        trail.record(db.round, &live.0);
+    }
+}
@@ -0,0 +1,24 @@
+use bevy::prelude::*;
+use bevy_game_of_life::{Ants, Config};
+
+use crate::draw::cell_center;
+use crate::Theme;
+
+// the cells of a turmite with more than two colors, past the first live one
+pub fn ant_color(color: u8, colors: u8, theme: &Theme) -> Color {
+    theme
+        .rings
+        .sample((color - 1) as f32 / (colors - 1).max(1) as f32)
+}
+
+// each ant as a red arrowhead on its cell, pointing where it's headed
+pub fn draw_ants(mut gizmos: Gizmos, config: Res<Config>, theme: Res<Theme>, ants: Res<Ants>) {
+    for ant in &ants.ants {
+        let center = cell_center(ant.at, theme.pitch(), config.topology);
+        let ahead = Vec2::new(ant.heading.0 as f32, ant.heading.1 as f32) * theme.size * 0.4;
+        let side = ahead.perp() * 0.6;
+        let tip = center + ahead;
+        let points = [tip, center - ahead + side, center - ahead - side, tip];
+        gizmos.linestrip_2d(points, Color::RED);
+    }
+}
@@ -0,0 +1,142 @@
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use bevy_game_of_life::{Cell, Config, Coord, LiveCells, Rule, State, Symmetry};
+
+use std::collections::HashSet;
+
+use crate::draw::{change_cells, cursor_cell, stroke, Brush, DrawAnchor, DrawTool, Stamp};
+use crate::scrub::Timeline;
+use crate::Theme;
+
+// the usual Wireworld colors
+const WIRE: Color = Color::rgb(0.9, 0.7, 0.1);
+const HEAD: Color = Color::rgb(0.2, 0.5, 1.0);
+const TAIL: Color = Color::rgb(1.0, 0.3, 0.2);
+
+// what the tools lay down under Wireworld; D goes round them
+#[derive(Resource, Default, Debug, PartialEq, Clone, Copy)]
+pub enum WirePaint {
+    #[default]
+    Wire,
+    Head,
+    Tail,
+}
+
+impl WirePaint {
+    fn state(self) -> State {
+        match self {
+            WirePaint::Wire => State::Wire,
+            WirePaint::Head => State::Alive,
+            WirePaint::Tail => State::Dying(1),
+        }
+    }
+}
+
+impl std::fmt::Display for WirePaint {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let name = match self {
+            WirePaint::Wire => "wire",
+            WirePaint::Head => "electron head",
+            WirePaint::Tail => "electron tail",
+        };
+        write!(f, "{name}")
+    }
+}
+
+// a run condition: Wireworld draws with draw_wires instead of draw
+pub fn wireworld(rule: Res<Rule>) -> bool {
+    rule.wireworld
+}
+
+pub fn wire_color(state: &State, theme: &Theme) -> Color {
+    match state {
+        State::Wire => WIRE,
+        State::Alive => HEAD,
+        State::Dying(_) => TAIL,
+        State::Dead => theme.dead,
+    }
+}
+
+// `state` wherever the cell isn't frozen, spawning the cells sparse mode
+// hasn't got; only heads are in LiveCells
+fn paint_wire(
+    coords: &HashSet<Coord>,
+    state: State,
+    commands: &mut Commands,
+    live: &mut LiveCells,
+    cells: &mut Query<&mut Cell>,
+) {
+    change_cells(coords, state != State::Dead, commands, cells, |cell| {
+        if cell.frozen {
+            return;
+        }
+        live.set(cell, state == State::Alive);
+        cell.state = state.clone();
+    });
+}
+
+pub fn cycle_wire_paint(keys: Res<Input<KeyCode>>, mut paint: ResMut<WirePaint>) {
+    if !keys.just_pressed(KeyCode::D) {
+        return;
+    }
+    *paint = match *paint {
+        WirePaint::Wire => WirePaint::Head,
+        WirePaint::Head => WirePaint::Tail,
+        WirePaint::Tail => WirePaint::Wire,
+    };
+}
+
+// the tools as in draw, laying down the WirePaint; the right button clears
+#[allow(clippy::too_many_arguments)]
+pub fn draw_wires(
+    mut commands: Commands,
+    keys: Res<Input<KeyCode>>,
+    buttons: Res<Input<MouseButton>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    config: Res<Config>,
+    theme: Res<Theme>,
+    paint: Res<WirePaint>,
+    tool: Res<DrawTool>,
+    brush: Res<Brush>,
+    stamp: Res<Stamp>,
+    symmetry: Res<Symmetry>,
+    mut anchor: ResMut<DrawAnchor>,
+    timeline: Res<Timeline>,
+    mut live: ResMut<LiveCells>,
+    mut cells: Query<&mut Cell>,
+) {
+    let modifiers = [
+        KeyCode::ShiftLeft,
+        KeyCode::ShiftRight,
+        KeyCode::ControlLeft,
+        KeyCode::ControlRight,
+    ];
+    // Shift-drags still erase
+    if timeline.scrubbing || keys.any_pressed(modifiers) {
+        return;
+    }
+    let Some(cursor) = cursor_cell(&windows, &cameras, theme.pitch(), config.topology) else {
+        return;
+    };
+    let wiping = buttons.pressed(MouseButton::Right);
+    let Some(targets) = stroke(
+        *tool,
+        cursor,
+        &buttons,
+        false,
+        wiping,
+        &brush,
+        &stamp,
+        &config,
+        &mut anchor,
+    ) else {
+        return;
+    };
+    let targets: HashSet<_> = targets
+        .into_iter()
+        .flat_map(|c| symmetry.images(c, &config))
+        .collect();
+    let state = if wiping { State::Dead } else { paint.state() };
+    paint_wire(&targets, state, &mut commands, &mut live, &mut cells);
+}
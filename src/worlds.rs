@@ -0,0 +1,249 @@
+use bevy::prelude::*;
+use bevy::sprite::Anchor;
+use bevy_game_of_life::{
+    seed, starting_cells, step, Config, Coord, Dashboard, EdgeMode, LiveCells, Rule,
+};
+use rand::{rngs::StdRng, SeedableRng};
+
+use std::collections::HashSet;
+
+use crate::{SimRng, Theme};
+
+// the boards next to the main one, each with its own soup and rule;
+// drawing, views and the like only ever touch the main board
+#[derive(Resource, Default, Debug)]
+pub struct SideWorlds(pub Vec<SideWorld>);
+
+#[derive(Debug)]
+pub struct SideWorld {
+    pub rule: Rule,
+    pub live: HashSet<Coord>,
+    pub round: usize,
+}
+
+#[derive(Component, Debug)]
+pub struct SideCell {
+    world: usize,
+    index_xy: Coord,
+}
+
+impl SideCell {
+    // the world's board sits at its slot's offset, cells `pitch` apart
+    pub fn position(&self, config: &Config, pitch: f32) -> Vec2 {
+        let (x, y) = self.index_xy;
+        let x = x as f32 + config.topology.row_shift(y);
+        offset(self.world + 1, config, pitch) + Vec2::new(x, y as f32) * pitch
+    }
+}
+
+// columns and rows of the most square grid that holds every world
+pub fn layout(worlds: usize) -> (usize, usize) {
+    let cols = (1..=worlds).find(|c| c * c >= worlds).unwrap_or(1);
+    (cols, worlds.div_ceil(cols))
+}
+
+// where board `slot` sits, the main board (slot 0) being on the origin
+pub fn offset(slot: usize, config: &Config, pitch: f32) -> Vec2 {
+    let (cols, _) = layout(config.worlds);
+    // one empty cell between the boards
+    let pitch_x = (config.width + 1) as f32 * pitch;
+    let pitch_y = (config.height + 1) as f32 * pitch;
+    Vec2::new(
+        (slot % cols) as f32 * pitch_x,
+        -((slot / cols) as f32) * pitch_y,
+    )
+}
+
+// the middle of all boards, for the camera
+pub fn layout_center(config: &Config, pitch: f32) -> Vec2 {
+    let (cols, rows) = layout(config.worlds);
+    // the far corner is the last slot of a full grid
+    offset(cols * rows - 1, config, pitch) / 2.0
+}
+
+#[test]
+fn test_layout() {
+    assert_eq!(layout(1), (1, 1));
+    assert_eq!(layout(2), (2, 1));
+    assert_eq!(layout(4), (2, 2));
+    assert_eq!(layout(5), (3, 2));
+    let config = Config {
+        width: 9,
+        height: 4,
+        worlds: 4,
+        ..Default::default()
+    };
+    let pitch = crate::SIZE + crate::GAP;
+    assert_eq!(offset(0, &config, pitch), Vec2::ZERO);
+    assert_eq!(
+        offset(3, &config, pitch),
+        Vec2::new(10.0 * pitch, -5.0 * pitch)
+    );
+    assert_eq!(
+        layout_center(&config, pitch),
+        Vec2::new(5.0 * pitch, -2.5 * pitch)
+    );
+}
+
+// what a side world starts from, warmed up under its own rule: the main
+// board's seed again with same_seed, a soup of its own otherwise
+fn side_start(config: &Config, rule: &Rule, edge: EdgeMode, rng: &mut SimRng) -> HashSet<Coord> {
+    if config.same_seed {
+        // seed_board's rng from the top draws the same soup
+        return starting_cells(config, rule, edge, &mut StdRng::seed_from_u64(rng.1));
+    }
+    let mut live = if config.empty {
+        HashSet::new()
+    } else {
+        seed(&mut rng.0, config)
+    };
+    for _ in 0..config.warmup {
+        live = step(&live, rule, config, edge);
+    }
+    live
+}
+
+#[test]
+fn test_side_start() {
+    let config = Config {
+        same_seed: true,
+        warmup: 3,
+        ..Default::default()
+    };
+    let edge = EdgeMode::default();
+    let highlife = Rule::named("highlife").unwrap();
+    let main = starting_cells(&config, &Rule::default(), edge, &mut SimRng::seeded(7).0);
+    let mut rng = SimRng::seeded(7);
+    // the main board's soup, run through the warmup under its own rule
+    let unwarmed = Config {
+        warmup: 0,
+        ..config.clone()
+    };
+    let mut expected = starting_cells(&unwarmed, &highlife, edge, &mut SimRng::seeded(7).0);
+    for _ in 0..3 {
+        expected = step(&expected, &highlife, &config, edge);
+    }
+    assert_eq!(side_start(&config, &highlife, edge, &mut rng), expected);
+    assert_eq!(side_start(&config, &Rule::default(), edge, &mut rng), main);
+    let own = Config {
+        same_seed: false,
+        ..config
+    };
+    // seed_board drew from the rng first
+    seed(&mut rng.0, &own);
+    assert_ne!(side_start(&own, &Rule::default(), edge, &mut rng), main);
+}
+
+pub fn spawn_side_worlds(
+    mut commands: Commands,
+    config: Res<Config>,
+    rule: Res<Rule>,
+    edge: Res<EdgeMode>,
+    theme: Res<Theme>,
+    mut rng: ResMut<SimRng>,
+    mut worlds: ResMut<SideWorlds>,
+) {
+    for world in 0..config.worlds - 1 {
+        for (x, y) in config.coords() {
+            let cell = SideCell {
+                world,
+                index_xy: (x, y),
+            };
+            let pos = cell.position(&config, theme.pitch());
+            commands.spawn((
+                cell,
+                SpriteBundle {
+                    sprite: Sprite {
+                        custom_size: Some(Vec2::splat(theme.size)),
+                        ..Default::default()
+                    },
+                    transform: Transform::from_translation(pos.extend(0.0)),
+                    ..Default::default()
+                },
+            ));
+        }
+        let rule = config.world_rules.get(world).unwrap_or(&rule).clone();
+        let live = side_start(&config, &rule, *edge, &mut rng);
+        worlds.0.push(SideWorld {
+            rule,
+            live,
+            round: config.warmup,
+        });
+    }
+}
+
+// in lockstep with the main board, an empty one counting on too
+pub fn step_side_worlds(config: Res<Config>, edge: Res<EdgeMode>, mut worlds: ResMut<SideWorlds>) {
+    for world in worlds.0.iter_mut() {
+        if !world.live.is_empty() {
+            world.live = step(&world.live, &world.rule, &config, *edge);
+        }
+        world.round += 1;
+    }
+}
+
+pub fn color_side_worlds(
+    worlds: Res<SideWorlds>,
+    theme: Res<Theme>,
+    mut query: Query<(&mut Sprite, &SideCell)>,
+) {
+    if !worlds.is_changed() && !theme.is_changed() {
+        return;
+    }
+    for (mut sprite, cell) in query.iter_mut() {
+        let alive = worlds.0[cell.world].live.contains(&cell.index_xy);
+        sprite.color = if alive { theme.alive } else { theme.dead };
+    }
+}
+
+// a line over each board with more than one, its rule, round and population
+#[derive(Component, Debug)]
+pub struct WorldLabel(usize);
+
+pub fn spawn_world_labels(mut commands: Commands, config: Res<Config>) {
+    if config.worlds < 2 {
+        return;
+    }
+    for slot in 0..config.worlds {
+        commands.spawn((
+            WorldLabel(slot),
+            Text2dBundle {
+                text: Text::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 24.0,
+                        ..Default::default()
+                    },
+                ),
+                text_anchor: Anchor::BottomCenter,
+                ..Default::default()
+            },
+        ));
+    }
+}
+
+pub fn update_world_labels(
+    db: Res<Dashboard>,
+    rule: Res<Rule>,
+    live: Res<LiveCells>,
+    config: Res<Config>,
+    theme: Res<Theme>,
+    worlds: Res<SideWorlds>,
+    mut labels: Query<(&mut Text, &mut Transform, &WorldLabel)>,
+) {
+    let pitch = theme.pitch();
+    let (xs, ys) = (config.x_range(), config.y_range());
+    // half a cell over the top row, in the middle
+    let above = Vec2::new((xs.start + xs.end - 1) as f32 / 2.0, ys.end as f32 - 0.5) * pitch;
+    for (mut text, mut transform, label) in labels.iter_mut() {
+        let (rule, round, alive) = match label.0 {
+            0 => (&*rule, db.round, live.0.len()),
+            slot => {
+                let world = &worlds.0[slot - 1];
+                (&world.rule, world.round, world.live.len())
+            }
+        };
+        text.sections[0].value = format!("{rule}: round {round}, {alive} alive");
+        transform.translation = (offset(label.0, &config, pitch) + above).extend(1.0);
+    }
+}
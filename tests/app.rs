@@ -0,0 +1,314 @@
+//! Runs the simulation inside a real Bevy app with no window, so the system wiring
+//! and ordering are checked against the bare step function.
+
+use bevy::prelude::*;
+use bevy_game_of_life::{
+    seed, step, step_once, Cell, Config, Dashboard, EdgeMode, GameOfLifePlugin, LiveCells, Palette,
+    PendingCells, Rule, SimPlugin, SimRng, SimState, State, Stepping, SPAWN_CHUNK,
+};
+use rand::{rngs::StdRng, SeedableRng};
+
+use std::time::Duration;
+
+const SEED: u64 = 7;
+const TICK: Duration = Duration::from_secs(1);
+
+fn app(config: Config) -> App {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .insert_resource(FixedTime::new(TICK))
+        .insert_resource(config)
+        .insert_resource(SimRng::seeded(SEED))
+        .add_plugins(SimPlugin);
+    app
+}
+
+// one whole tick of fixed time per frame, whatever the wall clock did
+fn tick(app: &mut App) {
+    app.world.resource_mut::<FixedTime>().tick(TICK);
+    app.update();
+}
+
+// the cell State is the crate's, this one Bevy's
+fn sim_state(app: &App) -> SimState {
+    *app.world
+        .resource::<bevy::ecs::schedule::State<SimState>>()
+        .get()
+}
+
+// from the next frame on
+fn set_sim_state(app: &mut App, state: SimState) {
+    app.world.resource_mut::<NextState<SimState>>().set(state);
+}
+
+#[test]
+fn test_app_follows_the_headless_run() {
+    let config = Config {
+        width: 32,
+        height: 32,
+        warmup: 3,
+        ..Default::default()
+    };
+    let mut expected = seed(&mut StdRng::seed_from_u64(SEED), &config);
+    for _ in 0..config.warmup {
+        expected = step(&expected, &Rule::default(), &config, EdgeMode::default());
+    }
+    let mut app = app(config.clone());
+    let mut peak = expected.len();
+    // startup and the first tick happen in the same frame
+    for round in config.warmup + 1..config.warmup + 10 {
+        let before = expected.clone();
+        expected = step(&expected, &Rule::default(), &config, EdgeMode::default());
+        peak = peak.max(expected.len());
+        tick(&mut app);
+        let db = app.world.resource::<Dashboard>();
+        assert_eq!((db.round, db.survival), (round, expected.len()));
+        assert_eq!(app.world.resource::<LiveCells>().0, expected);
+        let births = expected.difference(&before).count();
+        let deaths = before.difference(&expected).count();
+        assert_eq!((db.births, db.deaths, db.peak), (births, deaths, peak));
+    }
+    let alive = app
+        .world
+        .query::<&Cell>()
+        .iter(&app.world)
+        .filter(|c| c.state == State::Alive)
+        .count();
+    assert_eq!(alive, expected.len());
+}
+
+#[test]
+fn test_empty_board_waits_for_drawing() {
+    let mut app = app(Config {
+        empty: true,
+        ..Default::default()
+    });
+    tick(&mut app);
+    let db = app.world.resource::<Dashboard>();
+    assert_eq!((db.round, db.survival), (0, 0));
+    assert_eq!(sim_state(&app), SimState::Paused);
+    // a blinker drawn in, then unpaused
+    app.world.resource_scope(|world, mut live: Mut<LiveCells>| {
+        for mut cell in world.query::<&mut Cell>().iter_mut(world) {
+            if [(-1, 0), (0, 0), (1, 0)].contains(&cell.index_xy) {
+                live.set(&mut cell, true);
+            }
+        }
+    });
+    set_sim_state(&mut app, SimState::Running);
+    tick(&mut app);
+    let db = app.world.resource::<Dashboard>();
+    assert_eq!((db.round, db.survival), (1, 3));
+    let vertical = [(0, -1), (0, 0), (0, 1)].into_iter().collect();
+    assert_eq!(app.world.resource::<LiveCells>().0, vertical);
+}
+
+#[test]
+fn test_paused_app_stands_still() {
+    let mut app = app(Config::default());
+    set_sim_state(&mut app, SimState::Paused);
+    for _ in 0..5 {
+        tick(&mut app);
+    }
+    let db = app.world.resource::<Dashboard>();
+    assert_eq!(db.round, 0);
+    assert_eq!(db.survival, Config::default().seed_count());
+}
+
+#[test]
+fn test_single_step_while_paused() {
+    let mut app = app(Config::default());
+    set_sim_state(&mut app, SimState::Paused);
+    tick(&mut app);
+    assert_eq!(app.world.resource::<Dashboard>().round, 0);
+    step_once(&mut app.world);
+    assert_eq!(app.world.resource::<Dashboard>().round, 1);
+    // and no further on its own
+    tick(&mut app);
+    tick(&mut app);
+    assert_eq!(app.world.resource::<Dashboard>().round, 1);
+    assert_eq!(sim_state(&app), SimState::Paused);
+}
+
+#[test]
+fn test_settled_board_pauses() {
+    // packed full, it burns out within a few generations
+    let config = Config {
+        width: 6,
+        height: 6,
+        stop_when_settled: true,
+        ..Default::default()
+    };
+    let mut app = app(config);
+    for _ in 0..20 {
+        tick(&mut app);
+    }
+    assert_eq!(sim_state(&app), SimState::Paused);
+    let db = app.world.resource::<Dashboard>();
+    let (round, (since, period)) = (db.round, db.settled.unwrap());
+    assert_eq!(since + period, round);
+    tick(&mut app);
+    assert_eq!(app.world.resource::<Dashboard>().round, round);
+}
+
+#[test]
+fn test_big_board_builds_over_frames() {
+    let config = Config {
+        width: 300,
+        height: 300,
+        density: Some(0.2),
+        ..Default::default()
+    };
+    let seeded = config.seed_count();
+    let mut app = app(config.clone());
+    let cells = |app: &mut App| app.world.query::<&Cell>().iter(&app.world).count();
+    // never more than a chunk a frame, and no tick until the board is whole
+    tick(&mut app);
+    assert_eq!(cells(&mut app), SPAWN_CHUNK);
+    let mut frames = 1;
+    while !app.world.resource::<PendingCells>().0.is_empty() {
+        let before = cells(&mut app);
+        tick(&mut app);
+        assert!(cells(&mut app) - before <= SPAWN_CHUNK);
+        assert_eq!(app.world.resource::<Dashboard>().round, 0);
+        frames += 1;
+    }
+    assert!(frames > 1);
+    assert_eq!(cells(&mut app), config.area());
+    assert_eq!(app.world.resource::<LiveCells>().0.len(), seeded);
+    let alive = app
+        .world
+        .query::<&Cell>()
+        .iter(&app.world)
+        .filter(|c| c.state == State::Alive)
+        .count();
+    assert_eq!(alive, seeded);
+    tick(&mut app);
+    assert_eq!(app.world.resource::<Dashboard>().round, 1);
+}
+
+#[test]
+fn test_manual_stepping_keeps_pace() {
+    use bevy::time::TimeUpdateStrategy;
+
+    // frames a third of a tick apart, so the generations don't line up with them
+    let frame = TICK / 3 + Duration::from_millis(10);
+    let mut apps = [Stepping::Fixed, Stepping::Manual].map(|stepping| {
+        let mut app = app(Config {
+            stepping,
+            ..Default::default()
+        });
+        app.insert_resource(TimeUpdateStrategy::ManualDuration(frame));
+        app
+    });
+    let frames = 60;
+    for _ in 0..frames {
+        let [fixed, manual] = apps.each_mut().map(|app| {
+            app.update();
+            app.world.resource::<Dashboard>().round
+        });
+        assert!(fixed.abs_diff(manual) <= 1, "{fixed} against {manual}");
+    }
+    // the very first frame has no time behind it
+    let elapsed = frame * (frames - 1);
+    let expected = (elapsed.as_secs_f32() / TICK.as_secs_f32()) as usize;
+    for app in &apps {
+        let round = app.world.resource::<Dashboard>().round;
+        assert!(round.abs_diff(expected) <= 1, "{round} against {expected}");
+    }
+}
+
+#[test]
+fn test_embedded_plugin_draws_its_cells() {
+    let palette = Palette {
+        alive: Color::GREEN,
+        dead: Color::NONE,
+        size: 4.0,
+        ..Default::default()
+    };
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .insert_resource(SimRng::seeded(SEED))
+        .add_plugins(GameOfLifePlugin {
+            config: Config {
+                width: 16,
+                height: 16,
+                ..Default::default()
+            },
+            rule: Rule::named("highlife").unwrap(),
+            tick: Some(TICK),
+            palette,
+        });
+    for _ in 0..3 {
+        tick(&mut app);
+    }
+    assert_eq!(app.world.resource::<Dashboard>().round, 3);
+    assert_eq!(app.world.resource::<Rule>().to_string(), "B36/S23");
+    let mut sprites = app.world.query::<(&Cell, &Sprite, &Transform)>();
+    assert_eq!(sprites.iter(&app.world).count(), 16 * 16);
+    for (cell, sprite, transform) in sprites.iter(&app.world) {
+        let alive = cell.state == State::Alive;
+        assert_eq!(sprite.color, if alive { Color::GREEN } else { Color::NONE });
+        let (x, y) = cell.index_xy;
+        assert_eq!(
+            transform.translation.truncate(),
+            Vec2::new(x as f32, y as f32) * 4.0
+        );
+    }
+}
+
+#[test]
+fn test_wireworld_carries_an_electron() {
+    let config = Config {
+        empty: true,
+        ..Default::default()
+    };
+    let mut app = app(config);
+    app.insert_resource(Rule::wireworld());
+    app.update();
+    while !app.world.resource::<PendingCells>().0.is_empty() {
+        app.update();
+    }
+    // a wire along y = 0, the head at its left end and the tail behind it
+    let mut cells = app.world.query::<&mut Cell>();
+    for mut cell in cells.iter_mut(&mut app.world) {
+        cell.state = match cell.index_xy {
+            (-5, 0) => State::Dying(1),
+            (-4, 0) => State::Alive,
+            (x, 0) if x < 5 => State::Wire,
+            _ => continue,
+        };
+    }
+    app.world.resource_mut::<LiveCells>().0.insert((-4, 0));
+    set_sim_state(&mut app, SimState::Running);
+    for step in 1..=3 {
+        tick(&mut app);
+        let head = (-4 + step, 0);
+        assert_eq!(app.world.resource::<LiveCells>().0, [head].into());
+        let mut state = |xy| {
+            let mut cells = app.world.query::<&Cell>();
+            cells
+                .iter(&app.world)
+                .find(|c| c.index_xy == xy)
+                .unwrap()
+                .state
+                .clone()
+        };
+        assert_eq!(state((head.0 - 1, 0)), State::Dying(1));
+        assert_eq!(state((head.0 - 2, 0)), State::Wire);
+        // nothing is born off the wire
+        assert_eq!(state((head.0, 1)), State::Dead);
+    }
+    // past the wire's end, its last tail turns back into wire too
+    for _ in 0..7 {
+        tick(&mut app);
+    }
+    assert!(app.world.resource::<LiveCells>().0.is_empty());
+    let mut cells = app.world.query::<&Cell>();
+    let wire: Vec<_> = cells
+        .iter(&app.world)
+        .filter(|c| c.index_xy.1 == 0 && (-5..5).contains(&c.index_xy.0))
+        .map(|c| c.state.clone())
+        .collect();
+    assert_eq!(wire, vec![State::Wire; 10]);
+}